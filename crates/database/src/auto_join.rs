@@ -0,0 +1,88 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Text channel to read aloud when the bot joins each voice channel automatically.
+#[derive(Iden)]
+pub(crate) enum DatabaseAutoJoin {
+    #[iden = "auto_joins"]
+    Table,
+    VoiceChannelId,
+    GuildId,
+    TextChannelId,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AutoJoin {
+    pub voice_channel_id: i64,
+    pub text_channel_id: i64,
+}
+
+pub async fn create(database: &PgPool, voice_channel_id: u64, guild_id: u64, text_channel_id: u64) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseAutoJoin::Table)
+        .columns([
+            DatabaseAutoJoin::VoiceChannelId,
+            DatabaseAutoJoin::GuildId,
+            DatabaseAutoJoin::TextChannelId,
+        ])
+        .values_panic([voice_channel_id.into(), guild_id.into(), text_channel_id.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseAutoJoin::VoiceChannelId)
+                .update_column(DatabaseAutoJoin::TextChannelId)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the binding of the voice channel and returns whether there was one.
+pub async fn delete_by_voice_channel_id(database: &PgPool, voice_channel_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseAutoJoin::Table)
+        .and_where(Expr::col(DatabaseAutoJoin::VoiceChannelId).eq(voice_channel_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_voice_channel_id(database: &PgPool, voice_channel_id: u64) -> Result<Option<i64>> {
+    let (sql, values) = Query::select()
+        .column(DatabaseAutoJoin::TextChannelId)
+        .from(DatabaseAutoJoin::Table)
+        .and_where(Expr::col(DatabaseAutoJoin::VoiceChannelId).eq(voice_channel_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with::<_, i64, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<AutoJoin>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseAutoJoin::VoiceChannelId, DatabaseAutoJoin::TextChannelId])
+        .from(DatabaseAutoJoin::Table)
+        .and_where(Expr::col(DatabaseAutoJoin::GuildId).eq(guild_id))
+        .order_by(DatabaseAutoJoin::VoiceChannelId, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, AutoJoin, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,79 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::PgPool;
+
+/// Word each guild bans from being read, which is muted with a beep.
+#[derive(Iden)]
+pub(crate) enum DatabaseBlacklistedWord {
+    #[iden = "blacklisted_words"]
+    Table,
+    GuildId,
+    Word,
+}
+
+/// Registers the word and returns whether it has not been registered yet.
+pub async fn create(database: &PgPool, guild_id: u64, word: &str) -> Result<bool> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseBlacklistedWord::Table)
+        .columns([DatabaseBlacklistedWord::GuildId, DatabaseBlacklistedWord::Word])
+        .values_panic([guild_id.into(), word.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseBlacklistedWord::GuildId, DatabaseBlacklistedWord::Word])
+                .do_nothing()
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes the word and returns whether there was one.
+pub async fn delete(database: &PgPool, guild_id: u64, word: &str) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseBlacklistedWord::Table)
+        .and_where(Expr::col(DatabaseBlacklistedWord::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseBlacklistedWord::Word).eq(word))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<String>> {
+    let (sql, values) = Query::select()
+        .column(DatabaseBlacklistedWord::Word)
+        .from(DatabaseBlacklistedWord::Table)
+        .and_where(Expr::col(DatabaseBlacklistedWord::GuildId).eq(guild_id))
+        .order_by(DatabaseBlacklistedWord::Word, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn count_by_guild_id(database: &PgPool, guild_id: u64) -> Result<i64> {
+    let (sql, values) = Query::select()
+        .expr(Expr::col(DatabaseBlacklistedWord::Word).count())
+        .from(DatabaseBlacklistedWord::Table)
+        .and_where(Expr::col(DatabaseBlacklistedWord::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,87 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Voice that messages in each channel are always read with.
+#[derive(Iden)]
+pub(crate) enum DatabaseChannelVoice {
+    #[iden = "channel_voices"]
+    Table,
+    ChannelId,
+    GuildId,
+    SpeakerId,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ChannelVoice {
+    pub channel_id: i64,
+    pub speaker_id: i32,
+}
+
+pub async fn create(database: &PgPool, channel_id: u64, guild_id: u64, speaker_id: u16) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseChannelVoice::Table)
+        .columns([
+            DatabaseChannelVoice::ChannelId,
+            DatabaseChannelVoice::GuildId,
+            DatabaseChannelVoice::SpeakerId,
+        ])
+        .values_panic([channel_id.into(), guild_id.into(), speaker_id.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseChannelVoice::ChannelId)
+                .update_column(DatabaseChannelVoice::SpeakerId)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the voice of the channel and returns whether there was one.
+pub async fn delete_by_channel_id(database: &PgPool, channel_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseChannelVoice::Table)
+        .and_where(Expr::col(DatabaseChannelVoice::ChannelId).eq(channel_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_channel_id(database: &PgPool, channel_id: u64) -> Result<Option<i32>> {
+    let (sql, values) = Query::select()
+        .column(DatabaseChannelVoice::SpeakerId)
+        .from(DatabaseChannelVoice::Table)
+        .and_where(Expr::col(DatabaseChannelVoice::ChannelId).eq(channel_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with::<_, i32, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<ChannelVoice>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseChannelVoice::ChannelId, DatabaseChannelVoice::SpeakerId])
+        .from(DatabaseChannelVoice::Table)
+        .and_where(Expr::col(DatabaseChannelVoice::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, ChannelVoice, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,183 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Guild that registered each word into the user dictionary of VOICEVOX ENGINE, or the reading of a word in messages of
+/// the guild, which is applied before the conversion.
+#[derive(Iden)]
+pub(crate) enum DatabaseDictionaryWord {
+    #[iden = "dictionary_words"]
+    Table,
+    Id,
+    GuildId,
+    Surface,
+    Reading,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Reading {
+    pub surface: String,
+    pub reading: String,
+}
+
+pub async fn create(database: &PgPool, word_id: Uuid, guild_id: u64) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseDictionaryWord::Table)
+        .columns([DatabaseDictionaryWord::Id, DatabaseDictionaryWord::GuildId])
+        .values_panic([word_id.into(), guild_id.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseDictionaryWord::Id)
+                .update_column(DatabaseDictionaryWord::GuildId)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+pub async fn delete_by_id(database: &PgPool, word_id: Uuid) -> Result<()> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseDictionaryWord::Table)
+        .and_where(Expr::col(DatabaseDictionaryWord::Id).eq(word_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+pub async fn count_by_guild_id(database: &PgPool, guild_id: u64) -> Result<i64> {
+    let (sql, values) = Query::select()
+        .expr(Expr::col(DatabaseDictionaryWord::Id).count())
+        .from(DatabaseDictionaryWord::Table)
+        .and_where(Expr::col(DatabaseDictionaryWord::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseDictionaryWord::Reading).is_null())
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+/// IDs of the words the guild registered.
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<Uuid>> {
+    let (sql, values) = Query::select()
+        .column(DatabaseDictionaryWord::Id)
+        .from(DatabaseDictionaryWord::Table)
+        .and_where(Expr::col(DatabaseDictionaryWord::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseDictionaryWord::Reading).is_null())
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with::<_, Uuid, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+/// Records the words the guild registered at once, recording none of them if any fails.
+pub async fn create_all(database: &PgPool, word_ids: &[Uuid], guild_id: u64) -> Result<()> {
+    let mut tx = database.begin().await?;
+    for word_id in word_ids {
+        let (sql, values) = Query::insert()
+            .into_table(DatabaseDictionaryWord::Table)
+            .columns([DatabaseDictionaryWord::Id, DatabaseDictionaryWord::GuildId])
+            .values_panic([(*word_id).into(), guild_id.into()])
+            .on_conflict(
+                OnConflict::column(DatabaseDictionaryWord::Id)
+                    .update_column(DatabaseDictionaryWord::GuildId)
+                    .to_owned(),
+            )
+            .build_sqlx(PostgresQueryBuilder);
+        sqlx::query_with(&sql, values)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::msg)?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn create_reading(database: &PgPool, guild_id: u64, surface: &str, reading: &str) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseDictionaryWord::Table)
+        .columns([
+            DatabaseDictionaryWord::Id,
+            DatabaseDictionaryWord::GuildId,
+            DatabaseDictionaryWord::Surface,
+            DatabaseDictionaryWord::Reading,
+        ])
+        .values_panic([Uuid::new_v4().into(), guild_id.into(), surface.into(), reading.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseDictionaryWord::GuildId, DatabaseDictionaryWord::Surface])
+                .update_column(DatabaseDictionaryWord::Reading)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the reading of the word and returns whether there was one.
+pub async fn delete_reading(database: &PgPool, guild_id: u64, surface: &str) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseDictionaryWord::Table)
+        .and_where(Expr::col(DatabaseDictionaryWord::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseDictionaryWord::Surface).eq(surface))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_readings_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<Reading>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseDictionaryWord::Surface, DatabaseDictionaryWord::Reading])
+        .from(DatabaseDictionaryWord::Table)
+        .and_where(Expr::col(DatabaseDictionaryWord::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseDictionaryWord::Reading).is_not_null())
+        .order_by(DatabaseDictionaryWord::Surface, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Reading, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn count_readings_by_guild_id(database: &PgPool, guild_id: u64) -> Result<i64> {
+    let (sql, values) = Query::select()
+        .expr(Expr::col(DatabaseDictionaryWord::Id).count())
+        .from(DatabaseDictionaryWord::Table)
+        .and_where(Expr::col(DatabaseDictionaryWord::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseDictionaryWord::Reading).is_not_null())
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,97 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Reading of each custom emoji in messages of a guild, which is read in place of the emoji.
+#[derive(Iden)]
+pub(crate) enum DatabaseEmojiReading {
+    #[iden = "emoji_readings"]
+    Table,
+    GuildId,
+    EmojiId,
+    Name,
+    Reading,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct EmojiReading {
+    pub emoji_id: i64,
+    /// Name of the emoji when the reading was registered, for listing
+    pub name: String,
+    pub reading: String,
+}
+
+pub async fn create(database: &PgPool, guild_id: u64, emoji_id: u64, name: &str, reading: &str) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseEmojiReading::Table)
+        .columns([
+            DatabaseEmojiReading::GuildId,
+            DatabaseEmojiReading::EmojiId,
+            DatabaseEmojiReading::Name,
+            DatabaseEmojiReading::Reading,
+        ])
+        .values_panic([guild_id.into(), emoji_id.into(), name.into(), reading.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseEmojiReading::GuildId, DatabaseEmojiReading::EmojiId])
+                .update_columns([DatabaseEmojiReading::Name, DatabaseEmojiReading::Reading])
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the reading of the emoji and returns whether there was one.
+pub async fn delete(database: &PgPool, guild_id: u64, emoji_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseEmojiReading::Table)
+        .and_where(Expr::col(DatabaseEmojiReading::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseEmojiReading::EmojiId).eq(emoji_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<EmojiReading>> {
+    let (sql, values) = Query::select()
+        .columns([
+            DatabaseEmojiReading::EmojiId,
+            DatabaseEmojiReading::Name,
+            DatabaseEmojiReading::Reading,
+        ])
+        .from(DatabaseEmojiReading::Table)
+        .and_where(Expr::col(DatabaseEmojiReading::GuildId).eq(guild_id))
+        .order_by(DatabaseEmojiReading::Name, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, EmojiReading, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn count_by_guild_id(database: &PgPool, guild_id: u64) -> Result<i64> {
+    let (sql, values) = Query::select()
+        .expr(Expr::col(DatabaseEmojiReading::EmojiId).count())
+        .from(DatabaseEmojiReading::Table)
+        .and_where(Expr::col(DatabaseEmojiReading::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
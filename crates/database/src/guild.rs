@@ -0,0 +1,882 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result, anyhow};
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum DatabaseGuild {
+    #[iden = "guilds"]
+    Table,
+    Id,
+    ReadCrossposts,
+    AnnounceJoins,
+    AnnounceActivities,
+    SkipCommands,
+    CommandPrefixes,
+    FlattenMarkdown,
+    CelebrationThreshold,
+    CelebrationSound,
+    Volume,
+    TtsMessages,
+    ReadNames,
+    MaxLength,
+    AnnounceTruncation,
+    ShareTelemetry,
+    ReadingOutput,
+    Furigana,
+    FuriganaChannelId,
+    StripNameDecorations,
+    NamePatterns,
+    Jitter,
+    RandomVoices,
+    ReadTopics,
+    NameInterval,
+    BeepSound,
+    LeaveUtterance,
+    PriorityRoleId,
+    Ducking,
+    DuckingSensitivity,
+    DefaultSpeakerId,
+    ReadAttachments,
+    ReadEmbeds,
+    OmitCode,
+    Spoilers,
+    ReadReplies,
+    ReadThreads,
+    ReadVoiceChat,
+    ReadDelays,
+}
+
+/// Settings of a guild.
+#[derive(Debug, Clone, FromRow)]
+pub struct Guild {
+    pub id: i64,
+    /// Whether to read announcements crossposted from followed channels.
+    pub read_crossposts: bool,
+    /// Whether to announce members joining the voice channel.
+    pub announce_joins: bool,
+    /// Whether to append what the joining member is doing to the announcement.
+    pub announce_activities: bool,
+    /// Whether to skip messages which look like commands of bots.
+    pub skip_commands: bool,
+    /// Whitespace-separated prefixes of commands, or `None` for the default ones.
+    pub command_prefixes: Option<String>,
+    /// Whether to flatten markdown lists and omit tables.
+    pub flatten_markdown: bool,
+    /// Number of reactions on a message in the bound channel to play the celebration sound at, or `None` to disable.
+    pub celebration_threshold: Option<i32>,
+    /// Name of the sound played on celebration, or `None` for the default one.
+    pub celebration_sound: Option<String>,
+    /// Volume of the speech in percent.
+    pub volume: i32,
+    /// How to handle messages sent with the text-to-speech of Discord.
+    pub tts_messages: String,
+    /// Whether to read the name of the author before a message when another member has spoken in between.
+    pub read_names: bool,
+    /// Number of characters to read a message up to, or `None` to read it all.
+    pub max_length: Option<i32>,
+    /// Whether to announce that the rest of a message longer than `max_length` is omitted.
+    pub announce_truncation: bool,
+    /// Whether to include the usage of the guild in the anonymous telemetry sent to the maintainer.
+    pub share_telemetry: bool,
+    /// Where to output the reading of messages.
+    pub reading_output: String,
+    /// Whether to post the kana readings of messages.
+    pub furigana: bool,
+    /// Channel to post the kana readings in, or `None` to reply to the messages with them in spoilers.
+    pub furigana_channel_id: Option<i64>,
+    /// Whether to leave out decorations such as pronoun tags and clan tags when names are read.
+    pub strip_name_decorations: bool,
+    /// Whitespace-separated patterns of the decorations, or `None` for the default ones.
+    pub name_patterns: Option<String>,
+    /// Whether to vary the speed and pitch of each utterance slightly.
+    pub jitter: bool,
+    /// Whether to give members who have chosen no voice one picked from their user id.
+    pub random_voices: bool,
+    /// Whether to read the topic of the text channel on joining and whenever it changes.
+    pub read_topics: bool,
+    /// Seconds within which the name is not read again for consecutive messages by the same author, or 0 to read it
+    /// for every message.
+    pub name_interval: i32,
+    /// Name of the sound played in place of blacklisted words, which are read as "ピー" if none.
+    pub beep_sound: Option<String>,
+    /// Text read before leaving the voice channel with `/leave`, or empty to leave silently.
+    pub leave_utterance: String,
+    /// Role whose members' messages are read before the others waiting in the queue.
+    pub priority_role_id: Option<i64>,
+    /// What to do with the reading while members are talking in the voice channel, which is `off`, `pause` or `lower`.
+    pub ducking: String,
+    /// How soon to duck after members start talking, from 1 to 5.
+    pub ducking_sensitivity: i32,
+    /// Voice of members who have chosen none, when the guild has no voice of the day.
+    pub default_speaker_id: Option<i32>,
+    /// Whether to read what kind of files are attached and the names of files other than images, videos and audio.
+    pub read_attachments: bool,
+    /// Whether to read the titles and descriptions of embeds, including messages of bots with embeds.
+    pub read_embeds: bool,
+    /// Whether to omit code blocks and inline code, or to read the code without the backticks.
+    pub omit_code: bool,
+    /// How to read text hidden as spoilers, one of [`Spoilers`].
+    pub spoilers: String,
+    /// Whether to read whom a message replies to before it.
+    pub read_replies: bool,
+    /// Whether to read messages in threads of the channels read aloud.
+    pub read_threads: bool,
+    /// Whether to read messages in the text chat of the voice channel connected to, even if it is not registered.
+    pub read_voice_chat: bool,
+    /// Whether to read how long ago messages read late, e.g. after a reconnect, were sent before them.
+    pub read_delays: bool,
+}
+
+/// How to handle messages sent with the text-to-speech of Discord, which would be read twice with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtsMessages {
+    /// Leaves them to Discord.
+    Skip,
+    /// Reads them as well as the other messages.
+    Read,
+    /// Reads them and guides to deny the permission to send them.
+    Guide,
+}
+
+/// How to read text hidden as spoilers, which would spoil it for the members listening.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spoilers {
+    /// Reads "ネタバレ" instead.
+    Announce,
+    /// Skips it.
+    Skip,
+    /// Reads it as well as the rest.
+    Read,
+}
+
+/// Where the reading of messages goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingOutput {
+    /// Plays it in the voice channel.
+    Voice,
+    /// Posts the text to be read in reply to the message, for members who cannot hear it.
+    Text,
+    /// Does both.
+    Both,
+}
+
+/// What to do with the reading while members are talking in the voice channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ducking {
+    /// Keeps reading.
+    Off,
+    /// Pauses the reading until they stop talking.
+    Pause,
+    /// Lowers the volume of the reading until they stop talking.
+    Lower,
+}
+
+const COLUMNS: [DatabaseGuild; 38] = [
+    DatabaseGuild::Id,
+    DatabaseGuild::ReadCrossposts,
+    DatabaseGuild::AnnounceJoins,
+    DatabaseGuild::AnnounceActivities,
+    DatabaseGuild::SkipCommands,
+    DatabaseGuild::CommandPrefixes,
+    DatabaseGuild::FlattenMarkdown,
+    DatabaseGuild::CelebrationThreshold,
+    DatabaseGuild::CelebrationSound,
+    DatabaseGuild::Volume,
+    DatabaseGuild::TtsMessages,
+    DatabaseGuild::ReadNames,
+    DatabaseGuild::MaxLength,
+    DatabaseGuild::AnnounceTruncation,
+    DatabaseGuild::ShareTelemetry,
+    DatabaseGuild::ReadingOutput,
+    DatabaseGuild::Furigana,
+    DatabaseGuild::FuriganaChannelId,
+    DatabaseGuild::StripNameDecorations,
+    DatabaseGuild::NamePatterns,
+    DatabaseGuild::Jitter,
+    DatabaseGuild::RandomVoices,
+    DatabaseGuild::ReadTopics,
+    DatabaseGuild::NameInterval,
+    DatabaseGuild::BeepSound,
+    DatabaseGuild::LeaveUtterance,
+    DatabaseGuild::PriorityRoleId,
+    DatabaseGuild::Ducking,
+    DatabaseGuild::DuckingSensitivity,
+    DatabaseGuild::DefaultSpeakerId,
+    DatabaseGuild::ReadAttachments,
+    DatabaseGuild::ReadEmbeds,
+    DatabaseGuild::OmitCode,
+    DatabaseGuild::Spoilers,
+    DatabaseGuild::ReadReplies,
+    DatabaseGuild::ReadThreads,
+    DatabaseGuild::ReadVoiceChat,
+    DatabaseGuild::ReadDelays,
+];
+
+impl Default for Guild {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            read_crossposts: true,
+            announce_joins: false,
+            announce_activities: false,
+            skip_commands: true,
+            command_prefixes: None,
+            flatten_markdown: true,
+            celebration_threshold: None,
+            celebration_sound: None,
+            volume: 100,
+            tts_messages: TtsMessages::Skip.as_str().to_string(),
+            read_names: false,
+            max_length: None,
+            announce_truncation: true,
+            share_telemetry: false,
+            reading_output: ReadingOutput::Voice.as_str().to_string(),
+            furigana: false,
+            furigana_channel_id: None,
+            strip_name_decorations: true,
+            name_patterns: None,
+            jitter: false,
+            random_voices: false,
+            read_topics: false,
+            name_interval: 180,
+            beep_sound: None,
+            leave_utterance: "切断します".to_string(),
+            priority_role_id: None,
+            ducking: Ducking::Off.as_str().to_string(),
+            ducking_sensitivity: 3,
+            default_speaker_id: None,
+            read_attachments: false,
+            read_embeds: false,
+            omit_code: true,
+            spoilers: Spoilers::Announce.as_str().to_string(),
+            read_replies: false,
+            read_threads: false,
+            read_voice_chat: true,
+            read_delays: false,
+        }
+    }
+}
+
+impl Guild {
+    pub fn tts_messages(&self) -> TtsMessages {
+        self.tts_messages.parse().unwrap_or(TtsMessages::Skip)
+    }
+
+    pub fn reading_output(&self) -> ReadingOutput {
+        self.reading_output.parse().unwrap_or(ReadingOutput::Voice)
+    }
+
+    pub fn ducking(&self) -> Ducking {
+        self.ducking.parse().unwrap_or(Ducking::Off)
+    }
+
+    pub fn spoilers(&self) -> Spoilers {
+        self.spoilers.parse().unwrap_or(Spoilers::Announce)
+    }
+}
+
+impl TtsMessages {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Read => "read",
+            Self::Guide => "guide",
+        }
+    }
+}
+
+impl FromStr for TtsMessages {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "skip" => Ok(Self::Skip),
+            "read" => Ok(Self::Read),
+            "guide" => Ok(Self::Guide),
+            _ => Err(anyhow!("unknown handling of tts messages: {value}")),
+        }
+    }
+}
+
+impl ReadingOutput {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Voice => "voice",
+            Self::Text => "text",
+            Self::Both => "both",
+        }
+    }
+
+    pub fn plays_voice(self) -> bool {
+        matches!(self, Self::Voice | Self::Both)
+    }
+
+    pub fn posts_text(self) -> bool {
+        matches!(self, Self::Text | Self::Both)
+    }
+}
+
+impl FromStr for ReadingOutput {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "voice" => Ok(Self::Voice),
+            "text" => Ok(Self::Text),
+            "both" => Ok(Self::Both),
+            _ => Err(anyhow!("unknown reading output: {value}")),
+        }
+    }
+}
+
+impl Ducking {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Pause => "pause",
+            Self::Lower => "lower",
+        }
+    }
+}
+
+impl FromStr for Ducking {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "off" => Ok(Self::Off),
+            "pause" => Ok(Self::Pause),
+            "lower" => Ok(Self::Lower),
+            _ => Err(anyhow!("unknown ducking: {value}")),
+        }
+    }
+}
+
+impl Spoilers {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Announce => "announce",
+            Self::Skip => "skip",
+            Self::Read => "read",
+        }
+    }
+}
+
+impl FromStr for Spoilers {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "announce" => Ok(Self::Announce),
+            "skip" => Ok(Self::Skip),
+            "read" => Ok(Self::Read),
+            _ => Err(anyhow!("unknown handling of spoilers: {value}")),
+        }
+    }
+}
+
+/// Fetches the settings of the guild, falling back to the default when none has been changed.
+pub async fn fetch_by_id(database: &PgPool, guild_id: u64) -> Result<Guild> {
+    let (sql, values) = Query::select()
+        .columns(COLUMNS)
+        .from(DatabaseGuild::Table)
+        .and_where(Expr::col(DatabaseGuild::Id).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let guild = sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(guild.unwrap_or_else(|| Guild {
+        id: guild_id as i64,
+        ..Default::default()
+    }))
+}
+
+/// Overwrites every setting of the guild with `guild`, whose `id` is ignored.
+pub async fn update(database: &PgPool, guild_id: u64, guild: &Guild) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns(COLUMNS)
+        .values_panic([
+            guild_id.into(),
+            guild.read_crossposts.into(),
+            guild.announce_joins.into(),
+            guild.announce_activities.into(),
+            guild.skip_commands.into(),
+            guild.command_prefixes.clone().into(),
+            guild.flatten_markdown.into(),
+            guild.celebration_threshold.into(),
+            guild.celebration_sound.clone().into(),
+            guild.volume.into(),
+            guild.tts_messages.clone().into(),
+            guild.read_names.into(),
+            guild.max_length.into(),
+            guild.announce_truncation.into(),
+            guild.share_telemetry.into(),
+            guild.reading_output.clone().into(),
+            guild.furigana.into(),
+            guild.furigana_channel_id.into(),
+            guild.strip_name_decorations.into(),
+            guild.name_patterns.clone().into(),
+            guild.jitter.into(),
+            guild.random_voices.into(),
+            guild.read_topics.into(),
+            guild.name_interval.into(),
+            guild.beep_sound.clone().into(),
+            guild.leave_utterance.clone().into(),
+            guild.priority_role_id.into(),
+            guild.ducking.clone().into(),
+            guild.ducking_sensitivity.into(),
+            guild.default_speaker_id.into(),
+            guild.read_attachments.into(),
+            guild.read_embeds.into(),
+            guild.omit_code.into(),
+            guild.spoilers.clone().into(),
+            guild.read_replies.into(),
+            guild.read_threads.into(),
+            guild.read_voice_chat.into(),
+            guild.read_delays.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns(COLUMNS.into_iter().skip(1))
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_read_crossposts(database: &PgPool, guild_id: u64, read_crossposts: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadCrossposts, read_crossposts).await
+}
+
+pub async fn update_announce_joins(database: &PgPool, guild_id: u64, announce_joins: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::AnnounceJoins, announce_joins).await
+}
+
+pub async fn update_announce_activities(database: &PgPool, guild_id: u64, announce_activities: bool) -> Result<Guild> {
+    update_flag(
+        database,
+        guild_id,
+        DatabaseGuild::AnnounceActivities,
+        announce_activities,
+    )
+    .await
+}
+
+pub async fn update_flatten_markdown(database: &PgPool, guild_id: u64, flatten_markdown: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::FlattenMarkdown, flatten_markdown).await
+}
+
+pub async fn update_read_names(database: &PgPool, guild_id: u64, read_names: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadNames, read_names).await
+}
+
+pub async fn update_share_telemetry(database: &PgPool, guild_id: u64, share_telemetry: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ShareTelemetry, share_telemetry).await
+}
+
+pub async fn update_jitter(database: &PgPool, guild_id: u64, jitter: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::Jitter, jitter).await
+}
+
+pub async fn update_random_voices(database: &PgPool, guild_id: u64, random_voices: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::RandomVoices, random_voices).await
+}
+
+pub async fn update_read_topics(database: &PgPool, guild_id: u64, read_topics: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadTopics, read_topics).await
+}
+
+pub async fn update_read_attachments(database: &PgPool, guild_id: u64, read_attachments: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadAttachments, read_attachments).await
+}
+
+pub async fn update_read_embeds(database: &PgPool, guild_id: u64, read_embeds: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadEmbeds, read_embeds).await
+}
+
+pub async fn update_omit_code(database: &PgPool, guild_id: u64, omit_code: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::OmitCode, omit_code).await
+}
+
+pub async fn update_read_replies(database: &PgPool, guild_id: u64, read_replies: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadReplies, read_replies).await
+}
+
+pub async fn update_read_threads(database: &PgPool, guild_id: u64, read_threads: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadThreads, read_threads).await
+}
+
+pub async fn update_read_voice_chat(database: &PgPool, guild_id: u64, read_voice_chat: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadVoiceChat, read_voice_chat).await
+}
+
+pub async fn update_read_delays(database: &PgPool, guild_id: u64, read_delays: bool) -> Result<Guild> {
+    update_flag(database, guild_id, DatabaseGuild::ReadDelays, read_delays).await
+}
+
+pub async fn update_command_prefixes(
+    database: &PgPool,
+    guild_id: u64,
+    skip_commands: bool,
+    command_prefixes: Option<&str>,
+) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([
+            DatabaseGuild::Id,
+            DatabaseGuild::SkipCommands,
+            DatabaseGuild::CommandPrefixes,
+        ])
+        .values_panic([guild_id.into(), skip_commands.into(), command_prefixes.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns([DatabaseGuild::SkipCommands, DatabaseGuild::CommandPrefixes])
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_celebration(
+    database: &PgPool,
+    guild_id: u64,
+    celebration_threshold: Option<i32>,
+    celebration_sound: Option<&str>,
+) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([
+            DatabaseGuild::Id,
+            DatabaseGuild::CelebrationThreshold,
+            DatabaseGuild::CelebrationSound,
+        ])
+        .values_panic([guild_id.into(), celebration_threshold.into(), celebration_sound.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns([DatabaseGuild::CelebrationThreshold, DatabaseGuild::CelebrationSound])
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_furigana(
+    database: &PgPool,
+    guild_id: u64,
+    furigana: bool,
+    furigana_channel_id: Option<u64>,
+) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([
+            DatabaseGuild::Id,
+            DatabaseGuild::Furigana,
+            DatabaseGuild::FuriganaChannelId,
+        ])
+        .values_panic([guild_id.into(), furigana.into(), furigana_channel_id.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns([DatabaseGuild::Furigana, DatabaseGuild::FuriganaChannelId])
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_name_decorations(
+    database: &PgPool,
+    guild_id: u64,
+    strip_name_decorations: bool,
+    name_patterns: Option<&str>,
+) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([
+            DatabaseGuild::Id,
+            DatabaseGuild::StripNameDecorations,
+            DatabaseGuild::NamePatterns,
+        ])
+        .values_panic([guild_id.into(), strip_name_decorations.into(), name_patterns.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns([DatabaseGuild::StripNameDecorations, DatabaseGuild::NamePatterns])
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_max_length(
+    database: &PgPool,
+    guild_id: u64,
+    max_length: Option<i32>,
+    announce_truncation: bool,
+) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([
+            DatabaseGuild::Id,
+            DatabaseGuild::MaxLength,
+            DatabaseGuild::AnnounceTruncation,
+        ])
+        .values_panic([guild_id.into(), max_length.into(), announce_truncation.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns([DatabaseGuild::MaxLength, DatabaseGuild::AnnounceTruncation])
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_name_interval(
+    database: &PgPool,
+    guild_id: u64,
+    read_names: bool,
+    name_interval: i32,
+) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::ReadNames, DatabaseGuild::NameInterval])
+        .values_panic([guild_id.into(), read_names.into(), name_interval.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns([DatabaseGuild::ReadNames, DatabaseGuild::NameInterval])
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_beep_sound(database: &PgPool, guild_id: u64, beep_sound: Option<&str>) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::BeepSound])
+        .values_panic([guild_id.into(), beep_sound.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::BeepSound)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_leave_utterance(database: &PgPool, guild_id: u64, leave_utterance: &str) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::LeaveUtterance])
+        .values_panic([guild_id.into(), leave_utterance.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::LeaveUtterance)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_priority_role_id(database: &PgPool, guild_id: u64, role_id: Option<u64>) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::PriorityRoleId])
+        .values_panic([guild_id.into(), role_id.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::PriorityRoleId)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_default_speaker_id(database: &PgPool, guild_id: u64, speaker_id: Option<u16>) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::DefaultSpeakerId])
+        .values_panic([guild_id.into(), speaker_id.map(i32::from).into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::DefaultSpeakerId)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_ducking(database: &PgPool, guild_id: u64, ducking: Ducking, sensitivity: i32) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([
+            DatabaseGuild::Id,
+            DatabaseGuild::Ducking,
+            DatabaseGuild::DuckingSensitivity,
+        ])
+        .values_panic([guild_id.into(), ducking.as_str().into(), sensitivity.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_columns([DatabaseGuild::Ducking, DatabaseGuild::DuckingSensitivity])
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_volume(database: &PgPool, guild_id: u64, volume: i32) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::Volume])
+        .values_panic([guild_id.into(), volume.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::Volume)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_tts_messages(database: &PgPool, guild_id: u64, tts_messages: TtsMessages) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::TtsMessages])
+        .values_panic([guild_id.into(), tts_messages.as_str().into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::TtsMessages)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_spoilers(database: &PgPool, guild_id: u64, spoilers: Spoilers) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::Spoilers])
+        .values_panic([guild_id.into(), spoilers.as_str().into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::Spoilers)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn update_reading_output(database: &PgPool, guild_id: u64, reading_output: ReadingOutput) -> Result<Guild> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, DatabaseGuild::ReadingOutput])
+        .values_panic([guild_id.into(), reading_output.as_str().into()])
+        .on_conflict(
+            OnConflict::column(DatabaseGuild::Id)
+                .update_column(DatabaseGuild::ReadingOutput)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+async fn update_flag(database: &PgPool, guild_id: u64, column: DatabaseGuild, value: bool) -> Result<Guild> {
+    // 他の列は行が無い場合に限りデフォルト値で作られる
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuild::Table)
+        .columns([DatabaseGuild::Id, column])
+        .values_panic([guild_id.into(), value.into()])
+        .on_conflict(OnConflict::column(DatabaseGuild::Id).update_column(column).to_owned())
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Guild, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,81 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Limit of consecutive messages read in each guild, in place of the default one.
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum DatabaseGuildRateLimit {
+    #[iden = "guild_rate_limits"]
+    Table,
+    Id,
+    MaxMessages,
+    TimeWindow,
+    BaseCooldown,
+    MaxCooldown,
+    CooldownMultiplier,
+    ViolationResetTime,
+}
+
+/// Limit of a guild, with durations in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, FromRow)]
+pub struct GuildRateLimit {
+    pub id: i64,
+    pub max_messages: i32,
+    pub time_window: i32,
+    pub base_cooldown: i32,
+    pub max_cooldown: i32,
+    pub cooldown_multiplier: f32,
+    pub violation_reset_time: i32,
+}
+
+const COLUMNS: [DatabaseGuildRateLimit; 6] = [
+    DatabaseGuildRateLimit::MaxMessages,
+    DatabaseGuildRateLimit::TimeWindow,
+    DatabaseGuildRateLimit::BaseCooldown,
+    DatabaseGuildRateLimit::MaxCooldown,
+    DatabaseGuildRateLimit::CooldownMultiplier,
+    DatabaseGuildRateLimit::ViolationResetTime,
+];
+
+pub async fn create(database: &PgPool, rate_limit: GuildRateLimit) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuildRateLimit::Table)
+        .columns([DatabaseGuildRateLimit::Id].into_iter().chain(COLUMNS))
+        .values_panic([
+            rate_limit.id.into(),
+            rate_limit.max_messages.into(),
+            rate_limit.time_window.into(),
+            rate_limit.base_cooldown.into(),
+            rate_limit.max_cooldown.into(),
+            rate_limit.cooldown_multiplier.into(),
+            rate_limit.violation_reset_time.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(DatabaseGuildRateLimit::Id)
+                .update_columns(COLUMNS)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+pub async fn fetch_all(database: &PgPool) -> Result<Vec<GuildRateLimit>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseGuildRateLimit::Id].into_iter().chain(COLUMNS))
+        .from(DatabaseGuildRateLimit::Table)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, GuildRateLimit, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,93 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Users whose messages moderators excluded from reading aloud in each guild.
+#[derive(Iden)]
+pub(crate) enum DatabaseGuildUserMute {
+    #[iden = "guild_user_mutes"]
+    Table,
+    GuildId,
+    UserId,
+    MutedBy,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct GuildUserMute {
+    pub user_id: i64,
+    /// Moderator who muted the user, which is unknown for mutes made before it was recorded.
+    pub muted_by: Option<i64>,
+}
+
+pub async fn create(database: &PgPool, guild_id: u64, user_id: u64, muted_by: u64) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuildUserMute::Table)
+        .columns([
+            DatabaseGuildUserMute::GuildId,
+            DatabaseGuildUserMute::UserId,
+            DatabaseGuildUserMute::MutedBy,
+        ])
+        .values_panic([guild_id.into(), user_id.into(), muted_by.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseGuildUserMute::GuildId, DatabaseGuildUserMute::UserId])
+                .update_column(DatabaseGuildUserMute::MutedBy)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the mute of the user in the guild and returns whether the user was muted.
+pub async fn delete(database: &PgPool, guild_id: u64, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseGuildUserMute::Table)
+        .and_where(Expr::col(DatabaseGuildUserMute::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseGuildUserMute::UserId).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn is_muted(database: &PgPool, guild_id: u64, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::select()
+        .column(DatabaseGuildUserMute::UserId)
+        .from(DatabaseGuildUserMute::Table)
+        .and_where(Expr::col(DatabaseGuildUserMute::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseGuildUserMute::UserId).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let muted = sqlx::query_scalar_with::<_, i64, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(muted.is_some())
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<GuildUserMute>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseGuildUserMute::UserId, DatabaseGuildUserMute::MutedBy])
+        .from(DatabaseGuildUserMute::Table)
+        .and_where(Expr::col(DatabaseGuildUserMute::GuildId).eq(guild_id))
+        .order_by(DatabaseGuildUserMute::UserId, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, GuildUserMute, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,75 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Voice that messages containing each keyword are always read with.
+#[derive(Iden)]
+pub(crate) enum DatabaseKeywordVoice {
+    #[iden = "keyword_voices"]
+    Table,
+    GuildId,
+    Keyword,
+    SpeakerId,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct KeywordVoice {
+    pub keyword: String,
+    pub speaker_id: i32,
+}
+
+pub async fn create(database: &PgPool, guild_id: u64, keyword: &str, speaker_id: u16) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseKeywordVoice::Table)
+        .columns([
+            DatabaseKeywordVoice::GuildId,
+            DatabaseKeywordVoice::Keyword,
+            DatabaseKeywordVoice::SpeakerId,
+        ])
+        .values_panic([guild_id.into(), keyword.into(), speaker_id.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseKeywordVoice::GuildId, DatabaseKeywordVoice::Keyword])
+                .update_column(DatabaseKeywordVoice::SpeakerId)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the voice of the keyword and returns whether there was one.
+pub async fn delete(database: &PgPool, guild_id: u64, keyword: &str) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseKeywordVoice::Table)
+        .and_where(Expr::col(DatabaseKeywordVoice::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseKeywordVoice::Keyword).eq(keyword))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<KeywordVoice>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseKeywordVoice::Keyword, DatabaseKeywordVoice::SpeakerId])
+        .from(DatabaseKeywordVoice::Table)
+        .and_where(Expr::col(DatabaseKeywordVoice::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, KeywordVoice, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,99 @@
+use anyhow::{Error, Result};
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Voice channel each guild was last read aloud in, so that the bot can return to it.
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum DatabaseLastConnection {
+    #[iden = "last_connections"]
+    Table,
+    GuildId,
+    VoiceChannelId,
+    TextChannelId,
+    Connected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct LastConnection {
+    pub guild_id: i64,
+    pub voice_channel_id: i64,
+    pub text_channel_id: i64,
+    /// Whether the bot is still supposed to be in the voice channel, rather than having left it.
+    pub connected: bool,
+}
+
+const COLUMNS: [DatabaseLastConnection; 4] = [
+    DatabaseLastConnection::GuildId,
+    DatabaseLastConnection::VoiceChannelId,
+    DatabaseLastConnection::TextChannelId,
+    DatabaseLastConnection::Connected,
+];
+
+pub async fn create(database: &PgPool, guild_id: u64, voice_channel_id: u64, text_channel_id: u64) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseLastConnection::Table)
+        .columns(COLUMNS)
+        .values_panic([
+            guild_id.into(),
+            voice_channel_id.into(),
+            text_channel_id.into(),
+            true.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(DatabaseLastConnection::GuildId)
+                .update_columns(COLUMNS.into_iter().skip(1))
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Records that the bot has left the voice channel, keeping it to return to.
+pub async fn disconnect(database: &PgPool, guild_id: u64) -> Result<()> {
+    let (sql, values) = Query::update()
+        .table(DatabaseLastConnection::Table)
+        .value(DatabaseLastConnection::Connected, false)
+        .and_where(Expr::col(DatabaseLastConnection::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Option<LastConnection>> {
+    let (sql, values) = Query::select()
+        .columns(COLUMNS)
+        .from(DatabaseLastConnection::Table)
+        .and_where(Expr::col(DatabaseLastConnection::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, LastConnection, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+/// Connections the bot has not left, which were cut off by a restart or a reconnection to the gateway.
+pub async fn fetch_connected(database: &PgPool) -> Result<Vec<LastConnection>> {
+    let (sql, values) = Query::select()
+        .columns(COLUMNS)
+        .from(DatabaseLastConnection::Table)
+        .and_where(Expr::col(DatabaseLastConnection::Connected).eq(true))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, LastConnection, _>(&sql, values)
+        .fetch_all(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
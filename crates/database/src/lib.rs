@@ -3,9 +3,29 @@ pub use sqlx::{
     postgres::{PgConnectOptions, PgPoolOptions},
 };
 
+pub mod auto_join;
+pub mod blacklisted_word;
+pub mod channel_voice;
+pub mod dictionary_word;
+pub mod emoji_reading;
+pub mod guild;
+pub mod guild_rate_limit;
+pub mod guild_user_mute;
+pub mod keyword_voice;
+pub mod last_connection;
 pub mod migrations;
+pub mod read_channel;
+pub mod role_voice;
+pub mod scheduled_announcement;
 pub mod sound;
 pub mod soundsticker;
 pub mod speaker;
 pub mod sticker;
+pub mod style_shortcut;
+pub mod usage_statistic;
 pub mod user;
+pub mod user_morph;
+pub mod user_mute;
+pub mod user_speed;
+pub mod user_tone;
+pub mod voice_rotation;
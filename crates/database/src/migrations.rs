@@ -1,12 +1,66 @@
 use std::ops::Deref;
 
-use sqlx::Postgres;
-use sqlx_migrator::{Info, migrator, vec_box};
+use anyhow::{Context as _, Result};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{Info, Migrate, Plan, migrator, vec_box};
 
 pub use sqlx_migrator::MigrationCommand;
 
+pub mod v10_guild_markdown;
+pub mod v11_user_tones;
+pub mod v12_guild_celebrations;
+pub mod v13_guild_volumes;
+pub mod v14_readings;
+pub mod v15_auto_joins;
+pub mod v16_user_mutes;
+pub mod v17_guild_user_mutes;
+pub mod v18_read_channels;
+pub mod v19_guild_tts_messages;
 pub mod v1_users_and_speakers;
+pub mod v20_guild_read_names;
+pub mod v21_guild_max_lengths;
+pub mod v22_guild_user_voices;
+pub mod v23_guild_user_mute_moderators;
+pub mod v24_uploaded_sounds;
+pub mod v25_usage_statistics;
+pub mod v26_guild_share_telemetry;
+pub mod v27_guild_reading_outputs;
+pub mod v28_guild_furigana;
+pub mod v29_guild_name_decorations;
 pub mod v2_soundstickers;
+pub mod v30_guild_jitter;
+pub mod v31_user_morphs;
+pub mod v32_emoji_readings;
+pub mod v33_guild_random_voices;
+pub mod v34_guild_rate_limits;
+pub mod v35_guild_read_topics;
+pub mod v36_guild_name_intervals;
+pub mod v37_blacklisted_words;
+pub mod v38_guild_beep_sounds;
+pub mod v39_role_voices;
+pub mod v3_guilds;
+pub mod v40_guild_leave_utterances;
+pub mod v41_last_connections;
+pub mod v42_guild_priority_roles;
+pub mod v43_guild_ducking;
+pub mod v44_style_shortcuts;
+pub mod v45_scheduled_announcements;
+pub mod v46_guild_default_speakers;
+pub mod v47_guild_read_attachments;
+pub mod v48_guild_read_embeds;
+pub mod v49_guild_omit_code;
+pub mod v4_dictionary_words;
+pub mod v50_guild_spoilers;
+pub mod v51_guild_read_replies;
+pub mod v52_guild_read_threads;
+pub mod v53_guild_read_voice_chats;
+pub mod v54_guild_read_delays;
+pub mod v55_dictionary_readings;
+pub mod v5_guild_announcements;
+pub mod v6_voice_rotations;
+pub mod v7_voice_overrides;
+pub mod v8_guild_command_prefixes;
+pub mod v9_user_speeds;
 
 pub struct Migrator {
     inner: migrator::Migrator<Postgres>,
@@ -33,12 +87,73 @@ impl Migrator {
             .add_migrations(vec_box!(
                 v1_users_and_speakers::V1Migration,
                 v2_soundstickers::V2Migration,
+                v3_guilds::V3Migration,
+                v4_dictionary_words::V4Migration,
+                v5_guild_announcements::V5Migration,
+                v6_voice_rotations::V6Migration,
+                v7_voice_overrides::V7Migration,
+                v8_guild_command_prefixes::V8Migration,
+                v9_user_speeds::V9Migration,
+                v10_guild_markdown::V10Migration,
+                v11_user_tones::V11Migration,
+                v12_guild_celebrations::V12Migration,
+                v13_guild_volumes::V13Migration,
+                v14_readings::V14Migration,
+                v15_auto_joins::V15Migration,
+                v16_user_mutes::V16Migration,
+                v17_guild_user_mutes::V17Migration,
+                v18_read_channels::V18Migration,
+                v19_guild_tts_messages::V19Migration,
+                v20_guild_read_names::V20Migration,
+                v21_guild_max_lengths::V21Migration,
+                v22_guild_user_voices::V22Migration,
+                v23_guild_user_mute_moderators::V23Migration,
+                v24_uploaded_sounds::V24Migration,
+                v25_usage_statistics::V25Migration,
+                v26_guild_share_telemetry::V26Migration,
+                v27_guild_reading_outputs::V27Migration,
+                v28_guild_furigana::V28Migration,
+                v29_guild_name_decorations::V29Migration,
+                v30_guild_jitter::V30Migration,
+                v31_user_morphs::V31Migration,
+                v32_emoji_readings::V32Migration,
+                v33_guild_random_voices::V33Migration,
+                v34_guild_rate_limits::V34Migration,
+                v35_guild_read_topics::V35Migration,
+                v36_guild_name_intervals::V36Migration,
+                v37_blacklisted_words::V37Migration,
+                v38_guild_beep_sounds::V38Migration,
+                v39_role_voices::V39Migration,
+                v40_guild_leave_utterances::V40Migration,
+                v41_last_connections::V41Migration,
+                v42_guild_priority_roles::V42Migration,
+                v43_guild_ducking::V43Migration,
+                v44_style_shortcuts::V44Migration,
+                v45_scheduled_announcements::V45Migration,
+                v46_guild_default_speakers::V46Migration,
+                v47_guild_read_attachments::V47Migration,
+                v48_guild_read_embeds::V48Migration,
+                v49_guild_omit_code::V49Migration,
+                v50_guild_spoilers::V50Migration,
+                v51_guild_read_replies::V51Migration,
+                v52_guild_read_threads::V52Migration,
+                v53_guild_read_voice_chats::V53Migration,
+                v54_guild_read_delays::V54Migration,
+                v55_dictionary_readings::V55Migration,
             ))
             .expect("failed to add migrations to migrator");
 
         Self { inner: migrator }
     }
 
+    /// Applies the migrations which have not been applied yet, which are embedded in the binary.
+    pub async fn apply_all(&self, connection: &mut PgConnection) -> Result<()> {
+        self.inner
+            .run(connection, &Plan::apply_all())
+            .await
+            .context("failed to apply migrations")
+    }
+
     pub fn into_boxed_inner(self) -> Box<migrator::Migrator<Postgres>> {
         Box::new(self.inner)
     }
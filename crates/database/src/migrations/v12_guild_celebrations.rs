@@ -0,0 +1,65 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::guild::DatabaseGuild;
+
+pub(crate) struct AddColumnOperation;
+
+pub(crate) struct V12Migration;
+
+impl Operation<Postgres> for AddColumnOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::alter()
+                .table(DatabaseGuild::Table)
+                .add_column_if_not_exists(ColumnDef::new(DatabaseGuild::CelebrationThreshold).integer())
+                .add_column_if_not_exists(ColumnDef::new(DatabaseGuild::CelebrationSound).text())
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::alter()
+                .table(DatabaseGuild::Table)
+                .drop_column(DatabaseGuild::CelebrationThreshold)
+                .drop_column(DatabaseGuild::CelebrationSound)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V12Migration,
+    "seitai",
+    "add celebration settings to guilds",
+    vec_box![],
+    vec_box![AddColumnOperation,]
+);
@@ -0,0 +1,76 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Iden, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+/// Reading of each word in messages of a guild, which has been moved into the dictionary words since v55.
+#[derive(Iden)]
+pub(crate) enum DatabaseReading {
+    #[iden = "readings"]
+    Table,
+    GuildId,
+    Surface,
+    Reading,
+}
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V14Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseReading::Table)
+                .col(ColumnDef::new(DatabaseReading::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseReading::Surface).text().not_null())
+                .col(ColumnDef::new(DatabaseReading::Reading).text().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseReading::GuildId)
+                        .col(DatabaseReading::Surface),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop().table(DatabaseReading::Table).build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V14Migration,
+    "seitai",
+    "create readings",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
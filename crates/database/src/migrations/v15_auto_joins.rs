@@ -0,0 +1,114 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::auto_join::DatabaseAutoJoin;
+
+pub(crate) struct CreateTableOperation;
+pub(crate) struct CreateIndexOperation;
+
+pub(crate) struct V15Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseAutoJoin::Table)
+                .col(
+                    ColumnDef::new(DatabaseAutoJoin::VoiceChannelId)
+                        .big_integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(DatabaseAutoJoin::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseAutoJoin::TextChannelId).big_integer().not_null())
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop().table(DatabaseAutoJoin::Table).build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+impl Operation<Postgres> for CreateIndexOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Index::create()
+                .if_not_exists()
+                .name("auto_joins_guild_id_idx")
+                .table(DatabaseAutoJoin::Table)
+                .col(DatabaseAutoJoin::GuildId)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Index::drop()
+                .name("auto_joins_guild_id_idx")
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V15Migration,
+    "seitai",
+    "create auto joins",
+    vec_box![],
+    vec_box![CreateTableOperation, CreateIndexOperation,]
+);
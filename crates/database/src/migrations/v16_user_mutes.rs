@@ -0,0 +1,66 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::user_mute::DatabaseUserMute;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V16Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseUserMute::Table)
+                .col(
+                    ColumnDef::new(DatabaseUserMute::Id)
+                        .big_integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop().table(DatabaseUserMute::Table).build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V16Migration,
+    "seitai",
+    "create user mutes",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
@@ -0,0 +1,69 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::guild_user_mute::DatabaseGuildUserMute;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V17Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseGuildUserMute::Table)
+                .col(ColumnDef::new(DatabaseGuildUserMute::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseGuildUserMute::UserId).big_integer().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseGuildUserMute::GuildId)
+                        .col(DatabaseGuildUserMute::UserId),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseGuildUserMute::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V17Migration,
+    "seitai",
+    "create guild user mutes",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
@@ -0,0 +1,68 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::guild::DatabaseGuild;
+
+pub(crate) struct AddColumnOperation;
+
+pub(crate) struct V20Migration;
+
+impl Operation<Postgres> for AddColumnOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::alter()
+                .table(DatabaseGuild::Table)
+                .add_column_if_not_exists(
+                    ColumnDef::new(DatabaseGuild::ReadNames)
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::alter()
+                .table(DatabaseGuild::Table)
+                .drop_column(DatabaseGuild::ReadNames)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V20Migration,
+    "seitai",
+    "add setting of reading names to guilds",
+    vec_box![],
+    vec_box![AddColumnOperation,]
+);
@@ -0,0 +1,72 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::sound::DatabaseUploadedSound;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V24Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseUploadedSound::Table)
+                .col(
+                    ColumnDef::new(DatabaseUploadedSound::Name)
+                        .text()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(DatabaseUploadedSound::GuildId).big_integer())
+                .col(ColumnDef::new(DatabaseUploadedSound::UserId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseUploadedSound::Size).integer().not_null())
+                .col(ColumnDef::new(DatabaseUploadedSound::DurationMs).integer().not_null())
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseUploadedSound::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V24Migration,
+    "seitai",
+    "create uploaded sounds",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
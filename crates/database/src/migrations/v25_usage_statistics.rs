@@ -0,0 +1,96 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Expr, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::usage_statistic::DatabaseUsageStatistic;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V25Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseUsageStatistic::Table)
+                .col(ColumnDef::new(DatabaseUsageStatistic::GuildId).big_integer().not_null())
+                .col(
+                    ColumnDef::new(DatabaseUsageStatistic::Day)
+                        .date()
+                        .not_null()
+                        .default(Expr::current_date()),
+                )
+                .col(ColumnDef::new(DatabaseUsageStatistic::SpeakerId).integer().not_null())
+                .col(
+                    ColumnDef::new(DatabaseUsageStatistic::Messages)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(DatabaseUsageStatistic::Characters)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(DatabaseUsageStatistic::Syntheses)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(DatabaseUsageStatistic::CacheHits)
+                        .big_integer()
+                        .not_null(),
+                )
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseUsageStatistic::GuildId)
+                        .col(DatabaseUsageStatistic::Day)
+                        .col(DatabaseUsageStatistic::SpeakerId),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseUsageStatistic::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V25Migration,
+    "seitai",
+    "create usage statistics",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
@@ -0,0 +1,71 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::emoji_reading::DatabaseEmojiReading;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V32Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseEmojiReading::Table)
+                .col(ColumnDef::new(DatabaseEmojiReading::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseEmojiReading::EmojiId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseEmojiReading::Name).text().not_null())
+                .col(ColumnDef::new(DatabaseEmojiReading::Reading).text().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseEmojiReading::GuildId)
+                        .col(DatabaseEmojiReading::EmojiId),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseEmojiReading::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V32Migration,
+    "seitai",
+    "create emoji readings",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
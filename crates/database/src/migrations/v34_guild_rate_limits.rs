@@ -0,0 +1,86 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::guild_rate_limit::DatabaseGuildRateLimit;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V34Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseGuildRateLimit::Table)
+                .col(
+                    ColumnDef::new(DatabaseGuildRateLimit::Id)
+                        .big_integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(DatabaseGuildRateLimit::MaxMessages).integer().not_null())
+                .col(ColumnDef::new(DatabaseGuildRateLimit::TimeWindow).integer().not_null())
+                .col(
+                    ColumnDef::new(DatabaseGuildRateLimit::BaseCooldown)
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(DatabaseGuildRateLimit::MaxCooldown).integer().not_null())
+                .col(
+                    ColumnDef::new(DatabaseGuildRateLimit::CooldownMultiplier)
+                        .float()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(DatabaseGuildRateLimit::ViolationResetTime)
+                        .integer()
+                        .not_null(),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseGuildRateLimit::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V34Migration,
+    "seitai",
+    "create guild rate limits",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
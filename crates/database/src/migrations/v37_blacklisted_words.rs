@@ -0,0 +1,73 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::blacklisted_word::DatabaseBlacklistedWord;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V37Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseBlacklistedWord::Table)
+                .col(
+                    ColumnDef::new(DatabaseBlacklistedWord::GuildId)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(DatabaseBlacklistedWord::Word).text().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseBlacklistedWord::GuildId)
+                        .col(DatabaseBlacklistedWord::Word),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseBlacklistedWord::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V37Migration,
+    "seitai",
+    "create blacklisted words",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
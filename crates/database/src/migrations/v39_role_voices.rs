@@ -0,0 +1,70 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::role_voice::DatabaseRoleVoice;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V39Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseRoleVoice::Table)
+                .col(ColumnDef::new(DatabaseRoleVoice::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseRoleVoice::RoleId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseRoleVoice::SpeakerId).integer().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseRoleVoice::GuildId)
+                        .col(DatabaseRoleVoice::RoleId),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseRoleVoice::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V39Migration,
+    "seitai",
+    "create role voices",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
@@ -0,0 +1,70 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::style_shortcut::DatabaseStyleShortcut;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V44Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseStyleShortcut::Table)
+                .col(ColumnDef::new(DatabaseStyleShortcut::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseStyleShortcut::Prefix).text().not_null())
+                .col(ColumnDef::new(DatabaseStyleShortcut::Style).text().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseStyleShortcut::GuildId)
+                        .col(DatabaseStyleShortcut::Prefix),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseStyleShortcut::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V44Migration,
+    "seitai",
+    "create style shortcuts",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
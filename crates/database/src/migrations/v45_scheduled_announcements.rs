@@ -0,0 +1,94 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::scheduled_announcement::DatabaseScheduledAnnouncement;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V45Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseScheduledAnnouncement::Table)
+                .col(
+                    ColumnDef::new(DatabaseScheduledAnnouncement::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(DatabaseScheduledAnnouncement::GuildId)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(DatabaseScheduledAnnouncement::Schedule)
+                        .text()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(DatabaseScheduledAnnouncement::Text).text().not_null())
+                .col(
+                    ColumnDef::new(DatabaseScheduledAnnouncement::CreatedBy)
+                        .big_integer()
+                        .not_null(),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            let sql = Index::create()
+                .if_not_exists()
+                .name("scheduled_announcements_guild_id_idx")
+                .table(DatabaseScheduledAnnouncement::Table)
+                .col(DatabaseScheduledAnnouncement::GuildId)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseScheduledAnnouncement::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V45Migration,
+    "seitai",
+    "create scheduled announcements",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
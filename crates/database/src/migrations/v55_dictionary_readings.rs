@@ -0,0 +1,214 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Expr, Index, PostgresQueryBuilder, Query, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use super::v14_readings::DatabaseReading;
+use crate::dictionary_word::DatabaseDictionaryWord;
+
+pub(crate) struct AddColumnOperation;
+pub(crate) struct CreateIndexOperation;
+pub(crate) struct MoveReadingsOperation;
+
+pub(crate) struct V55Migration;
+
+impl Operation<Postgres> for AddColumnOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::alter()
+                .table(DatabaseDictionaryWord::Table)
+                .add_column_if_not_exists(ColumnDef::new(DatabaseDictionaryWord::Surface).text())
+                .add_column_if_not_exists(ColumnDef::new(DatabaseDictionaryWord::Reading).text())
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::alter()
+                .table(DatabaseDictionaryWord::Table)
+                .drop_column(DatabaseDictionaryWord::Surface)
+                .drop_column(DatabaseDictionaryWord::Reading)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+impl Operation<Postgres> for CreateIndexOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            // 単語の ID で登録した語は surface が NULL なので重ならない
+            let sql = Index::create()
+                .if_not_exists()
+                .name("dictionary_words_guild_id_surface_idx")
+                .table(DatabaseDictionaryWord::Table)
+                .col(DatabaseDictionaryWord::GuildId)
+                .col(DatabaseDictionaryWord::Surface)
+                .unique()
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Index::drop()
+                .name("dictionary_words_guild_id_surface_idx")
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+impl Operation<Postgres> for MoveReadingsOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Query::insert()
+                .into_table(DatabaseDictionaryWord::Table)
+                .columns([
+                    DatabaseDictionaryWord::Id,
+                    DatabaseDictionaryWord::GuildId,
+                    DatabaseDictionaryWord::Surface,
+                    DatabaseDictionaryWord::Reading,
+                ])
+                .select_from(
+                    Query::select()
+                        .expr(Expr::cust("gen_random_uuid()"))
+                        .columns([
+                            DatabaseReading::GuildId,
+                            DatabaseReading::Surface,
+                            DatabaseReading::Reading,
+                        ])
+                        .from(DatabaseReading::Table)
+                        .to_owned(),
+                )
+                .expect("readings should have as many columns as selected")
+                .to_string(PostgresQueryBuilder);
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            let sql = Table::drop().table(DatabaseReading::Table).build(PostgresQueryBuilder);
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseReading::Table)
+                .col(ColumnDef::new(DatabaseReading::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseReading::Surface).text().not_null())
+                .col(ColumnDef::new(DatabaseReading::Reading).text().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseReading::GuildId)
+                        .col(DatabaseReading::Surface),
+                )
+                .build(PostgresQueryBuilder);
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            let sql = Query::insert()
+                .into_table(DatabaseReading::Table)
+                .columns([
+                    DatabaseReading::GuildId,
+                    DatabaseReading::Surface,
+                    DatabaseReading::Reading,
+                ])
+                .select_from(
+                    Query::select()
+                        .columns([
+                            DatabaseDictionaryWord::GuildId,
+                            DatabaseDictionaryWord::Surface,
+                            DatabaseDictionaryWord::Reading,
+                        ])
+                        .from(DatabaseDictionaryWord::Table)
+                        .and_where(Expr::col(DatabaseDictionaryWord::Reading).is_not_null())
+                        .to_owned(),
+                )
+                .expect("dictionary words should have as many columns as selected")
+                .to_string(PostgresQueryBuilder);
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            let sql = Query::delete()
+                .from_table(DatabaseDictionaryWord::Table)
+                .and_where(Expr::col(DatabaseDictionaryWord::Reading).is_not_null())
+                .to_string(PostgresQueryBuilder);
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V55Migration,
+    "seitai",
+    "move readings into dictionary words",
+    vec_box![],
+    vec_box![AddColumnOperation, CreateIndexOperation, MoveReadingsOperation,]
+);
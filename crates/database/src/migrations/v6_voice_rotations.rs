@@ -0,0 +1,69 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::voice_rotation::DatabaseVoiceRotation;
+
+pub(crate) struct CreateTableOperation;
+
+pub(crate) struct V6Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseVoiceRotation::Table)
+                .col(ColumnDef::new(DatabaseVoiceRotation::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseVoiceRotation::SpeakerId).integer().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseVoiceRotation::GuildId)
+                        .col(DatabaseVoiceRotation::SpeakerId),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseVoiceRotation::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V6Migration,
+    "seitai",
+    "create voice rotations",
+    vec_box![],
+    vec_box![CreateTableOperation,]
+);
@@ -0,0 +1,137 @@
+use futures::future::BoxFuture;
+use sea_query::{ColumnDef, Index, PostgresQueryBuilder, Table};
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::{operation::Operation, vec_box};
+
+use crate::{channel_voice::DatabaseChannelVoice, keyword_voice::DatabaseKeywordVoice};
+
+pub(crate) struct CreateTableOperation;
+pub(crate) struct CreateIndexOperation;
+
+pub(crate) struct V7Migration;
+
+impl Operation<Postgres> for CreateTableOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseChannelVoice::Table)
+                .col(
+                    ColumnDef::new(DatabaseChannelVoice::ChannelId)
+                        .big_integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(DatabaseChannelVoice::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseChannelVoice::SpeakerId).integer().not_null())
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            let sql = Table::create()
+                .if_not_exists()
+                .table(DatabaseKeywordVoice::Table)
+                .col(ColumnDef::new(DatabaseKeywordVoice::GuildId).big_integer().not_null())
+                .col(ColumnDef::new(DatabaseKeywordVoice::Keyword).text().not_null())
+                .col(ColumnDef::new(DatabaseKeywordVoice::SpeakerId).integer().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(DatabaseKeywordVoice::GuildId)
+                        .col(DatabaseKeywordVoice::Keyword),
+                )
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Table::drop()
+                .table(DatabaseKeywordVoice::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            let sql = Table::drop()
+                .table(DatabaseChannelVoice::Table)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+impl Operation<Postgres> for CreateIndexOperation {
+    fn up<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Index::create()
+                .if_not_exists()
+                .name("channel_voices_guild_id_idx")
+                .table(DatabaseChannelVoice::Table)
+                .col(DatabaseChannelVoice::GuildId)
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+
+    fn down<'a, 'b, 'async_trait>(
+        &'a self,
+        connection: &'b mut PgConnection,
+    ) -> BoxFuture<'async_trait, Result<(), sqlx_migrator::error::Error>>
+    where
+        Self: 'async_trait,
+        'a: 'async_trait,
+        'b: 'async_trait,
+    {
+        Box::pin(async {
+            let sql = Index::drop()
+                .name("channel_voices_guild_id_idx")
+                .build(PostgresQueryBuilder);
+
+            sqlx::query(&sql).execute(&mut *connection).await?;
+
+            Ok(())
+        })
+    }
+}
+
+sqlx_migrator::migration!(
+    sqlx::Postgres,
+    V7Migration,
+    "seitai",
+    "create voice overrides",
+    vec_box![],
+    vec_box![CreateTableOperation, CreateIndexOperation,]
+);
@@ -0,0 +1,64 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::PgPool;
+
+/// Text channels each guild allows to be read aloud, instead of the channel where `/join` was used.
+#[derive(Iden)]
+pub(crate) enum DatabaseReadChannel {
+    #[iden = "read_channels"]
+    Table,
+    ChannelId,
+    GuildId,
+}
+
+pub async fn create(database: &PgPool, channel_id: u64, guild_id: u64) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseReadChannel::Table)
+        .columns([DatabaseReadChannel::ChannelId, DatabaseReadChannel::GuildId])
+        .values_panic([channel_id.into(), guild_id.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseReadChannel::ChannelId)
+                .do_nothing()
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the channel from the allowlist and returns whether it was there.
+pub async fn delete_by_channel_id(database: &PgPool, channel_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseReadChannel::Table)
+        .and_where(Expr::col(DatabaseReadChannel::ChannelId).eq(channel_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<i64>> {
+    let (sql, values) = Query::select()
+        .column(DatabaseReadChannel::ChannelId)
+        .from(DatabaseReadChannel::Table)
+        .and_where(Expr::col(DatabaseReadChannel::GuildId).eq(guild_id))
+        .order_by(DatabaseReadChannel::ChannelId, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with::<_, i64, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
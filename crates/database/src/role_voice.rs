@@ -0,0 +1,75 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Voice members with each role are read with unless they choose one.
+#[derive(Iden)]
+pub(crate) enum DatabaseRoleVoice {
+    #[iden = "role_voices"]
+    Table,
+    GuildId,
+    RoleId,
+    SpeakerId,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct RoleVoice {
+    pub role_id: i64,
+    pub speaker_id: i32,
+}
+
+pub async fn create(database: &PgPool, guild_id: u64, role_id: u64, speaker_id: u16) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseRoleVoice::Table)
+        .columns([
+            DatabaseRoleVoice::GuildId,
+            DatabaseRoleVoice::RoleId,
+            DatabaseRoleVoice::SpeakerId,
+        ])
+        .values_panic([guild_id.into(), role_id.into(), speaker_id.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseRoleVoice::GuildId, DatabaseRoleVoice::RoleId])
+                .update_column(DatabaseRoleVoice::SpeakerId)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the voice of the role and returns whether there was one.
+pub async fn delete(database: &PgPool, guild_id: u64, role_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseRoleVoice::Table)
+        .and_where(Expr::col(DatabaseRoleVoice::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseRoleVoice::RoleId).eq(role_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<RoleVoice>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseRoleVoice::RoleId, DatabaseRoleVoice::SpeakerId])
+        .from(DatabaseRoleVoice::Table)
+        .and_where(Expr::col(DatabaseRoleVoice::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, RoleVoice, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,116 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Text each guild reads in the voice channel on the schedule written in the syntax of cron.
+#[derive(Iden)]
+pub(crate) enum DatabaseScheduledAnnouncement {
+    #[iden = "scheduled_announcements"]
+    Table,
+    Id,
+    GuildId,
+    Schedule,
+    Text,
+    CreatedBy,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ScheduledAnnouncement {
+    pub id: i32,
+    pub guild_id: i64,
+    pub schedule: String,
+    pub text: String,
+    pub created_by: i64,
+}
+
+const COLUMNS: [DatabaseScheduledAnnouncement; 5] = [
+    DatabaseScheduledAnnouncement::Id,
+    DatabaseScheduledAnnouncement::GuildId,
+    DatabaseScheduledAnnouncement::Schedule,
+    DatabaseScheduledAnnouncement::Text,
+    DatabaseScheduledAnnouncement::CreatedBy,
+];
+
+pub async fn create(
+    database: &PgPool,
+    guild_id: u64,
+    schedule: &str,
+    text: &str,
+    created_by: u64,
+) -> Result<ScheduledAnnouncement> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseScheduledAnnouncement::Table)
+        .columns([
+            DatabaseScheduledAnnouncement::GuildId,
+            DatabaseScheduledAnnouncement::Schedule,
+            DatabaseScheduledAnnouncement::Text,
+            DatabaseScheduledAnnouncement::CreatedBy,
+        ])
+        .values_panic([guild_id.into(), schedule.into(), text.into(), created_by.into()])
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, ScheduledAnnouncement, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+/// Deletes the announcement of the guild and returns whether there was one.
+pub async fn delete(database: &PgPool, guild_id: u64, id: i32) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseScheduledAnnouncement::Table)
+        .and_where(Expr::col(DatabaseScheduledAnnouncement::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseScheduledAnnouncement::Id).eq(id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<ScheduledAnnouncement>> {
+    let (sql, values) = Query::select()
+        .columns(COLUMNS)
+        .from(DatabaseScheduledAnnouncement::Table)
+        .and_where(Expr::col(DatabaseScheduledAnnouncement::GuildId).eq(guild_id))
+        .order_by(DatabaseScheduledAnnouncement::Id, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, ScheduledAnnouncement, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn fetch_all(database: &PgPool) -> Result<Vec<ScheduledAnnouncement>> {
+    let (sql, values) = Query::select()
+        .columns(COLUMNS)
+        .from(DatabaseScheduledAnnouncement::Table)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, ScheduledAnnouncement, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+pub async fn count_by_guild_id(database: &PgPool, guild_id: u64) -> Result<i64> {
+    let (sql, values) = Query::select()
+        .expr(Expr::col(DatabaseScheduledAnnouncement::Id).count())
+        .from(DatabaseScheduledAnnouncement::Table)
+        .and_where(Expr::col(DatabaseScheduledAnnouncement::GuildId).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
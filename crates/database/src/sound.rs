@@ -1,5 +1,8 @@
-use sea_query::Iden;
-use sqlx::prelude::FromRow;
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{PgPool, prelude::FromRow};
 use uuid::Uuid;
 
 #[derive(Iden)]
@@ -12,6 +15,18 @@ pub(crate) enum DatabaseSound {
     GuildId,
 }
 
+/// Sounds uploaded with `/sound add`, kept apart from the sounds of Discord's soundboard.
+#[derive(Iden)]
+pub(crate) enum DatabaseUploadedSound {
+    #[iden = "uploaded_sounds"]
+    Table,
+    Name,
+    GuildId,
+    UserId,
+    Size,
+    DurationMs,
+}
+
 #[derive(Debug, Default, FromRow)]
 pub(crate) struct DatabaseSoundRow {
     pub(crate) id: Uuid,
@@ -38,3 +53,82 @@ impl From<DatabaseSoundRow> for Sound {
         }
     }
 }
+
+#[derive(Debug, Default, FromRow)]
+pub(crate) struct DatabaseUploadedSoundRow {
+    pub(crate) name: String,
+    pub(crate) guild_id: Option<i64>,
+    pub(crate) user_id: i64,
+    pub(crate) size: i32,
+    pub(crate) duration_ms: i32,
+}
+
+#[derive(Debug, Default)]
+pub struct UploadedSound {
+    pub name: String,
+    pub guild_id: Option<u64>,
+    pub user_id: u64,
+    pub size: u32,
+    pub duration_ms: u32,
+}
+
+impl From<DatabaseUploadedSoundRow> for UploadedSound {
+    fn from(value: DatabaseUploadedSoundRow) -> Self {
+        Self {
+            name: value.name,
+            guild_id: value.guild_id.map(|v| v as u64),
+            user_id: value.user_id as u64,
+            size: value.size as u32,
+            duration_ms: value.duration_ms as u32,
+        }
+    }
+}
+
+pub async fn create_uploaded(database: &PgPool, sound: &UploadedSound) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseUploadedSound::Table)
+        .columns([
+            DatabaseUploadedSound::Name,
+            DatabaseUploadedSound::GuildId,
+            DatabaseUploadedSound::UserId,
+            DatabaseUploadedSound::Size,
+            DatabaseUploadedSound::DurationMs,
+        ])
+        .values_panic([
+            sound.name.as_str().into(),
+            sound.guild_id.into(),
+            sound.user_id.into(),
+            sound.size.into(),
+            sound.duration_ms.into(),
+        ])
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+pub async fn fetch_uploaded_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<UploadedSound>> {
+    let (sql, values) = Query::select()
+        .columns([
+            DatabaseUploadedSound::Name,
+            DatabaseUploadedSound::GuildId,
+            DatabaseUploadedSound::UserId,
+            DatabaseUploadedSound::Size,
+            DatabaseUploadedSound::DurationMs,
+        ])
+        .from(DatabaseUploadedSound::Table)
+        .and_where(Expr::col(DatabaseUploadedSound::GuildId).eq(guild_id))
+        .order_by(DatabaseUploadedSound::Name, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, DatabaseUploadedSoundRow, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .map_ok(Into::into)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -299,3 +299,28 @@ pub async fn delete_by_id(database: &PgPool, id: u64) -> Result<Option<Soundstic
 
     Ok(Some(soundsticker.to_owned()))
 }
+
+#[tracing::instrument(skip(database))]
+pub async fn count_by_guild_id(database: &PgPool, guild_id: u64) -> Result<i64> {
+    let (sql, values) = Query::select()
+        .expr(Expr::col((DatabaseSoundsticker::Table, DatabaseSoundsticker::Id)).count())
+        .from(DatabaseSoundsticker::Table)
+        .inner_join(
+            DatabaseSticker::Table,
+            Expr::col((DatabaseSoundsticker::Table, DatabaseSoundsticker::StickerId))
+                .equals((DatabaseSticker::Table, DatabaseSticker::Id)),
+        )
+        .and_where(Expr::col((DatabaseSticker::Table, DatabaseSticker::GuildId)).eq(guild_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    match sqlx::query_scalar_with(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+    {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            tracing::error!("failed to count soundstickers\nError: {err:?}");
+            Err(err.into())
+        },
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt::Debug;
 
 use anyhow::{Error, Result};
-use sea_query::{Iden, OnConflict, PostgresQueryBuilder, Query};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
 use sea_query_binder::SqlxBinder;
 use sqlx::{FromRow, PgPool};
 
@@ -42,3 +43,17 @@ where
         .await
         .map_err(Error::msg)
 }
+
+pub async fn fetch_by_ids(database: &PgPool, ids: &[i32]) -> Result<Vec<Speaker>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseSpeaker::Id, DatabaseSpeaker::Speed])
+        .from(DatabaseSpeaker::Table)
+        .and_where(Expr::col(DatabaseSpeaker::Id).is_in(ids.iter().cloned()))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, Speaker, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,77 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Prefix of messages each guild registered to read them with a style of the voice, such as whispering.
+#[derive(Iden)]
+pub(crate) enum DatabaseStyleShortcut {
+    #[iden = "style_shortcuts"]
+    Table,
+    GuildId,
+    Prefix,
+    Style,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StyleShortcut {
+    pub prefix: String,
+    /// Name of the style, which is looked up among the styles of the voice of each message.
+    pub style: String,
+}
+
+pub async fn create(database: &PgPool, guild_id: u64, prefix: &str, style: &str) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseStyleShortcut::Table)
+        .columns([
+            DatabaseStyleShortcut::GuildId,
+            DatabaseStyleShortcut::Prefix,
+            DatabaseStyleShortcut::Style,
+        ])
+        .values_panic([guild_id.into(), prefix.into(), style.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseStyleShortcut::GuildId, DatabaseStyleShortcut::Prefix])
+                .update_column(DatabaseStyleShortcut::Style)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the shortcut of the prefix and returns whether there was one.
+pub async fn delete(database: &PgPool, guild_id: u64, prefix: &str) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseStyleShortcut::Table)
+        .and_where(Expr::col(DatabaseStyleShortcut::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseStyleShortcut::Prefix).eq(prefix))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<StyleShortcut>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseStyleShortcut::Prefix, DatabaseStyleShortcut::Style])
+        .from(DatabaseStyleShortcut::Table)
+        .and_where(Expr::col(DatabaseStyleShortcut::GuildId).eq(guild_id))
+        .order_by(DatabaseStyleShortcut::Prefix, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, StyleShortcut, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,135 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Alias, Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query, SimpleExpr};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Daily usage of each voice in each guild, added up in batches by the bot.
+#[derive(Clone, Copy, Iden)]
+pub(crate) enum DatabaseUsageStatistic {
+    #[iden = "usage_statistics"]
+    Table,
+    GuildId,
+    Day,
+    SpeakerId,
+    Messages,
+    Characters,
+    Syntheses,
+    CacheHits,
+}
+
+/// Usage to be added to the statistics of today.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub messages: i64,
+    pub characters: i64,
+    /// Texts synthesized or served from the caches
+    pub syntheses: i64,
+    pub cache_hits: i64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct SpeakerUsage {
+    pub speaker_id: i32,
+    pub messages: i64,
+    pub characters: i64,
+    pub syntheses: i64,
+    pub cache_hits: i64,
+}
+
+/// Adds the usages of `(guild_id, speaker_id)` to today's statistics in one statement.
+pub async fn add_all(database: &PgPool, usages: &[(u64, i32, Usage)]) -> Result<()> {
+    if usages.is_empty() {
+        return Ok(());
+    }
+
+    let mut query = Query::insert();
+    query.into_table(DatabaseUsageStatistic::Table).columns([
+        DatabaseUsageStatistic::GuildId,
+        DatabaseUsageStatistic::Day,
+        DatabaseUsageStatistic::SpeakerId,
+        DatabaseUsageStatistic::Messages,
+        DatabaseUsageStatistic::Characters,
+        DatabaseUsageStatistic::Syntheses,
+        DatabaseUsageStatistic::CacheHits,
+    ]);
+    for (guild_id, speaker_id, usage) in usages {
+        query.values_panic([
+            (*guild_id).into(),
+            Expr::current_date().into(),
+            (*speaker_id).into(),
+            usage.messages.into(),
+            usage.characters.into(),
+            usage.syntheses.into(),
+            usage.cache_hits.into(),
+        ]);
+    }
+    let (sql, values) = query
+        .on_conflict(
+            OnConflict::columns([
+                DatabaseUsageStatistic::GuildId,
+                DatabaseUsageStatistic::Day,
+                DatabaseUsageStatistic::SpeakerId,
+            ])
+            .values([
+                (
+                    DatabaseUsageStatistic::Messages,
+                    increment(DatabaseUsageStatistic::Messages),
+                ),
+                (
+                    DatabaseUsageStatistic::Characters,
+                    increment(DatabaseUsageStatistic::Characters),
+                ),
+                (
+                    DatabaseUsageStatistic::Syntheses,
+                    increment(DatabaseUsageStatistic::Syntheses),
+                ),
+                (
+                    DatabaseUsageStatistic::CacheHits,
+                    increment(DatabaseUsageStatistic::CacheHits),
+                ),
+            ])
+            .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Usage of each voice in the guild for the last `days` days including today, most used first.
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64, days: i32) -> Result<Vec<SpeakerUsage>> {
+    let mut query = Query::select();
+    query.column(DatabaseUsageStatistic::SpeakerId);
+    for column in [
+        DatabaseUsageStatistic::Messages,
+        DatabaseUsageStatistic::Characters,
+        DatabaseUsageStatistic::Syntheses,
+        DatabaseUsageStatistic::CacheHits,
+    ] {
+        // bigint の合計は numeric になるので戻す
+        query.expr_as(Expr::col(column).sum().cast_as(Alias::new("bigint")), column);
+    }
+    let (sql, values) = query
+        .from(DatabaseUsageStatistic::Table)
+        .and_where(Expr::col(DatabaseUsageStatistic::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseUsageStatistic::Day).gt(Expr::current_date().sub(days)))
+        .group_by_col(DatabaseUsageStatistic::SpeakerId)
+        .order_by(DatabaseUsageStatistic::Messages, Order::Desc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, SpeakerUsage, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+// 同じ日の同じボイスの行には足し合わせる
+fn increment(column: DatabaseUsageStatistic) -> SimpleExpr {
+    Expr::col((DatabaseUsageStatistic::Table, column)).add(Expr::col((Alias::new("excluded"), column)))
+}
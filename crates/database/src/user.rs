@@ -1,10 +1,13 @@
 use anyhow::{Error, Result};
 use futures::TryStreamExt;
-use sea_query::{Expr, Iden, JoinType, OnConflict, PostgresQueryBuilder, Query};
+use sea_query::{Expr, Iden, JoinType, OnConflict, Order, PostgresQueryBuilder, Query};
 use sea_query_binder::SqlxBinder;
 use sqlx::{FromRow, PgPool};
 
-use crate::speaker::DatabaseSpeaker;
+use crate::{
+    speaker::DatabaseSpeaker, user_morph::DatabaseUserMorph, user_mute::DatabaseUserMute,
+    user_speed::DatabaseUserSpeed, user_tone::DatabaseUserTone,
+};
 
 #[derive(Iden)]
 pub(crate) enum DatabaseUser {
@@ -14,6 +17,19 @@ pub(crate) enum DatabaseUser {
     SpeakerId,
 }
 
+/// Voices users chose to be read with only in a guild, over the voice they chose for every guild.
+#[derive(Iden)]
+pub(crate) enum DatabaseGuildUserVoice {
+    #[iden = "guild_user_voices"]
+    Table,
+    GuildId,
+    UserId,
+    SpeakerId,
+}
+
+/// Voice of users who have chosen none, in guilds without a default voice.
+pub const DEFAULT_SPEAKER_ID: i32 = 1;
+
 #[derive(Debug, FromRow)]
 pub struct User {
     pub id: i64,
@@ -27,9 +43,26 @@ pub struct UserSpeaker {
     pub speed: Option<f32>,
 }
 
+/// Voice a user chose to be read with only in a guild.
+#[derive(Debug, Clone, Copy, FromRow)]
+pub struct GuildUserVoice {
+    pub user_id: i64,
+    pub speaker_id: i32,
+}
+
+/// Voices a user chose, for the guild and for every guild.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UserVoices {
+    pub guild: Option<i32>,
+    pub global: Option<i32>,
+}
+
 impl Default for User {
     fn default() -> Self {
-        Self { id: 0, speaker_id: 1 }
+        Self {
+            id: 0,
+            speaker_id: DEFAULT_SPEAKER_ID,
+        }
     }
 }
 
@@ -37,7 +70,7 @@ impl Default for UserSpeaker {
     fn default() -> Self {
         Self {
             id: 0,
-            speaker_id: 1,
+            speaker_id: DEFAULT_SPEAKER_ID,
             speed: Some(1.2),
         }
     }
@@ -62,6 +95,21 @@ pub async fn create(database: &PgPool, user_id: u64, speaker_id: u16) -> Result<
         .map_err(Error::msg)
 }
 
+/// Deletes the voice the user chose and returns whether there was one.
+pub async fn delete_by_id(database: &PgPool, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseUser::Table)
+        .and_where(Expr::col(DatabaseUser::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn fetch_by_ids(database: &PgPool, ids: &[i64]) -> Result<Vec<User>> {
     let (sql, values) = Query::select()
         .columns([DatabaseUser::Id, DatabaseUser::SpeakerId])
@@ -99,3 +147,130 @@ pub async fn fetch_with_speaker_by_ids(database: &PgPool, ids: &[i64]) -> Result
         .await
         .map_err(Error::msg)
 }
+
+/// Sets the voice the user is read with only in the guild.
+pub async fn create_in_guild(database: &PgPool, guild_id: u64, user_id: u64, speaker_id: u16) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseGuildUserVoice::Table)
+        .columns([
+            DatabaseGuildUserVoice::GuildId,
+            DatabaseGuildUserVoice::UserId,
+            DatabaseGuildUserVoice::SpeakerId,
+        ])
+        .values_panic([guild_id.into(), user_id.into(), speaker_id.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseGuildUserVoice::GuildId, DatabaseGuildUserVoice::UserId])
+                .update_column(DatabaseGuildUserVoice::SpeakerId)
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Voices every user chose to be read with only in the guild.
+pub async fn fetch_in_guild(database: &PgPool, guild_id: u64) -> Result<Vec<GuildUserVoice>> {
+    let (sql, values) = Query::select()
+        .columns([DatabaseGuildUserVoice::UserId, DatabaseGuildUserVoice::SpeakerId])
+        .from(DatabaseGuildUserVoice::Table)
+        .and_where(Expr::col(DatabaseGuildUserVoice::GuildId).eq(guild_id))
+        .order_by(DatabaseGuildUserVoice::UserId, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, GuildUserVoice, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
+
+/// Deletes the voice the user chose in the guild and returns whether there was one.
+pub async fn delete_in_guild(database: &PgPool, guild_id: u64, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseGuildUserVoice::Table)
+        .and_where(Expr::col(DatabaseGuildUserVoice::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseGuildUserVoice::UserId).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes every setting of the user in one transaction, and returns whether there was any.
+pub async fn reset(database: &PgPool, user_id: u64) -> Result<bool> {
+    let statements = [
+        Query::delete()
+            .from_table(DatabaseUser::Table)
+            .and_where(Expr::col(DatabaseUser::Id).eq(user_id))
+            .build_sqlx(PostgresQueryBuilder),
+        Query::delete()
+            .from_table(DatabaseGuildUserVoice::Table)
+            .and_where(Expr::col(DatabaseGuildUserVoice::UserId).eq(user_id))
+            .build_sqlx(PostgresQueryBuilder),
+        Query::delete()
+            .from_table(DatabaseUserSpeed::Table)
+            .and_where(Expr::col(DatabaseUserSpeed::Id).eq(user_id))
+            .build_sqlx(PostgresQueryBuilder),
+        Query::delete()
+            .from_table(DatabaseUserTone::Table)
+            .and_where(Expr::col(DatabaseUserTone::Id).eq(user_id))
+            .build_sqlx(PostgresQueryBuilder),
+        Query::delete()
+            .from_table(DatabaseUserMute::Table)
+            .and_where(Expr::col(DatabaseUserMute::Id).eq(user_id))
+            .build_sqlx(PostgresQueryBuilder),
+        Query::delete()
+            .from_table(DatabaseUserMorph::Table)
+            .and_where(Expr::col(DatabaseUserMorph::Id).eq(user_id))
+            .build_sqlx(PostgresQueryBuilder),
+    ];
+
+    let mut tx = database.begin().await?;
+    let mut rows_affected = 0;
+    for (sql, values) in statements {
+        rows_affected += sqlx::query_with(&sql, values)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::msg)?
+            .rows_affected();
+    }
+    tx.commit().await?;
+
+    Ok(rows_affected > 0)
+}
+
+pub async fn fetch_voices(database: &PgPool, guild_id: u64, user_id: u64) -> Result<UserVoices> {
+    let mut connection = database.acquire().await?;
+
+    let (sql, values) = Query::select()
+        .column(DatabaseGuildUserVoice::SpeakerId)
+        .from(DatabaseGuildUserVoice::Table)
+        .and_where(Expr::col(DatabaseGuildUserVoice::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseGuildUserVoice::UserId).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+    let guild = sqlx::query_scalar_with::<_, i32, _>(&sql, values)
+        .fetch_optional(&mut *connection)
+        .await
+        .map_err(Error::msg)?;
+
+    let (sql, values) = Query::select()
+        .column(DatabaseUser::SpeakerId)
+        .from(DatabaseUser::Table)
+        .and_where(Expr::col(DatabaseUser::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+    let global = sqlx::query_scalar_with::<_, i32, _>(&sql, values)
+        .fetch_optional(&mut *connection)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(UserVoices { guild, global })
+}
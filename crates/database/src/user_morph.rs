@@ -0,0 +1,80 @@
+use anyhow::{Error, Result};
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Morphing of two voices each user's messages are read with.
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum DatabaseUserMorph {
+    #[iden = "user_morphs"]
+    Table,
+    Id,
+    BaseSpeakerId,
+    TargetSpeakerId,
+    Rate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromRow)]
+pub struct UserMorph {
+    pub base_speaker_id: i32,
+    pub target_speaker_id: i32,
+    pub rate: f32,
+}
+
+const COLUMNS: [DatabaseUserMorph; 3] = [
+    DatabaseUserMorph::BaseSpeakerId,
+    DatabaseUserMorph::TargetSpeakerId,
+    DatabaseUserMorph::Rate,
+];
+
+pub async fn create(database: &PgPool, user_id: u64, morph: UserMorph) -> Result<UserMorph> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseUserMorph::Table)
+        .columns([DatabaseUserMorph::Id].into_iter().chain(COLUMNS))
+        .values_panic([
+            user_id.into(),
+            morph.base_speaker_id.into(),
+            morph.target_speaker_id.into(),
+            morph.rate.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(DatabaseUserMorph::Id)
+                .update_columns(COLUMNS)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, UserMorph, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+/// Deletes the morphing of the user and returns whether there was one.
+pub async fn delete_by_id(database: &PgPool, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseUserMorph::Table)
+        .and_where(Expr::col(DatabaseUserMorph::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_id(database: &PgPool, user_id: u64) -> Result<Option<UserMorph>> {
+    let (sql, values) = Query::select()
+        .columns(COLUMNS)
+        .from(DatabaseUserMorph::Table)
+        .and_where(Expr::col(DatabaseUserMorph::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, UserMorph, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
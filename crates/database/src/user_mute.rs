@@ -0,0 +1,58 @@
+use anyhow::{Error, Result};
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::PgPool;
+
+/// Users who turned off reading their messages aloud.
+#[derive(Iden)]
+pub(crate) enum DatabaseUserMute {
+    #[iden = "user_mutes"]
+    Table,
+    Id,
+}
+
+pub async fn create(database: &PgPool, user_id: u64) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseUserMute::Table)
+        .columns([DatabaseUserMute::Id])
+        .values_panic([user_id.into()])
+        .on_conflict(OnConflict::column(DatabaseUserMute::Id).do_nothing().to_owned())
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Deletes the mute of the user and returns whether the user was muted.
+pub async fn delete_by_id(database: &PgPool, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseUserMute::Table)
+        .and_where(Expr::col(DatabaseUserMute::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn is_muted(database: &PgPool, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::select()
+        .column(DatabaseUserMute::Id)
+        .from(DatabaseUserMute::Table)
+        .and_where(Expr::col(DatabaseUserMute::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let muted = sqlx::query_scalar_with::<_, i64, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(muted.is_some())
+}
@@ -0,0 +1,60 @@
+use anyhow::{Error, Result};
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::PgPool;
+
+/// Speed each user's messages are read at regardless of the voice.
+#[derive(Iden)]
+pub(crate) enum DatabaseUserSpeed {
+    #[iden = "user_speeds"]
+    Table,
+    Id,
+    Speed,
+}
+
+pub async fn create(database: &PgPool, user_id: u64, speed: f32) -> Result<f32> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseUserSpeed::Table)
+        .columns([DatabaseUserSpeed::Id, DatabaseUserSpeed::Speed])
+        .values_panic([user_id.into(), speed.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseUserSpeed::Id)
+                .update_column(DatabaseUserSpeed::Speed)
+                .to_owned(),
+        )
+        .returning(Query::returning().column(DatabaseUserSpeed::Speed))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with::<_, f32, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+/// Deletes the speed of the user and returns whether there was one.
+pub async fn delete_by_id(database: &PgPool, user_id: u64) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseUserSpeed::Table)
+        .and_where(Expr::col(DatabaseUserSpeed::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_id(database: &PgPool, user_id: u64) -> Result<Option<f32>> {
+    let (sql, values) = Query::select()
+        .column(DatabaseUserSpeed::Speed)
+        .from(DatabaseUserSpeed::Table)
+        .and_where(Expr::col(DatabaseUserSpeed::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with::<_, f32, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
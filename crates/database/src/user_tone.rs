@@ -0,0 +1,69 @@
+use anyhow::{Error, Result};
+use sea_query::{Expr, Iden, OnConflict, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::{FromRow, PgPool};
+
+/// Pitch and intonation each user's messages are read with regardless of the voice.
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum DatabaseUserTone {
+    #[iden = "user_tones"]
+    Table,
+    Id,
+    Pitch,
+    Intonation,
+}
+
+#[derive(Debug, Default, Clone, Copy, FromRow)]
+pub struct UserTone {
+    pub pitch: Option<f32>,
+    pub intonation: Option<f32>,
+}
+
+const COLUMNS: [DatabaseUserTone; 2] = [DatabaseUserTone::Pitch, DatabaseUserTone::Intonation];
+
+/// Sets the pitch of the user, or resets it with `None`.
+pub async fn update_pitch(database: &PgPool, user_id: u64, pitch: Option<f32>) -> Result<UserTone> {
+    update_scale(database, user_id, DatabaseUserTone::Pitch, pitch).await
+}
+
+/// Sets the intonation of the user, or resets it with `None`.
+pub async fn update_intonation(database: &PgPool, user_id: u64, intonation: Option<f32>) -> Result<UserTone> {
+    update_scale(database, user_id, DatabaseUserTone::Intonation, intonation).await
+}
+
+pub async fn fetch_by_id(database: &PgPool, user_id: u64) -> Result<Option<UserTone>> {
+    let (sql, values) = Query::select()
+        .columns(COLUMNS)
+        .from(DatabaseUserTone::Table)
+        .and_where(Expr::col(DatabaseUserTone::Id).eq(user_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, UserTone, _>(&sql, values)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
+
+async fn update_scale(
+    database: &PgPool,
+    user_id: u64,
+    column: DatabaseUserTone,
+    value: Option<f32>,
+) -> Result<UserTone> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseUserTone::Table)
+        .columns([DatabaseUserTone::Id, column])
+        .values_panic([user_id.into(), value.into()])
+        .on_conflict(
+            OnConflict::column(DatabaseUserTone::Id)
+                .update_column(column)
+                .to_owned(),
+        )
+        .returning(Query::returning().columns(COLUMNS))
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_as_with::<_, UserTone, _>(&sql, values)
+        .fetch_one(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)
+}
@@ -0,0 +1,65 @@
+use anyhow::{Error, Result};
+use futures::TryStreamExt;
+use sea_query::{Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use sqlx::PgPool;
+
+/// Voices the default voice of each guild rotates among day by day.
+#[derive(Iden)]
+pub(crate) enum DatabaseVoiceRotation {
+    #[iden = "voice_rotations"]
+    Table,
+    GuildId,
+    SpeakerId,
+}
+
+pub async fn add(database: &PgPool, guild_id: u64, speaker_id: u16) -> Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(DatabaseVoiceRotation::Table)
+        .columns([DatabaseVoiceRotation::GuildId, DatabaseVoiceRotation::SpeakerId])
+        .values_panic([guild_id.into(), speaker_id.into()])
+        .on_conflict(
+            OnConflict::columns([DatabaseVoiceRotation::GuildId, DatabaseVoiceRotation::SpeakerId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(())
+}
+
+/// Removes the voice from the rotation and returns whether it was in the rotation.
+pub async fn remove(database: &PgPool, guild_id: u64, speaker_id: u16) -> Result<bool> {
+    let (sql, values) = Query::delete()
+        .from_table(DatabaseVoiceRotation::Table)
+        .and_where(Expr::col(DatabaseVoiceRotation::GuildId).eq(guild_id))
+        .and_where(Expr::col(DatabaseVoiceRotation::SpeakerId).eq(speaker_id))
+        .build_sqlx(PostgresQueryBuilder);
+
+    let result = sqlx::query_with(&sql, values)
+        .execute(&mut *database.acquire().await?)
+        .await
+        .map_err(Error::msg)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_by_guild_id(database: &PgPool, guild_id: u64) -> Result<Vec<i32>> {
+    let (sql, values) = Query::select()
+        .column(DatabaseVoiceRotation::SpeakerId)
+        .from(DatabaseVoiceRotation::Table)
+        .and_where(Expr::col(DatabaseVoiceRotation::GuildId).eq(guild_id))
+        .order_by(DatabaseVoiceRotation::SpeakerId, Order::Asc)
+        .build_sqlx(PostgresQueryBuilder);
+
+    sqlx::query_scalar_with::<_, i32, _>(&sql, values)
+        .fetch(&mut *database.acquire().await?)
+        .try_collect()
+        .await
+        .map_err(Error::msg)
+}
@@ -1,14 +1,85 @@
+use anyhow::{Context as _, Result};
 use time::macros::format_description;
-use tracing_subscriber::{EnvFilter, fmt::time::UtcTime};
+use tracing_subscriber::{EnvFilter, Registry, fmt::time::UtcTime, prelude::*, reload};
 
-pub fn initialize_logging() {
+/// Handle to replace the `EnvFilter` of the global subscriber at runtime.
+#[derive(Clone)]
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+pub fn initialize_logging() -> LogFilter {
     let local_timer = UtcTime::new(format_description!(
         "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]+[offset_hour]:[offset_minute]"
     ));
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .with_timer(local_timer)
-        .with_file(true)
-        .with_line_number(true)
+    let (filter, handle) = reload::Layer::new(EnvFilter::from_default_env());
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_timer(local_timer)
+                .with_file(true)
+                .with_line_number(true),
+        )
         .init();
+
+    LogFilter { handle }
+}
+
+impl LogFilter {
+    /// Sets the level of the target, or the default level when the target is `*`, and returns the new directives.
+    pub fn set_level(&self, target: &str, level: &str) -> Result<String> {
+        let current = self
+            .handle
+            .with_current(|filter| filter.to_string())
+            .context("failed to get current log filter")?;
+        let directives = replace_directive(&current, target, level);
+        let filter = EnvFilter::try_new(&directives).with_context(|| format!("invalid log filter: {directives}"))?;
+        self.handle.reload(filter).context("failed to reload log filter")?;
+
+        Ok(directives)
+    }
+}
+
+fn replace_directive(current: &str, target: &str, level: &str) -> String {
+    let directive = match target {
+        "*" => level.to_string(),
+        target => format!("{target}={level}"),
+    };
+
+    current
+        .split(',')
+        .filter(|directive| !directive.is_empty())
+        .filter(|directive| match directive.split_once('=') {
+            Some((directive_target, _)) => directive_target != target,
+            // 対象の無いディレクティブはデフォルトのレベル
+            None => target != "*",
+        })
+        .chain([directive.as_str()])
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::replace_directive;
+
+    #[test]
+    fn add_directive() {
+        assert_eq!(replace_directive("info", "voicevox", "debug"), "info,voicevox=debug");
+        assert_eq!(replace_directive("", "voicevox", "debug"), "voicevox=debug");
+    }
+
+    #[test]
+    fn replace_directive_of_target() {
+        assert_eq!(
+            replace_directive("info,seitai=debug,songbird=warn", "songbird", "trace"),
+            "info,seitai=debug,songbird=trace"
+        );
+    }
+
+    #[test]
+    fn replace_default_level() {
+        assert_eq!(replace_directive("info,seitai=debug", "*", "warn"), "seitai=debug,warn");
+    }
 }
@@ -0,0 +1,92 @@
+use std::{
+    marker::PhantomData,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Decides which texts are worth keeping compressed in memory.
+#[cfg_attr(test, mockall::automock)]
+pub trait Cacheable {
+    fn should_cache(&self, text: &str) -> bool;
+}
+
+/// Numbers of audio served from the caches and synthesized since the start.
+#[derive(Debug, Default)]
+pub struct CacheStatistics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Caches exactly the texts that parse as `Utterance`, e.g. an enum of fixed announcements.
+pub struct ConstCacheable<Utterance> {
+    _marker: PhantomData<fn() -> Utterance>,
+}
+
+impl<Utterance> ConstCacheable<Utterance> {
+    pub fn new() -> Self {
+        Self { _marker: PhantomData }
+    }
+}
+
+impl<Utterance> Clone for ConstCacheable<Utterance> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<Utterance> Default for ConstCacheable<Utterance> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Utterance> Cacheable for ConstCacheable<Utterance>
+where
+    Utterance: FromStr,
+{
+    fn should_cache(&self, text: &str) -> bool {
+        Utterance::from_str(text).is_ok()
+    }
+}
+
+impl CacheStatistics {
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Ratio of audio served from the caches, or `None` before any audio is requested.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let (hits, misses) = (self.hits(), self.misses());
+        let total = hits + misses;
+        (total > 0).then(|| hits as f64 / total as f64)
+    }
+
+    pub(crate) fn record(&self, hit: bool) {
+        let counter = match hit {
+            true => &self.hits,
+            false => &self.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CacheStatistics;
+
+    #[test]
+    fn calculate_hit_rate() {
+        let statistics = CacheStatistics::default();
+        assert_eq!(statistics.hit_rate(), None);
+
+        statistics.record(true);
+        statistics.record(true);
+        statistics.record(true);
+        statistics.record(false);
+        assert_eq!(statistics.hit_rate(), Some(0.75));
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Error, Result};
+use futures::channel::oneshot;
+use hashbrown::HashMap;
+
+type Waiters<Value> = Vec<oneshot::Sender<Result<Value, String>>>;
+
+/// Work in flight by key, which concurrent callers with the same key share instead of repeating it.
+pub(crate) struct SingleFlight<Key, Value> {
+    waiters: Arc<Mutex<HashMap<Key, Waiters<Value>>>>,
+}
+
+/// Removes the key when the work finishes or is dropped halfway, so that the waiters do not wait forever.
+struct Leader<'a, Key, Value>
+where
+    Key: Eq + Hash,
+{
+    waiters: &'a Mutex<HashMap<Key, Waiters<Value>>>,
+    key: Option<Key>,
+}
+
+impl<Key, Value> SingleFlight<Key, Value> {
+    pub(crate) fn new() -> Self {
+        Self {
+            waiters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<Key, Value> SingleFlight<Key, Value>
+where
+    Key: Eq + Hash + Clone,
+    Value: Clone,
+{
+    /// Runs `work` unless work with the same key is in flight, in which case waits for its result.
+    pub(crate) async fn run<Work>(&self, key: Key, work: impl FnOnce() -> Work) -> Result<Value>
+    where
+        Work: Future<Output = Result<Value>>,
+    {
+        let receiver = {
+            let mut waiters = self.waiters.lock().expect("in-flight work has been poisoned");
+            match waiters.get_mut(&key) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                },
+                None => {
+                    waiters.insert(key.clone(), Vec::new());
+                    None
+                },
+            }
+        };
+
+        if let Some(receiver) = receiver {
+            return match receiver.await {
+                Ok(result) => result.map_err(Error::msg),
+                // 先に始めた処理が取り消されたら自分で処理する
+                Err(_) => work().await,
+            };
+        }
+
+        let leader = Leader {
+            waiters: &self.waiters,
+            key: Some(key),
+        };
+        let result = work().await;
+        for waiter in leader.finish() {
+            let shared = match &result {
+                Ok(value) => Ok(value.clone()),
+                Err(error) => Err(format!("{error:?}")),
+            };
+            let _ = waiter.send(shared);
+        }
+
+        result
+    }
+}
+
+impl<Key, Value> Clone for SingleFlight<Key, Value> {
+    fn clone(&self) -> Self {
+        Self {
+            waiters: self.waiters.clone(),
+        }
+    }
+}
+
+impl<Key, Value> Leader<'_, Key, Value>
+where
+    Key: Eq + Hash,
+{
+    fn finish(mut self) -> Waiters<Value> {
+        let Some(key) = self.key.take() else {
+            return Vec::new();
+        };
+        self.waiters
+            .lock()
+            .expect("in-flight work has been poisoned")
+            .remove(&key)
+            .unwrap_or_default()
+    }
+}
+
+impl<Key, Value> Drop for Leader<'_, Key, Value>
+where
+    Key: Eq + Hash,
+{
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take()
+            && let Ok(mut waiters) = self.waiters.lock()
+        {
+            waiters.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use anyhow::bail;
+
+    use super::SingleFlight;
+
+    #[tokio::test]
+    async fn share_result_of_concurrent_work() {
+        let flight = SingleFlight::<&str, u32>::new();
+        let runs = AtomicUsize::new(0);
+        let work = || async {
+            runs.fetch_add(1, Ordering::Relaxed);
+            tokio::task::yield_now().await;
+            Ok(42)
+        };
+
+        let (first, second) = futures::join!(flight.run("foo", work), flight.run("foo", work));
+        assert_eq!(first.unwrap(), 42);
+        assert_eq!(second.unwrap(), 42);
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+        // 終わった処理の結果は使い回さない
+        flight.run("foo", work).await.unwrap();
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn share_error_of_concurrent_work() {
+        let flight = SingleFlight::<&str, u32>::new();
+        let work = || async {
+            tokio::task::yield_now().await;
+            bail!("engine is down")
+        };
+
+        let (first, second) = futures::join!(flight.run("foo", work), flight.run("foo", work));
+        assert!(first.is_err());
+        assert!(second.unwrap_err().to_string().contains("engine is down"));
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::Result;
+use serde::Serialize;
+use voicevox::{Bytes, audio::AudioQuery};
+
+/// Turns text into audio in two steps so that the audio query can be inspected and cached in between.
+#[cfg_attr(test, mockall::automock(type Query = String; type Raw = Vec<u8>;))]
+pub trait AudioGenerator {
+    type Query: Serialize + Send + Sync;
+    type Raw;
+
+    /// Generates the query that fully determines the synthesized audio.
+    fn generate_query(
+        &self,
+        speaker: &str,
+        text: &str,
+        speed: f32,
+        pitch: f32,
+        intonation: f32,
+    ) -> impl Future<Output = Result<Self::Query>> + Send;
+    /// Synthesizes the audio of a query generated by [`AudioGenerator::generate_query`].
+    fn synthesize(&self, speaker: &str, query: &Self::Query) -> impl Future<Output = Result<Self::Raw>> + Send;
+    /// Synthesizes the audio of a query with the voice of `speaker` morphed with `target` at `rate`.
+    fn synthesize_morphing(
+        &self,
+        speaker: &str,
+        target: &str,
+        rate: f32,
+        query: &Self::Query,
+    ) -> impl Future<Output = Result<Self::Raw>> + Send;
+}
+
+impl AudioGenerator for voicevox::audio::AudioGenerator {
+    type Query = AudioQuery;
+    type Raw = Bytes;
+
+    async fn generate_query(
+        &self,
+        speaker: &str,
+        text: &str,
+        speed: f32,
+        pitch: f32,
+        intonation: f32,
+    ) -> Result<Self::Query> {
+        let query = self.query(speaker, text, speed, pitch, intonation).await?;
+        Ok(query)
+    }
+
+    async fn synthesize(&self, speaker: &str, query: &Self::Query) -> Result<Self::Raw> {
+        let audio = self.synthesize_query(speaker, query).await?;
+        Ok(audio)
+    }
+
+    async fn synthesize_morphing(
+        &self,
+        speaker: &str,
+        target: &str,
+        rate: f32,
+        query: &Self::Query,
+    ) -> Result<Self::Raw> {
+        let audio = self.synthesize_morphing_query(speaker, target, rate, query).await?;
+        Ok(audio)
+    }
+}
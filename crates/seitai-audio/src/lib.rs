@@ -0,0 +1,403 @@
+//! Audio pipeline which synthesizes text with VOICEVOX ENGINE and plays it with songbird.
+//!
+//! [`VoicevoxAudioRepository`] combines an [`AudioGenerator`] (e.g. [`voicevox::audio::AudioGenerator`]), an
+//! [`AudioProcessor`] (e.g. [`SongbirdAudioProcessor`]) and a [`Cacheable`] policy into an [`AudioRepository`] that
+//! yields [`songbird::input::Input`] ready to be enqueued into a call.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use hashbrown::HashMap;
+use indexmap::IndexMap;
+use ordered_float::NotNan;
+
+pub mod cache;
+mod flight;
+pub mod generator;
+pub mod processor;
+
+use self::flight::SingleFlight;
+pub use self::{
+    cache::{CacheStatistics, Cacheable, ConstCacheable},
+    generator::AudioGenerator,
+    processor::{AudioProcessor, SongbirdAudioProcessor},
+};
+
+// 音声クエリをキーにキャッシュする合成結果の最大数
+const SYNTHESIS_CACHE_CAPACITY: usize = 64;
+
+/// Text to be read aloud and how to read it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Audio {
+    pub text: String,
+    /// Style id of VOICEVOX
+    pub speaker: String,
+    pub speed: NotNan<f32>,
+    pub pitch: NotNan<f32>,
+    pub intonation: NotNan<f32>,
+    pub morph: Option<Morph>,
+}
+
+/// Voice which [`Audio::speaker`] is morphed with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Morph {
+    /// Style id of VOICEVOX
+    pub target: String,
+    /// From 0.0 for the voice of the speaker to 1.0 for the voice of the target
+    pub rate: NotNan<f32>,
+}
+
+/// Key of synthesized audio: hash of the final audio query JSON, the style id and the morphing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SynthesisKey {
+    query_hash: u64,
+    speaker: String,
+    morph: Option<Morph>,
+}
+
+/// [`AudioRepository`] backed by an [`AudioGenerator`] with two levels of cache.
+///
+/// Texts accepted by the [`Cacheable`] are kept compressed forever, and the other recently synthesized audio is reused
+/// when a text generates the same audio query as before. Texts generating the same audio query at the same time are
+/// synthesized once.
+pub struct VoicevoxAudioRepository<AudioCacheable, Compressed, Generator, Input, Processor, Raw> {
+    audio_generator: Generator,
+    audio_processor: Processor,
+    cache: Arc<Mutex<HashMap<Audio, Compressed>>>,
+    synthesis_cache: Arc<Mutex<IndexMap<SynthesisKey, Raw>>>,
+    synthesizing: SingleFlight<SynthesisKey, Raw>,
+    cacheable: AudioCacheable,
+    statistics: Arc<CacheStatistics>,
+    phantom: PhantomData<fn() -> Input>,
+}
+
+/// Source of playable audio.
+pub trait AudioRepository {
+    type Input;
+
+    /// Gets input to play `audio`, synthesizing it unless cached.
+    fn get(&self, audio: Audio) -> impl Future<Output = Result<Self::Input>> + Send;
+
+    /// Gets input like [`get`](Self::get), along with whether it was served from a cache.
+    fn fetch(&self, audio: Audio) -> impl Future<Output = Result<(Self::Input, bool)>> + Send;
+}
+
+impl<AudioCacheable, Compressed, Generator, Input, Processor, Raw>
+    VoicevoxAudioRepository<AudioCacheable, Compressed, Generator, Input, Processor, Raw>
+where
+    Generator: AudioGenerator + Send + Sync,
+    Processor: AudioProcessor + Send + Sync,
+{
+    pub fn new(audio_generator: Generator, audio_processor: Processor, cacheable: AudioCacheable) -> Self {
+        Self {
+            audio_generator,
+            audio_processor,
+            cache: Arc::new(Mutex::new(HashMap::default())),
+            synthesis_cache: Arc::new(Mutex::new(IndexMap::default())),
+            synthesizing: SingleFlight::new(),
+            cacheable,
+            statistics: Arc::new(CacheStatistics::default()),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Statistics of the caches shared with the clones of the repository.
+    pub fn statistics(&self) -> Arc<CacheStatistics> {
+        self.statistics.clone()
+    }
+}
+
+impl<AudioCacheable, Compressed, Generator, Input, Processor, Raw> Clone
+    for VoicevoxAudioRepository<AudioCacheable, Compressed, Generator, Input, Processor, Raw>
+where
+    AudioCacheable: Clone,
+    Generator: Clone,
+    Processor: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            audio_generator: self.audio_generator.clone(),
+            audio_processor: self.audio_processor.clone(),
+            cache: self.cache.clone(),
+            synthesis_cache: self.synthesis_cache.clone(),
+            synthesizing: self.synthesizing.clone(),
+            cacheable: self.cacheable.clone(),
+            statistics: self.statistics.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<AudioCacheable, Compressed, Generator, Input, Processor, Raw> AudioRepository
+    for VoicevoxAudioRepository<AudioCacheable, Compressed, Generator, Input, Processor, Raw>
+where
+    AudioCacheable: Cacheable + Send + Sync,
+    Compressed: Send,
+    Generator: AudioGenerator<Raw = Raw> + Send + Sync,
+    Input: Send,
+    Processor: AudioProcessor<Compressed = Compressed, Input = Input, Raw = Raw> + Send + Sync,
+    Raw: Into<Input> + Clone + Send,
+{
+    type Input = Input;
+
+    async fn get(&self, audio: Audio) -> Result<Self::Input> {
+        self.fetch(audio).await.map(|(input, _)| input)
+    }
+
+    async fn fetch(&self, audio: Audio) -> Result<(Self::Input, bool)> {
+        if let Some(sound) = self.cache.lock().expect("audio cache has been poisoned").get(&audio) {
+            self.statistics.record(true);
+            let input = self.audio_processor.to_input(sound);
+            return Ok((input, true));
+        }
+
+        let query = self
+            .audio_generator
+            .generate_query(
+                &audio.speaker,
+                &audio.text,
+                *audio.speed,
+                *audio.pitch,
+                *audio.intonation,
+            )
+            .await?;
+
+        // 辞書による置換などで異なるテキストが同じ音声クエリになることがある
+        let key = SynthesisKey {
+            query_hash: hash_query(&query)?,
+            speaker: audio.speaker.clone(),
+            morph: audio.morph.clone(),
+        };
+        let synthesized = self
+            .synthesis_cache
+            .lock()
+            .expect("synthesis cache has been poisoned")
+            .get(&key)
+            .cloned();
+        let cached = synthesized.is_some();
+        self.statistics.record(cached);
+        let raw = match synthesized {
+            Some(raw) => raw,
+            // 同じ音声クエリの合成が進行中ならその結果を待つ
+            None => {
+                self.synthesizing
+                    .run(key.clone(), || async {
+                        match &audio.morph {
+                            Some(morph) => {
+                                self.audio_generator
+                                    .synthesize_morphing(&audio.speaker, &morph.target, *morph.rate, &query)
+                                    .await
+                            },
+                            None => self.audio_generator.synthesize(&audio.speaker, &query).await,
+                        }
+                    })
+                    .await?
+            },
+        };
+
+        if self.cacheable.should_cache(&audio.text) {
+            let compressed = self.audio_processor.compress(raw).await?;
+            let input = self.audio_processor.to_input(&compressed);
+            self.cache
+                .lock()
+                .expect("audio cache has been poisoned")
+                .insert(audio, compressed);
+            return Ok((input, cached));
+        }
+
+        let mut synthesis_cache = self.synthesis_cache.lock().expect("synthesis cache has been poisoned");
+        synthesis_cache.insert(key, raw.clone());
+        if synthesis_cache.len() > SYNTHESIS_CACHE_CAPACITY {
+            synthesis_cache.shift_remove_index(0);
+        }
+
+        Ok((raw.into(), cached))
+    }
+}
+
+fn hash_query(query: &impl serde::Serialize) -> Result<u64> {
+    let json = serde_json::to_string(query)?;
+    let mut hasher = DefaultHasher::new();
+    json.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future::ok;
+    use ordered_float::NotNan;
+
+    use super::{Audio, AudioRepository, Morph, VoicevoxAudioRepository};
+    use crate::{cache::MockCacheable, generator::MockAudioGenerator, processor::MockAudioProcessor};
+
+    #[tokio::test]
+    async fn get_audio() {
+        let audio = Audio {
+            text: "foo".to_string(),
+            speaker: "1".to_string(),
+            speed: NotNan::new(1.0).unwrap(),
+            pitch: NotNan::new(0.0).unwrap(),
+            intonation: NotNan::new(1.0).unwrap(),
+            morph: None,
+        };
+
+        let mut mock_cacheable = MockCacheable::new();
+        mock_cacheable
+            .expect_should_cache()
+            .times(1)
+            .withf(|x| x == "foo")
+            .returning(|_| false);
+
+        let mut mock_audio_generator = MockAudioGenerator::new();
+        mock_audio_generator
+            .expect_generate_query()
+            .times(1)
+            .withf(|a, b, c, d, e| (a, b, c, d, e) == ("1", "foo", &1.0, &0.0, &1.0))
+            .returning(|_, _, _, _, _| Box::pin(ok("foo".to_string())));
+        mock_audio_generator
+            .expect_synthesize()
+            .times(1)
+            .withf(|x, y| (x, y.as_str()) == ("1", "foo"))
+            .returning(|_, _| Box::pin(ok(vec![0x00, 0x01, 0x02, 0x03])));
+
+        let mock_audio_processor = MockAudioProcessor::new();
+
+        let audio_repository = VoicevoxAudioRepository::new(mock_audio_generator, mock_audio_processor, mock_cacheable);
+
+        let actual = audio_repository.get(audio).await.unwrap();
+        assert_eq!(actual, vec![0x00, 0x01, 0x02, 0x03]);
+    }
+
+    #[tokio::test]
+    async fn get_morphed_audio() {
+        let audio = Audio {
+            text: "foo".to_string(),
+            speaker: "1".to_string(),
+            speed: NotNan::new(1.0).unwrap(),
+            pitch: NotNan::new(0.0).unwrap(),
+            intonation: NotNan::new(1.0).unwrap(),
+            morph: Some(Morph {
+                target: "2".to_string(),
+                rate: NotNan::new(0.5).unwrap(),
+            }),
+        };
+
+        let mut mock_cacheable = MockCacheable::new();
+        mock_cacheable.expect_should_cache().times(1).returning(|_| false);
+
+        let mut mock_audio_generator = MockAudioGenerator::new();
+        mock_audio_generator
+            .expect_generate_query()
+            .times(1)
+            .returning(|_, _, _, _, _| Box::pin(ok("foo".to_string())));
+        mock_audio_generator.expect_synthesize().never();
+        mock_audio_generator
+            .expect_synthesize_morphing()
+            .times(1)
+            .withf(|a, b, c, d| (a, b, c, d.as_str()) == ("1", "2", &0.5, "foo"))
+            .returning(|_, _, _, _| Box::pin(ok(vec![0x00, 0x01])));
+
+        let mock_audio_processor = MockAudioProcessor::new();
+
+        let audio_repository = VoicevoxAudioRepository::new(mock_audio_generator, mock_audio_processor, mock_cacheable);
+
+        let actual = audio_repository.get(audio).await.unwrap();
+        assert_eq!(actual, vec![0x00, 0x01]);
+    }
+
+    #[tokio::test]
+    async fn get_cached_audio() {
+        let audio = Audio {
+            text: "bar".to_string(),
+            speaker: "1".to_string(),
+            speed: NotNan::new(1.0).unwrap(),
+            pitch: NotNan::new(0.0).unwrap(),
+            intonation: NotNan::new(1.0).unwrap(),
+            morph: None,
+        };
+
+        let mut mock_cacheable = MockCacheable::new();
+        mock_cacheable
+            .expect_should_cache()
+            .times(1)
+            .withf(|x| x == "bar")
+            .returning(|_| true);
+
+        let mut mock_audio_generator = MockAudioGenerator::new();
+        mock_audio_generator
+            .expect_generate_query()
+            .times(1)
+            .withf(|a, b, c, d, e| (a, b, c, d, e) == ("1", "bar", &1.0, &0.0, &1.0))
+            .returning(|_, _, _, _, _| Box::pin(ok("bar".to_string())));
+        mock_audio_generator
+            .expect_synthesize()
+            .times(1)
+            .withf(|x, y| (x, y.as_str()) == ("1", "bar"))
+            .returning(|_, _| Box::pin(ok(vec![0x00, 0x01, 0x02, 0x03])));
+
+        let mut mock_audio_processor = MockAudioProcessor::new();
+        mock_audio_processor
+            .expect_compress()
+            .times(1)
+            .withf(|x| x == &[0x00, 0x01, 0x02, 0x03])
+            .returning(|_| Box::pin(ok(vec![0x04, 0x05])));
+
+        mock_audio_processor
+            .expect_to_input()
+            .times(2)
+            .withf(|x| x == &[0x04, 0x05])
+            .returning(|_| vec![0x00, 0x01, 0x02, 0x03]);
+
+        let audio_repository = VoicevoxAudioRepository::new(mock_audio_generator, mock_audio_processor, mock_cacheable);
+
+        let actual = audio_repository.fetch(audio.clone()).await.unwrap();
+        assert_eq!(actual, (vec![0x00, 0x01, 0x02, 0x03], false));
+
+        let actual = audio_repository.fetch(audio).await.unwrap();
+        assert_eq!(actual, (vec![0x00, 0x01, 0x02, 0x03], true));
+    }
+
+    #[tokio::test]
+    async fn get_audio_synthesized_from_same_query() {
+        let audio = Audio {
+            text: "ｗ".to_string(),
+            speaker: "1".to_string(),
+            speed: NotNan::new(1.0).unwrap(),
+            pitch: NotNan::new(0.0).unwrap(),
+            intonation: NotNan::new(1.0).unwrap(),
+            morph: None,
+        };
+        let equivalent_audio = Audio {
+            text: "w".to_string(),
+            ..audio.clone()
+        };
+
+        let mut mock_cacheable = MockCacheable::new();
+        mock_cacheable.expect_should_cache().times(2).returning(|_| false);
+
+        let mut mock_audio_generator = MockAudioGenerator::new();
+        mock_audio_generator
+            .expect_generate_query()
+            .times(2)
+            .returning(|_, _, _, _, _| Box::pin(ok("ダブリュー".to_string())));
+        mock_audio_generator
+            .expect_synthesize()
+            .times(1)
+            .withf(|x, y| (x, y.as_str()) == ("1", "ダブリュー"))
+            .returning(|_, _| Box::pin(ok(vec![0x00, 0x01, 0x02, 0x03])));
+
+        let mock_audio_processor = MockAudioProcessor::new();
+
+        let audio_repository = VoicevoxAudioRepository::new(mock_audio_generator, mock_audio_processor, mock_cacheable);
+
+        let actual = audio_repository.get(audio).await.unwrap();
+        assert_eq!(actual, vec![0x00, 0x01, 0x02, 0x03]);
+
+        let actual = audio_repository.get(equivalent_audio).await.unwrap();
+        assert_eq!(actual, vec![0x00, 0x01, 0x02, 0x03]);
+    }
+}
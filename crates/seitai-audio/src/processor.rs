@@ -5,15 +5,20 @@ use songbird::{
 };
 use voicevox::Bytes;
 
-pub(crate) struct SongbirdAudioProcessor;
+/// Compresses audio into Opus and plays it with songbird.
+#[derive(Clone)]
+pub struct SongbirdAudioProcessor;
 
+/// Converts synthesized audio into what a player takes.
 #[cfg_attr(test, mockall::automock(type Compressed = Vec<u8>; type Input = Vec<u8>; type Raw = Vec<u8>;))]
-pub(crate) trait AudioProcessor {
+pub trait AudioProcessor {
     type Compressed;
     type Input;
     type Raw;
 
+    /// Compresses audio to keep it in memory.
     fn compress(&self, raw: Self::Raw) -> impl Future<Output = Result<Self::Compressed>> + Send;
+    /// Creates a new input which plays compressed audio from the beginning.
     fn to_input(&self, compressed: &Self::Compressed) -> Self::Input;
 }
 
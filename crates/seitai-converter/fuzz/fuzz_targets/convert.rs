@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use seitai_converter::{ReadContext, code, convert_with_stages, markdown, to_full_width, to_half_width, to_katakana};
+
+fuzz_target!(|input: (u8, bool, bool, &str)| {
+    let (limit, flatten_markdown, omit_code, text) = input;
+    let context = ReadContext {
+        limit: limit.into(),
+        flatten_markdown,
+        omit_code,
+    };
+    convert_with_stages(&context, text);
+
+    to_katakana(to_half_width(to_full_width(text)));
+    markdown::flatten(text);
+    code::omit(text);
+    code::unwrap(text);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use seitai_converter::reading;
+
+fuzz_target!(|input: (&str, Vec<(&str, &str)>)| {
+    let (text, readings) = input;
+    reading::apply(text, &readings);
+});
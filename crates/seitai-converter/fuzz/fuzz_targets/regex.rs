@@ -0,0 +1,29 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use seitai_converter::{regex, soundmoji};
+
+fuzz_target!(|text: &str| {
+    let _ = soundmoji::parse(text);
+
+    for regex in [
+        &regex::CODE,
+        &regex::EMOJI,
+        &regex::FULL_GRAPHICAL_AND_IDEOGRAPHIC_SPACE,
+        &regex::HALF_GRAPHICAL,
+        &regex::HIRAGANA,
+        &regex::IDEOGRAPHIC_FULL_STOP,
+        &regex::LIST_ITEM,
+        &regex::MENTION_CHANNEL,
+        &regex::SOUNDMOJI,
+        &regex::TABLE_ROW,
+        &regex::URL,
+        &regex::W,
+        &regex::WW,
+        &regex::WORD,
+    ] {
+        for captures in regex.captures_iter(text) {
+            let _ = captures.iter().flatten().map(|m| m.as_str()).count();
+        }
+    }
+});
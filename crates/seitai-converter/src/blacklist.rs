@@ -0,0 +1,41 @@
+//! Words each guild bans from being read, which are muted before [`crate::reading::apply`] and [`crate::convert`].
+
+use std::borrow::Cow;
+
+use crate::reading;
+
+/// Placeholder of a muted word, which is put on its own line so that a beep sound can be played in place of it.
+pub const BEEP: &str = "\u{E000}";
+/// Replacement putting [`BEEP`] on its own line.
+pub const BEEP_LINE: &str = "\n\u{E000}\n";
+/// What a muted word is read as when no beep sound is played.
+pub const BEEP_READING: &str = "ピー";
+
+/// Replaces each banned word with the replacement, preferring the longest word at each position and ignoring ASCII case.
+pub fn mute<'a, Word>(text: &'a str, words: &[Word], replacement: &str) -> Cow<'a, str>
+where
+    Word: AsRef<str>,
+{
+    let words = words
+        .iter()
+        .map(|word| (word.as_ref(), replacement))
+        .collect::<Vec<_>>();
+    reading::apply(text, &words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BEEP, BEEP_LINE, BEEP_READING, mute};
+    use crate::{ReadContext, convert};
+
+    #[test]
+    fn mute_banned_words() {
+        let words = ["ばか", "NG"];
+        assert_eq!(mute("ばかとngとOK", &words, BEEP_READING), "ピーとピーとOK");
+
+        let muted = mute("これはばかです", &words, BEEP_LINE);
+        let lines = convert(&ReadContext::default(), &muted);
+        let lines = lines.split('\n').map(str::trim).collect::<Vec<_>>();
+        assert_eq!(lines, ["これは", BEEP, "です"]);
+    }
+}
@@ -13,7 +13,8 @@ const HIRAGANA_BEGIN: u32 = 'ぁ' as u32;
 const HIRAGANA_END: u32 = 'ゖ' as u32;
 const HIRAGANA_KATAKANA_DIFF: u32 = 0x60;
 
-pub(crate) fn to_full_width<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
+/// Converts half-width graphical characters into full-width ones.
+pub fn to_full_width<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
     let text = text.into();
     match regex::HALF_GRAPHICAL.replace_all(&text, |captures: &Captures| {
         captures[0]
@@ -32,7 +33,8 @@ pub(crate) fn to_full_width<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
     }
 }
 
-pub(crate) fn to_half_width<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
+/// Converts full-width graphical characters and ideographic spaces into half-width ones.
+pub fn to_half_width<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
     let text = text.into();
     match regex::FULL_GRAPHICAL_AND_IDEOGRAPHIC_SPACE.replace_all(&text, |captures: &Captures| {
         captures[0]
@@ -52,7 +54,8 @@ pub(crate) fn to_half_width<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
     }
 }
 
-pub(crate) fn to_katakana<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
+/// Converts hiragana into katakana.
+pub fn to_katakana<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
     let text = text.into();
     match regex::HIRAGANA.replace_all(&text, |captures: &Captures| {
         captures[0]
@@ -68,3 +71,50 @@ pub(crate) fn to_katakana<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
         Cow::Owned(owned) => Cow::Owned(owned),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::{to_full_width, to_half_width, to_katakana};
+
+    #[test]
+    fn convert_half_width_into_full_width() {
+        assert_eq!(to_full_width("Rust 1.86!"), "Ｒｕｓｔ １．８６！");
+        assert_eq!(to_full_width("!~"), "！～");
+        assert_eq!(to_full_width("全角ｗ"), "全角ｗ");
+    }
+
+    #[test]
+    fn convert_full_width_into_half_width() {
+        assert_eq!(to_half_width("Ｒｕｓｔ\u{3000}１．８６！"), "Rust 1.86!");
+        assert_eq!(to_half_width("！～"), "!~");
+        assert_eq!(to_half_width("half w"), "half w");
+    }
+
+    #[test]
+    fn convert_hiragana_into_katakana() {
+        assert_eq!(to_katakana("ぁあをんゔゕゖ"), "ァアヲンヴヵヶ");
+        assert_eq!(to_katakana("漢字とカタカナ"), "漢字トカタカナ");
+    }
+
+    #[test]
+    fn keep_characters_out_of_range() {
+        assert_eq!(to_full_width(" \u{7f}"), " \u{7f}");
+        assert_eq!(to_half_width("｟ー"), "｟ー");
+        assert_eq!(to_katakana("ゝゞ"), "ゝゞ");
+    }
+
+    #[test]
+    fn borrow_unchanged_text() {
+        assert!(matches!(to_full_width("全角"), Cow::Borrowed(_)));
+        assert!(matches!(to_half_width("half"), Cow::Borrowed(_)));
+        assert!(matches!(to_katakana("カタカナ"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn round_trip_width() {
+        let text = "seitai-bot v0.2.2 (#1)";
+        assert_eq!(to_half_width(to_full_width(text)), text);
+    }
+}
@@ -0,0 +1,129 @@
+use std::{borrow::Cow, ops::Range};
+
+const CODE_OMISSION: &str = "コード省略";
+const FENCE: &str = "```";
+
+/// Code in a message, fenced by three backticks or inline.
+struct Span {
+    /// Range including the backticks.
+    outer: Range<usize>,
+    /// Range of the code, without the language of a code block.
+    inner: Range<usize>,
+    block: bool,
+}
+
+/// Replaces code blocks and inline code with "コード省略", since code read character by character is meaningless.
+pub fn omit(text: &str) -> Cow<'_, str> {
+    replace(text, Some(CODE_OMISSION))
+}
+
+/// Strips the backticks of code blocks and inline code, and the languages of code blocks, to read the code as text.
+pub fn unwrap(text: &str) -> Cow<'_, str> {
+    replace(text, None)
+}
+
+fn replace<'a>(text: &'a str, omission: Option<&str>) -> Cow<'a, str> {
+    let spans = spans(text);
+    if spans.is_empty() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for span in spans {
+        replaced.push_str(&text[last..span.outer.start]);
+        let code = &text[span.inner];
+        match (omission, span.block) {
+            // 省略したものとコードブロックは前後の文と別に読み上げる
+            (Some(omission), _) => {
+                replaced.push('\n');
+                replaced.push_str(omission);
+                replaced.push('\n');
+            },
+            (None, true) => {
+                replaced.push('\n');
+                replaced.push_str(code.trim());
+                replaced.push('\n');
+            },
+            (None, false) => replaced.push_str(code),
+        }
+        last = span.outer.end;
+    }
+    replaced.push_str(&text[last..]);
+    Cow::Owned(replaced)
+}
+
+fn spans(text: &str) -> Vec<Span> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] != b'`' {
+            index += 1;
+            continue;
+        }
+        let run = bytes[index..].iter().take_while(|&&byte| byte == b'`').count();
+        let (fence, block) = match run >= FENCE.len() {
+            true => (FENCE, true),
+            false => (&text[index..index + run], false),
+        };
+        let start = index + fence.len();
+        match text[start..].find(fence) {
+            // 空のコードはコードとみなさない
+            Some(length) if length > 0 => {
+                let end = start + length;
+                let inner = match block {
+                    true => start + language_length(&text[start..end])..end,
+                    false => start..end,
+                };
+                spans.push(Span {
+                    outer: index..end + fence.len(),
+                    inner,
+                    block,
+                });
+                index = end + fence.len();
+            },
+            _ => index += run,
+        }
+    }
+    spans
+}
+
+// コードブロックの 1 行目に書かれた言語の長さ（改行を含む）
+fn language_length(code: &str) -> usize {
+    match code.split_once('\n') {
+        Some((language, _))
+            if !language.is_empty()
+                && language
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '_' | '#')) =>
+        {
+            language.len() + 1
+        },
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{omit, unwrap};
+
+    #[test]
+    fn omit_and_unwrap_code() {
+        assert_eq!(omit("コードはありません"), "コードはありません");
+        assert_eq!(omit("これは`cargo build`です"), "これは\nコード省略\nです");
+        assert_eq!(
+            omit("次のコード```rust\nlet tick = '`';\n```です"),
+            "次のコード\nコード省略\nです"
+        );
+        assert_eq!(omit("``a`b``と`c`"), "\nコード省略\nと\nコード省略\n");
+        // 閉じられていないバッククォートはそのまま
+        assert_eq!(omit("```未完"), "```未完");
+
+        assert_eq!(unwrap("これは`cargo build`です"), "これはcargo buildです");
+        assert_eq!(
+            unwrap("次のコード```rust\nfn main() {}\n```です"),
+            "次のコード\nfn main() {}\nです"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Readings of custom emoji registered by each guild, which are applied to the text before [`crate::convert`].
+
+use std::borrow::Cow;
+
+use crate::regex;
+
+/// Replaces each custom emoji with its reading, or with its name whose underscores are read as spaces if it has none.
+pub fn apply<'a, Reading>(text: &'a str, readings: &[(u64, Reading)]) -> Cow<'a, str>
+where
+    Reading: AsRef<str>,
+{
+    regex::EMOJI.replace_all(text, |captures: &lazy_regex::Captures| {
+        let reading = captures[2].parse::<u64>().ok().and_then(|emoji_id| {
+            readings
+                .iter()
+                .find(|(id, _)| *id == emoji_id)
+                .map(|(_, reading)| reading.as_ref())
+        });
+        match reading {
+            Some(reading) => reading.to_string(),
+            None => captures[1].replace('_', " "),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply;
+
+    #[test]
+    fn replace_emoji_with_reading_or_name() {
+        let readings = [(123456789012345678, "はれ")];
+        assert_eq!(apply("おはよう<:sunny:123456789012345678>", &readings), "おはようはれ");
+        assert_eq!(
+            apply("おはよう<a:party_parrot:876543210987654321>", &readings),
+            "おはようparty parrot"
+        );
+        assert_eq!(apply("おはよう", &readings), "おはよう");
+    }
+}
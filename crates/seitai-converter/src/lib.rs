@@ -0,0 +1,336 @@
+//! Conversion of Discord messages into text which VOICEVOX ENGINE reads aloud.
+//!
+//! [`convert`] is a pure function over already normalised text (i.e. mentions resolved into names), so it can be
+//! shared between the bot and anything previewing how a message is going to be read.
+
+use std::borrow::Cow;
+
+use lazy_regex::Regex;
+use wana_kana::ConvertJapanese;
+use whatlang::{Lang, detect_lang};
+
+pub mod blacklist;
+pub mod character;
+pub mod code;
+pub mod emoji;
+pub mod markdown;
+pub mod name;
+pub mod reading;
+pub mod regex;
+pub mod soundmoji;
+pub mod spoiler;
+
+pub use self::character::{to_full_width, to_half_width, to_katakana};
+
+const DEFAULT_LIMIT: usize = 150;
+const OMISSION: &str = "、以下省略";
+
+/// Settings the text is read with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadContext {
+    /// Number of characters beyond which the text is cut off and marked as omitted.
+    pub limit: usize,
+    /// Whether to read "以下省略" in place of the text cut off.
+    pub announce_omission: bool,
+    /// Whether to flatten markdown lists and omit tables.
+    pub flatten_markdown: bool,
+    /// Whether to omit code blocks and inline code, or to read the code without the backticks.
+    pub omit_code: bool,
+}
+
+/// Text after a stage of the conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    pub name: &'static str,
+    pub text: String,
+}
+
+enum Replacement {
+    General(&'static str, &'static Regex, &'static str),
+    Function(&'static str, fn(&str) -> Cow<'_, str>),
+    Katakana(&'static str, &'static Regex),
+}
+
+impl Default for ReadContext {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            announce_omission: true,
+            flatten_markdown: true,
+            omit_code: true,
+        }
+    }
+}
+
+/// Converts the text into what is read aloud. Each line of the result is meant to be synthesized separately.
+pub fn convert(context: &ReadContext, text: &str) -> String {
+    convert_inspecting(context, text, |_, _| {})
+}
+
+/// Converts the text like [`convert`], recording the text after each stage to show how it is going to be read.
+pub fn convert_with_stages(context: &ReadContext, text: &str) -> Vec<Stage> {
+    let mut stages = Vec::new();
+    convert_inspecting(context, text, |name, text| {
+        stages.push(Stage {
+            name,
+            text: text.to_string(),
+        })
+    });
+    stages
+}
+
+fn convert_inspecting(context: &ReadContext, text: &str, mut inspect: impl FnMut(&'static str, &str)) -> String {
+    let mut replacements = vec![
+        Replacement::Function(
+            "code",
+            match context.omit_code {
+                true => code::omit,
+                false => code::unwrap,
+            },
+        ),
+        Replacement::General("url", &regex::URL, "\nURL\n"),
+    ];
+    if context.flatten_markdown {
+        replacements.push(Replacement::Function("markdown", markdown::flatten));
+    }
+    replacements.extend([
+        Replacement::General("ww", &regex::WW, "$1ワラワラ$2"),
+        Replacement::General("w", &regex::W, "$1ワラ$2"),
+        Replacement::General("full_stop", &regex::IDEOGRAPHIC_FULL_STOP, "。\n"),
+        Replacement::General("emoji", &regex::EMOJI, ""), // 絵文字は読み上げない
+        Replacement::Katakana("romaji", &regex::WORD),
+    ]);
+
+    let replaced = replacements
+        .into_iter()
+        .fold(Cow::Borrowed(text), |accumulator, replacement| {
+            let (name, replaced) = match replacement {
+                Replacement::General(name, regex, replacer) => match regex.replace_all(&accumulator, replacer) {
+                    Cow::Borrowed(borrowed) if borrowed.len() == accumulator.len() => (name, accumulator),
+                    Cow::Borrowed(borrowed) => (name, Cow::Owned(borrowed.to_owned())),
+                    Cow::Owned(owned) => (name, Cow::Owned(owned)),
+                },
+                Replacement::Function(name, function) => match function(&accumulator) {
+                    Cow::Borrowed(_) => (name, accumulator),
+                    Cow::Owned(owned) => (name, Cow::Owned(owned)),
+                },
+                Replacement::Katakana(name, _regex) => {
+                    let cloned = accumulator.into_owned();
+                    let text_opt = detect_lang(&cloned);
+                    let replaced = text_opt
+                        .filter(|&opt| opt == Lang::Jpn)
+                        .map_or_else(|| cloned.to_hiragana(), |_| cloned.to_string());
+                    (name, Cow::Owned(replaced))
+                },
+            };
+            inspect(name, &replaced);
+            replaced
+        });
+
+    let omission = match context.announce_omission {
+        true => OMISSION,
+        false => "",
+    };
+    let truncated = truncate_message(&replaced, context.limit, omission).into_owned();
+    inspect("truncate", &truncated);
+    truncated
+}
+
+fn truncate_message<'a>(message: &'a str, limit: usize, suffix: &str) -> Cow<'a, str> {
+    match message.char_indices().nth(limit) {
+        Some((end, _)) => Cow::Owned(format!("{}{suffix}", &message[..end])),
+        None => Cow::Borrowed(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReadContext, convert, convert_with_stages, truncate_message};
+
+    fn convert_default(text: &str) -> String {
+        convert(&ReadContext::default(), text)
+    }
+
+    #[test]
+    fn keep_japanese_text() {
+        assert_eq!(convert_default("今日はいい天気ですね"), "今日はいい天気ですね");
+    }
+
+    #[test]
+    fn omit_inline_code() {
+        assert_eq!(
+            convert_default("これは`cargo build`を実行した結果です"),
+            "これは\nコード省略\nを実行した結果です"
+        );
+    }
+
+    #[test]
+    fn omit_code_block() {
+        assert_eq!(
+            convert_default("次のコードを見てください```fn main() {}```どうでしょうか"),
+            "次のコードを見てください\nコード省略\nどうでしょうか"
+        );
+    }
+
+    #[test]
+    fn omit_url() {
+        assert_eq!(
+            convert_default("詳しくは https://example.com/path?query=1 を見てください"),
+            "詳しくは \nURL\n を見てください"
+        );
+    }
+
+    #[test]
+    fn omit_url_with_custom_scheme() {
+        assert_eq!(
+            convert_default("設定は steam://open/friends から開けます"),
+            "設定は \nURL\n から開けます"
+        );
+    }
+
+    #[test]
+    fn read_single_w() {
+        assert_eq!(convert_default("それは面白いですね w"), "それは面白いですね ワラ");
+        assert_eq!(convert_default("それは面白いですね ｗ"), "それは面白いですね ワラ");
+    }
+
+    #[test]
+    fn read_repeated_w() {
+        assert_eq!(convert_default("それは面白いですね www"), "それは面白いですね ワラワラ");
+        assert_eq!(
+            convert_default("それは面白いですね ｗｗｗ"),
+            "それは面白いですね ワラワラ"
+        );
+        assert_eq!(convert_default("それは面白いですね wｗ"), "それは面白いですね ワラワラ");
+    }
+
+    #[test]
+    fn keep_w_in_word() {
+        assert_eq!(convert_default("新しいwindowを開きました"), "新しいwindowを開きました");
+    }
+
+    #[test]
+    fn split_lines_at_ideographic_full_stop() {
+        assert_eq!(
+            convert_default("今日は晴れです。明日は雨です。"),
+            "今日は晴れです。\n明日は雨です。\n"
+        );
+    }
+
+    #[test]
+    fn drop_custom_emoji() {
+        assert_eq!(
+            convert_default("おはようございます<:sunny:123456789012345678>"),
+            "おはようございます"
+        );
+        assert_eq!(
+            convert_default("おはようございます<a:party_parrot:123456789012345678>"),
+            "おはようございます"
+        );
+    }
+
+    #[test]
+    fn convert_romaji_into_hiragana() {
+        assert_eq!(convert_default("konnichiha"), "こんにちは");
+    }
+
+    #[test]
+    fn keep_latin_words_in_japanese_text() {
+        assert_eq!(
+            convert_default("明日はRustの勉強会に参加する予定です"),
+            "明日はRustの勉強会に参加する予定です"
+        );
+    }
+
+    #[test]
+    fn apply_replacements_in_order() {
+        // コードの中の URL や w は読まれない
+        assert_eq!(
+            convert_default("これを見てください`https://example.com www`とのことです"),
+            "これを見てください\nコード省略\nとのことです"
+        );
+    }
+
+    #[test]
+    fn keep_text_within_limit() {
+        let context = ReadContext {
+            limit: 30,
+            ..Default::default()
+        };
+        assert_eq!(convert(&context, "今日はいい天気ですね"), "今日はいい天気ですね");
+    }
+
+    #[test]
+    fn omit_text_beyond_limit() {
+        let context = ReadContext {
+            limit: 5,
+            ..Default::default()
+        };
+        assert_eq!(convert(&context, "今日はいい天気ですね"), "今日はいい、以下省略");
+    }
+
+    #[test]
+    fn cut_text_beyond_limit_silently() {
+        let context = ReadContext {
+            limit: 200,
+            announce_omission: false,
+            ..Default::default()
+        };
+        let text = "あ".repeat(300);
+        assert_eq!(convert(&context, &text), "あ".repeat(200));
+    }
+
+    #[test]
+    fn keep_lists_unless_flattening_markdown() {
+        let context = ReadContext {
+            flatten_markdown: false,
+            ..Default::default()
+        };
+        assert!(!convert(&context, "- 牛乳\n- 卵").contains("牛乳、卵"));
+        assert_eq!(convert_default("- 牛乳\n- 卵"), "牛乳、卵");
+    }
+
+    #[test]
+    fn read_code_unless_omitting() {
+        let context = ReadContext {
+            omit_code: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert(&context, "関数`合計`を呼び出してください"),
+            "関数合計を呼び出してください"
+        );
+    }
+
+    #[test]
+    fn record_text_after_each_stage() {
+        let stages = convert_with_stages(&ReadContext::default(), "`code` www。");
+        let stages = stages
+            .iter()
+            .map(|stage| (stage.name, stage.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stages,
+            [
+                ("code", "\nコード省略\n www。"),
+                ("url", "\nコード省略\n www。"),
+                ("markdown", "\nコード省略\n www。"),
+                ("ww", "\nコード省略\n ワラワラ。"),
+                ("w", "\nコード省略\n ワラワラ。"),
+                ("full_stop", "\nコード省略\n ワラワラ。\n"),
+                ("emoji", "\nコード省略\n ワラワラ。\n"),
+                ("romaji", "\nコード省略\n ワラワラ。\n"),
+                ("truncate", "\nコード省略\n ワラワラ。\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn truncate_by_characters() {
+        assert_eq!(truncate_message("abcdef", 6, "…"), "abcdef");
+        assert_eq!(truncate_message("abcdefg", 6, "…"), "abcdef…");
+        assert_eq!(truncate_message("あいう", 3, "…"), "あいう");
+        assert_eq!(truncate_message("あいうえ", 3, "…"), "あいう…");
+        assert_eq!(truncate_message("", 0, "…"), "");
+    }
+}
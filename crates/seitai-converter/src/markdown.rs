@@ -0,0 +1,80 @@
+use std::borrow::Cow;
+
+use crate::regex;
+
+const TABLE_OMISSION: &str = "表は省略";
+
+/// Flattens markdown lists into phrases separated by "、" and omits tables, which read awkwardly line by line.
+pub fn flatten(text: &str) -> Cow<'_, str> {
+    let lines = text.lines().collect::<Vec<_>>();
+    if !lines
+        .iter()
+        .any(|line| regex::LIST_ITEM.is_match(line) || regex::TABLE_ROW.is_match(line))
+    {
+        return Cow::Borrowed(text);
+    }
+
+    let mut flattened = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let table_rows = lines[index..]
+            .iter()
+            .take_while(|line| regex::TABLE_ROW.is_match(line))
+            .count();
+        // 1 行だけなら表ではなく | を含む文とみなす
+        if table_rows >= 2 {
+            flattened.push(TABLE_OMISSION.to_string());
+            index += table_rows;
+            continue;
+        }
+
+        let items = lines[index..]
+            .iter()
+            .map_while(|line| regex::LIST_ITEM.captures(line))
+            .filter_map(|captures| captures.get(1))
+            .map(|item| item.as_str().trim())
+            .collect::<Vec<_>>();
+        if !items.is_empty() {
+            index += items.len();
+            flattened.push(items.join("、"));
+            continue;
+        }
+
+        flattened.push(lines[index].to_string());
+        index += 1;
+    }
+
+    Cow::Owned(flattened.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flatten;
+
+    #[test]
+    fn flatten_bullets() {
+        assert_eq!(
+            flatten("買うもの\n- 牛乳\n- 卵\n* パン\nよろしく"),
+            "買うもの\n牛乳、卵、パン\nよろしく"
+        );
+    }
+
+    #[test]
+    fn flatten_numbered_list() {
+        assert_eq!(flatten("1. 起きる\n2. 食べる\n3) 寝る"), "起きる、食べる、寝る");
+    }
+
+    #[test]
+    fn omit_table() {
+        assert_eq!(
+            flatten("結果です\n| 名前 | 点数 |\n| --- | --- |\n| A | 10 |\n以上"),
+            "結果です\n表は省略\n以上"
+        );
+    }
+
+    #[test]
+    fn keep_text_without_markdown() {
+        assert_eq!(flatten("-3度です\n| だけの行"), "-3度です\n| だけの行");
+        assert_eq!(flatten("a|b|c"), "a|b|c");
+    }
+}
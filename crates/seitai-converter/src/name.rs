@@ -0,0 +1,66 @@
+//! Decorations around display names, such as pronoun tags and clan tags, which are left out when names are read.
+
+use std::borrow::Cow;
+
+use lazy_regex::{Lazy, Regex};
+
+/// Patterns of the decorations removed unless the guild configures its own: spoiler markers, pronoun tags like
+/// `(she/her)` and clan tags like `[ABC]` at the start.
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    r"\|\|",
+    r"[(\[（［【]\s*[A-Za-z]+(?:\s*/\s*[A-Za-z]+)+\s*[)\]）］】]",
+    r"^\s*[\[［【][^\]］】\s]{1,10}[\]］】]",
+];
+
+static DEFAULT: Lazy<Vec<Regex>> = Lazy::new(|| compile(DEFAULT_PATTERNS).expect("default name patterns are invalid"));
+
+/// Compiles the patterns of decorations.
+pub fn compile<Pattern>(patterns: &[Pattern]) -> Result<Vec<Regex>, lazy_regex::regex::Error>
+where
+    Pattern: AsRef<str>,
+{
+    patterns.iter().map(|pattern| Regex::new(pattern.as_ref())).collect()
+}
+
+/// Removes the decorations matching the patterns, or the default ones if `None`, from the name.
+///
+/// The name is left as it is if nothing but decorations would remain.
+pub fn strip_decorations<'a>(name: &'a str, patterns: Option<&[Regex]>) -> Cow<'a, str> {
+    let patterns = patterns.unwrap_or(&DEFAULT);
+    let mut stripped = Cow::Borrowed(name);
+    for pattern in patterns {
+        if let Cow::Owned(replaced) = pattern.replace_all(&stripped, "") {
+            stripped = Cow::Owned(replaced);
+        }
+    }
+
+    match stripped.trim() {
+        "" => Cow::Borrowed(name),
+        trimmed if trimmed.len() == name.len() => Cow::Borrowed(name),
+        trimmed => Cow::Owned(trimmed.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile, strip_decorations};
+
+    #[test]
+    fn strip_default_decorations() {
+        assert_eq!(strip_decorations("[ABC] Alice", None), "Alice");
+        assert_eq!(strip_decorations("【ABC】ボブ", None), "ボブ");
+        assert_eq!(strip_decorations("Alice (she/her)", None), "Alice");
+        assert_eq!(strip_decorations("Bob [he/they/them]", None), "Bob");
+        assert_eq!(strip_decorations("||Carol||", None), "Carol");
+        assert_eq!(strip_decorations("Alice", None), "Alice");
+        assert_eq!(strip_decorations("(she/her)", None), "(she/her)");
+    }
+
+    #[test]
+    fn strip_custom_decorations() {
+        let patterns = compile(&["^★", "★$"]).unwrap();
+        assert_eq!(strip_decorations("★Alice★", Some(&patterns)), "Alice");
+        assert_eq!(strip_decorations("[ABC] Alice", Some(&patterns)), "[ABC] Alice");
+        assert!(compile(&["("]).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Readings of words registered by each guild, which are applied to the text before [`crate::convert`].
+
+use std::borrow::Cow;
+
+/// Replaces each word with its reading, preferring the longest word at each position and ignoring ASCII case.
+pub fn apply<'a, Surface, Reading>(text: &'a str, readings: &[(Surface, Reading)]) -> Cow<'a, str>
+where
+    Surface: AsRef<str>,
+    Reading: AsRef<str>,
+{
+    let mut readings = readings
+        .iter()
+        .map(|(surface, reading)| (surface.as_ref(), reading.as_ref()))
+        .filter(|(surface, _)| !surface.is_empty())
+        .collect::<Vec<_>>();
+    if readings.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    readings.sort_by_key(|(surface, _)| std::cmp::Reverse(surface.len()));
+
+    let mut applied = String::with_capacity(text.len());
+    let mut is_applied = false;
+    let mut rest = text;
+    while let Some(character) = rest.chars().next() {
+        let matched = readings.iter().find(|(surface, _)| {
+            rest.get(..surface.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(surface))
+        });
+        match matched {
+            Some((surface, reading)) => {
+                applied.push_str(reading);
+                rest = &rest[surface.len()..];
+                is_applied = true;
+            },
+            None => {
+                applied.push(character);
+                rest = &rest[character.len_utf8()..];
+            },
+        }
+    }
+
+    match is_applied {
+        true => Cow::Owned(applied),
+        false => Cow::Borrowed(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::apply;
+
+    #[test]
+    fn replace_words_with_readings() {
+        let readings = [("seitai", "セイタイ"), ("VOICEVOX", "ボイスボックス")];
+        assert_eq!(
+            apply("seitaiはVOICEVOXで喋ります", &readings),
+            "セイタイはボイスボックスで喋ります"
+        );
+    }
+
+    #[test]
+    fn prefer_longest_word() {
+        let readings = [("東京", "トウキョウ"), ("東京都", "トウキョウト")];
+        assert_eq!(apply("東京都と東京", &readings), "トウキョウトとトウキョウ");
+    }
+
+    #[test]
+    fn ignore_ascii_case() {
+        let readings = [("Rust", "ラスト")];
+        assert_eq!(apply("RUSTとrust", &readings), "ラストとラスト");
+    }
+
+    #[test]
+    fn keep_text_without_words() {
+        let readings = [("Rust", "ラスト")];
+        assert!(matches!(apply("今日はいい天気ですね", &readings), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,17 @@
+use lazy_regex::{Lazy, Regex, lazy_regex};
+
+pub static CODE: Lazy<Regex> = lazy_regex!(r"(?:`[^`]+`|```[^`]+```)");
+pub static EMOJI: Lazy<Regex> = lazy_regex!(r"<(?:a)?:([[:word:]]+):(\d+)>");
+pub static FULL_GRAPHICAL_AND_IDEOGRAPHIC_SPACE: Lazy<Regex> = lazy_regex!(r"[\u3000！-～]+");
+pub static HALF_GRAPHICAL: Lazy<Regex> = lazy_regex!(r"[!-~]+");
+pub static HIRAGANA: Lazy<Regex> = lazy_regex!(r"[ぁ-ゖ]+");
+pub static IDEOGRAPHIC_FULL_STOP: Lazy<Regex> = lazy_regex!(r"。");
+pub static LIST_ITEM: Lazy<Regex> = lazy_regex!(r"^\s*(?:[-*+・]|\d+[.)])\s+(.*\S)\s*$");
+pub static MENTION_CHANNEL: Lazy<Regex> = lazy_regex!(r"<[@#].+>");
+pub static SOUNDMOJI: Lazy<Regex> = lazy_regex!(r"<sound:(?<guild_id>\d+):(?<sound_id>\d+)>");
+pub static SPOILER: Lazy<Regex> = lazy_regex!(r"(?s)\|\|(.+?)\|\|");
+pub static TABLE_ROW: Lazy<Regex> = lazy_regex!(r"^\s*\|.*\|\s*$");
+pub static URL: Lazy<Regex> = lazy_regex!(r"[[:alpha:]][[:alnum:]+\-.]*?://[^\s]+");
+pub static W: Lazy<Regex> = lazy_regex!(r"([^ｗ[:word:]]|^)[wｗ]([^ｗ[:word:]]|$)");
+pub static WW: Lazy<Regex> = lazy_regex!(r"([^ｗ[:word:]]|^)[wｗ]{2,}([^ｗ[:word:]]|$)");
+pub static WORD: Lazy<Regex> = lazy_regex!(r"[[:alpha:]'-]{2,}");
@@ -0,0 +1,40 @@
+use std::num::{NonZeroU64, ParseIntError};
+
+use crate::regex::SOUNDMOJI;
+
+/// Parses the first soundmoji like `<sound:123:456>` in the text into the ids of its guild and sound.
+///
+/// Ids which are zero or do not fit in `u64` are errors, since they would panic as ids of Discord.
+pub fn parse(text: &str) -> Result<Option<(NonZeroU64, NonZeroU64)>, ParseIntError> {
+    let Some(captures) = SOUNDMOJI.captures(text) else {
+        return Ok(None);
+    };
+    let guild_id = captures["guild_id"].parse::<NonZeroU64>()?;
+    let sound_id = captures["sound_id"].parse::<NonZeroU64>()?;
+
+    Ok(Some((guild_id, sound_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use super::parse;
+
+    fn ids(guild_id: u64, sound_id: u64) -> Option<(NonZeroU64, NonZeroU64)> {
+        Some((NonZeroU64::new(guild_id)?, NonZeroU64::new(sound_id)?))
+    }
+
+    #[test]
+    fn parse_soundmoji() {
+        assert_eq!(parse("<sound:123:456>"), Ok(ids(123, 456)));
+        assert_eq!(parse("鳴らす <sound:1:2> <sound:3:4>"), Ok(ids(1, 2)));
+        assert_eq!(parse("<sound:123>"), Ok(None));
+    }
+
+    #[test]
+    fn reject_invalid_ids() {
+        assert!(parse("<sound:123:99999999999999999999>").is_err());
+        assert!(parse("<sound:0:456>").is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use std::borrow::Cow;
+
+use lazy_regex::regex::NoExpand;
+
+use crate::regex;
+
+/// Replaces text hidden as spoilers with the utterance read on its own line, or removes it if `None`.
+pub fn replace<'a>(text: &'a str, utterance: Option<&str>) -> Cow<'a, str> {
+    let replacer = utterance.map_or_else(String::new, |utterance| format!("\n{utterance}\n"));
+    regex::SPOILER.replace_all(text, NoExpand(&replacer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::replace;
+
+    #[test]
+    fn replace_spoilers() {
+        assert_eq!(
+            replace("ネタバレはありません", Some("ネタバレ")),
+            "ネタバレはありません"
+        );
+        assert_eq!(
+            replace("犯人は||執事||でした", Some("ネタバレ")),
+            "犯人は\nネタバレ\nでした"
+        );
+        assert_eq!(replace("犯人は||執事\nと庭師||でした||", None), "犯人はでした||");
+        // 空の || は伏せ字ではない
+        assert_eq!(replace("a |||| b", None), "a |||| b");
+    }
+}
@@ -1,5 +1,7 @@
 pub mod response;
 
+use std::time::Duration;
+
 use anyhow::{Context as _, Result, bail};
 use http_body_util::{Empty, Full};
 use hyper::{StatusCode, body::Bytes};
@@ -7,10 +9,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use url::Url;
 
-use self::response::{PostAudioQueryResult, PostSynthesisResult};
+use self::response::{PostAudioQueryResult, PostMorphableTargetsResult, PostSynthesisResult};
 use crate::request::Request;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioQuery {
     #[serde(rename = "accent_phrases")]
@@ -26,7 +28,7 @@ pub struct AudioQuery {
     pub kana: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AccentPhrases {
     pub moras: Vec<Value>,
     pub accent: f32,
@@ -34,6 +36,36 @@ pub struct AccentPhrases {
     pub is_interrogative: bool,
 }
 
+impl AudioQuery {
+    /// Sets the speed scale adjusted to the length of the query and the given pitch and intonation scales.
+    pub fn scale(&mut self, speed: f32, pitch: f32, intonation: f32) {
+        // TODO: Truncate message too long
+        let mora_length = self
+            .accent_phrases
+            .iter()
+            .map(|accent_phrases| accent_phrases.moras.len())
+            .sum::<usize>();
+        self.speed_scale = speed + (mora_length / 50) as f32 * 0.1;
+        self.pitch_scale = pitch;
+        self.intonation_scale = intonation;
+    }
+
+    /// Length of the synthesized audio estimated from the lengths of the moras and the speed scale.
+    pub fn duration(&self) -> Duration {
+        let length = |mora: &Value, key: &str| mora.get(key).and_then(Value::as_f64).unwrap_or_default();
+        let moras = self
+            .accent_phrases
+            .iter()
+            .flat_map(|accent_phrase| accent_phrase.moras.iter().chain(Some(&accent_phrase.pause_mora)))
+            .map(|mora| length(mora, "consonant_length") + length(mora, "vowel_length"))
+            .sum::<f64>();
+        let seconds = moras / f64::from(self.speed_scale.max(f32::EPSILON))
+            + f64::from(self.pre_phoneme_length)
+            + f64::from(self.post_phoneme_length);
+        Duration::try_from_secs_f64(seconds).unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AudioGenerator {
     pub default_speed: f32,
@@ -83,27 +115,86 @@ impl AudioGenerator {
         }
     }
 
-    pub async fn generate(&self, speaker: &str, text: &str, speed: f32) -> Result<Audio> {
-        let mut audio_query = match self
+    pub async fn morphable_targets(&self, base_speakers: &[u32]) -> Result<PostMorphableTargetsResult> {
+        let (status, bytes) = self
+            .post(
+                "morphable_targets",
+                &[],
+                Full::<Bytes>::from(serde_json::to_string(base_speakers)?),
+            )
+            .await?;
+        match status {
+            StatusCode::OK => Ok(PostMorphableTargetsResult::Ok(serde_json::from_slice(&bytes)?)),
+            StatusCode::UNPROCESSABLE_ENTITY => Ok(PostMorphableTargetsResult::UnprocessableEntity(
+                serde_json::from_slice(&bytes)?,
+            )),
+            code => bail!("received unexpected {code} from POST morphable_targets"),
+        }
+    }
+
+    pub async fn synthesize_morphing(
+        &self,
+        base_speaker: &str,
+        target_speaker: &str,
+        rate: f32,
+        json: &str,
+    ) -> Result<PostSynthesisResult> {
+        let (status, bytes) = self
+            .post(
+                "synthesis_morphing",
+                &[
+                    ("base_speaker", base_speaker),
+                    ("target_speaker", target_speaker),
+                    ("morph_rate", &rate.to_string()),
+                ],
+                Full::<Bytes>::from(json.to_owned()),
+            )
+            .await?;
+        match status {
+            StatusCode::OK => Ok(PostSynthesisResult::Ok(bytes)),
+            StatusCode::UNPROCESSABLE_ENTITY => Ok(PostSynthesisResult::UnprocessableEntity(serde_json::from_slice(
+                &bytes,
+            )?)),
+            code => bail!("received unexpected {code} from POST synthesis_morphing"),
+        }
+    }
+
+    pub async fn generate(&self, speaker: &str, text: &str, speed: f32, pitch: f32, intonation: f32) -> Result<Audio> {
+        let audio_query = self.query(speaker, text, speed, pitch, intonation).await?;
+        self.synthesize_query(speaker, &audio_query).await
+    }
+
+    /// Generates the audio query of `text` with the speed scale adjusted to its length and the given pitch and
+    /// intonation scales.
+    pub async fn query(
+        &self,
+        speaker: &str,
+        text: &str,
+        speed: f32,
+        pitch: f32,
+        intonation: f32,
+    ) -> Result<AudioQuery> {
+        let mut audio_query = self.accent_phrases(speaker, text).await?;
+        audio_query.scale(speed, pitch, intonation);
+        Ok(audio_query)
+    }
+
+    /// Generates the audio query of `text` as the engine returns it, whose scales are yet to be applied.
+    pub async fn accent_phrases(&self, speaker: &str, text: &str) -> Result<AudioQuery> {
+        match self
             .generate_query(speaker, text)
             .await
             .with_context(|| format!("failed to generate audio query with `{text}`"))?
         {
-            PostAudioQueryResult::Ok(audio_query) => audio_query,
+            PostAudioQueryResult::Ok(audio_query) => Ok(audio_query),
             PostAudioQueryResult::UnprocessableEntity(error) => {
                 bail!(error.detail);
             },
-        };
-
-        // TODO: Truncate message too long
-        let mora_length = audio_query
-            .accent_phrases
-            .iter()
-            .map(|accent_phrases| accent_phrases.moras.len())
-            .sum::<usize>();
-        audio_query.speed_scale = speed + (mora_length / 50) as f32 * 0.1;
+        }
+    }
 
-        let json = serde_json::to_string(&audio_query)?;
+    pub async fn synthesize_query(&self, speaker: &str, audio_query: &AudioQuery) -> Result<Audio> {
+        let json = serde_json::to_string(audio_query)?;
         match self
             .synthesize(speaker, &json)
             .await
@@ -115,4 +206,43 @@ impl AudioGenerator {
             },
         }
     }
+
+    /// Whether `base_speaker` can be morphed with `target_speaker`.
+    pub async fn is_morphable(&self, base_speaker: u32, target_speaker: u32) -> Result<bool> {
+        match self
+            .morphable_targets(&[base_speaker])
+            .await
+            .with_context(|| format!("failed to get morphable targets of {base_speaker}"))?
+        {
+            PostMorphableTargetsResult::Ok(targets) => Ok(targets
+                .first()
+                .and_then(|targets| targets.get(&target_speaker.to_string()))
+                .is_some_and(|target| target.is_morphable)),
+            PostMorphableTargetsResult::UnprocessableEntity(error) => {
+                bail!(error.detail);
+            },
+        }
+    }
+
+    /// Synthesizes `audio_query` with the voice of `base_speaker` morphed with `target_speaker` at `rate`, where 0.0 is
+    /// the base voice and 1.0 is the target voice.
+    pub async fn synthesize_morphing_query(
+        &self,
+        base_speaker: &str,
+        target_speaker: &str,
+        rate: f32,
+        audio_query: &AudioQuery,
+    ) -> Result<Audio> {
+        let json = serde_json::to_string(audio_query)?;
+        match self
+            .synthesize_morphing(base_speaker, target_speaker, rate, &json)
+            .await
+            .with_context(|| format!("failed to synthesize with morphing with {json}"))?
+        {
+            PostSynthesisResult::Ok(audio) => Ok(audio),
+            PostSynthesisResult::UnprocessableEntity(error) => {
+                bail!(error.detail);
+            },
+        }
+    }
 }
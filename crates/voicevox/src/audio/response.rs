@@ -1,10 +1,20 @@
 use hyper::body::Bytes;
+use indexmap::IndexMap;
+use serde::Deserialize;
 
 use super::AudioQuery;
 use crate::response::UnprocessableEntity;
 
 pub type Audio = Bytes;
 
+/// Styles each base style can be morphed with, keyed by the style id.
+pub type MorphableTargets = Vec<IndexMap<String, MorphableTarget>>;
+
+#[derive(Debug, Deserialize)]
+pub struct MorphableTarget {
+    pub is_morphable: bool,
+}
+
 #[derive(Debug)]
 pub enum PostAudioQueryResult {
     Ok(AudioQuery),
@@ -16,3 +26,9 @@ pub enum PostSynthesisResult {
     Ok(Audio),
     UnprocessableEntity(UnprocessableEntity),
 }
+
+#[derive(Debug)]
+pub enum PostMorphableTargetsResult {
+    Ok(MorphableTargets),
+    UnprocessableEntity(UnprocessableEntity),
+}
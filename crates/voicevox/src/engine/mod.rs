@@ -0,0 +1,82 @@
+use anyhow::{Result, bail};
+use http_body_util::Empty;
+use hyper::{StatusCode, body::Bytes};
+use serde::Deserialize;
+use url::Url;
+
+use crate::request::Request;
+
+#[derive(Debug, Clone)]
+pub struct Engine {
+    pub(crate) base: Url,
+}
+
+/// Part of the manifest of the engine needed to tell how it synthesizes.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EngineManifest {
+    pub name: String,
+    pub brand_name: String,
+    pub default_sampling_rate: u32,
+}
+
+/// Devices the engine is able to synthesize on.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SupportedDevices {
+    pub cpu: bool,
+    pub cuda: bool,
+    pub dml: bool,
+}
+
+impl SupportedDevices {
+    /// Whether the engine is able to synthesize on a GPU with CUDA or DirectML.
+    pub fn has_gpu(&self) -> bool {
+        self.cuda || self.dml
+    }
+}
+
+impl Request for Engine {
+    fn base(&self) -> &Url {
+        &self.base
+    }
+}
+
+impl Engine {
+    pub async fn version(&self) -> Result<String> {
+        let (status, bytes) = self.get("version", &[]).await?;
+        match status {
+            StatusCode::OK => Ok(serde_json::from_slice(&bytes)?),
+            code => bail!("received unexpected {code} from GET version"),
+        }
+    }
+
+    pub async fn manifest(&self) -> Result<EngineManifest> {
+        let (status, bytes) = self.get("engine_manifest", &[]).await?;
+        match status {
+            StatusCode::OK => Ok(serde_json::from_slice(&bytes)?),
+            code => bail!("received unexpected {code} from GET engine_manifest"),
+        }
+    }
+
+    pub async fn supported_devices(&self) -> Result<SupportedDevices> {
+        let (status, bytes) = self.get("supported_devices", &[]).await?;
+        match status {
+            StatusCode::OK => Ok(serde_json::from_slice(&bytes)?),
+            code => bail!("received unexpected {code} from GET supported_devices"),
+        }
+    }
+
+    /// Loads the model of the style so that the first synthesis with it does not have to wait for loading.
+    pub async fn initialize_speaker(&self, speaker: &str) -> Result<()> {
+        let (status, _) = self
+            .post(
+                "initialize_speaker",
+                &[("speaker", speaker), ("skip_reinit", "true")],
+                Empty::<Bytes>::new(),
+            )
+            .await?;
+        match status {
+            StatusCode::NO_CONTENT => Ok(()),
+            code => bail!("received unexpected {code} from POST initialize_speaker"),
+        }
+    }
+}
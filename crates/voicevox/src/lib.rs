@@ -1,5 +1,6 @@
 pub mod audio;
 pub mod dictionary;
+pub mod engine;
 pub mod request;
 pub mod response;
 pub mod speaker;
@@ -1,11 +1,12 @@
 use anyhow::Result;
 use url::Url;
 
-use crate::{audio::AudioGenerator, dictionary::Dictionary, speaker::Speaker};
+use crate::{audio::AudioGenerator, dictionary::Dictionary, engine::Engine, speaker::Speaker};
 
 pub struct Voicevox {
     pub audio_generator: AudioGenerator,
     pub dictionary: Dictionary,
+    pub engine: Engine,
     pub speaker: Speaker,
 }
 
@@ -19,6 +20,7 @@ impl Voicevox {
                 default_speed: 1.2,
             },
             dictionary: Dictionary { base: base.clone() },
+            engine: Engine { base: base.clone() },
             speaker: Speaker { base },
         })
     }
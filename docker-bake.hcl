@@ -6,6 +6,10 @@ variable "VERSION" {
   default = "latest"
 }
 
+variable "GIT_COMMIT" {
+  default = ""
+}
+
 target "restarter" {
   target = "restarter"
   tags = [
@@ -16,6 +20,9 @@ target "restarter" {
 
 target "seitai" {
   target = "seitai"
+  args = {
+    GIT_COMMIT = GIT_COMMIT
+  }
   tags = [
     "ghcr.io/hexium310/seitai:latest",
     "ghcr.io/hexium310/seitai:${VERSION}"
@@ -0,0 +1,135 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use dashmap::DashSet;
+use database::PgPool;
+use seitai_audio::AudioRepository;
+use seitai_converter::ReadContext;
+use serenity::{all::GuildId, client::Context};
+use songbird::input::Input;
+
+use crate::{
+    cron::{LocalTime, Schedule},
+    settings_resolver::SettingsResolver,
+    utils::{enqueue, get_manager, system_audio},
+};
+
+/// Maximum number of characters of the text of each announcement.
+pub(crate) const MAX_ANNOUNCEMENT_CHARS: usize = 100;
+
+/// Scheduler which reads the announcements of each guild into its call at the times scheduled by `/announce`.
+#[derive(Debug, Default)]
+pub(crate) struct Announcer {
+    running: AtomicBool,
+}
+
+impl Announcer {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the schedules at the start of every minute, except in guilds where reading is paused.
+    pub(crate) fn spawn<Repository>(
+        self: &Arc<Self>,
+        context: Context,
+        database: PgPool,
+        settings: Arc<SettingsResolver>,
+        paused_guilds: Arc<DashSet<GuildId>>,
+        audio_repository: Repository,
+    ) where
+        Repository: AudioRepository<Input = Input> + Send + Sync + 'static,
+    {
+        if self.running.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let mut last_minute = None;
+            loop {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                let minute = now.as_secs() / 60;
+                // 早く起きても同じ分に二度読み上げない
+                if last_minute != Some(minute) {
+                    last_minute = Some(minute);
+                    let time = LocalTime::from_unix(minute * 60);
+                    announce(&context, &database, &settings, &paused_guilds, &audio_repository, &time).await;
+                }
+
+                let elapsed = Duration::from_millis((now.as_millis() % 60_000) as u64);
+                tokio::time::sleep(Duration::from_secs(60) - elapsed).await;
+            }
+        });
+    }
+}
+
+async fn announce<Repository>(
+    context: &Context,
+    database: &PgPool,
+    settings: &SettingsResolver,
+    paused_guilds: &DashSet<GuildId>,
+    audio_repository: &Repository,
+    time: &LocalTime,
+) where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let announcements = match database::scheduled_announcement::fetch_all(database).await {
+        Ok(announcements) => announcements,
+        Err(error) => {
+            tracing::error!("failed to fetch scheduled announcements\nError: {error:?}");
+            return;
+        },
+    };
+    let due = announcements
+        .into_iter()
+        .filter(|announcement| {
+            announcement
+                .schedule
+                .parse::<Schedule>()
+                .is_ok_and(|schedule| schedule.matches(time))
+        })
+        .collect::<Vec<_>>();
+    if due.is_empty() {
+        return;
+    }
+
+    let manager = match get_manager(context).await {
+        Ok(manager) => manager,
+        Err(error) => {
+            tracing::error!("failed to get manager to read scheduled announcements\nError: {error:?}");
+            return;
+        },
+    };
+
+    for announcement in due {
+        let guild_id = GuildId::new(announcement.guild_id as u64);
+        if paused_guilds.contains(&guild_id) {
+            continue;
+        }
+        // 接続していないサーバーでは読み上げない
+        let Some(call) = manager.get(guild_id) else {
+            continue;
+        };
+
+        let settings = settings.guild(guild_id).await;
+        let audio = system_audio(
+            seitai_converter::convert(&ReadContext::default(), &announcement.text),
+            settings.default_speaker_id,
+        );
+        let input = match audio_repository.get(audio).await {
+            Ok(input) => input,
+            Err(error) => {
+                tracing::error!(
+                    "failed to get audio source to read scheduled announcement {} in guild {guild_id}\nError: {error:?}",
+                    announcement.id
+                );
+                continue;
+            },
+        };
+        enqueue(&mut *call.lock().await, input, settings.volume).await;
+    }
+}
@@ -0,0 +1,264 @@
+//! REST API to edit the user dictionary in bulk from a web UI, validated the same way as `/dictionary`.
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::{
+    Method, Request, Response, StatusCode,
+    body::{Bytes, Incoming},
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_util::rt::TokioIo;
+use seitai_converter::to_half_width;
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+use voicevox::dictionary::Dictionary;
+
+use crate::dictionary::{self, DictionaryError, Upsert, WordRequest};
+
+// 一度に送られる単語の一覧を読み込む最大サイズ
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+pub(crate) struct Api {
+    pub(crate) dictionary: Dictionary,
+    pub(crate) database: PgPool,
+    pub(crate) token: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WordEntry {
+    id: Uuid,
+    surface: String,
+    pronunciation: String,
+    accent_type: u32,
+    priority: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct Outcome {
+    surface: String,
+    result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl Outcome {
+    fn new(surface: String, result: Result<(&'static str, Uuid), DictionaryError>) -> Self {
+        match result {
+            Ok((result, id)) => Self {
+                surface,
+                result,
+                id: Some(id),
+                detail: None,
+            },
+            Err(error) => {
+                let result = match error {
+                    DictionaryError::Invalid(_) => "invalid",
+                    DictionaryError::QuotaExceeded(_) => "quota_exceeded",
+                    DictionaryError::NotFound => "not_found",
+                    DictionaryError::Rejected(_) => "rejected",
+                    DictionaryError::Failed(ref error) => {
+                        tracing::error!("failed to edit {surface} in dictionary through api\nError: {error:?}");
+                        "failed"
+                    },
+                };
+                Self {
+                    surface,
+                    result,
+                    id: None,
+                    detail: Some(error.to_string()),
+                }
+            },
+        }
+    }
+}
+
+/// Serves the API at `addr` until the process exits.
+///
+/// - `GET /guilds/{guild_id}/dictionary/words` lists the words in the dictionary.
+/// - `POST /guilds/{guild_id}/dictionary/words` registers or updates the words in the JSON array of the body.
+/// - `DELETE /guilds/{guild_id}/dictionary/words` deletes the words whose surfaces are in the JSON array of the body.
+///
+/// Every request needs `Authorization: Bearer <token>`.
+pub(crate) async fn serve(addr: SocketAddr, api: Api) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind api to {addr}"))?;
+    let api = Arc::new(api);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(error) => {
+                tracing::error!("failed to accept api connection\nError: {error:?}");
+                continue;
+            },
+        };
+        let api = api.clone();
+        tokio::spawn(async move {
+            let service = service_fn(|request| {
+                let api = api.clone();
+                async move { Ok::<_, Infallible>(api.handle(request).await) }
+            });
+            if let Err(error) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("api connection closed with error\nError: {error:?}");
+            }
+        });
+    }
+}
+
+impl Api {
+    async fn handle(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        if !self.authorized(&request) {
+            return error_response(StatusCode::UNAUTHORIZED, "invalid token");
+        }
+
+        let segments = request
+            .uri()
+            .path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        let guild_id = match segments.as_slice() {
+            ["guilds", guild_id, "dictionary", "words"] => match guild_id.parse::<u64>() {
+                Ok(guild_id) => guild_id,
+                Err(_) => return error_response(StatusCode::BAD_REQUEST, "invalid guild id"),
+            },
+            _ => return error_response(StatusCode::NOT_FOUND, "not found"),
+        };
+
+        match request.method().clone() {
+            Method::GET => self.list().await,
+            Method::POST => match read_json::<Vec<WordRequest>>(request).await {
+                Ok(words) => self.upsert(guild_id, words).await,
+                Err(response) => response,
+            },
+            Method::DELETE => match read_json::<Vec<String>>(request).await {
+                Ok(surfaces) => self.delete(surfaces).await,
+                Err(response) => response,
+            },
+            _ => error_response(StatusCode::METHOD_NOT_ALLOWED, "method not allowed"),
+        }
+    }
+
+    fn authorized(&self, request: &Request<Incoming>) -> bool {
+        request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token == self.token)
+    }
+
+    async fn list(&self) -> Response<Full<Bytes>> {
+        match dictionary::list(&self.dictionary).await {
+            Ok(list) => {
+                let words = list
+                    .into_iter()
+                    .map(|(id, item)| WordEntry {
+                        id,
+                        surface: to_half_width(item.surface).into_owned(),
+                        pronunciation: item.pronunciation,
+                        accent_type: item.accent_type,
+                        priority: item.priority,
+                    })
+                    .collect::<Vec<_>>();
+                json(StatusCode::OK, &words)
+            },
+            Err(error) => {
+                tracing::error!("failed to get dictionary through api\nError: {error:?}");
+                error_response(StatusCode::BAD_GATEWAY, "failed to get dictionary")
+            },
+        }
+    }
+
+    async fn upsert(&self, guild_id: u64, words: Vec<WordRequest>) -> Response<Full<Bytes>> {
+        let mut outcomes = Vec::with_capacity(words.len());
+        // 上限の確認に前の単語の登録が反映されるように 1 語ずつ処理する
+        for word in words {
+            let surface = word.surface.clone();
+            let result = match word.validate() {
+                Ok(word) => dictionary::upsert(&self.dictionary, &self.database, guild_id, &word)
+                    .await
+                    .map(|upserted| match upserted {
+                        Upsert::Registered(id) => ("registered", id),
+                        Upsert::Updated(id) => ("updated", id),
+                    }),
+                Err(error) => Err(error),
+            };
+            outcomes.push(Outcome::new(surface, result));
+        }
+        json(StatusCode::OK, &outcomes)
+    }
+
+    async fn delete(&self, surfaces: Vec<String>) -> Response<Full<Bytes>> {
+        let mut outcomes = Vec::with_capacity(surfaces.len());
+        for surface in surfaces {
+            let result = dictionary::delete(&self.dictionary, &self.database, &surface)
+                .await
+                .map(|id| ("deleted", id));
+            outcomes.push(Outcome::new(surface, result));
+        }
+        json(StatusCode::OK, &outcomes)
+    }
+}
+
+async fn read_json<T>(request: Request<Incoming>) -> Result<T, Response<Full<Bytes>>>
+where
+    T: DeserializeOwned,
+{
+    let body = match Limited::new(request.into_body(), MAX_BODY_SIZE).collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(error) if error.is::<LengthLimitError>() => {
+            return Err(error_response(StatusCode::PAYLOAD_TOO_LARGE, "body is too large"));
+        },
+        Err(_) => return Err(error_response(StatusCode::BAD_REQUEST, "failed to read body")),
+    };
+    serde_json::from_slice(&body).map_err(|error| error_response(StatusCode::BAD_REQUEST, &error.to_string()))
+}
+
+fn json<T>(status: StatusCode, body: &T) -> Response<Full<Bytes>>
+where
+    T: Serialize,
+{
+    match serde_json::to_vec(body) {
+        Ok(body) => {
+            let mut response = Response::new(Full::new(Bytes::from(body)));
+            *response.status_mut() = status;
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, "application/json".parse().unwrap());
+            response
+        },
+        Err(error) => {
+            tracing::error!("failed to serialize api response\nError: {error:?}");
+            let mut response = Response::new(Full::default());
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        },
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    json(
+        status,
+        &ErrorBody {
+            error: message.to_string(),
+        },
+    )
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use crate::utils::truncate_chars;
+
+// 名前を読み上げるファイルの最大数（これより多ければ数だけを読み上げる）
+const MAX_NAMED_FILES: usize = 3;
+// 読み上げるファイル名の最大文字数
+const MAX_FILE_NAME_CHARS: usize = 30;
+// Discord でネタバレとして伏せられたファイルの名前の接頭辞
+const SPOILER_PREFIX: &str = "SPOILER_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Image,
+    Video,
+    Audio,
+    File,
+}
+
+impl Kind {
+    fn of(file_name: &str, content_type: Option<&str>) -> Self {
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match (
+            content_type.and_then(|content_type| content_type.split('/').next()),
+            extension.as_deref(),
+        ) {
+            (Some("image"), _) | (None, Some("png" | "jpg" | "jpeg" | "gif" | "webp" | "avif")) => Self::Image,
+            (Some("video"), _) | (None, Some("mp4" | "mov" | "webm")) => Self::Video,
+            (Some("audio"), _) | (None, Some("mp3" | "wav" | "ogg" | "m4a" | "flac")) => Self::Audio,
+            _ => Self::File,
+        }
+    }
+}
+
+/// Text read for the files attached to a message, which tells how many images, videos and audio there are and the names
+/// of the other files, or `None` if there is none.
+///
+/// The names of files hidden as spoilers are not read, and only the number of files is read when there are many.
+pub(crate) fn describe<'a>(attachments: impl IntoIterator<Item = (&'a str, Option<&'a str>)>) -> Option<String> {
+    let mut counts = [0; 4];
+    let mut files = Vec::new();
+    let mut hidden = 0;
+    for (file_name, content_type) in attachments {
+        let kind = Kind::of(file_name, content_type);
+        counts[kind as usize] += 1;
+        if kind == Kind::File {
+            match file_name.starts_with(SPOILER_PREFIX) {
+                true => hidden += 1,
+                false => files.push(file_name),
+            }
+        }
+    }
+
+    let [images, videos, audio, file_count] = counts;
+    let mut parts = Vec::new();
+    if images > 0 {
+        parts.push(format!("画像を{images}枚"));
+    }
+    if videos > 0 {
+        parts.push(format!("動画を{videos}本"));
+    }
+    if audio > 0 {
+        parts.push(format!("音声を{audio}件"));
+    }
+    if file_count > MAX_NAMED_FILES || (hidden > 0 && files.is_empty()) {
+        parts.push(format!("ファイルを{file_count}件"));
+    } else if file_count > 0 {
+        let names = files
+            .iter()
+            .map(|file_name| {
+                let mut name = file_name.to_string();
+                truncate_chars(&mut name, MAX_FILE_NAME_CHARS);
+                name
+            })
+            .collect::<Vec<_>>();
+        let others = match hidden {
+            0 => String::new(),
+            hidden => format!("ほか{hidden}件"),
+        };
+        parts.push(format!("ファイル「{}」{others}を", names.join("、")));
+    }
+
+    match parts.is_empty() {
+        true => None,
+        false => Some(format!("{}送信しました", parts.join("、"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe;
+
+    #[test]
+    fn describe_attachments() {
+        assert_eq!(describe([]), None);
+        assert_eq!(
+            describe([("a.png", Some("image/png")), ("b.JPG", None), ("c", Some("image/jpeg"))]).as_deref(),
+            Some("画像を3枚送信しました")
+        );
+        assert_eq!(
+            describe([("clip.mp4", Some("video/mp4")), ("memo.txt", Some("text/plain"))]).as_deref(),
+            Some("動画を1本、ファイル「memo.txt」を送信しました")
+        );
+        assert_eq!(
+            describe([("SPOILER_memo.txt", None)]).as_deref(),
+            Some("ファイルを1件送信しました")
+        );
+        assert_eq!(
+            describe([("1.txt", None), ("2.txt", None), ("3.txt", None), ("4.txt", None)]).as_deref(),
+            Some("ファイルを4件送信しました")
+        );
+    }
+}
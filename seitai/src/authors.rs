@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serenity::all::{GuildId, UserId};
+
+/// Author who last spoke in each guild, to read the name of the author only when a new one starts speaking.
+#[derive(Debug, Default)]
+pub(crate) struct LastAuthors {
+    guilds: DashMap<GuildId, (UserId, Instant)>,
+}
+
+impl LastAuthors {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the author of a message and returns whether it starts a new burst, i.e. someone else spoke last or the
+    /// author has been silent for the interval.
+    pub(crate) fn record(&self, guild_id: GuildId, user_id: UserId, now: Instant, interval: Duration) -> bool {
+        match self.guilds.insert(guild_id, (user_id, now)) {
+            Some((last_user_id, spoken_at)) => {
+                last_user_id != user_id || now.saturating_duration_since(spoken_at) >= interval
+            },
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use serenity::all::{GuildId, UserId};
+
+    use super::LastAuthors;
+
+    const INTERVAL: Duration = Duration::from_secs(3 * 60);
+
+    #[test]
+    fn start_burst_on_new_author() {
+        let authors = LastAuthors::new();
+        let guild_id = GuildId::new(1);
+        let now = Instant::now();
+
+        assert!(authors.record(guild_id, UserId::new(1), now, INTERVAL));
+        assert!(!authors.record(guild_id, UserId::new(1), now + Duration::from_secs(10), INTERVAL));
+        assert!(authors.record(guild_id, UserId::new(2), now + Duration::from_secs(20), INTERVAL));
+        assert!(authors.record(guild_id, UserId::new(1), now + Duration::from_secs(30), INTERVAL));
+        // 他のサーバーの発言とは区別する
+        assert!(authors.record(GuildId::new(2), UserId::new(1), now + Duration::from_secs(40), INTERVAL));
+    }
+
+    #[test]
+    fn start_burst_after_silence() {
+        let authors = LastAuthors::new();
+        let guild_id = GuildId::new(1);
+        let now = Instant::now();
+
+        assert!(authors.record(guild_id, UserId::new(1), now, INTERVAL));
+        assert!(authors.record(guild_id, UserId::new(1), now + INTERVAL, INTERVAL));
+        // 間隔が 0 なら毎回名前を読み上げる
+        assert!(authors.record(guild_id, UserId::new(1), now + INTERVAL, Duration::ZERO));
+    }
+}
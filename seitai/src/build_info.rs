@@ -0,0 +1,6 @@
+/// Version of seitai.
+pub(crate) const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Short hash of the commit seitai was built from, or `unknown`.
+pub(crate) const GIT_COMMIT: &str = env!("SEITAI_GIT_COMMIT");
+/// Repository of seitai.
+pub(crate) const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
@@ -0,0 +1,61 @@
+use std::{num::NonZeroUsize, sync::Mutex};
+
+use lru::LruCache;
+use serenity::all::MessageId;
+
+/// Name of the sound played on celebration unless the guild chooses another one.
+pub(crate) const DEFAULT_CELEBRATION_SOUND: &str = "celebration";
+
+// 祝ったことを覚えておくメッセージの数
+const CAPACITY: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+
+/// Messages the celebration sound has been played for, so that each message is celebrated only once.
+pub(crate) struct Celebrations {
+    celebrated: Mutex<LruCache<MessageId, ()>>,
+}
+
+impl Celebrations {
+    pub(crate) fn new() -> Self {
+        Self {
+            celebrated: Mutex::new(LruCache::new(CAPACITY)),
+        }
+    }
+
+    /// Marks the message as celebrated and returns whether it has not been yet.
+    pub(crate) fn mark(&self, message_id: MessageId) -> bool {
+        self.celebrated
+            .lock()
+            .expect("celebrations have been poisoned")
+            .put(message_id, ())
+            .is_none()
+    }
+}
+
+/// Whether the counts of each reaction on a message add up to the threshold, which is never reached unless positive.
+pub(crate) fn reaches(counts: impl IntoIterator<Item = u64>, threshold: i32) -> bool {
+    let total = counts.into_iter().sum::<u64>();
+    u64::try_from(threshold).is_ok_and(|threshold| threshold > 0 && total >= threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::MessageId;
+
+    use super::{Celebrations, reaches};
+
+    #[test]
+    fn celebrate_message_once() {
+        let celebrations = Celebrations::new();
+        assert!(celebrations.mark(MessageId::new(1)));
+        assert!(!celebrations.mark(MessageId::new(1)));
+        assert!(celebrations.mark(MessageId::new(2)));
+    }
+
+    #[test]
+    fn reach_threshold_in_total() {
+        assert!(!reaches([2, 1], 5));
+        assert!(reaches([3, 2], 5));
+        assert!(reaches([6], 5));
+        assert!(!reaches([6], 0));
+    }
+}
@@ -0,0 +1,42 @@
+/// Prefixes of commands of common bots, used unless the guild configures its own.
+pub(crate) const DEFAULT_COMMAND_PREFIXES: &[&str] = &["/", "!", "?", "$", ";", ".", "-", "m!", "t!", "k!"];
+
+/// Whether the text looks like a command, i.e. one of the prefixes immediately followed by an ASCII letter.
+///
+/// Requiring a letter keeps ordinary messages such as `!!` or `...そうなんだ` from being taken as commands.
+pub(crate) fn is_command_like<'a>(text: &str, prefixes: impl IntoIterator<Item = &'a str>) -> bool {
+    let text = text.trim_start();
+    prefixes.into_iter().any(|prefix| {
+        text.strip_prefix(prefix)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|next| next.is_ascii_alphabetic())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_COMMAND_PREFIXES, is_command_like};
+
+    #[test]
+    fn detect_commands() {
+        let prefixes = DEFAULT_COMMAND_PREFIXES.iter().copied();
+        assert!(is_command_like("/join", prefixes.clone()));
+        assert!(is_command_like("!play https://example.com", prefixes.clone()));
+        assert!(is_command_like("m!skip", prefixes.clone()));
+    }
+
+    #[test]
+    fn keep_ordinary_messages() {
+        let prefixes = DEFAULT_COMMAND_PREFIXES.iter().copied();
+        assert!(!is_command_like("!!", prefixes.clone()));
+        assert!(!is_command_like("...そうなんだ", prefixes.clone()));
+        assert!(!is_command_like("?なにそれ", prefixes.clone()));
+        assert!(!is_command_like("こんにちは", prefixes.clone()));
+    }
+
+    #[test]
+    fn detect_commands_with_custom_prefixes() {
+        assert!(is_command_like("%help", ["%"]));
+        assert!(!is_command_like("!help", ["%"]));
+    }
+}
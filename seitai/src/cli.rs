@@ -3,8 +3,9 @@ use std::process;
 use anyhow::Result;
 use clap::{Parser, error::ErrorKind};
 use database::migrations::{MigrationCommand, Migrator};
+use logging::LogFilter;
 
-use crate::{set_up_database, start_bot};
+use crate::{replay::ReplayCommand, set_up_database, sounds::SoundsCommand, start_bot};
 
 pub struct Application;
 
@@ -19,14 +20,17 @@ pub struct Cli {
 #[derive(clap::Subcommand)]
 enum Subcommand {
     Migration(MigrationCommand),
+    /// Reproduces how a message is read in a guild
+    Replay(ReplayCommand),
+    Sounds(SoundsCommand),
 }
 
 impl Application {
-    pub async fn start() -> Result<()> {
+    pub async fn start(log_filter: LogFilter) -> Result<()> {
         let cli = match Cli::try_parse() {
             Ok(cli) => cli,
             Err(err) if err.kind() == ErrorKind::MissingSubcommand => {
-                start_bot().await;
+                start_bot(log_filter).await;
                 return Ok(());
             },
             Err(help) => {
@@ -35,15 +39,23 @@ impl Application {
             },
         };
 
-        let migrator = Migrator::new();
+        // サウンドの整理にはデータベースを使わない
+        if let Subcommand::Sounds(sounds) = &cli.subcommand {
+            return sounds.run();
+        }
+
         let pgpool = set_up_database().await?;
 
-        #[allow(irrefutable_let_patterns)]
-        if let Subcommand::Migration(migration) = cli.subcommand {
-            migration
-                .run(&mut *pgpool.acquire().await?, migrator.into_boxed_inner())
-                .await?;
-            process::exit(0);
+        match cli.subcommand {
+            Subcommand::Migration(migration) => {
+                let migrator = Migrator::new();
+                migration
+                    .run(&mut *pgpool.acquire().await?, migrator.into_boxed_inner())
+                    .await?;
+                process::exit(0);
+            },
+            Subcommand::Replay(replay) => replay.run(&pgpool).await?,
+            Subcommand::Sounds(_) => unreachable!(),
         }
 
         Ok(())
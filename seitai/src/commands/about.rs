@@ -0,0 +1,71 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use serenity::{
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::{
+    build_info,
+    utils::{format_duration, get_voicevox, respond},
+};
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    started_at: Instant,
+    support_server_url: Option<&str>,
+) -> Result<()> {
+    let engine_version = {
+        let voicevox = get_voicevox(context).await.ok_or(CommandError::EngineUnavailable)?;
+        let voicevox = voicevox.lock().await;
+        voicevox.engine.version().await
+    };
+    let engine_version = match engine_version {
+        Ok(version) => version,
+        Err(error) => {
+            tracing::error!("failed to get voicevox engine version\nError: {error:?}");
+            "取得できませんでした".to_string()
+        },
+    };
+
+    let bot_id = context.cache.current_user().id;
+    let permissions = Permissions::VIEW_CHANNEL
+        | Permissions::SEND_MESSAGES
+        | Permissions::EMBED_LINKS
+        | Permissions::CONNECT
+        | Permissions::SPEAK;
+    let invite_url = format!(
+        "https://discord.com/oauth2/authorize?client_id={bot_id}&scope=bot+applications.commands&permissions={}",
+        permissions.bits()
+    );
+
+    let mut links = vec![
+        format!("[招待]({invite_url})"),
+        format!("[リポジトリ]({})", build_info::REPOSITORY),
+    ];
+    if let Some(support_server_url) = support_server_url {
+        links.push(format!("[サポートサーバー]({support_server_url})"));
+    }
+
+    let message = CreateInteractionResponseMessage::new().embed(
+        CreateEmbed::new()
+            .title("seitai について")
+            .field("バージョン", build_info::VERSION, true)
+            .field("コミット", format!("`{}`", build_info::GIT_COMMIT), true)
+            .field("VOICEVOX ENGINE", engine_version, true)
+            .field("稼働時間", format_duration(started_at.elapsed()), true)
+            .field("リンク", links.join(" / "), false)
+            .colour(Colour::FOOYOO),
+    );
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("about").description("バージョンや稼働時間など、bot の情報を表示します。")
+}
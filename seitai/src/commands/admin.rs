@@ -0,0 +1,105 @@
+use anyhow::{Context as _, Result};
+use logging::LogFilter;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use crate::utils::{is_owner, respond};
+
+use super::subcommand::Subcommand;
+
+const LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, log_filter: &LogFilter) -> Result<()> {
+    if !is_owner(context, interaction.user.id).await? {
+        let message = CreateInteractionResponseMessage::new()
+            .embed(
+                CreateEmbed::new()
+                    .description("このコマンドは bot のオーナーのみ使用できます。")
+                    .colour(Colour::RED),
+            )
+            .ephemeral(true);
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /admin subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+
+    match subcommand.name {
+        "loglevel" => {
+            let target = subcommand
+                .options
+                .get("target")
+                .and_then(|v| v.as_str())
+                .context("no target option")?;
+            let level = subcommand
+                .options
+                .get("level")
+                .and_then(|v| v.as_str())
+                .context("no level option")?;
+
+            let embed = match log_filter.set_level(target, level) {
+                Ok(directives) => {
+                    tracing::info!("log filter has been changed to {directives} by {}", interaction.user.id);
+                    CreateEmbed::new()
+                        .description(format!("`{target}` のログレベルを `{level}` に変更しました。"))
+                        .field("フィルター", format!("`{directives}`"), false)
+                        .colour(Colour::FOOYOO)
+                },
+                Err(error) => {
+                    tracing::error!("failed to change log level\nError: {error:?}");
+                    CreateEmbed::new()
+                        .description("ログレベルの変更に失敗しました。")
+                        .field("詳細", format!("```\n{error:#}\n```"), false)
+                        .colour(Colour::RED)
+                },
+            };
+            let message = CreateInteractionResponseMessage::new().embed(embed).ephemeral(true);
+            respond(context, interaction, &message).await?;
+        },
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let loglevel = {
+        let target = CreateCommandOption::new(
+            CommandOptionType::String,
+            "target",
+            "Target of logs such as voicevox or songbird, or * for the default level",
+        )
+        .name_localized("ja", "対象")
+        .description_localized("ja", "voicevox や songbird などのログの対象。* でデフォルトのレベル。")
+        .required(true);
+        let level = LEVELS.into_iter().fold(
+            CreateCommandOption::new(CommandOptionType::String, "level", "Log level")
+                .name_localized("ja", "レベル")
+                .description_localized("ja", "ログレベル")
+                .required(true),
+            |option, level| option.add_string_choice(level, level),
+        );
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "loglevel",
+            "Changes log level at runtime",
+        )
+        .description_localized("ja", "ログレベルを再起動せずに変更します。")
+        .add_sub_option(target)
+        .add_sub_option(level)
+    };
+
+    CreateCommand::new("admin")
+        .description("bot のオーナー向けのコマンドです。")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .set_options(vec![loglevel])
+}
@@ -0,0 +1,165 @@
+use anyhow::{Context as _, Result};
+use database::{PgPool, scheduled_announcement};
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::{announcement::MAX_ANNOUNCEMENT_CHARS, cron::Schedule, quota, utils::respond};
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /announce subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+
+    let embed = match subcommand.name {
+        "schedule" => {
+            let schedule = subcommand
+                .options
+                .get("cron")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .context("cannot get cron")?;
+            let text = subcommand
+                .options
+                .get("text")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .context("cannot get text")?;
+            let count = scheduled_announcement::count_by_guild_id(database, guild_id.get()).await?;
+
+            match schedule.parse::<Schedule>() {
+                Err(error) => CreateEmbed::new()
+                    .title("スケジュールの形式が正しくありません。")
+                    .description("`分 時 日 月 曜日` の 5 つを空白で区切って指定してください。\n例: `0 12 * * 1-5` は平日の 12 時です。")
+                    .field("詳細", format!("```\n{error}\n```"), false)
+                    .colour(Colour::RED),
+                _ if text.is_empty() => CreateEmbed::new()
+                    .description("読み上げる文章を指定してください。")
+                    .colour(Colour::RED),
+                _ if text.chars().count() > MAX_ANNOUNCEMENT_CHARS => CreateEmbed::new()
+                    .description(format!("読み上げる文章は {MAX_ANNOUNCEMENT_CHARS} 文字以内にしてください。"))
+                    .colour(Colour::RED),
+                _ if count >= quota::SCHEDULED_ANNOUNCEMENTS => CreateEmbed::new()
+                    .title("予約できるお知らせの数の上限に達しています。")
+                    .description("`/announce remove` で不要なお知らせを削除してください。")
+                    .field("使用量", format!("{count} / {}", quota::SCHEDULED_ANNOUNCEMENTS), false)
+                    .colour(Colour::RED),
+                Ok(_) => {
+                    let announcement = scheduled_announcement::create(
+                        database,
+                        guild_id.get(),
+                        schedule,
+                        text,
+                        interaction.user.id.get(),
+                    )
+                    .await?;
+                    CreateEmbed::new()
+                        .title("お知らせを予約しました。")
+                        .description("ボイスチャンネルに接続している間、予約した時刻（日本時間）に読み上げます。")
+                        .field("ID", announcement.id.to_string(), true)
+                        .field("スケジュール", format!("`{schedule}`"), true)
+                        .field("文章", text, false)
+                        .colour(Colour::FOOYOO)
+                },
+            }
+        },
+        "remove" => {
+            let id = subcommand
+                .options
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .context("cannot get id")?;
+            let deleted = match i32::try_from(id) {
+                Ok(id) => scheduled_announcement::delete(database, guild_id.get(), id).await?,
+                Err(_) => false,
+            };
+            match deleted {
+                true => CreateEmbed::new()
+                    .description(format!("お知らせ {id} を削除しました。"))
+                    .colour(Colour::FOOYOO),
+                false => CreateEmbed::new()
+                    .description(format!("お知らせ {id} は予約されていません。"))
+                    .colour(Colour::RED),
+            }
+        },
+        "list" => {
+            let announcements = scheduled_announcement::fetch_by_guild_id(database, guild_id.get()).await?;
+            let description = match announcements.is_empty() {
+                true => "予約されているお知らせはありません。".to_string(),
+                false => announcements
+                    .iter()
+                    .map(|announcement| {
+                        format!(
+                            "**{}** `{}` {}",
+                            announcement.id, announcement.schedule, announcement.text
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            };
+            CreateEmbed::new()
+                .title("予約されているお知らせ一覧")
+                .description(description)
+                .colour(Colour::FOOYOO)
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let schedule = {
+        let cron = CreateCommandOption::new(
+            CommandOptionType::String,
+            "cron",
+            "Schedule in the syntax of cron in Japan time, such as `0 12 * * 1-5`",
+        )
+        .description_localized("ja", "cron 形式のスケジュール（日本時間、例: `0 12 * * 1-5`）")
+        .required(true);
+        let text = CreateCommandOption::new(CommandOptionType::String, "text", "Text to be read")
+            .name_localized("ja", "文章")
+            .description_localized("ja", "読み上げる文章")
+            .max_length(MAX_ANNOUNCEMENT_CHARS as u16)
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "schedule",
+            "Schedules an announcement read in the voice channel",
+        )
+        .description_localized("ja", "ボイスチャンネルで読み上げるお知らせを予約します。")
+        .add_sub_option(cron)
+        .add_sub_option(text)
+    };
+    let list = CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Lists scheduled announcements")
+        .description_localized("ja", "予約されているお知らせを表示します。");
+    let remove = {
+        let id = CreateCommandOption::new(CommandOptionType::Integer, "id", "ID of the announcement")
+            .description_localized("ja", "お知らせの ID")
+            .min_int_value(1)
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "remove",
+            "Removes a scheduled announcement",
+        )
+        .description_localized("ja", "予約したお知らせを削除します。")
+        .add_sub_option(id)
+    };
+
+    CreateCommand::new("announce")
+        .description("ボイスチャンネルで読み上げるお知らせを予約します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![schedule, list, remove])
+}
@@ -0,0 +1,141 @@
+use anyhow::{Context as _, Result};
+use database::{PgPool, auto_join};
+use serenity::{
+    all::{ChannelId, ChannelType, CommandOptionType, Mentionable},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::utils::respond;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /autojoin subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+    let voice_channel_id = subcommand.options.get("voice").and_then(|v| v.as_channel_id());
+
+    let embed = match subcommand.name {
+        "add" => {
+            let voice_channel_id = voice_channel_id.context("no voice option")?;
+            let text_channel_id = subcommand
+                .options
+                .get("text")
+                .and_then(|v| v.as_channel_id())
+                .unwrap_or(interaction.channel_id);
+            auto_join::create(database, voice_channel_id.get(), guild_id.get(), text_channel_id.get()).await?;
+            CreateEmbed::new()
+                .description(format!(
+                    "{}に最初の人が入ったら自動で接続し、{}のメッセージを読み上げます。",
+                    voice_channel_id.mention(),
+                    text_channel_id.mention()
+                ))
+                .colour(Colour::FOOYOO)
+        },
+        "remove" => {
+            let voice_channel_id = voice_channel_id.context("no voice option")?;
+            match auto_join::delete_by_voice_channel_id(database, voice_channel_id.get()).await? {
+                true => CreateEmbed::new()
+                    .description(format!("{}への自動接続をやめました。", voice_channel_id.mention()))
+                    .colour(Colour::FOOYOO),
+                false => CreateEmbed::new()
+                    .description(format!(
+                        "{}には自動接続が設定されていません。",
+                        voice_channel_id.mention()
+                    ))
+                    .colour(Colour::RED),
+            }
+        },
+        "list" => {
+            let auto_joins = auto_join::fetch_by_guild_id(database, guild_id.get())
+                .await?
+                .into_iter()
+                .map(|auto_join| {
+                    format!(
+                        "{} -> {}",
+                        ChannelId::new(auto_join.voice_channel_id as u64).mention(),
+                        ChannelId::new(auto_join.text_channel_id as u64).mention()
+                    )
+                })
+                .collect::<Vec<_>>();
+            let description = match auto_joins.is_empty() {
+                true => "自動接続は設定されていません。".to_string(),
+                false => auto_joins.join("\n"),
+            };
+            CreateEmbed::new()
+                .title("自動接続一覧")
+                .description(description)
+                .colour(Colour::FOOYOO)
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let add = {
+        let voice = CreateCommandOption::new(
+            CommandOptionType::Channel,
+            "voice",
+            "Voice channel to join automatically",
+        )
+        .name_localized("ja", "ボイスチャンネル")
+        .description_localized("ja", "自動で接続するボイスチャンネル")
+        .channel_types(vec![ChannelType::Voice, ChannelType::Stage])
+        .required(true);
+        let text = CreateCommandOption::new(
+            CommandOptionType::Channel,
+            "text",
+            "Text channel to read aloud, or none for this channel",
+        )
+        .name_localized("ja", "テキストチャンネル")
+        .description_localized("ja", "読み上げるテキストチャンネル（省略するとこのチャンネル）")
+        .channel_types(vec![ChannelType::Text, ChannelType::Voice]);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "add",
+            "Joins the voice channel automatically when the first person enters",
+        )
+        .description_localized("ja", "ボイスチャンネルに最初の人が入ったら自動で接続します。")
+        .add_sub_option(voice)
+        .add_sub_option(text)
+    };
+    let remove = {
+        let voice = CreateCommandOption::new(
+            CommandOptionType::Channel,
+            "voice",
+            "Voice channel to stop joining automatically",
+        )
+        .name_localized("ja", "ボイスチャンネル")
+        .description_localized("ja", "自動接続をやめるボイスチャンネル")
+        .channel_types(vec![ChannelType::Voice, ChannelType::Stage])
+        .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "remove",
+            "Stops joining the voice channel automatically",
+        )
+        .description_localized("ja", "ボイスチャンネルへの自動接続をやめます。")
+        .add_sub_option(voice)
+    };
+    let list = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "list",
+        "Lists voice channels joined automatically",
+    )
+    .description_localized("ja", "自動接続するボイスチャンネルを表示します。");
+
+    CreateCommand::new("autojoin")
+        .description("ボイスチャンネルへの自動接続を設定します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![add, remove, list])
+}
@@ -0,0 +1,159 @@
+use std::ffi::OsString;
+
+use anyhow::{Context as _, Result};
+use dashmap::DashMap;
+use database::{PgPool, blacklisted_word, guild};
+use seitai_converter::blacklist::BEEP_READING;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+use songbird::input::cached::Memory;
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::{quota, utils::respond};
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    sounds: &DashMap<OsString, Memory>,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /blacklist subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+    let word = subcommand.options.get("word").and_then(|v| v.as_str()).map(str::trim);
+
+    let embed = match (subcommand.name, word) {
+        (_, Some("")) => CreateEmbed::new()
+            .description("単語を指定してください。")
+            .colour(Colour::RED),
+        ("add", Some(word)) => {
+            let count = blacklisted_word::count_by_guild_id(database, guild_id.get()).await?;
+            if count >= quota::BLACKLISTED_WORDS {
+                CreateEmbed::new()
+                    .title("禁止できる単語の数の上限に達しています。")
+                    .description("`/blacklist remove` で不要な単語を削除してください。")
+                    .field("使用量", format!("{count} / {}", quota::BLACKLISTED_WORDS), false)
+                    .colour(Colour::RED)
+            } else {
+                match blacklisted_word::create(database, guild_id.get(), word).await? {
+                    true => CreateEmbed::new()
+                        .title("単語を禁止しました。")
+                        .description(format!("`{word}`"))
+                        .colour(Colour::FOOYOO),
+                    false => CreateEmbed::new()
+                        .title("単語は既に禁止されています。")
+                        .description(format!("`{word}`"))
+                        .colour(Colour::RED),
+                }
+            }
+        },
+        ("remove", Some(word)) => match blacklisted_word::delete(database, guild_id.get(), word).await? {
+            true => CreateEmbed::new()
+                .title("単語の禁止を解除しました。")
+                .description(format!("`{word}`"))
+                .colour(Colour::FOOYOO),
+            false => CreateEmbed::new()
+                .title("単語は禁止されていません。")
+                .description(format!("`{word}`"))
+                .colour(Colour::RED),
+        },
+        ("list", _) => {
+            let words = blacklisted_word::fetch_by_guild_id(database, guild_id.get()).await?;
+            let description = match words.is_empty() {
+                true => "禁止されている単語はありません。".to_string(),
+                false => format!("```\n{}\n```", words.join("\n")),
+            };
+            CreateEmbed::new()
+                .title("禁止されている単語一覧")
+                .description(description)
+                .colour(Colour::FOOYOO)
+        },
+        ("sound", _) => {
+            let sound = subcommand
+                .options
+                .get("sound")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|sound| !sound.is_empty());
+            match sound {
+                Some(name) if !sounds.contains_key(&OsString::from(name)) => CreateEmbed::new()
+                    .description(format!("サウンド「{name}」が見つかりません。"))
+                    .colour(Colour::RED),
+                Some(name) => {
+                    guild::update_beep_sound(database, guild_id.get(), Some(name)).await?;
+                    CreateEmbed::new()
+                        .description(format!("禁止された単語の代わりに「{name}」を鳴らします。"))
+                        .colour(Colour::FOOYOO)
+                },
+                None => {
+                    guild::update_beep_sound(database, guild_id.get(), None).await?;
+                    CreateEmbed::new()
+                        .description(format!("禁止された単語を「{BEEP_READING}」と読み上げます。"))
+                        .colour(Colour::FOOYOO)
+                },
+            }
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let add = {
+        let word = CreateCommandOption::new(CommandOptionType::String, "word", "Word to be banned")
+            .name_localized("ja", "単語")
+            .description_localized("ja", "読み上げを禁止する単語")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "add",
+            "Bans a word from being read in this server",
+        )
+        .description_localized("ja", "このサーバーで単語を読み上げないようにします。")
+        .add_sub_option(word)
+    };
+    let remove = {
+        let word = CreateCommandOption::new(CommandOptionType::String, "word", "Word to be allowed")
+            .name_localized("ja", "単語")
+            .description_localized("ja", "禁止を解除する単語")
+            .required(true);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Allows a banned word")
+            .description_localized("ja", "単語の禁止を解除します。")
+            .add_sub_option(word)
+    };
+    let list = CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Lists banned words")
+        .description_localized("ja", "禁止されている単語を表示します。");
+    let sound = {
+        let sound = CreateCommandOption::new(
+            CommandOptionType::String,
+            "sound",
+            "Name of the sound, or none to read them as a beep",
+        )
+        .name_localized("ja", "サウンド")
+        .description_localized("ja", "鳴らすサウンドの名前（省略すると「ピー」と読み上げます）");
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "sound",
+            "Sets the sound played in place of banned words",
+        )
+        .description_localized("ja", "禁止された単語の代わりに鳴らすサウンドを設定します。")
+        .add_sub_option(sound)
+    };
+
+    CreateCommand::new("blacklist")
+        .description("サーバーで読み上げを禁止する単語を設定します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![add, remove, list, sound])
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context as _, Result};
+use database::{PgPool, guild, read_channel};
+use serenity::{
+    all::{ChannelId, ChannelType, CommandOptionType, Mentionable},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::utils::respond;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /channels subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+    let channel_id = subcommand.options.get("channel").and_then(|v| v.as_channel_id());
+
+    let embed = match subcommand.name {
+        "add" => {
+            let channel_id = channel_id.unwrap_or(interaction.channel_id);
+            read_channel::create(database, channel_id.get(), guild_id.get()).await?;
+            CreateEmbed::new()
+                .description(format!("{}のメッセージを読み上げます。", channel_id.mention()))
+                .colour(Colour::FOOYOO)
+        },
+        "remove" => {
+            let channel_id = channel_id.context("no channel option")?;
+            match read_channel::delete_by_channel_id(database, channel_id.get()).await? {
+                true => CreateEmbed::new()
+                    .description(format!(
+                        "{}のメッセージを読み上げないようにしました。",
+                        channel_id.mention()
+                    ))
+                    .colour(Colour::FOOYOO),
+                false => CreateEmbed::new()
+                    .description(format!(
+                        "{}は読み上げるチャンネルに登録されていません。",
+                        channel_id.mention()
+                    ))
+                    .colour(Colour::RED),
+            }
+        },
+        "list" => {
+            let channels = read_channel::fetch_by_guild_id(database, guild_id.get())
+                .await?
+                .into_iter()
+                .map(|channel_id| ChannelId::new(channel_id as u64).mention().to_string())
+                .collect::<Vec<_>>();
+            let description = match channels.is_empty() {
+                true => "登録されていません。`/join` を使ったチャンネルを読み上げます。".to_string(),
+                false => channels.join("\n"),
+            };
+            CreateEmbed::new()
+                .title("読み上げるチャンネル一覧")
+                .description(description)
+                .colour(Colour::FOOYOO)
+        },
+        "threads" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            guild::update_read_threads(database, guild_id.get(), enabled).await?;
+
+            let description = match enabled {
+                true => "読み上げるチャンネルから作られたスレッドのメッセージも読み上げます。",
+                false => "スレッドのメッセージは、スレッドを登録しなければ読み上げません。",
+            };
+            CreateEmbed::new().description(description).colour(Colour::FOOYOO)
+        },
+        "voice-chat" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            guild::update_read_voice_chat(database, guild_id.get(), enabled).await?;
+
+            let description = match enabled {
+                true => "接続しているボイスチャンネルのチャットも読み上げます。",
+                false => {
+                    "ボイスチャンネルのチャットは、登録したチャンネルか /join を使ったチャンネルでなければ読み上げません。"
+                },
+            };
+            CreateEmbed::new().description(description).colour(Colour::FOOYOO)
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let add = {
+        let channel = CreateCommandOption::new(
+            CommandOptionType::Channel,
+            "channel",
+            "Text channel to read aloud, or none for this channel",
+        )
+        .name_localized("ja", "チャンネル")
+        .description_localized("ja", "読み上げるテキストチャンネル（省略するとこのチャンネル）")
+        .channel_types(vec![ChannelType::Text, ChannelType::News, ChannelType::Voice]);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "add",
+            "Reads messages in the channel aloud",
+        )
+        .description_localized("ja", "チャンネルのメッセージを読み上げるようにします。")
+        .add_sub_option(channel)
+    };
+    let remove = {
+        let channel = CreateCommandOption::new(
+            CommandOptionType::Channel,
+            "channel",
+            "Text channel to stop reading aloud",
+        )
+        .name_localized("ja", "チャンネル")
+        .description_localized("ja", "読み上げをやめるテキストチャンネル")
+        .channel_types(vec![ChannelType::Text, ChannelType::News, ChannelType::Voice])
+        .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "remove",
+            "Stops reading messages in the channel aloud",
+        )
+        .description_localized("ja", "チャンネルのメッセージを読み上げないようにします。")
+        .add_sub_option(channel)
+    };
+    let list = CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Lists text channels read aloud")
+        .description_localized("ja", "読み上げるチャンネルを表示します。");
+    let threads = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to read them")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み上げるかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "threads",
+            "Reads messages in threads of the channels read aloud",
+        )
+        .description_localized(
+            "ja",
+            "読み上げるチャンネルから作られたスレッドのメッセージも読み上げます。",
+        )
+        .add_sub_option(enabled)
+    };
+    let voice_chat = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to read it")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み上げるかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "voice-chat",
+            "Reads messages in the text chat of the voice channel connected to",
+        )
+        .description_localized("ja", "接続しているボイスチャンネルのチャットを読み上げます。")
+        .add_sub_option(enabled)
+    };
+
+    CreateCommand::new("channels")
+        .description("読み上げるテキストチャンネルを設定します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![add, remove, list, threads, voice_chat])
+}
@@ -0,0 +1,77 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, EditInteractionResponse},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::{
+    error::CommandError,
+    settings::{channel_names, get_dictionary},
+};
+use crate::guild_archive::GuildArchive;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /config subcommand")?;
+
+    match subcommand.name.as_str() {
+        "export-all" => {
+            // 管理者でもメンバーの設定まで見られるのはサーバーの所有者だけにする
+            let guild = guild_id
+                .to_partial_guild(&context.http)
+                .await
+                .with_context(|| format!("failed to get guild ({guild_id})"))?;
+            if guild.owner_id != interaction.user.id {
+                return Err(CommandError::OwnerOnly.into());
+            }
+
+            // 辞書の取得で応答期限を過ぎないようにする
+            interaction.defer_ephemeral(&context.http).await?;
+
+            let dictionary = get_dictionary(context).await?;
+            let channels = channel_names(context, guild_id).await?;
+            let archive = GuildArchive::export(database, &dictionary, guild_id, &channels).await?;
+            let json = serde_json::to_vec_pretty(&archive).context("failed to serialize guild archive")?;
+
+            let embed = CreateEmbed::new()
+                .description(
+                    "このサーバーについて保存しているデータをすべて書き出しました。`settings` の部分は `/settings import` で読み込めます。",
+                )
+                .colour(Colour::FOOYOO);
+            let attachment = CreateAttachment::bytes(json, format!("seitai-guild-{guild_id}.json"));
+            interaction
+                .edit_response(
+                    &context.http,
+                    EditInteractionResponse::new().embed(embed).new_attachment(attachment),
+                )
+                .await?;
+        },
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let export_all = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "export-all",
+        "Exports everything stored about this server, only for the owner of the server",
+    )
+    .description_localized(
+        "ja",
+        "このサーバーについて保存しているデータをすべて書き出します（サーバーの所有者のみ）。",
+    );
+
+    CreateCommand::new("config")
+        .description("サーバーについて保存しているデータを管理します。")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .set_options(vec![export_all])
+}
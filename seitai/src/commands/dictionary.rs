@@ -1,44 +1,79 @@
+use std::{cmp::Reverse, ops::Range};
+
 use anyhow::{Context as _, Result, bail};
+use database::{
+    PgPool,
+    dictionary_word::{self, Reading},
+    guild,
+};
 use futures::{StreamExt, future, stream};
 use hashbrown::HashMap;
-use indexmap::IndexMap;
-use ordered_float::NotNan;
+use seitai_audio::AudioRepository;
+use seitai_converter::{to_full_width, to_half_width};
 use serenity::{
-    all::{CommandDataOptionValue, CommandOptionType},
-    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    all::{ButtonStyle, CommandDataOption, CommandDataOptionValue, CommandOptionType, ComponentInteraction, GuildId},
+    builder::{
+        CreateActionRow, CreateAttachment, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
+        CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, EditInteractionResponse,
+    },
     client::Context,
     model::{Colour, application::CommandInteraction},
 };
 use songbird::input::Input;
-use uuid::Uuid;
-use voicevox::dictionary::{
-    Dictionary,
-    response::{DeleteUserDictWordResult, GetUserDictResult, PostUserDictWordResult, PutUserDictWordResult},
-};
+use voicevox::dictionary::response::{GetUserDictResult, Item};
 
 use crate::{
-    audio::{Audio, AudioRepository, cache::PredefinedUtterance},
-    character_converter::{to_full_width, to_half_width, to_katakana},
-    regex,
-    speaker::Speaker,
-    utils::{get_manager, get_voicevox, normalize, respond},
+    dictionary::{DictionaryError, Upsert, WordRequest},
+    dictionary_csv,
+    i18n::{Locale, t, t_with},
+    quota,
+    utils::{enqueue, get_manager, get_voicevox, normalize, read_context, respond, system_audio, truncate_chars},
+    utterance::PredefinedUtterance,
 };
 
-use super::subcommand::Subcommand;
+use super::{error::CommandError, subcommand::Subcommand};
 
-const SYSTEM_SPEAKER: &str = "1";
+// 変換の段階ごとに表示する最大文字数
+const STAGE_TEXT_LIMIT: usize = 200;
+// 一致した単語を表示する最大数
+const MATCH_DISPLAY_LIMIT: usize = 20;
+const MAX_IMPORT_SIZE: u32 = 256 * 1024;
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+// 読みの一覧のページ送りのボタンの custom_id の接頭辞
+pub(crate) const READINGS_PREFIX: &str = "dictionary:readings:";
+// 読みの一覧の 1 ページに表示する数
+const READINGS_PAGE_SIZE: usize = 20;
+// 読みの一覧で単語と読みをそれぞれ表示する最大文字数
+const READING_TEXT_LIMIT: usize = 90;
+
+struct WordMatch<'a> {
+    // 何文字目で一致したか
+    position: usize,
+    range: Range<usize>,
+    item: &'a Item,
+}
 
 pub(crate) async fn run<Repository>(
     context: &Context,
     audio_repository: &Repository,
     interaction: &CommandInteraction,
+    database: &PgPool,
 ) -> Result<()>
 where
     Repository: AudioRepository<Input = Input> + Send + Sync,
 {
-    let Some(guild_id) = interaction.guild_id else {
-        return Ok(());
-    };
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let locale = Locale::from_tag(&interaction.locale);
+    // 読みは VOICEVOX ENGINE の辞書を使わない
+    if let Some(group) = interaction
+        .data
+        .options
+        .first()
+        .filter(|option| option.name == "reading")
+    {
+        return run_reading(context, interaction, database, guild_id, group, locale).await;
+    }
+
     let users = guild_id
         .members(&context.http, None, None)
         .await
@@ -47,50 +82,107 @@ where
         .map(|member| member.user.clone())
         .collect::<Vec<_>>();
     let dictionary = {
-        let voicevox = get_voicevox(context)
-            .await
-            .context("failed to get voicevox client for /dictionary command")?;
+        let voicevox = get_voicevox(context).await.ok_or(CommandError::EngineUnavailable)?;
         let voicevox = voicevox.lock().await;
         voicevox.dictionary.clone()
     };
 
     for option in &interaction.data.options {
         let subcommand = Subcommand::from_command_data_option(option).unwrap_or_default();
+        let attachment = subcommand
+            .options
+            .get("file")
+            .and_then(|v| v.as_attachment_id())
+            .and_then(|id| interaction.data.resolved.attachments.get(&id));
         let mut subcommand_options = subcommand
             .options
             .into_iter()
             .map(|(k, v)| match v {
                 CommandDataOptionValue::String(v) => (k, v.to_string()),
                 CommandDataOptionValue::Integer(v) => (k, v.to_string()),
+                CommandDataOptionValue::Attachment(v) => (k, v.to_string()),
                 _ => unreachable!(),
             })
             .collect::<HashMap<_, _>>();
         subcommand_options
             .entry("surface")
-            .and_replace_entry_with(|_key, word| {
-                let text = normalize(context, &guild_id, &users, &word);
-                Some(regex::EMOJI.replace_all(&text, ":$1:").into_owned())
-            });
+            .and_replace_entry_with(|_key, word| Some(normalize(context, &guild_id, &users, &word).into_owned()));
+        subcommand_options
+            .entry("text")
+            .and_replace_entry_with(|_key, text| Some(normalize(context, &guild_id, &users, &text).into_owned()));
 
         match option.name.as_str() {
             "add" => {
-                subcommand_options.entry("accent_type").or_insert("0".to_string());
-                subcommand_options.entry("priority").or_insert("10".to_string());
-                subcommand_options
-                    .entry("pronunciation")
-                    .and_replace_entry_with(|_key, pronunciation| Some(to_katakana(&*pronunciation).into_owned()));
-
-                let word = subcommand_options
-                    .get("surface")
-                    .context("there is no surface to make sure whether word is regsitered")?;
-                let uuid = match get_regsiterd(&dictionary, word).await {
-                    Ok(uuid) => uuid,
+                let request = WordRequest {
+                    surface: subcommand_options
+                        .get("surface")
+                        .context("there is no surface to register word")?
+                        .clone(),
+                    pronunciation: subcommand_options
+                        .get("pronunciation")
+                        .context("there is no pronunciation to register word")?
+                        .clone(),
+                    accent_type: subcommand_options
+                        .get("accent_type")
+                        .map(|accent_type| accent_type.parse())
+                        .transpose()?,
+                    word_type: subcommand_options.get("word_type").cloned(),
+                    priority: subcommand_options
+                        .get("priority")
+                        .map(|priority| priority.parse())
+                        .transpose()?,
+                };
+                let upserted = match request.validate() {
+                    Ok(word) => crate::dictionary::upsert(&dictionary, database, guild_id.get(), &word)
+                        .await
+                        .map(|upserted| (word, upserted)),
+                    Err(error) => Err(error),
+                };
+                let word = match upserted {
+                    Ok((word, upserted)) => {
+                        let title = match upserted {
+                            Upsert::Registered(_) => t(locale, "dictionary.add.registered"),
+                            Upsert::Updated(_) => t(locale, "dictionary.add.updated"),
+                        };
+                        let message = CreateInteractionResponseMessage::new().embed(
+                            CreateEmbed::new()
+                                .title(title)
+                                .field(
+                                    t(locale, "dictionary.word"),
+                                    format!("```\n{}\n```", word.surface),
+                                    false,
+                                )
+                                .field(
+                                    t(locale, "dictionary.pronunciation"),
+                                    format!("```\n{}\n```", word.pronunciation),
+                                    false,
+                                )
+                                .colour(Colour::FOOYOO),
+                        );
+                        respond(context, interaction, &message).await?;
+                        word
+                    },
+                    Err(DictionaryError::QuotaExceeded(count)) => {
+                        let message = CreateInteractionResponseMessage::new().embed(
+                            CreateEmbed::new()
+                                .title(t(locale, "dictionary.quota.reached"))
+                                .description(t(locale, "dictionary.quota.delete"))
+                                .field(
+                                    t(locale, "common.usage"),
+                                    format!("{count} / {}", quota::DICTIONARY_WORDS),
+                                    false,
+                                )
+                                .colour(Colour::RED),
+                        );
+                        respond(context, interaction, &message).await?;
+                        continue;
+                    },
                     Err(error) => {
-                        tracing::error!("failed to register {word} into dictionary\nError: {error:?}");
+                        tracing::error!("failed to register word into dictionary\nError: {error:?}");
                         let message = CreateInteractionResponseMessage::new().embed(
                             CreateEmbed::new()
-                                .title("単語の登録に失敗しました。")
-                                .field("詳細", format!("```\n{}\n```", error), false)
+                                .title(t(locale, "dictionary.add.failed"))
+                                .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
                                 .colour(Colour::RED),
                         );
                         respond(context, interaction, &message).await?;
@@ -98,12 +190,6 @@ where
                     },
                 };
 
-                if let Some(uuid) = uuid {
-                    update_word(context, interaction, &dictionary, &uuid, &subcommand_options).await?;
-                } else {
-                    register_word(context, interaction, &dictionary, &subcommand_options).await?;
-                }
-
                 let manager = get_manager(context).await?;
                 let call = manager.get_or_insert(guild_id);
                 let mut call = call.lock().await;
@@ -112,13 +198,10 @@ where
                     continue;
                 };
 
-                let inputs = stream::iter([word, PredefinedUtterance::Registered.as_ref()])
+                let settings = guild::fetch_by_id(database, guild_id.get()).await?;
+                let inputs = stream::iter([word.surface.as_str(), PredefinedUtterance::Registered.as_ref()])
                     .map(async |text| {
-                        let audio = Audio {
-                            text: text.to_string(),
-                            speaker: SYSTEM_SPEAKER.to_string(),
-                            speed: NotNan::new(Speaker::default_speed()).unwrap(),
-                        };
+                        let audio = system_audio(text.to_string(), settings.default_speaker_id);
                         match audio_repository.get(audio).await {
                             Ok(input) => Some(input),
                             Err(error) => {
@@ -131,7 +214,7 @@ where
                     .await;
 
                 for input in future::join_all(inputs).await.into_iter().flatten() {
-                    call.enqueue_input(input).await;
+                    enqueue(&mut call, input, settings.volume).await;
                 }
             },
             // TODO: Paginate
@@ -141,8 +224,8 @@ where
                     Err(error) => {
                         let message = CreateInteractionResponseMessage::new().embed(
                             CreateEmbed::new()
-                                .title("単語の更新に失敗しました。")
-                                .field("詳細", format!("```\n{}\n```", error), false)
+                                .title(t(locale, "dictionary.fetch_failed"))
+                                .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
                                 .colour(Colour::RED),
                         );
                         respond(context, interaction, &message).await?;
@@ -157,44 +240,210 @@ where
                     .collect::<Vec<_>>();
                 let message = CreateInteractionResponseMessage::new().embed(
                     CreateEmbed::new()
-                        .title("単語一覧")
+                        .title(t(locale, "dictionary.list.title"))
                         .description(format!("```\n{}\n```", words.join("\n")))
                         .colour(Colour::FOOYOO),
                 );
                 respond(context, interaction, &message).await?;
             },
-            "delete" => {
-                let word = subcommand_options
-                    .get("surface")
-                    .context("there is no surface to delete word")?;
-                let uuid = match get_regsiterd(&dictionary, word).await {
-                    Ok(uuid) => uuid,
+            "test" => {
+                let text = subcommand_options.get("text").context("there is no text to test")?;
+                let response = match dictionary.list().await {
+                    Ok(response) => response,
                     Err(error) => {
-                        tracing::error!("failed to delete {word} in dictionary\nError: {error:?}");
                         let message = CreateInteractionResponseMessage::new().embed(
                             CreateEmbed::new()
-                                .title("単語の削除に失敗しました。")
-                                .field("詳細", format!("```\n{}\n```", error), false)
+                                .title(t(locale, "dictionary.fetch_failed"))
+                                .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
                                 .colour(Colour::RED),
                         );
                         respond(context, interaction, &message).await?;
-                        continue;
+                        bail!("failed to get dictionary for /dictionary test command\nError: {error:?}");
                     },
                 };
+                let GetUserDictResult::Ok(list) = response;
 
-                if let Some(uuid) = uuid {
-                    delete_word(context, interaction, &dictionary, &uuid, word).await?;
-                    continue;
+                let readings = dictionary_word::fetch_readings_by_guild_id(database, guild_id.get()).await?;
+                let readings = readings
+                    .iter()
+                    .map(|reading| (reading.surface.as_str(), reading.reading.as_str()))
+                    .collect::<Vec<_>>();
+                let read = seitai_converter::reading::apply(text, &readings);
+                let settings = guild::fetch_by_id(database, guild_id.get()).await?;
+                let stages = seitai_converter::convert_with_stages(&read_context(&settings), &read);
+                let converted = to_full_width(stages.last().map_or(&*read, |stage| stage.text.as_str()));
+                let matches = match_words(list.values(), &converted);
+
+                let mut lines = vec![format!("input: {}", escape_stage_text(text))];
+                if read != text.as_str() {
+                    lines.push(format!("reading: {}", escape_stage_text(&read)));
+                }
+                let mut previous = &*read;
+                for stage in &stages {
+                    if stage.text != previous {
+                        lines.push(format!("{}: {}", stage.name, escape_stage_text(&stage.text)));
+                    }
+                    previous = &stage.text;
+                }
+                lines.push(format!(
+                    "dictionary: {}",
+                    escape_stage_text(&apply_matches(&converted, &matches))
+                ));
+
+                let mut matched = match matches.is_empty() {
+                    true => t(locale, "dictionary.test.no_match").to_string(),
+                    false => matches
+                        .iter()
+                        .take(MATCH_DISPLAY_LIMIT)
+                        .enumerate()
+                        .map(|(index, word_match)| {
+                            t_with(
+                                locale,
+                                "dictionary.test.match",
+                                &[
+                                    ("index", &(index + 1).to_string()),
+                                    ("surface", &to_half_width(&word_match.item.surface)),
+                                    ("pronunciation", &word_match.item.pronunciation),
+                                    ("position", &(word_match.position + 1).to_string()),
+                                    ("priority", &word_match.item.priority.to_string()),
+                                ],
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                };
+                if matches.len() > MATCH_DISPLAY_LIMIT {
+                    matched.push('\n');
+                    matched.push_str(&t_with(
+                        locale,
+                        "dictionary.test.more",
+                        &[("count", &(matches.len() - MATCH_DISPLAY_LIMIT).to_string())],
+                    ));
                 }
 
                 let message = CreateInteractionResponseMessage::new().embed(
                     CreateEmbed::new()
-                        .title("単語は登録されていません。")
-                        .field("単語", format!("```\n{}\n```", word), false)
-                        .colour(Colour::RED),
+                        .title(t(locale, "dictionary.test.title"))
+                        .description(format!("```\n{}\n```", lines.join("\n")))
+                        .field(t(locale, "dictionary.test.matched"), matched, false)
+                        .footer(CreateEmbedFooter::new(t(locale, "dictionary.test.footer")))
+                        .colour(Colour::FOOYOO),
                 );
                 respond(context, interaction, &message).await?;
             },
+            "delete" => {
+                let word = subcommand_options
+                    .get("surface")
+                    .context("there is no surface to delete word")?;
+                let embed = match crate::dictionary::delete(&dictionary, database, word).await {
+                    Ok(_) => CreateEmbed::new()
+                        .title(t(locale, "dictionary.delete.deleted"))
+                        .field(t(locale, "dictionary.word"), format!("```\n{}\n```", word), false)
+                        .colour(Colour::FOOYOO),
+                    Err(DictionaryError::NotFound) => CreateEmbed::new()
+                        .title(t(locale, "dictionary.delete.not_found"))
+                        .field(t(locale, "dictionary.word"), format!("```\n{}\n```", word), false)
+                        .colour(Colour::RED),
+                    Err(error) => {
+                        tracing::error!("failed to delete {word} in dictionary\nError: {error:?}");
+                        CreateEmbed::new()
+                            .title(t(locale, "dictionary.delete.failed"))
+                            .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
+                            .colour(Colour::RED)
+                    },
+                };
+                let message = CreateInteractionResponseMessage::new().embed(embed);
+                respond(context, interaction, &message).await?;
+            },
+            "import" => {
+                let attachment = attachment.context("no file option")?;
+                if attachment.size > MAX_IMPORT_SIZE {
+                    let message = CreateInteractionResponseMessage::new().embed(
+                        CreateEmbed::new()
+                            .description(t_with(
+                                locale,
+                                "common.file_too_large",
+                                &[("size", &(MAX_IMPORT_SIZE / 1024).to_string())],
+                            ))
+                            .colour(Colour::RED),
+                    );
+                    respond(context, interaction, &message).await?;
+                    continue;
+                }
+
+                // 単語の登録で応答期限を過ぎないようにする
+                interaction.defer(&context.http).await?;
+
+                let bytes = attachment.download().await.context("failed to download csv")?;
+                let parsed = match String::from_utf8(bytes) {
+                    Ok(text) => dictionary_csv::parse(&text),
+                    Err(_) => Err(vec![t(locale, "dictionary.import.not_utf8").to_string()]),
+                };
+                let embed = match parsed {
+                    Ok(words) if words.is_empty() => CreateEmbed::new()
+                        .description(t(locale, "dictionary.import.empty"))
+                        .colour(Colour::RED),
+                    Ok(words) => match crate::dictionary::import(&dictionary, database, guild_id.get(), &words).await {
+                        Ok(imported) => CreateEmbed::new()
+                            .description(t_with(
+                                locale,
+                                "dictionary.import.imported",
+                                &[
+                                    ("registered", &imported.registered.to_string()),
+                                    ("updated", &imported.updated.to_string()),
+                                ],
+                            ))
+                            .colour(Colour::FOOYOO),
+                        Err(DictionaryError::QuotaExceeded(count)) => CreateEmbed::new()
+                            .title(t(locale, "dictionary.import.quota"))
+                            .description(t(locale, "dictionary.quota.delete"))
+                            .field(
+                                t(locale, "common.usage"),
+                                format!("{count} / {}", quota::DICTIONARY_WORDS),
+                                false,
+                            )
+                            .colour(Colour::RED),
+                        Err(error) => {
+                            tracing::error!("failed to import words into dictionary\nError: {error:?}");
+                            CreateEmbed::new()
+                                .title(t(locale, "dictionary.import.failed"))
+                                .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
+                                .colour(Colour::RED)
+                        },
+                    },
+                    Err(problems) => {
+                        let mut description =
+                            format!("{}\n{}", t(locale, "dictionary.import.rejected"), problems.join("\n"));
+                        truncate_chars(&mut description, MAX_DESCRIPTION_LENGTH);
+                        CreateEmbed::new().description(description).colour(Colour::RED)
+                    },
+                };
+                interaction
+                    .edit_response(&context.http, EditInteractionResponse::new().embed(embed))
+                    .await?;
+            },
+            "export" => {
+                let list = crate::dictionary::list(&dictionary).await?;
+                let word_ids = dictionary_word::fetch_by_guild_id(database, guild_id.get()).await?;
+                let items = word_ids
+                    .iter()
+                    .filter_map(|word_id| list.get(word_id))
+                    .collect::<Vec<_>>();
+                let csv = dictionary_csv::write(items.iter().copied());
+
+                let embed = CreateEmbed::new()
+                    .description(t_with(
+                        locale,
+                        "dictionary.export.exported",
+                        &[("count", &items.len().to_string())],
+                    ))
+                    .colour(Colour::FOOYOO);
+                let attachment = CreateAttachment::bytes(csv, format!("seitai-dictionary-{guild_id}.csv"));
+                let message = CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .add_file(attachment);
+                respond(context, interaction, &message).await?;
+            },
             _ => {
                 unreachable!();
             },
@@ -203,6 +452,158 @@ where
     Ok(())
 }
 
+/// Registers, deletes or lists the readings of words in messages of the guild, which are applied before the conversion.
+async fn run_reading(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    guild_id: GuildId,
+    group: &CommandDataOption,
+    locale: Locale,
+) -> Result<()> {
+    let subcommand = match &group.value {
+        CommandDataOptionValue::SubCommandGroup(options) => options.first(),
+        _ => None,
+    }
+    .and_then(Subcommand::from_command_data_option)
+    .context("cannot get /dictionary reading subcommand")?;
+    let surface = subcommand
+        .options
+        .get("surface")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|surface| !surface.is_empty());
+
+    let embed = match subcommand.name {
+        "add" => {
+            let surface = surface.context("no surface option")?;
+            let reading = subcommand
+                .options
+                .get("reading")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .context("no reading option")?;
+
+            let readings = dictionary_word::fetch_readings_by_guild_id(database, guild_id.get()).await?;
+            let is_registered = readings.iter().any(|registered| registered.surface == surface);
+            let count = readings.len() as i64;
+            if !is_registered && count >= quota::READINGS {
+                CreateEmbed::new()
+                    .title(t(locale, "dictionary.reading.quota.reached"))
+                    .description(t(locale, "dictionary.reading.quota.delete"))
+                    .field(
+                        t(locale, "common.usage"),
+                        format!("{count} / {}", quota::READINGS),
+                        false,
+                    )
+                    .colour(Colour::RED)
+            } else {
+                dictionary_word::create_reading(database, guild_id.get(), surface, reading).await?;
+                CreateEmbed::new()
+                    .title(t(locale, "dictionary.reading.registered"))
+                    .description(format!("{surface} -> {reading}"))
+                    .colour(Colour::FOOYOO)
+            }
+        },
+        "delete" => {
+            let surface = surface.context("no surface option")?;
+            match dictionary_word::delete_reading(database, guild_id.get(), surface).await? {
+                true => CreateEmbed::new()
+                    .title(t(locale, "dictionary.reading.deleted"))
+                    .description(surface)
+                    .colour(Colour::FOOYOO),
+                false => CreateEmbed::new()
+                    .title(t(locale, "dictionary.reading.not_found"))
+                    .description(surface)
+                    .colour(Colour::RED),
+            }
+        },
+        "list" => {
+            let readings = dictionary_word::fetch_readings_by_guild_id(database, guild_id.get()).await?;
+            respond(context, interaction, &readings_message(&readings, 1, locale)).await?;
+            return Ok(());
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+/// Turns pages of the readings listed with `/dictionary reading list`.
+pub(crate) async fn handle_component(
+    context: &Context,
+    component: &ComponentInteraction,
+    database: &PgPool,
+) -> Result<()> {
+    let page = component
+        .data
+        .custom_id
+        .strip_prefix(READINGS_PREFIX)
+        .and_then(|page| page.parse::<usize>().ok())
+        .context("component is not of reading pages")?;
+    let guild_id = component.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&component.locale);
+
+    let readings = dictionary_word::fetch_readings_by_guild_id(database, guild_id.get()).await?;
+    component
+        .create_response(
+            &context.http,
+            CreateInteractionResponse::UpdateMessage(readings_message(&readings, page, locale)),
+        )
+        .await
+        .context("failed to turn reading page")?;
+
+    Ok(())
+}
+
+fn readings_message(readings: &[Reading], page: usize, locale: Locale) -> CreateInteractionResponseMessage {
+    let pages = readings.len().div_ceil(READINGS_PAGE_SIZE).max(1);
+    let page = page.clamp(1, pages);
+    let lines = readings
+        .iter()
+        .skip((page - 1) * READINGS_PAGE_SIZE)
+        .take(READINGS_PAGE_SIZE)
+        .map(|reading| {
+            format!(
+                "{} -> {}",
+                escape(&reading.surface, READING_TEXT_LIMIT),
+                escape(&reading.reading, READING_TEXT_LIMIT)
+            )
+        })
+        .collect::<Vec<_>>();
+    let description = match lines.is_empty() {
+        true => t(locale, "dictionary.reading.list.empty").to_string(),
+        false => format!("```\n{}\n```", lines.join("\n")),
+    };
+
+    let embed = CreateEmbed::new()
+        .title(t(locale, "dictionary.reading.list.title"))
+        .description(description)
+        .footer(CreateEmbedFooter::new(t_with(
+            locale,
+            "common.page",
+            &[("page", &page.to_string()), ("pages", &pages.to_string())],
+        )))
+        .colour(Colour::FOOYOO);
+    let buttons = vec![
+        CreateButton::new(format!("{READINGS_PREFIX}{}", page - 1))
+            .label(t(locale, "common.previous"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 1),
+        CreateButton::new(format!("{READINGS_PREFIX}{}", page + 1))
+            .label(t(locale, "common.next"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page == pages),
+    ];
+
+    CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(vec![CreateActionRow::Buttons(buttons)])
+}
+
 #[rustfmt::skip]
 pub fn register() -> CreateCommand {
     let add = {
@@ -251,182 +652,177 @@ pub fn register() -> CreateCommand {
             .add_sub_option(word)
     };
 
+    let test = {
+        let text = CreateCommandOption::new(CommandOptionType::String, "text", "Text to be read")
+            .name_localized("ja", "テキスト")
+            .description_localized("ja", "読み上げるテキスト")
+            .required(true);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "test", "Shows how text is converted and which words match")
+            .description_localized("ja", "テキストの変換の過程と一致する単語を表示します")
+            .add_sub_option(text)
+    };
+
+    let import = {
+        let file = CreateCommandOption::new(CommandOptionType::Attachment, "file", "CSV file with columns of word, reading and priority")
+            .name_localized("ja", "ファイル")
+            .description_localized("ja", "単語,ヨミ,優先度 の列の CSV ファイル")
+            .required(true);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "import", "Registers words in CSV file into dictionary at once")
+            .description_localized("ja", "CSV ファイルの単語をまとめて辞書に登録します")
+            .add_sub_option(file)
+    };
+    let export = CreateCommandOption::new(CommandOptionType::SubCommand, "export", "Exports words registered in this server as CSV file")
+        .description_localized("ja", "このサーバーで登録した単語を CSV ファイルに書き出します");
+
+    let reading = {
+        let add = {
+            let surface = CreateCommandOption::new(CommandOptionType::String, "surface", "Word to be read differently")
+                .name_localized("ja", "単語")
+                .description_localized("ja", "読みを登録する単語")
+                .required(true);
+            let reading = CreateCommandOption::new(CommandOptionType::String, "reading", "How the word is read")
+                .name_localized("ja", "読み")
+                .description_localized("ja", "単語の読み")
+                .required(true);
+            CreateCommandOption::new(CommandOptionType::SubCommand, "add", "Registers how a word is read in this server")
+                .description_localized("ja", "このサーバーでの単語の読みを登録します")
+                .add_sub_option(surface)
+                .add_sub_option(reading)
+        };
+        let delete = {
+            let surface = CreateCommandOption::new(CommandOptionType::String, "surface", "Word to be deleted")
+                .name_localized("ja", "単語")
+                .description_localized("ja", "読みを削除する単語")
+                .required(true);
+            CreateCommandOption::new(CommandOptionType::SubCommand, "delete", "Deletes how a word is read")
+                .description_localized("ja", "単語の読みを削除します")
+                .add_sub_option(surface)
+        };
+        let list = CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Lists registered readings")
+            .description_localized("ja", "登録されている読みを表示します");
+        CreateCommandOption::new(CommandOptionType::SubCommandGroup, "reading", "Readings of words applied before the dictionary")
+            .description_localized("ja", "辞書より先に適用する単語の読み")
+            .add_sub_option(add)
+            .add_sub_option(delete)
+            .add_sub_option(list)
+    };
+
     CreateCommand::new("dictionary")
         .description("Dictionary")
-        .set_options(vec![add, list, delete])
+        .set_options(vec![add, list, delete, test, import, export, reading])
 }
 
-async fn get_regsiterd(dictionary: &Dictionary, word: &str) -> Result<Option<Uuid>> {
-    let GetUserDictResult::Ok(list) = dictionary
-        .list()
-        .await
-        .context("failed to get dictionary to register word")?;
-    let uuids = list
-        .into_iter()
-        .filter(|(_uuid, item)| item.surface == to_full_width(word))
-        .collect::<IndexMap<_, _>>();
-    if uuids.len() > 1 {
-        bail!("{word} is registered in more than one");
-    }
+/// Finds registered words in the text in full width, ordered by where they appear and then by priority.
+fn match_words<'a>(items: impl Iterator<Item = &'a Item>, text: &str) -> Vec<WordMatch<'a>> {
+    let mut matches = items
+        .filter(|item| !item.surface.is_empty())
+        .flat_map(|item| {
+            text.match_indices(&item.surface)
+                .map(move |(start, surface)| WordMatch {
+                    position: text[..start].chars().count(),
+                    range: start..start + surface.len(),
+                    item,
+                })
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|word_match| {
+        (
+            word_match.range.start,
+            Reverse(word_match.item.priority),
+            Reverse(word_match.range.len()),
+        )
+    });
 
-    Ok(uuids.into_keys().next())
+    // 先に一致した単語と重なるものは適用されない
+    let mut end = 0;
+    matches.retain(|word_match| {
+        let applied = word_match.range.start >= end;
+        if applied {
+            end = word_match.range.end;
+        }
+        applied
+    });
+    matches
 }
 
-async fn register_word(
-    context: &Context,
-    interaction: &CommandInteraction,
-    dictionary: &Dictionary,
-    property: &HashMap<&str, String>,
-) -> Result<()> {
-    let word = property
-        .get("surface")
-        .context("there is no surface to register word")?;
-    let pronunciation = property
-        .get("pronunciation")
-        .context("there is no pronunciation to register word")?;
-    let parameters = property
-        .iter()
-        .map(|(&key, value)| (key, value.as_str()))
-        .collect::<Vec<_>>();
-
-    let response = match dictionary.register_word(&parameters).await {
-        Ok(response) => response,
-        Err(error) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語の登録に失敗しました。")
-                    .field("詳細", format!("```\n{}\n```", error), false)
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            bail!("failed to register {word} into dictionary\nError: {error:?}");
-        },
-    };
+fn apply_matches(text: &str, matches: &[WordMatch<'_>]) -> String {
+    let mut applied = String::with_capacity(text.len());
+    let mut start = 0;
+    for word_match in matches {
+        applied.push_str(&text[start..word_match.range.start]);
+        applied.push_str(&word_match.item.pronunciation);
+        start = word_match.range.end;
+    }
+    applied.push_str(&text[start..]);
+    applied
+}
 
-    match response {
-        PostUserDictWordResult::Ok(_id) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語を登録しました。")
-                    .field("単語", format!("```\n{}\n```", word), false)
-                    .field("ヨミ", format!("```\n{}\n```", pronunciation), false)
-                    .colour(Colour::FOOYOO),
-            );
-            respond(context, interaction, &message).await?;
-        },
-        PostUserDictWordResult::UnprocessableEntity(error) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語の登録に失敗しました。")
-                    .field("詳細", format!("```\n{}\n```", error.detail), false)
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            bail!("failed to register {word} into dictionary\nError: {error:?}");
-        },
-    };
+fn escape_stage_text(text: &str) -> String {
+    escape(text, STAGE_TEXT_LIMIT)
+}
 
-    Ok(())
+/// Escapes the text to be shown in a code block, cutting it at the number of characters.
+fn escape(text: &str, limit: usize) -> String {
+    let escaped = text.replace('\n', "⏎").replace("```", "`\u{200B}``");
+    match escaped.char_indices().nth(limit) {
+        Some((index, _)) => format!("{}…", &escaped[..index]),
+        None => escaped,
+    }
 }
 
-async fn update_word(
-    context: &Context,
-    interaction: &CommandInteraction,
-    dictionary: &Dictionary,
-    uuid: &Uuid,
-    property: &HashMap<&str, String>,
-) -> Result<()> {
-    let word = property.get("surface").context("there is no surface to update word")?;
-    let pronunciation = property
-        .get("pronunciation")
-        .context("there is no pronunciation to update word")?;
-    let parameters = property
-        .iter()
-        .map(|(&key, value)| (key, value.as_str()))
-        .collect::<Vec<_>>();
+#[cfg(test)]
+mod tests {
+    use voicevox::dictionary::response::Item;
 
-    let response = match dictionary.update_word(uuid, &parameters).await {
-        Ok(response) => response,
-        Err(error) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語の更新に失敗しました。")
-                    .field("詳細", format!("```\n{}\n```", error), false)
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            bail!("failed to update {word} in dictionary\nError: {error:?}");
-        },
-    };
+    use super::{apply_matches, match_words};
 
-    match response {
-        PutUserDictWordResult::NoContent => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語を更新しました。")
-                    .field("単語", format!("```\n{}\n```", word), false)
-                    .field("ヨミ", format!("```\n{}\n```", pronunciation), false)
-                    .colour(Colour::FOOYOO),
-            );
-            respond(context, interaction, &message).await?;
-        },
-        PutUserDictWordResult::UnprocessableEntity(error) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語の更新に失敗しました。")
-                    .field("詳細", format!("```\n{}\n```", error.detail), false)
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            bail!("failed to update {word} in dictionary\nError: {error:?}");
-        },
-    };
+    fn item(surface: &str, pronunciation: &str, priority: u32) -> Item {
+        Item {
+            accent_associative_rule: "*".to_string(),
+            accent_type: 0,
+            context_id: 1348,
+            inflectional_form: "*".to_string(),
+            inflectional_type: "*".to_string(),
+            mora_count: 0,
+            part_of_speech: "名詞".to_string(),
+            part_of_speech_detail_1: "固有名詞".to_string(),
+            part_of_speech_detail_2: "一般".to_string(),
+            part_of_speech_detail_3: "*".to_string(),
+            priority,
+            pronunciation: pronunciation.to_string(),
+            stem: "*".to_string(),
+            surface: surface.to_string(),
+        }
+    }
 
-    Ok(())
-}
+    fn surfaces(items: &[Item], text: &str) -> Vec<String> {
+        match_words(items.iter(), text)
+            .iter()
+            .map(|word_match| word_match.item.surface.clone())
+            .collect()
+    }
 
-async fn delete_word(
-    context: &Context,
-    interaction: &CommandInteraction,
-    dictionary: &Dictionary,
-    uuid: &Uuid,
-    word: &str,
-) -> Result<()> {
-    let response = match dictionary.delete_word(uuid).await {
-        Ok(response) => response,
-        Err(error) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語の削除に失敗しました。")
-                    .field("詳細", format!("```\n{}\n```", error), false)
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            bail!("failed to delete {word} in dictionary\nError: {error:?}");
-        },
-    };
+    #[test]
+    fn skip_words_overlapping_earlier_matches() {
+        let items = [item("ＡＢ", "エービー", 5), item("ＢＣ", "ビーシー", 5)];
+        assert_eq!(surfaces(&items, "ＡＢＣ"), ["ＡＢ"]);
+        assert_eq!(
+            apply_matches("ＡＢＣ", &match_words(items.iter(), "ＡＢＣ")),
+            "エービーＣ"
+        );
+        assert_eq!(surfaces(&items, "ＡＢとＢＣ"), ["ＡＢ", "ＢＣ"]);
+    }
 
-    match response {
-        DeleteUserDictWordResult::NoContent => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語を削除しました。")
-                    .field("単語", format!("```\n{}\n```", word), false)
-                    .colour(Colour::FOOYOO),
-            );
-            respond(context, interaction, &message).await?;
-        },
-        DeleteUserDictWordResult::UnprocessableEntity(error) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .title("単語の削除に失敗しました。")
-                    .field("詳細", format!("```\n{}\n```", error.detail), false)
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            bail!("failed to delete {word} in dictionary\nError: {error:?}");
-        },
-    };
+    #[test]
+    fn prefer_higher_priority_then_longer_words_at_same_position() {
+        let items = [
+            item("ＡＢ", "エービー", 5),
+            item("ＡＢＣ", "エービーシー", 5),
+            item("Ａ", "エー", 8),
+        ];
+        assert_eq!(surfaces(&items, "ＡＢＣ"), ["Ａ"]);
 
-    Ok(())
+        let items = [item("ＡＢ", "エービー", 5), item("ＡＢＣ", "エービーシー", 5)];
+        assert_eq!(surfaces(&items, "ＡＢＣ"), ["ＡＢＣ"]);
+    }
 }
@@ -0,0 +1,144 @@
+use anyhow::{Context as _, Result};
+use database::{PgPool, emoji_reading};
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::{quota, utils::respond};
+
+use super::{error::CommandError, subcommand::Subcommand};
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /emoji subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+    let emoji = subcommand
+        .options
+        .get("emoji")
+        .and_then(|v| v.as_str())
+        .map(|emoji| (emoji.trim(), parse_emoji(emoji)));
+
+    let embed = match (subcommand.name, emoji) {
+        (_, Some((emoji, None))) => CreateEmbed::new()
+            .title("カスタム絵文字を指定してください。")
+            .description(emoji)
+            .colour(Colour::RED),
+        ("set", Some((emoji, Some((name, emoji_id))))) => {
+            let reading = subcommand
+                .options
+                .get("reading")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .context("no reading option")?;
+
+            let readings = emoji_reading::fetch_by_guild_id(database, guild_id.get()).await?;
+            let is_registered = readings.iter().any(|registered| registered.emoji_id == emoji_id as i64);
+            let count = readings.len() as i64;
+            if !is_registered && count >= quota::EMOJI_READINGS {
+                CreateEmbed::new()
+                    .title("登録できる絵文字の読みの数の上限に達しています。")
+                    .description("`/emoji remove` で不要な読みを削除してください。")
+                    .field("使用量", format!("{count} / {}", quota::EMOJI_READINGS), false)
+                    .colour(Colour::RED)
+            } else {
+                emoji_reading::create(database, guild_id.get(), emoji_id, name, reading).await?;
+                CreateEmbed::new()
+                    .title("絵文字の読みを登録しました。")
+                    .description(format!("{emoji} -> {reading}"))
+                    .colour(Colour::FOOYOO)
+            }
+        },
+        ("remove", Some((emoji, Some((_, emoji_id))))) => {
+            match emoji_reading::delete(database, guild_id.get(), emoji_id).await? {
+                true => CreateEmbed::new()
+                    .title("絵文字の読みを削除しました。")
+                    .description(emoji)
+                    .colour(Colour::FOOYOO),
+                false => CreateEmbed::new()
+                    .title("絵文字の読みは登録されていません。")
+                    .description(emoji)
+                    .colour(Colour::RED),
+            }
+        },
+        ("list", _) => {
+            let readings = emoji_reading::fetch_by_guild_id(database, guild_id.get())
+                .await?
+                .into_iter()
+                .map(|reading| format!(":{}: -> {}", reading.name, reading.reading))
+                .collect::<Vec<_>>();
+            let description = match readings.is_empty() {
+                true => "登録されている絵文字の読みはありません。\n読みのない絵文字は名前で読み上げます。".to_string(),
+                false => format!("```\n{}\n```", readings.join("\n")),
+            };
+            CreateEmbed::new()
+                .title("絵文字の読み一覧")
+                .description(description)
+                .colour(Colour::FOOYOO)
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let set = {
+        let emoji = CreateCommandOption::new(CommandOptionType::String, "emoji", "Custom emoji to be read")
+            .name_localized("ja", "絵文字")
+            .description_localized("ja", "読みを登録するカスタム絵文字")
+            .required(true);
+        let reading = CreateCommandOption::new(CommandOptionType::String, "reading", "How the emoji is read")
+            .name_localized("ja", "読み")
+            .description_localized("ja", "絵文字の読み")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "set",
+            "Registers how a custom emoji is read in this server",
+        )
+        .description_localized("ja", "このサーバーでのカスタム絵文字の読みを登録します。")
+        .add_sub_option(emoji)
+        .add_sub_option(reading)
+    };
+    let remove = {
+        let emoji = CreateCommandOption::new(CommandOptionType::String, "emoji", "Custom emoji to be removed")
+            .name_localized("ja", "絵文字")
+            .description_localized("ja", "読みを削除するカスタム絵文字")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "remove",
+            "Removes how a custom emoji is read",
+        )
+        .description_localized("ja", "カスタム絵文字の読みを削除します。")
+        .add_sub_option(emoji)
+    };
+    let list = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "list",
+        "Lists registered readings of emoji",
+    )
+    .description_localized("ja", "登録されているカスタム絵文字の読みを表示します。");
+
+    CreateCommand::new("emoji")
+        .description("サーバーごとのカスタム絵文字の読みを設定します。")
+        .set_options(vec![set, remove, list])
+}
+
+/// Name and id of the custom emoji, such as `<:name:id>`.
+fn parse_emoji(emoji: &str) -> Option<(&str, u64)> {
+    let captures = seitai_converter::regex::EMOJI.captures(emoji.trim())?;
+    let name = captures.get(1)?.as_str();
+    let emoji_id = captures.get(2)?.as_str().parse().ok()?;
+    Some((name, emoji_id))
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serenity::{
+    builder::{CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, EditInteractionResponse},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::i18n::{Locale, t};
+
+/// Reason a command could not be executed, shown to the user as an embed.
+///
+/// Commands return it through [`anyhow::Error`], and any other error is reported as [`CommandError::Failed`].
+#[derive(Debug)]
+pub(crate) enum CommandError {
+    /// The command was used outside of guilds.
+    GuildOnly,
+    /// The user lacks the permission to manage the guild.
+    PermissionDenied,
+    /// The user is not the owner of the guild.
+    OwnerOnly,
+    /// The user is not in any voice channel.
+    NotInVoiceChannel,
+    /// VOICEVOX ENGINE is not available.
+    EngineUnavailable,
+    Failed(anyhow::Error),
+}
+
+impl CommandError {
+    fn embed(&self, locale: Locale) -> CreateEmbed {
+        let key = match self {
+            Self::GuildOnly => "error.guild_only",
+            Self::PermissionDenied => "error.permission_denied",
+            Self::OwnerOnly => "error.owner_only",
+            Self::NotInVoiceChannel => "error.not_in_voice_channel",
+            Self::EngineUnavailable => "error.engine_unavailable",
+            Self::Failed(_) => "error.failed",
+        };
+        let embed = CreateEmbed::new().description(t(locale, key)).colour(Colour::RED);
+        match self {
+            Self::Failed(error) => embed.field(t(locale, "common.detail"), format!("```\n{error}\n```"), false),
+            _ => embed,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GuildOnly => f.write_str("command is only for guilds"),
+            Self::PermissionDenied => f.write_str("user is not permitted to manage guild"),
+            Self::OwnerOnly => f.write_str("user is not owner of guild"),
+            Self::NotInVoiceChannel => f.write_str("user is not in voice channel"),
+            Self::EngineUnavailable => f.write_str("voicevox engine is not available"),
+            Self::Failed(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<anyhow::Error> for CommandError {
+    fn from(error: anyhow::Error) -> Self {
+        error.downcast::<Self>().unwrap_or_else(Self::Failed)
+    }
+}
+
+/// Logs the error of the command with the context and tells the user why it failed.
+pub(crate) async fn report(context: &Context, interaction: &CommandInteraction, error: anyhow::Error) {
+    let name = &interaction.data.name;
+    let guild_id = interaction.guild_id;
+    let error = CommandError::from(error);
+    match &error {
+        CommandError::Failed(error) => {
+            tracing::error!("failed to handle /{name} in guild {guild_id:?}\nError: {error:?}");
+        },
+        error => {
+            tracing::info!("refused /{name} in guild {guild_id:?}: {error}");
+        },
+    }
+
+    let embed = error.embed(Locale::from_tag(&interaction.locale));
+    let message = CreateInteractionResponseMessage::new()
+        .embed(embed.clone())
+        .ephemeral(true);
+    if interaction
+        .create_response(&context.http, CreateInteractionResponse::Message(message))
+        .await
+        .is_ok()
+    {
+        return;
+    }
+
+    // 応答を保留したか既に応答していれば書き換える
+    if let Err(error) = interaction
+        .edit_response(&context.http, EditInteractionResponse::new().embed(embed))
+        .await
+    {
+        tracing::error!("failed to report error of /{name}\nError: {error:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context as _;
+
+    use super::CommandError;
+
+    #[test]
+    fn keep_reason_through_context() {
+        let error = Err::<(), _>(CommandError::NotInVoiceChannel)
+            .context("failed to execute /join")
+            .unwrap_err();
+        assert!(matches!(CommandError::from(error), CommandError::NotInVoiceChannel));
+
+        let error = anyhow::anyhow!("connection refused").context("failed to execute /join");
+        assert!(matches!(CommandError::from(error), CommandError::Failed(_)));
+    }
+}
@@ -1,73 +1,285 @@
-use anyhow::Result;
+use std::collections::HashMap;
+
+use anyhow::{Context as _, Result};
+use serde::Deserialize;
 use serenity::{
-    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    all::{ButtonStyle, CommandOption, ComponentInteraction},
+    builder::{
+        AutocompleteChoice, CreateActionRow, CreateAutocompleteResponse, CreateButton, CreateCommand,
+        CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+        CreateInteractionResponseMessage,
+    },
     client::Context,
-    model::application::{CommandInteraction, CommandOptionType},
+    model::{
+        Colour,
+        application::{CommandInteraction, CommandOptionType},
+    },
+};
+
+use crate::{
+    i18n::{Locale, t, t_with},
+    utils::respond,
 };
 
-use crate::utils::respond;
+// ページ送りのボタンの custom_id の接頭辞
+pub(crate) const PAGE_PREFIX: &str = "help:page:";
+// 1 ページに表示するコマンドの数
+const PAGE_SIZE: usize = 12;
+
+/// What `/help` needs to know of a registered command, read back from the JSON sent to Discord.
+#[derive(Debug, Deserialize)]
+struct CommandHelp {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    description_localizations: HashMap<String, String>,
+    #[serde(default)]
+    options: Vec<CommandOption>,
+}
 
 pub async fn run(context: &Context, interaction: &CommandInteraction) -> Result<()> {
-    let mut embeds = interaction
+    let helps = command_helps()?;
+    let locale = Locale::from_tag(&interaction.locale);
+    let name = interaction
         .data
         .options
         .iter()
-        .filter_map(|response_option| match response_option.name.as_str() {
-            "command" => match response_option.value.as_str().unwrap_or_default() {
-                "join" => Some(CreateEmbed::new().title("/join").description("ボイスチャンネルに接続します。")),
-                "leave" => Some(CreateEmbed::new().title("/leave").description("ボイスチャンネルから切断します。")),
-                "dictionary" => Some(
-                    CreateEmbed::new().title("/dictionary").description("辞書関連のコマンドです。").fields([
-                        (
-                            "add",
-                            format!(
-                                "{}\n{}",
-                                "単語を辞書に追加します。任意で指定できる`音が下がる位置`については次のリンクを参照してください。",
-                                "https://tdmelodic.readthedocs.io/ja/latest/pages/introduction.html#representation-of-accent-nuclei-by-digits"
-                            )
-                            .as_str(),
-                            false,
-                        ),
-                        ("list", "単語一覧を表示します。", true),
-                        ("delete", "単語を削除します。", true),
-                    ]),
-                ),
-                _ => None,
-            },
-            _ => None,
-        })
-        .collect::<Vec<_>>();
+        .find(|option| option.name == "command")
+        .and_then(|option| option.value.as_str())
+        .map(|name| name.trim_start_matches('/'));
 
-    if embeds.is_empty() {
-        embeds.push(CreateEmbed::new().title("help").fields([
-            ("/join", "ボイスチャンネルに接続します。", true),
-            ("/leave", "ボイスチャンネルから切断します。", true),
-            (
-                "/dictionary add",
-                &format!(
-                    "{}\n{}",
-                    "単語を辞書に追加します。任意で指定できる`音が下がる位置`については次のリンクを参照してください。",
-                    "https://tdmelodic.readthedocs.io/ja/latest/pages/introduction.html#representation-of-accent-nuclei-by-digits"
-                ),
-                false,
+    let message = match name {
+        Some(name) => match helps.iter().find(|help| help.name == name) {
+            Some(help) => CreateInteractionResponseMessage::new().embed(describe(help, locale)),
+            None => CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(t_with(locale, "help.not_found", &[("name", name)]))
+                    .colour(Colour::RED),
             ),
-            ("/dictionary list", "単語一覧を表示します。", true),
-            ("/dictionary delete", "単語を削除します。", true),
-        ]));
-    }
-
-    let message = CreateInteractionResponseMessage::new().embeds(embeds);
+        },
+        None => page_message(&helps, 1, locale),
+    };
     respond(context, interaction, &message).await?;
     Ok(())
 }
 
+pub(crate) async fn handle_component(context: &Context, component: &ComponentInteraction) -> Result<()> {
+    let page = component
+        .data
+        .custom_id
+        .strip_prefix(PAGE_PREFIX)
+        .and_then(|page| page.parse::<usize>().ok())
+        .context("component is not of help pages")?;
+
+    let helps = command_helps()?;
+    let locale = Locale::from_tag(&component.locale);
+    component
+        .create_response(
+            &context.http,
+            CreateInteractionResponse::UpdateMessage(page_message(&helps, page, locale)),
+        )
+        .await
+        .context("failed to turn help page")?;
+
+    Ok(())
+}
+
+pub(crate) async fn autocomplete(context: &Context, interaction: &CommandInteraction) -> Result<()> {
+    let Some(option) = interaction.data.autocomplete() else {
+        return Ok(());
+    };
+
+    let value = option.value.trim_start_matches('/');
+    let choices = command_helps()?
+        .into_iter()
+        .filter(|help| help.name.contains(value))
+        .take(25)
+        .map(|help| AutocompleteChoice::new(format!("/{}", help.name), help.name))
+        .collect::<Vec<_>>();
+    interaction
+        .create_response(
+            &context.http,
+            CreateInteractionResponse::Autocomplete(CreateAutocompleteResponse::new().set_choices(choices)),
+        )
+        .await
+        .context("failed to autocomplete command names")?;
+
+    Ok(())
+}
+
+fn command_helps() -> Result<Vec<CommandHelp>> {
+    let mut helps = super::register_all()
+        .iter()
+        .map(|command| serde_json::to_value(command).and_then(serde_json::from_value::<CommandHelp>))
+        .collect::<serde_json::Result<Vec<_>>>()
+        .context("failed to read registered commands")?;
+    helps.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(helps)
+}
+
+fn page_message(helps: &[CommandHelp], page: usize, locale: Locale) -> CreateInteractionResponseMessage {
+    let pages = helps.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.clamp(1, pages);
+    let fields = helps.iter().skip((page - 1) * PAGE_SIZE).take(PAGE_SIZE).map(|help| {
+        (
+            format!("/{}", help.name),
+            localized(locale, &help.description, &help.description_localizations),
+            true,
+        )
+    });
+
+    let embed = CreateEmbed::new()
+        .title("help")
+        .description(t(locale, "help.description"))
+        .fields(fields)
+        .footer(CreateEmbedFooter::new(t_with(
+            locale,
+            "common.page",
+            &[("page", &page.to_string()), ("pages", &pages.to_string())],
+        )))
+        .colour(Colour::FOOYOO);
+    let buttons = vec![
+        CreateButton::new(format!("{PAGE_PREFIX}{}", page - 1))
+            .label(t(locale, "common.previous"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 1),
+        CreateButton::new(format!("{PAGE_PREFIX}{}", page + 1))
+            .label(t(locale, "common.next"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page == pages),
+    ];
+
+    CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(vec![CreateActionRow::Buttons(buttons)])
+}
+
+fn describe(help: &CommandHelp, locale: Locale) -> CreateEmbed {
+    let mut description = localized(locale, &help.description, &help.description_localizations).to_string();
+    if let Some(note) = note(&help.name) {
+        description = format!("{description}\n{}", t(locale, note));
+    }
+
+    let mut fields = Vec::new();
+    let arguments = describe_arguments(&help.options, locale);
+    if !arguments.is_empty() {
+        fields.push((t(locale, "help.options").to_string(), arguments, false));
+    }
+    for option in &help.options {
+        match option.kind {
+            CommandOptionType::SubCommand => {
+                fields.push(describe_subcommand(&format!("/{}", help.name), option, locale));
+            },
+            CommandOptionType::SubCommandGroup => {
+                let prefix = format!("/{} {}", help.name, option.name);
+                fields.extend(
+                    option
+                        .options
+                        .iter()
+                        .map(|subcommand| describe_subcommand(&prefix, subcommand, locale)),
+                );
+            },
+            _ => {},
+        }
+    }
+
+    CreateEmbed::new()
+        .title(format!("/{}", help.name))
+        .description(description)
+        .fields(fields)
+        .colour(Colour::FOOYOO)
+}
+
+fn describe_subcommand(prefix: &str, subcommand: &CommandOption, locale: Locale) -> (String, String, bool) {
+    let mut value = localized(
+        locale,
+        &subcommand.description,
+        subcommand.description_localizations.as_ref(),
+    )
+    .to_string();
+    let arguments = describe_arguments(&subcommand.options, locale);
+    if !arguments.is_empty() {
+        value = format!("{value}\n{arguments}");
+    }
+    (format!("{prefix} {}", subcommand.name), value, false)
+}
+
+// サブコマンドではないオプションを 1 行ずつ説明する
+fn describe_arguments(options: &[CommandOption], locale: Locale) -> String {
+    options
+        .iter()
+        .filter(|option| {
+            !matches!(
+                option.kind,
+                CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup
+            )
+        })
+        .map(|option| {
+            let name = match locale {
+                Locale::Ja => option
+                    .name_localizations
+                    .as_ref()
+                    .and_then(|names| names.get("ja"))
+                    .unwrap_or(&option.name),
+                Locale::En => &option.name,
+            };
+            let description = localized(locale, &option.description, option.description_localizations.as_ref());
+            let arguments = [("name", name.as_str()), ("description", description)];
+            match option.required {
+                true => t_with(locale, "help.argument.required", &arguments),
+                false => t_with(locale, "help.argument.optional", &arguments),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// 英語の説明は既定の説明か en-US のローカライズにある
+fn localized<'a>(
+    locale: Locale,
+    description: &'a str,
+    localizations: impl Into<Option<&'a HashMap<String, String>>>,
+) -> &'a str {
+    let tag = match locale {
+        Locale::Ja => "ja",
+        Locale::En => "en-US",
+    };
+    localizations
+        .into()
+        .and_then(|localizations| localizations.get(tag))
+        .map_or(description, String::as_str)
+}
+
+// 登録した説明だけでは伝わらない補足のメッセージのキー
+fn note(name: &str) -> Option<&'static str> {
+    match name {
+        "dictionary" => Some("help.note.dictionary"),
+        _ => None,
+    }
+}
+
 pub fn register() -> CreateCommand {
-    let channels = CreateCommandOption::new(CommandOptionType::String, "command", "chose command")
-        .add_string_choice("join", "join")
-        .add_string_choice("leave", "leave")
-        .add_string_choice("dictionary", "dictionary");
+    let command = CreateCommandOption::new(CommandOptionType::String, "command", "Command to show help about")
+        .name_localized("ja", "コマンド")
+        .description_localized("ja", "使い方を表示するコマンド")
+        .set_autocomplete(true);
 
     CreateCommand::new("help")
-        .description("Specific command to show help about")
-        .set_options(vec![channels])
+        .description("コマンドの使い方を表示します。")
+        .add_option(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::command_helps;
+
+    #[test]
+    fn every_command_is_documented() {
+        let helps = command_helps().unwrap();
+        assert!(helps.iter().any(|help| help.name == "help"));
+        for help in helps {
+            assert!(!help.description.is_empty(), "/{} has no description", help.name);
+        }
+    }
 }
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::{speaker::Speaker, utils::respond};
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let intonation = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "intonation")
+        .and_then(|option| option.value.as_f64());
+
+    let embed = match intonation {
+        Some(intonation) if !(Speaker::min_intonation()..=Speaker::max_intonation()).contains(&(intonation as f32)) => {
+            CreateEmbed::new()
+                .description(format!(
+                    "抑揚は{}から{}の間で指定してください。",
+                    Speaker::min_intonation(),
+                    Speaker::max_intonation()
+                ))
+                .colour(Colour::RED)
+        },
+        Some(intonation) => {
+            let tone =
+                database::user_tone::update_intonation(database, interaction.user.id.get(), Some(intonation as f32))
+                    .await?;
+            CreateEmbed::new()
+                .title("読み上げの抑揚を変更しました。")
+                .description(format!("{}", tone.intonation.unwrap_or(Speaker::default_intonation())))
+                .colour(Colour::FOOYOO)
+        },
+        None => {
+            database::user_tone::update_intonation(database, interaction.user.id.get(), None).await?;
+            CreateEmbed::new()
+                .title("読み上げの抑揚をリセットしました。")
+                .description(format!("{}", Speaker::default_intonation()))
+                .colour(Colour::FOOYOO)
+        },
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let intonation = CreateCommandOption::new(CommandOptionType::Number, "intonation", "Intonation, or none to reset")
+        .name_localized("ja", "抑揚")
+        .description_localized("ja", "読み上げの抑揚（省略するとリセットします）")
+        .min_number_value(f64::from(Speaker::min_intonation()))
+        .max_number_value(f64::from(Speaker::max_intonation()));
+
+    CreateCommand::new("intonation")
+        .description("あなたのメッセージを読み上げる抑揚を設定します。")
+        .add_option(intonation)
+}
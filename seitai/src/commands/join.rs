@@ -1,99 +1,253 @@
 use std::sync::Arc;
 
 use anyhow::{Context as _, Result};
+use database::PgPool;
+use futures::lock::Mutex;
 use hashbrown::HashMap;
-use ordered_float::NotNan;
+use seitai_audio::AudioRepository;
 use serenity::{
-    all::{ChannelId, GuildId},
+    all::{ButtonStyle, ChannelId, ComponentInteraction, GuildId},
     async_trait,
-    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    builder::{
+        CreateActionRow, CreateButton, CreateCommand, CreateEmbed, CreateInteractionResponse,
+        CreateInteractionResponseMessage, EditInteractionResponse,
+    },
     client::Context,
     model::{Colour, application::CommandInteraction},
 };
 use songbird::{CoreEvent, Event, EventContext, EventHandler, Songbird, input::Input};
 
+use super::error::CommandError;
 use crate::{
-    audio::{Audio, AudioRepository, cache::PredefinedUtterance},
-    speaker::Speaker,
+    connection,
+    fallback::TextFallbacks,
+    i18n::{Locale, t},
+    topic::{self, Topics},
     utils::{get_guild, get_manager, respond},
+    voice_activity::{self, VoiceActivityHandler},
 };
 
+// テキストでの読み上げのボタンの custom_id の接頭辞
+pub(crate) const FALLBACK_PREFIX: &str = "join:fallback:";
+
 pub(crate) async fn run<Repository>(
     context: &Context,
+    database: &PgPool,
     audio_repository: &Repository,
-    connections: &mut HashMap<GuildId, ChannelId>,
+    connections: &Mutex<HashMap<GuildId, ChannelId>>,
+    topics: &Topics,
+    fallbacks: &TextFallbacks,
     interaction: &CommandInteraction,
 ) -> Result<()>
 where
     Repository: AudioRepository<Input = Input> + Send + Sync,
 {
-    let guild = match get_guild(context, interaction) {
-        Some(guild) => guild,
-        None => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .description("このコマンドは使えません。")
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            return Ok(());
-        },
-    };
-    let channel_id = guild
+    let guild = get_guild(context, interaction).ok_or(CommandError::GuildOnly)?;
+    let locale = Locale::from_tag(&interaction.locale);
+    let connect_to = guild
         .voice_states
         .get(&interaction.user.id)
-        .and_then(|voice_state| voice_state.channel_id);
-
-    let connect_to = match channel_id {
-        Some(channel) => channel,
-        None => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .description("接続先のボイスチャンネルが見つかりません。")
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
-            return Ok(());
-        },
-    };
+        .and_then(|voice_state| voice_state.channel_id)
+        .ok_or(CommandError::NotInVoiceChannel)?;
 
     let manager = get_manager(context).await?;
     let call = manager.get_or_insert(guild.id);
 
-    let join = { call.lock().await.join(connect_to).await? };
-    join.await?;
-    call.lock().await.add_global_event(
-        CoreEvent::DriverDisconnect.into(),
-        DriverDisconnectNotifier {
-            songbird_manager: manager,
-        },
-    );
+    let mut deferred = false;
+    let current_channel = call.lock().await.current_channel();
+    if current_channel.is_some_and(|channel_id| ChannelId::from(channel_id.0) != connect_to) {
+        // 読み上げ中の発話を待つと応答期限を過ぎてしまう
+        interaction.defer(&context.http).await?;
+        deferred = true;
+        let settings = database::guild::fetch_by_id(database, guild.id.get()).await?;
+        connection::drain(&call, audio_repository, &settings).await;
+    }
+    if let Err(error) = connect(
+        context,
+        database,
+        guild.id,
+        connect_to,
+        interaction.channel_id,
+        connections,
+    )
+    .await
+    {
+        tracing::error!("failed to join channel {connect_to}\nError: {error:?}");
+        let mut embed = CreateEmbed::new()
+            .description(t(locale, "join.failed"))
+            .field(t(locale, "common.detail"), format!("```\n{error}\n```"), false)
+            .colour(Colour::RED);
+        let mut components = Vec::new();
+        // 障害や権限の不足で続けて失敗する場合は、テキストで読み上げられるようにする
+        if fallbacks.record_failure(guild.id) {
+            embed = embed.field(
+                t(locale, "join.fallback.title"),
+                t(locale, "join.fallback.offer"),
+                false,
+            );
+            components.push(CreateActionRow::Buttons(vec![
+                CreateButton::new(format!("{FALLBACK_PREFIX}start"))
+                    .label(t(locale, "join.fallback.start"))
+                    .style(ButtonStyle::Primary),
+            ]));
+        }
+        if deferred {
+            interaction
+                .edit_response(
+                    &context.http,
+                    EditInteractionResponse::new().embed(embed).components(components),
+                )
+                .await?;
+        } else {
+            let message = CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(components);
+            respond(context, interaction, &message).await?;
+        }
+        return Ok(());
+    }
+
+    let embed = CreateEmbed::new()
+        .description(t(locale, "join.joined"))
+        .colour(Colour::FOOYOO);
+    if deferred {
+        interaction
+            .edit_response(&context.http, EditInteractionResponse::new().embed(embed))
+            .await?;
+    } else {
+        let message = CreateInteractionResponseMessage::new().embed(embed);
+        respond(context, interaction, &message).await?;
+    }
 
-    connections.insert(guild.id, interaction.channel_id);
+    read_topic(
+        context,
+        database,
+        audio_repository,
+        topics,
+        guild.id,
+        interaction.channel_id,
+    )
+    .await?;
 
-    let message = CreateInteractionResponseMessage::new().embed(
-        CreateEmbed::new()
-            .description("ボイスチャンネルに接続しました。")
-            .colour(Colour::FOOYOO),
-    );
-    respond(context, interaction, &message).await?;
+    Ok(())
+}
+
+pub(crate) async fn handle_component(
+    context: &Context,
+    component: &ComponentInteraction,
+    fallbacks: &TextFallbacks,
+) -> Result<()> {
+    let guild_id = component.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&component.locale);
+    let action = component
+        .data
+        .custom_id
+        .strip_prefix(FALLBACK_PREFIX)
+        .context("component is not of text fallback")?;
 
-    /*
+    let message = match action {
+        "start" => {
+            fallbacks.start(guild_id, component.channel_id);
+            let embed = CreateEmbed::new()
+                .description(t(locale, "join.fallback.started"))
+                .colour(Colour::ORANGE);
+            let stop = CreateButton::new(format!("{FALLBACK_PREFIX}stop"))
+                .label(t(locale, "join.fallback.stop"))
+                .style(ButtonStyle::Secondary);
+            CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(vec![CreateActionRow::Buttons(vec![stop])])
+        },
+        _ => {
+            let description = match fallbacks.stop(guild_id) {
+                true => t(locale, "join.fallback.stopped"),
+                false => t(locale, "join.fallback.already_stopped"),
+            };
+            CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO))
+                .components(Vec::new())
+        },
+    };
+    component
+        .create_response(&context.http, CreateInteractionResponse::UpdateMessage(message))
+        .await
+        .context("failed to update text fallback")?;
+
+    Ok(())
+}
+
+/// Joins the voice channel and reads messages in the text channel aloud.
+pub(crate) async fn connect(
+    context: &Context,
+    database: &PgPool,
+    guild_id: GuildId,
+    voice_channel_id: ChannelId,
+    text_channel_id: ChannelId,
+    connections: &Mutex<HashMap<GuildId, ChannelId>>,
+) -> Result<()> {
+    let manager = get_manager(context).await?;
+    let voice_activity = voice_activity::get(context).await?;
+    let call = manager.get_or_insert(guild_id);
+    let join = call.lock().await.join(voice_channel_id).await?;
+    join.await?;
     {
         let mut call = call.lock().await;
+        // 移動で接続し直すたびにハンドラーが重ならないようにする
+        call.remove_all_global_events();
+        call.add_global_event(
+            CoreEvent::DriverDisconnect.into(),
+            DriverDisconnectNotifier {
+                songbird_manager: manager,
+            },
+        );
+        let queue = call.queue().clone();
+        call.add_global_event(
+            CoreEvent::VoiceTick.into(),
+            VoiceActivityHandler::new(voice_activity, guild_id, queue),
+        );
+    }
 
-        let audio = Audio {
-            text: PredefinedUtterance::Connected.as_ref().to_string(),
-            speaker: "1".to_string(),
-            speed: NotNan::new(Speaker::default_speed()).unwrap(),
-        };
-        let input = audio_repository
-            .get(audio)
-            .await
-            .context("failed to get audio source")?;
-        call.enqueue_input(input).await;
+    connections.lock().await.insert(guild_id, text_channel_id);
+    // `/rejoin` や再接続で戻れるように覚えておく
+    if let Err(error) =
+        database::last_connection::create(database, guild_id.get(), voice_channel_id.get(), text_channel_id.get()).await
+    {
+        tracing::error!("failed to record connection of guild {guild_id}\nError: {error:?}");
     }
-    */
+
+    Ok(())
+}
+
+/// Reads the topic of the text channel just connected with if the guild chooses.
+pub(crate) async fn read_topic<Repository>(
+    context: &Context,
+    database: &PgPool,
+    audio_repository: &Repository,
+    topics: &Topics,
+    guild_id: GuildId,
+    text_channel_id: ChannelId,
+) -> Result<()>
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let settings = database::guild::fetch_by_id(database, guild_id.get()).await?;
+    if !settings.read_topics {
+        return Ok(());
+    }
+    let topic = context
+        .cache
+        .guild(guild_id)
+        .and_then(|guild| {
+            guild
+                .channels
+                .get(&text_channel_id)
+                .and_then(|channel| channel.topic.clone())
+        })
+        .unwrap_or_default();
+    topics.update(text_channel_id, &topic);
+    let call = get_manager(context).await?.get_or_insert(guild_id);
+    topic::read(&mut *call.lock().await, audio_repository, &topic, &settings).await;
+
     Ok(())
 }
 
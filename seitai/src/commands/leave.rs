@@ -1,48 +1,79 @@
 use anyhow::{Context as _, Result};
+use dashmap::DashSet;
+use database::PgPool;
+use futures::lock::Mutex;
+use hashbrown::HashMap;
+use seitai_audio::AudioRepository;
 use serenity::{
-    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    all::{ChannelId, GuildId},
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage, EditInteractionResponse},
     client::Context,
     model::{Colour, application::CommandInteraction},
 };
+use songbird::input::Input;
 
-use crate::utils::{get_guild, get_manager, respond};
+use crate::{
+    connection,
+    i18n::{Locale, t},
+    utils::{get_manager, respond},
+    worker::ReadingWorkers,
+};
 
-pub(crate) async fn run(context: &Context, interaction: &CommandInteraction) -> Result<()> {
-    let guild = get_guild(context, interaction).context("failed to get guild")?;
+pub(crate) async fn run<Repository>(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    audio_repository: &Repository,
+    connections: &Mutex<HashMap<GuildId, ChannelId>>,
+    reading_workers: &ReadingWorkers,
+    paused_guilds: &DashSet<GuildId>,
+) -> Result<()>
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let guild_id = interaction.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&interaction.locale);
     let manager = get_manager(context).await?;
-    let call = manager.get_or_insert(guild.id);
+    let call = manager.get_or_insert(guild_id);
     let mut call = call.lock().await;
 
     if call.current_connection().is_none() {
         let message = CreateInteractionResponseMessage::new().embed(
             CreateEmbed::new()
-                .description("ボイスチャンネルに接続していません。")
+                .description(t(locale, "common.not_connected"))
                 .colour(Colour::RED),
         );
         respond(context, interaction, &message).await?;
         return Ok(());
     }
 
-    match call.leave().await {
+    // 切断のアナウンスを待つと応答期限を過ぎてしまう
+    interaction.defer(&context.http).await?;
+
+    // 合成中のメッセージが切断後に追加されないように先に取り消す
+    reading_workers.cancel(guild_id);
+    paused_guilds.remove(&guild_id);
+    let settings = database::guild::fetch_by_id(database, guild_id.get()).await?;
+    connection::farewell(&mut call, audio_repository, &settings).await;
+
+    let embed = match call.leave().await {
         Ok(_) => {
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .description("ボイスチャンネルから切断しました。")
-                    .colour(Colour::FOOYOO),
-            );
-            respond(context, interaction, &message).await?;
+            connections.lock().await.remove(&guild_id);
+            CreateEmbed::new()
+                .description(t(locale, "leave.left"))
+                .colour(Colour::FOOYOO)
         },
         Err(error) => {
             tracing::error!("failed to disconnect from voice channel\nError: {error:?}");
-            let message = CreateInteractionResponseMessage::new().embed(
-                CreateEmbed::new()
-                    .description("ボイスチャンネルからの切断に失敗しました。")
-                    .field("詳細", format!("```\n{}\n```", error), false)
-                    .colour(Colour::RED),
-            );
-            respond(context, interaction, &message).await?;
+            CreateEmbed::new()
+                .description(t(locale, "leave.failed"))
+                .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
+                .colour(Colour::RED)
         },
     };
+    interaction
+        .edit_response(&context.http, EditInteractionResponse::new().embed(embed))
+        .await?;
 
     Ok(())
 }
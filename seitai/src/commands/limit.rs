@@ -0,0 +1,74 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::{utils::respond, utterance::PredefinedUtterance};
+
+pub(crate) const MAX_LENGTH: u64 = 2000;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let chars = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "chars")
+        .and_then(|option| option.value.as_i64())
+        .map(i32::try_from)
+        .transpose()
+        .context("failed to convert chars to i32")?;
+    let announce = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "announce")
+        .and_then(|option| option.value.as_bool())
+        .unwrap_or(true);
+
+    database::guild::update_max_length(database, guild_id.get(), chars, announce).await?;
+
+    let description = match (chars, announce) {
+        (Some(chars), true) => format!(
+            "メッセージを{chars}文字まで読み上げ、残りは「{}」と読み上げます。",
+            PredefinedUtterance::Omitted.as_ref()
+        ),
+        (Some(chars), false) => format!("メッセージを{chars}文字まで読み上げ、残りは読み上げません。"),
+        (None, _) => "メッセージを既定の文字数まで読み上げます。".to_string(),
+    };
+    let message = CreateInteractionResponseMessage::new()
+        .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let chars = CreateCommandOption::new(
+        CommandOptionType::Integer,
+        "chars",
+        "Number of characters to read up to, or none for the default",
+    )
+    .name_localized("ja", "文字数")
+    .description_localized("ja", "読み上げる最大の文字数（省略すると既定の文字数まで読み上げます）")
+    .min_int_value(1)
+    .max_int_value(MAX_LENGTH);
+    let announce = CreateCommandOption::new(
+        CommandOptionType::Boolean,
+        "announce",
+        "Whether to announce the rest is omitted, true by default",
+    )
+    .name_localized("ja", "省略を伝える")
+    .description_localized("ja", "省略したことを読み上げるかどうか（省略すると読み上げます）");
+
+    CreateCommand::new("limit")
+        .description("メッセージを読み上げる最大の文字数を設定します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(chars)
+        .add_option(announce)
+}
@@ -1,7 +1,97 @@
+use serenity::builder::CreateCommand;
+
+use crate::i18n;
+
+pub mod about;
+pub mod admin;
+pub mod announce;
+pub mod autojoin;
+pub mod blacklist;
+pub mod channels;
+pub mod config;
 pub mod dictionary;
+pub mod emoji;
+pub mod error;
 pub mod help;
+pub mod intonation;
 pub mod join;
 pub mod leave;
+pub mod limit;
+pub mod mute_user;
+pub mod pause;
+pub mod pitch;
+pub mod quota;
+pub mod ratelimit;
+pub mod read;
+pub mod readname;
+pub mod rejoin;
+pub mod replay;
+pub mod reset;
+pub mod resume;
+pub mod sample;
+pub mod session;
+pub mod settings;
+pub mod skip;
+pub mod sound;
+pub mod soundboard;
 pub mod soundsticker;
+pub mod speed;
+pub mod stats;
+pub mod status;
+pub mod stop;
 pub mod subcommand;
+pub mod telemetry;
+pub mod tts;
+pub mod unmute_user;
 pub mod voice;
+pub mod volume;
+
+/// Every command registered to guilds, which `/help` also reads to describe them.
+pub fn register_all() -> Vec<CreateCommand> {
+    vec![
+        about::register(),
+        admin::register(),
+        dictionary::register(),
+        help::register(),
+        join::register(),
+        leave::register(),
+        quota::register(),
+        settings::register(),
+        skip::register(),
+        stop::register(),
+        voice::register(),
+        soundsticker::register(),
+        speed::register(),
+        pitch::register(),
+        intonation::register(),
+        volume::register(),
+        autojoin::register(),
+        channels::register(),
+        status::register(),
+        tts::register(),
+        mute_user::register(),
+        unmute_user::register(),
+        readname::register(),
+        limit::register(),
+        replay::register(),
+        sample::register(),
+        soundboard::register(),
+        ratelimit::register(),
+        read::register(),
+        sound::register(),
+        stats::register(),
+        telemetry::register(),
+        reset::register(),
+        pause::register(),
+        resume::register(),
+        emoji::register(),
+        session::register(),
+        blacklist::register(),
+        rejoin::register(),
+        config::register(),
+        announce::register(),
+    ]
+    .into_iter()
+    .map(i18n::localize_command)
+    .collect()
+}
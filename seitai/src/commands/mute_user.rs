@@ -0,0 +1,53 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::{CommandOptionType, Mentionable},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::utils::respond;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let user_id = interaction
+        .data
+        .options
+        .first()
+        .and_then(|option| option.value.as_user_id())
+        .context("cannot get member to mute")?;
+
+    database::guild_user_mute::create(database, guild_id.get(), user_id.get(), interaction.user.id.get()).await?;
+    tracing::info!(
+        "{user_id} has been muted in guild {guild_id} by {}",
+        interaction.user.id
+    );
+
+    let message = CreateInteractionResponseMessage::new()
+        .embed(
+            CreateEmbed::new()
+                .description(format!(
+                    "{}のメッセージを読み上げません。`/unmute-user` で元に戻せます。",
+                    user_id.mention()
+                ))
+                .colour(Colour::FOOYOO),
+        )
+        .ephemeral(true);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let member = CreateCommandOption::new(CommandOptionType::User, "member", "Member not to read messages of")
+        .name_localized("ja", "メンバー")
+        .description_localized("ja", "メッセージを読み上げないメンバー")
+        .required(true);
+
+    CreateCommand::new("mute-user")
+        .description("メンバーのメッセージを読み上げないようにします。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(member)
+}
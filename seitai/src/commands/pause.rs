@@ -0,0 +1,75 @@
+use anyhow::{Context as _, Result};
+use dashmap::DashSet;
+use serenity::{
+    all::GuildId,
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use crate::{
+    i18n::{Locale, t},
+    utils::{get_manager, respond},
+};
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    paused_guilds: &DashSet<GuildId>,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&interaction.locale);
+    let manager = get_manager(context).await?;
+    let call = manager.get_or_insert(guild_id);
+    let call = call.lock().await;
+
+    if call.current_connection().is_none() {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description(t(locale, "common.not_connected"))
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+    if !paused_guilds.insert(guild_id) {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description(t(locale, "pause.already_paused"))
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    // 読み上げ中の発話を止めれば、後のメッセージはキューで待つ
+    match call.queue().pause() {
+        Ok(_) => {
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(t(locale, "pause.paused"))
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        Err(error) => {
+            paused_guilds.remove(&guild_id);
+            tracing::error!("failed to pause current track\nError: {error:?}");
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(t(locale, "pause.failed"))
+                    .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
+                    .colour(Colour::RED),
+            );
+            respond(context, interaction, &message).await?;
+        },
+    };
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("pause")
+        .description("ボイスチャンネルに接続したまま読み上げを一時停止します。")
+        .default_member_permissions(Permissions::MUTE_MEMBERS)
+}
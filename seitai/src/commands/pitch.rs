@@ -0,0 +1,61 @@
+use anyhow::Result;
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::{speaker::Speaker, utils::respond};
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let pitch = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "pitch")
+        .and_then(|option| option.value.as_f64());
+
+    let embed = match pitch {
+        Some(pitch) if !(Speaker::min_pitch()..=Speaker::max_pitch()).contains(&(pitch as f32)) => CreateEmbed::new()
+            .description(format!(
+                "高さは{}から{}の間で指定してください。",
+                Speaker::min_pitch(),
+                Speaker::max_pitch()
+            ))
+            .colour(Colour::RED),
+        Some(pitch) => {
+            let tone =
+                database::user_tone::update_pitch(database, interaction.user.id.get(), Some(pitch as f32)).await?;
+            CreateEmbed::new()
+                .title("読み上げの高さを変更しました。")
+                .description(format!("{}", tone.pitch.unwrap_or(Speaker::default_pitch())))
+                .colour(Colour::FOOYOO)
+        },
+        None => {
+            database::user_tone::update_pitch(database, interaction.user.id.get(), None).await?;
+            CreateEmbed::new()
+                .title("読み上げの高さをリセットしました。")
+                .description(format!("{}", Speaker::default_pitch()))
+                .colour(Colour::FOOYOO)
+        },
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let pitch = CreateCommandOption::new(CommandOptionType::Number, "pitch", "Pitch, or none to reset")
+        .name_localized("ja", "高さ")
+        .description_localized("ja", "読み上げの高さ（省略するとリセットします）")
+        .min_number_value(f64::from(Speaker::min_pitch()))
+        .max_number_value(f64::from(Speaker::max_pitch()));
+
+    CreateCommand::new("pitch")
+        .description("あなたのメッセージを読み上げる声の高さを設定します。")
+        .add_option(pitch)
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use database::PgPool;
+use serenity::{
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::{quota, utils::respond};
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let usage = quota::usage(database, guild_id).await?;
+
+    let message = CreateInteractionResponseMessage::new().embed(
+        CreateEmbed::new()
+            .title("使用量")
+            .field(
+                "辞書の単語",
+                format!("{} / {}", usage.dictionary_words, quota::DICTIONARY_WORDS),
+                true,
+            )
+            .field("単語の読み", format!("{} / {}", usage.readings, quota::READINGS), true)
+            .field(
+                "絵文字の読み",
+                format!("{} / {}", usage.emoji_readings, quota::EMOJI_READINGS),
+                true,
+            )
+            .field(
+                "禁止された単語",
+                format!("{} / {}", usage.blacklisted_words, quota::BLACKLISTED_WORDS),
+                true,
+            )
+            .field(
+                "サウンドが紐づいたスタンプ",
+                format!("{} / {}", usage.soundstickers, quota::SOUNDSTICKERS),
+                true,
+            )
+            .colour(Colour::FOOYOO),
+    );
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("quota").description("サーバーごとの辞書やスタンプの使用量と上限を表示します。")
+}
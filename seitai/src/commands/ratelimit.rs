@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::{CommandOptionType, Mentionable},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::utils::{RateLimit, RateLimiter, respond};
+
+// 設定できる値の上限
+const MAX_MESSAGES: i64 = 100;
+const MAX_WINDOW_SECS: i64 = 3600;
+const MAX_COOLDOWN_SECS: i64 = 24 * 3600;
+const MAX_MULTIPLIER: f64 = 10.0;
+const MAX_RESET_HOURS: i64 = 7 * 24;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    rate_limiter: &RateLimiter,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /ratelimit subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+    let member = || {
+        subcommand
+            .options
+            .get("member")
+            .and_then(|v| v.as_user_id())
+            .context("cannot get member to inspect")
+    };
+
+    let embed = match subcommand.name {
+        "set" => {
+            let current = rate_limiter.limit(guild_id);
+            let secs = |name: &str, unit: u64| {
+                subcommand
+                    .options
+                    .get(name)
+                    .and_then(|v| v.as_i64())
+                    .map(|value| Duration::from_secs(value.max(0) as u64 * unit))
+            };
+            let limit = RateLimit {
+                max_messages: subcommand
+                    .options
+                    .get("messages")
+                    .and_then(|v| v.as_i64())
+                    .map_or(current.max_messages, |messages| messages.max(1) as usize),
+                time_window: secs("window", 1).unwrap_or(current.time_window),
+                base_cooldown: secs("cooldown", 1).unwrap_or(current.base_cooldown),
+                max_cooldown: secs("max-cooldown", 1).unwrap_or(current.max_cooldown),
+                cooldown_multiplier: subcommand
+                    .options
+                    .get("multiplier")
+                    .and_then(|v| v.as_f64())
+                    .map_or(current.cooldown_multiplier, |multiplier| multiplier as f32),
+                violation_reset_time: secs("reset-hours", 3600).unwrap_or(current.violation_reset_time),
+            };
+
+            if limit.base_cooldown > limit.max_cooldown {
+                CreateEmbed::new()
+                    .description("クールダウンは最大クールダウン以下にしてください。")
+                    .colour(Colour::RED)
+            } else {
+                database::guild_rate_limit::create(database, limit.to_database(guild_id))
+                    .await
+                    .context("failed to update rate limit of guild")?;
+                rate_limiter.set_limit(guild_id, limit);
+                limit_embed(limit, false).title("連投制限を変更しました")
+            }
+        },
+        "show" => limit_embed(rate_limiter.limit(guild_id), rate_limiter.is_default(guild_id)).title("連投制限"),
+        "status" => match rate_limiter.snapshot(guild_id, member()?).await {
+            Some(snapshot) => {
+                let cooldown = match snapshot.remaining_cooldown {
+                    Some(remaining) => format!("残り{}秒", remaining.as_secs().max(1)),
+                    None => "なし".to_string(),
+                };
+                CreateEmbed::new()
+                    .title("連投制限")
+                    .description(member()?.mention().to_string())
+                    .fields([
+                        ("直近のメッセージ", snapshot.recent_messages.to_string(), true),
+                        ("違反回数", snapshot.violation_count.to_string(), true),
+                        ("クールダウン", cooldown, true),
+                    ])
+                    .colour(Colour::FOOYOO)
+            },
+            None => CreateEmbed::new()
+                .description(format!("{}は連投制限の対象になっていません。", member()?.mention()))
+                .colour(Colour::FOOYOO),
+        },
+        "reset" => {
+            let user_id = member()?;
+            if rate_limiter.reset_user(guild_id, user_id).await {
+                CreateEmbed::new()
+                    .description(format!("{}の連投制限を解除しました。", user_id.mention()))
+                    .colour(Colour::FOOYOO)
+            } else {
+                CreateEmbed::new()
+                    .description(format!("{}は連投制限の対象になっていません。", user_id.mention()))
+                    .colour(Colour::RED)
+            }
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed).ephemeral(true);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+fn limit_embed(limit: RateLimit, is_default: bool) -> CreateEmbed {
+    let embed = CreateEmbed::new()
+        .fields([
+            (
+                "メッセージ数",
+                format!("{}秒に{}件まで", limit.time_window.as_secs(), limit.max_messages),
+                false,
+            ),
+            (
+                "クールダウン",
+                format!(
+                    "{}秒から{}倍ずつ、最大{}秒",
+                    limit.base_cooldown.as_secs(),
+                    limit.cooldown_multiplier,
+                    limit.max_cooldown.as_secs()
+                ),
+                false,
+            ),
+            (
+                "違反回数のリセット",
+                format!("{}時間", limit.violation_reset_time.as_secs() / 3600),
+                false,
+            ),
+        ])
+        .colour(Colour::FOOYOO);
+
+    match is_default {
+        true => embed.footer(CreateEmbedFooter::new("既定の制限です。")),
+        false => embed,
+    }
+}
+
+pub fn register() -> CreateCommand {
+    let member = || {
+        CreateCommandOption::new(CommandOptionType::User, "member", "Member to be inspected")
+            .name_localized("ja", "メンバー")
+            .description_localized("ja", "対象のメンバー")
+            .required(true)
+    };
+    let status = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "status",
+        "Shows the rate limit of the member",
+    )
+    .description_localized("ja", "メンバーの連投制限の状態を表示します。")
+    .add_sub_option(member());
+    let reset = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "reset",
+        "Lifts the rate limit of the member",
+    )
+    .description_localized("ja", "メンバーの連投制限を解除します。")
+    .add_sub_option(member());
+
+    let set = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "set",
+        "Changes the rate limit of the server",
+    )
+    .description_localized("ja", "サーバーの連投制限を変更します。指定しなかった値はそのままです。")
+    .add_sub_option(
+        CreateCommandOption::new(CommandOptionType::Integer, "messages", "Messages allowed in the window")
+            .description_localized("ja", "時間枠内に読み上げるメッセージ数")
+            .min_int_value(1)
+            .max_int_value(MAX_MESSAGES as u64),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(CommandOptionType::Integer, "window", "Window in seconds")
+            .description_localized("ja", "時間枠（秒）")
+            .min_int_value(1)
+            .max_int_value(MAX_WINDOW_SECS as u64),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(CommandOptionType::Integer, "cooldown", "Cooldown in seconds")
+            .description_localized("ja", "最初のクールダウン（秒）")
+            .min_int_value(0)
+            .max_int_value(MAX_COOLDOWN_SECS as u64),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(CommandOptionType::Integer, "max-cooldown", "Max cooldown in seconds")
+            .description_localized("ja", "最大のクールダウン（秒）")
+            .min_int_value(0)
+            .max_int_value(MAX_COOLDOWN_SECS as u64),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::Number,
+            "multiplier",
+            "Multiplier of the cooldown for each violation",
+        )
+        .description_localized("ja", "違反するたびにクールダウンにかける倍率")
+        .min_number_value(1.0)
+        .max_number_value(MAX_MULTIPLIER),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "reset-hours",
+            "Hours until violations are forgotten",
+        )
+        .description_localized("ja", "違反回数がリセットされるまでの時間（時間）")
+        .min_int_value(1)
+        .max_int_value(MAX_RESET_HOURS as u64),
+    );
+    let show = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "show",
+        "Shows the rate limit of the server",
+    )
+    .description_localized("ja", "サーバーの連投制限を表示します。");
+
+    CreateCommand::new("ratelimit")
+        .description("連投制限を設定したり、メンバーの連投制限を確認したり解除したりします。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![set, show, status, reset])
+}
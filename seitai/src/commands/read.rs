@@ -0,0 +1,131 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::{CommandOptionType, Mentionable, UserId},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::utils::respond;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /read subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+    let member = || {
+        subcommand
+            .options
+            .get("member")
+            .and_then(|v| v.as_user_id())
+            .context("cannot get member of /read subcommand")
+    };
+
+    let embed = match subcommand.name {
+        "block" => {
+            let user_id = member()?;
+            database::guild_user_mute::create(database, guild_id.get(), user_id.get(), interaction.user.id.get())
+                .await?;
+            tracing::info!(
+                "{user_id} has been blocked in guild {guild_id} by {}",
+                interaction.user.id
+            );
+            CreateEmbed::new()
+                .description(format!(
+                    "{}のメッセージを読み上げません。`/read unblock` で元に戻せます。",
+                    user_id.mention()
+                ))
+                .colour(Colour::FOOYOO)
+        },
+        "unblock" => {
+            let user_id = member()?;
+            if database::guild_user_mute::delete(database, guild_id.get(), user_id.get()).await? {
+                tracing::info!(
+                    "{user_id} has been unblocked in guild {guild_id} by {}",
+                    interaction.user.id
+                );
+                CreateEmbed::new()
+                    .description(format!("{}のメッセージを読み上げます。", user_id.mention()))
+                    .colour(Colour::FOOYOO)
+            } else {
+                CreateEmbed::new()
+                    .description(format!("{}は読み上げの対象外になっていません。", user_id.mention()))
+                    .colour(Colour::RED)
+            }
+        },
+        "list" => {
+            let mutes = database::guild_user_mute::fetch_by_guild_id(database, guild_id.get()).await?;
+            if mutes.is_empty() {
+                CreateEmbed::new()
+                    .description("読み上げの対象外のメンバーはいません。")
+                    .colour(Colour::FOOYOO)
+            } else {
+                let lines = mutes
+                    .iter()
+                    .map(|mute| {
+                        let user = UserId::new(mute.user_id as u64).mention();
+                        match mute.muted_by {
+                            Some(muted_by) => format!("{user}（{}が設定）", UserId::new(muted_by as u64).mention()),
+                            None => user.to_string(),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                CreateEmbed::new()
+                    .title("読み上げの対象外のメンバー")
+                    .description(lines.join("\n"))
+                    .colour(Colour::FOOYOO)
+            }
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed).ephemeral(true);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let block = {
+        let member = CreateCommandOption::new(CommandOptionType::User, "member", "Member not to read messages of")
+            .name_localized("ja", "メンバー")
+            .description_localized("ja", "メッセージを読み上げないメンバー")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "block",
+            "Never reads messages of the member in this server",
+        )
+        .description_localized("ja", "このサーバーでメンバーのメッセージを読み上げないようにします。")
+        .add_sub_option(member)
+    };
+    let unblock = {
+        let member = CreateCommandOption::new(CommandOptionType::User, "member", "Member to read messages of again")
+            .name_localized("ja", "メンバー")
+            .description_localized("ja", "メッセージを再び読み上げるメンバー")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "unblock",
+            "Reads messages of the member again",
+        )
+        .description_localized("ja", "メンバーのメッセージを再び読み上げます。")
+        .add_sub_option(member)
+    };
+    let list = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "list",
+        "Lists the members not to be read",
+    )
+    .description_localized("ja", "読み上げの対象外のメンバーを表示します。");
+
+    CreateCommand::new("read")
+        .description("このサーバーでメッセージを読み上げないメンバーを管理します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![block, unblock, list])
+}
@@ -0,0 +1,85 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::utils::respond;
+
+// 続けて話したとみなす間隔の上限（秒）
+const MAX_INTERVAL: u64 = 60 * 60;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /readname subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+
+    let description = match subcommand.name {
+        "on" => {
+            let interval = match subcommand.options.get("interval").and_then(|v| v.as_i64()) {
+                Some(interval) => i32::try_from(interval).context("failed to convert interval to i32")?,
+                None => {
+                    database::guild::fetch_by_id(database, guild_id.get())
+                        .await?
+                        .name_interval
+                },
+            };
+            database::guild::update_name_interval(database, guild_id.get(), true, interval).await?;
+            match interval {
+                0 => "メッセージの前に毎回名前を読み上げます。".to_string(),
+                _ => format!(
+                    "話す人が変わったら、メッセージの前に名前を読み上げます。同じ人が{interval}秒以内に続けて話したときは読み上げません。"
+                ),
+            }
+        },
+        "off" => {
+            database::guild::update_read_names(database, guild_id.get(), false).await?;
+            "メッセージの前に名前を読み上げません。".to_string()
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new()
+        .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let on = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "on",
+        "Reads the name of the author when another member starts speaking",
+    )
+    .description_localized("ja", "話す人が変わったら、メッセージの前に名前を読み上げます。")
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "interval",
+            "Seconds to omit the name for consecutive messages by the same member (0 to always read)",
+        )
+        .name_localized("ja", "間隔")
+        .description_localized(
+            "ja",
+            "同じ人が続けて話したときに名前を省く間隔（秒、0 にすると毎回読み上げます）",
+        )
+        .min_int_value(0)
+        .max_int_value(MAX_INTERVAL),
+    );
+    let off = CreateCommandOption::new(CommandOptionType::SubCommand, "off", "Stops reading names of authors")
+        .description_localized("ja", "メッセージの前に名前を読み上げないようにします。");
+
+    CreateCommand::new("readname")
+        .description("メッセージの前に名前を読み上げるかどうかを切り替えます。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![on, off])
+}
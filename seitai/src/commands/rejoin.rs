@@ -0,0 +1,88 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use futures::lock::Mutex;
+use hashbrown::HashMap;
+use serenity::{
+    all::{ChannelId, GuildId, Mentionable},
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use super::join::connect;
+use crate::{
+    i18n::{Locale, t, t_with},
+    utils::{get_manager, respond},
+};
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    connections: &Mutex<HashMap<GuildId, ChannelId>>,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&interaction.locale);
+    let manager = get_manager(context).await?;
+
+    let connected = match manager.get(guild_id) {
+        Some(call) => call.lock().await.current_connection().is_some(),
+        None => false,
+    };
+    let last_connection = database::last_connection::fetch_by_guild_id(database, guild_id.get()).await?;
+    let embed = match last_connection {
+        _ if connected => CreateEmbed::new()
+            .description(t(locale, "rejoin.already_connected"))
+            .colour(Colour::RED),
+        None => CreateEmbed::new()
+            .description(t(locale, "rejoin.never_connected"))
+            .colour(Colour::RED),
+        Some(last_connection) => {
+            let voice_channel_id = ChannelId::new(last_connection.voice_channel_id as u64);
+            let text_channel_id = ChannelId::new(last_connection.text_channel_id as u64);
+            match connect(
+                context,
+                database,
+                guild_id,
+                voice_channel_id,
+                text_channel_id,
+                connections,
+            )
+            .await
+            {
+                Ok(_) => CreateEmbed::new()
+                    .description(t_with(
+                        locale,
+                        "rejoin.rejoined",
+                        &[("channel", &voice_channel_id.mention().to_string())],
+                    ))
+                    .field(
+                        t(locale, "rejoin.text_channel"),
+                        text_channel_id.mention().to_string(),
+                        false,
+                    )
+                    .colour(Colour::FOOYOO),
+                Err(error) => {
+                    tracing::error!("failed to rejoin channel {voice_channel_id}\nError: {error:?}");
+                    CreateEmbed::new()
+                        .description(t_with(
+                            locale,
+                            "rejoin.failed",
+                            &[("channel", &voice_channel_id.mention().to_string())],
+                        ))
+                        .field(t(locale, "common.detail"), format!("```\n{error}\n```"), false)
+                        .colour(Colour::RED)
+                },
+            }
+        },
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("rejoin").description("最後に接続していたボイスチャンネルに再接続します。")
+}
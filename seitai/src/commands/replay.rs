@@ -0,0 +1,78 @@
+use anyhow::Result;
+use database::PgPool;
+use seitai_audio::AudioRepository;
+use serenity::{
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+use songbird::input::Input;
+
+use super::error::CommandError;
+use crate::{
+    recent::RecentAudios,
+    scheduler::SynthesisScheduler,
+    utils::{enqueue, get_manager, respond},
+};
+
+pub(crate) async fn run<Repository>(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    audio_repository: &Repository,
+    recent_audios: &RecentAudios,
+    synthesis_scheduler: &SynthesisScheduler,
+) -> Result<()>
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let manager = get_manager(context).await?;
+    let Some(call) = manager.get(guild_id) else {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description("ボイスチャンネルに接続していません。")
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    };
+    let Some(audios) = recent_audios.latest(guild_id) else {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description("読み上げたメッセージはありません。")
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    };
+
+    // 合成を待つと応答期限を過ぎることがあるので先に応答する
+    let message = CreateInteractionResponseMessage::new().embed(
+        CreateEmbed::new()
+            .description("最後に読み上げたメッセージをもう一度読み上げます。")
+            .colour(Colour::FOOYOO),
+    );
+    respond(context, interaction, &message).await?;
+
+    let settings = database::guild::fetch_by_id(database, guild_id.get()).await?;
+    for audio in audios {
+        let slot = synthesis_scheduler.acquire(guild_id).await;
+        let input = audio_repository.get(audio).await;
+        drop(slot);
+        match input {
+            Ok(input) => {
+                enqueue(&mut *call.lock().await, input, settings.volume).await;
+            },
+            Err(error) => {
+                tracing::error!("failed to get audio source to replay\nError: {error:?}");
+            },
+        }
+    }
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("replay").description("最後に読み上げたメッセージをもう一度読み上げます。")
+}
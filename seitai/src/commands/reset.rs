@@ -0,0 +1,30 @@
+use anyhow::Result;
+use database::PgPool;
+use serenity::{
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::utils::respond;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let embed = if database::user::reset(database, interaction.user.id.get()).await? {
+        CreateEmbed::new()
+            .description("ボイス、話速、ピッチ、抑揚、読み上げの設定を初期状態に戻しました。")
+            .colour(Colour::FOOYOO)
+    } else {
+        CreateEmbed::new()
+            .description("変更された設定はありません。")
+            .colour(Colour::FOOYOO)
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed).ephemeral(true);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("reset").description("自分のボイスや話速などの設定をすべて初期状態に戻します。")
+}
@@ -0,0 +1,64 @@
+use anyhow::{Context as _, Result};
+use dashmap::DashSet;
+use serenity::{
+    all::GuildId,
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use crate::{
+    i18n::{Locale, t},
+    utils::{get_manager, respond},
+};
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    paused_guilds: &DashSet<GuildId>,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&interaction.locale);
+    if paused_guilds.remove(&guild_id).is_none() {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description(t(locale, "resume.not_paused"))
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    let manager = get_manager(context).await?;
+    let call = manager.get_or_insert(guild_id);
+    let call = call.lock().await;
+
+    match call.queue().resume() {
+        Ok(_) => {
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(t(locale, "resume.resumed"))
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        Err(error) => {
+            tracing::error!("failed to resume current track\nError: {error:?}");
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(t(locale, "resume.failed"))
+                    .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
+                    .colour(Colour::RED),
+            );
+            respond(context, interaction, &message).await?;
+        },
+    };
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("resume")
+        .description("一時停止した読み上げを再開します。")
+        .default_member_permissions(Permissions::MUTE_MEMBERS)
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use ordered_float::NotNan;
+use seitai_audio::{Audio, AudioRepository};
+use serenity::{
+    all::{CommandOptionType, GuildId},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+use songbird::{Call, input::Input};
+use tokio::sync::Mutex;
+
+use super::error::CommandError;
+use crate::{
+    scheduler::SynthesisScheduler,
+    speaker::Speaker,
+    utils::{enqueue, get_manager, respond},
+    utterance::PredefinedUtterance,
+};
+
+pub(crate) async fn run<Repository>(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    speaker: &Speaker,
+    audio_repository: &Repository,
+    synthesis_scheduler: &SynthesisScheduler,
+) -> Result<()>
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let speaker_id = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "speaker")
+        .and_then(|option| option.value.as_i64())
+        .context("no speaker option")?;
+    let speaker_id = u16::try_from(speaker_id).context("failed to convert speaker_id to u16")?;
+    let name = speaker.get_name(speaker_id)?;
+
+    // 試聴のためだけに接続はせず、読み上げ中の通話に流す
+    let manager = get_manager(context).await?;
+    let Some(call) = manager.get(guild_id) else {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description("ボイスチャンネルに接続していません。`/join` で接続してから試してください。")
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(
+        CreateEmbed::new()
+            .description(format!("{name}で読み上げます。"))
+            .footer(CreateEmbedFooter::new("気に入ったら `/voice use` で設定できます。"))
+            .colour(Colour::FOOYOO),
+    );
+    respond(context, interaction, &message).await?;
+
+    read_sample(
+        &call,
+        database,
+        audio_repository,
+        synthesis_scheduler,
+        guild_id,
+        speaker_id,
+    )
+    .await
+}
+
+/// Reads the sample sentence with the voice into the call of the guild.
+pub(crate) async fn read_sample<Repository>(
+    call: &Mutex<Call>,
+    database: &PgPool,
+    audio_repository: &Repository,
+    synthesis_scheduler: &SynthesisScheduler,
+    guild_id: GuildId,
+    speaker_id: u16,
+) -> Result<()>
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let audio = Audio {
+        text: PredefinedUtterance::Sample.as_ref().to_string(),
+        speaker: speaker_id.to_string(),
+        speed: NotNan::new(Speaker::default_speed()).unwrap(),
+        pitch: NotNan::new(Speaker::default_pitch()).unwrap(),
+        intonation: NotNan::new(Speaker::default_intonation()).unwrap(),
+        morph: None,
+    };
+    let slot = synthesis_scheduler.acquire(guild_id).await;
+    let input = audio_repository.get(audio).await;
+    drop(slot);
+    let settings = database::guild::fetch_by_id(database, guild_id.get()).await?;
+    enqueue(&mut *call.lock().await, input?, settings.volume).await;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let speaker = CreateCommandOption::new(CommandOptionType::Integer, "speaker", "Voice to be sampled")
+        .name_localized("ja", "ボイス")
+        .description_localized("ja", "試聴するボイス")
+        .set_autocomplete(true)
+        .required(true);
+
+    CreateCommand::new("sample")
+        .description("ボイスでサンプルの文章を読み上げます。")
+        .add_option(speaker)
+}
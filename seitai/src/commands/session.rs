@@ -0,0 +1,117 @@
+use anyhow::{Context as _, Result};
+use serenity::{
+    all::{CommandOptionType, Mentionable},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::{
+    statistics::UsageStatistics,
+    utils::{format_duration, respond},
+};
+
+use super::{error::CommandError, subcommand::Subcommand};
+
+// 要約に載せるよく話した人の数
+const TOP_SPEAKERS: usize = 5;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    usage_statistics: &UsageStatistics,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /session subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+
+    let embed = match subcommand.name {
+        "start" => {
+            let name = subcommand
+                .options
+                .get("name")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|name| !name.is_empty());
+            match usage_statistics.start_session(guild_id, name.map(str::to_string)).await {
+                true => CreateEmbed::new()
+                    .title("セッションを開始しました。")
+                    .description(name.unwrap_or_default())
+                    .footer(CreateEmbedFooter::new(
+                        "`/session stop` で終了すると、読み上げの記録を表示します。",
+                    ))
+                    .colour(Colour::FOOYOO),
+                false => CreateEmbed::new()
+                    .title("セッションはすでに開始しています。")
+                    .description("`/session stop` で終了してから開始してください。")
+                    .colour(Colour::RED),
+            }
+        },
+        "stop" => match usage_statistics.stop_session(guild_id).await {
+            Some(session) => {
+                let top_speakers = session
+                    .top_speakers(TOP_SPEAKERS)
+                    .into_iter()
+                    .map(|(user_id, messages)| format!("{} {messages}件", user_id.mention()))
+                    .collect::<Vec<_>>();
+                CreateEmbed::new()
+                    .title(match &session.name {
+                        Some(name) => format!("セッション「{name}」を終了しました。"),
+                        None => "セッションを終了しました。".to_string(),
+                    })
+                    .field("時間", format_duration(session.started_at.elapsed()), true)
+                    .field("読み上げたメッセージ", format!("{}件", session.usage.messages), true)
+                    .field("合成した文字数", format!("{}文字", session.usage.characters), true)
+                    .field(
+                        "よく話した人",
+                        match top_speakers.is_empty() {
+                            true => "なし".to_string(),
+                            false => top_speakers.join("\n"),
+                        },
+                        false,
+                    )
+                    .colour(Colour::FOOYOO)
+            },
+            None => CreateEmbed::new()
+                .title("セッションを開始していません。")
+                .description("`/session start` で開始してください。")
+                .colour(Colour::RED),
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let start = {
+        let name = CreateCommandOption::new(CommandOptionType::String, "name", "Name of the session")
+            .name_localized("ja", "名前")
+            .description_localized("ja", "セッションの名前")
+            .max_length(100);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "start",
+            "Starts recording a reading session",
+        )
+        .description_localized("ja", "読み上げの記録を開始します。")
+        .add_sub_option(name)
+    };
+    let stop = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "stop",
+        "Stops and summarizes the session",
+    )
+    .description_localized("ja", "記録を終了して、セッションの要約を表示します。");
+
+    CreateCommand::new("session")
+        .description("TRPG や会議などの読み上げを記録して、終了時に要約を表示します。")
+        .set_options(vec![start, stop])
+}
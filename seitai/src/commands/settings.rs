@@ -0,0 +1,1696 @@
+use std::{collections::HashMap, ffi::OsString};
+
+use anyhow::{Context as _, Result};
+use dashmap::DashMap;
+use database::{
+    PgPool, auto_join, channel_voice,
+    guild::{self, Ducking, ReadingOutput, Spoilers, TtsMessages},
+    keyword_voice, style_shortcut, voice_rotation,
+};
+use serenity::{
+    all::{
+        ButtonStyle, ChannelId, ChannelType, CommandOptionType, ComponentInteraction, ComponentInteractionDataKind,
+        GuildId, Mentionable,
+    },
+    builder::{
+        CreateActionRow, CreateAttachment, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
+        CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu,
+        CreateSelectMenuKind, CreateSelectMenuOption, EditInteractionResponse,
+    },
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+use songbird::input::cached::Memory;
+use voicevox::dictionary::Dictionary;
+
+use crate::{
+    celebration::DEFAULT_CELEBRATION_SOUND,
+    classification::DEFAULT_COMMAND_PREFIXES,
+    i18n::{Locale, t, t_with},
+    settings_file::SettingsFile,
+    speaker::Speaker,
+    style::{self, STYLE_PREFIX},
+    utils::{get_voicevox, respond, truncate_chars},
+};
+
+use super::{error::CommandError, subcommand::Subcommand, volume::set_volume};
+
+// パネルのコンポーネントの custom_id の接頭辞
+pub(crate) const PANEL_PREFIX: &str = "settings:";
+// パネルで選べる音量
+const PANEL_VOLUMES: [i32; 9] = [0, 25, 50, 75, 100, 125, 150, 175, 200];
+// 読み込める設定のファイルの大きさの上限
+const MAX_IMPORT_SIZE: u32 = 1024 * 1024;
+// 埋め込みの説明の文字数の上限
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+// 切断する前に読み上げるテキストの文字数の上限
+const MAX_LEAVE_UTTERANCE_LENGTH: u16 = 100;
+// 話し声への反応の感度の既定値
+const DEFAULT_DUCKING_SENSITIVITY: i32 = 3;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    speaker: &Speaker,
+    sounds: &DashMap<OsString, Memory>,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let locale = Locale::from_tag(&interaction.locale);
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /settings subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+
+    match subcommand.name {
+        "panel" => {
+            let message = panel(database, speaker, guild_id, locale).await?;
+            respond(context, interaction, &message).await?;
+        },
+        "crosspost" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            guild::update_read_crossposts(database, guild_id.get(), enabled).await?;
+
+            let description = match enabled {
+                true => t(locale, "settings.crosspost.on"),
+                false => t(locale, "settings.crosspost.off"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "join" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            guild::update_announce_joins(database, guild_id.get(), enabled).await?;
+
+            let description = match enabled {
+                true => t(locale, "settings.join.on"),
+                false => t(locale, "settings.join.off"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "activity" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            let guild = guild::update_announce_activities(database, guild_id.get(), enabled).await?;
+
+            let description = match (enabled, guild.announce_joins) {
+                (true, true) => t(locale, "settings.activity.on"),
+                (true, false) => t(locale, "settings.activity.on_without_join"),
+                (false, _) => t(locale, "settings.activity.off"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "rotation-add" | "rotation-remove" => {
+            let speaker_id = subcommand
+                .options
+                .get("speaker")
+                .and_then(|v| v.as_i64())
+                .context("no speaker option")?;
+            let speaker_id = u16::try_from(speaker_id).context("failed to convert speaker_id to u16")?;
+            let name = speaker.get_name(speaker_id)?;
+
+            let title = match subcommand.name {
+                "rotation-add" => {
+                    voice_rotation::add(database, guild_id.get(), speaker_id).await?;
+                    t_with(locale, "settings.rotation.added", &[("name", &name)])
+                },
+                _ => match voice_rotation::remove(database, guild_id.get(), speaker_id).await? {
+                    true => t_with(locale, "settings.rotation.removed", &[("name", &name)]),
+                    false => t_with(locale, "settings.rotation.not_included", &[("name", &name)]),
+                },
+            };
+
+            let rotation = voice_rotation::fetch_by_guild_id(database, guild_id.get())
+                .await?
+                .into_iter()
+                .filter_map(|speaker_id| u16::try_from(speaker_id).ok())
+                .filter_map(|speaker_id| speaker.get_name(speaker_id).ok())
+                .collect::<Vec<_>>();
+            let description = match rotation.is_empty() {
+                true => t(locale, "settings.rotation.empty").to_string(),
+                false => format!("{}\n{}", t(locale, "settings.rotation.list"), rotation.join("\n")),
+            };
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .title(title)
+                    .description(description)
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        "channel-voice" => {
+            let channel_id = subcommand
+                .options
+                .get("channel")
+                .and_then(|v| v.as_channel_id())
+                .context("no channel option")?;
+            let speaker_id = subcommand.options.get("speaker").and_then(|v| v.as_i64());
+
+            let description = match speaker_id {
+                Some(speaker_id) => {
+                    let speaker_id = u16::try_from(speaker_id).context("failed to convert speaker_id to u16")?;
+                    let name = speaker.get_name(speaker_id)?;
+                    channel_voice::create(database, channel_id.get(), guild_id.get(), speaker_id).await?;
+                    t_with(
+                        locale,
+                        "settings.channel_voice.set",
+                        &[("channel", &channel_id.mention().to_string()), ("name", &name)],
+                    )
+                },
+                None => match channel_voice::delete_by_channel_id(database, channel_id.get()).await? {
+                    true => t_with(
+                        locale,
+                        "settings.channel_voice.reset",
+                        &[("channel", &channel_id.mention().to_string())],
+                    ),
+                    false => t_with(
+                        locale,
+                        "settings.channel_voice.not_set",
+                        &[("channel", &channel_id.mention().to_string())],
+                    ),
+                },
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "keyword-voice" => {
+            let keyword = subcommand
+                .options
+                .get("keyword")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .context("no keyword option")?;
+            let speaker_id = subcommand.options.get("speaker").and_then(|v| v.as_i64());
+
+            let description = match speaker_id {
+                Some(speaker_id) => {
+                    let speaker_id = u16::try_from(speaker_id).context("failed to convert speaker_id to u16")?;
+                    let name = speaker.get_name(speaker_id)?;
+                    keyword_voice::create(database, guild_id.get(), keyword, speaker_id).await?;
+                    t_with(
+                        locale,
+                        "settings.keyword_voice.set",
+                        &[("keyword", keyword), ("name", &name)],
+                    )
+                },
+                None => match keyword_voice::delete(database, guild_id.get(), keyword).await? {
+                    true => t_with(locale, "settings.keyword_voice.reset", &[("keyword", keyword)]),
+                    false => t_with(locale, "settings.keyword_voice.not_set", &[("keyword", keyword)]),
+                },
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "style-shortcut" => {
+            let prefix = subcommand
+                .options
+                .get("prefix")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|prefix| !prefix.is_empty())
+                .context("no prefix option")?;
+            let style = subcommand
+                .options
+                .get("style")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|style| !style.is_empty());
+
+            let (description, colour) = match style {
+                Some(style)
+                    if !speaker
+                        .characters()
+                        .any(|(_, styles)| styles.iter().any(|(name, _)| style::matches(name, style))) =>
+                {
+                    (
+                        t_with(locale, "settings.style_shortcut.unknown", &[("style", style)]),
+                        Colour::RED,
+                    )
+                },
+                Some(style) => {
+                    style_shortcut::create(database, guild_id.get(), prefix, style).await?;
+                    let description = t_with(
+                        locale,
+                        "settings.style_shortcut.set",
+                        &[("prefix", prefix), ("style", style), ("style_prefix", STYLE_PREFIX)],
+                    );
+                    (description, Colour::FOOYOO)
+                },
+                None => match style_shortcut::delete(database, guild_id.get(), prefix).await? {
+                    true => (
+                        t_with(locale, "settings.style_shortcut.reset", &[("prefix", prefix)]),
+                        Colour::FOOYOO,
+                    ),
+                    false => (
+                        t_with(locale, "settings.style_shortcut.not_set", &[("prefix", prefix)]),
+                        Colour::RED,
+                    ),
+                },
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(colour));
+            respond(context, interaction, &message).await?;
+        },
+        "markdown" => {
+            let enabled = subcommand.options.get("enabled").and_then(|v| v.as_bool());
+            let code = subcommand.options.get("code").and_then(|v| v.as_bool());
+            if let Some(enabled) = enabled {
+                guild::update_flatten_markdown(database, guild_id.get(), enabled).await?;
+            }
+            if let Some(omit) = code {
+                guild::update_omit_code(database, guild_id.get(), omit).await?;
+            }
+            if let Some(spoilers) = subcommand.options.get("spoilers").and_then(|v| v.as_str()) {
+                guild::update_spoilers(database, guild_id.get(), spoilers.parse::<Spoilers>()?).await?;
+            }
+            let settings = guild::fetch_by_id(database, guild_id.get()).await?;
+
+            let markdown = match settings.flatten_markdown {
+                true => t(locale, "settings.markdown.on"),
+                false => t(locale, "settings.markdown.off"),
+            };
+            let code = match settings.omit_code {
+                true => t(locale, "settings.omit_code.on"),
+                false => t(locale, "settings.omit_code.off"),
+            };
+            let spoilers = match settings.spoilers() {
+                Spoilers::Announce => t(locale, "settings.spoilers.announce"),
+                Spoilers::Skip => t(locale, "settings.spoilers.skip"),
+                Spoilers::Read => t(locale, "settings.spoilers.read"),
+            };
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(format!("{markdown}\n{code}\n{spoilers}"))
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        "jitter" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            guild::update_jitter(database, guild_id.get(), enabled).await?;
+
+            let description = match enabled {
+                true => t(locale, "settings.jitter.on"),
+                false => t(locale, "settings.jitter.off"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "random-voices" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            guild::update_random_voices(database, guild_id.get(), enabled).await?;
+
+            let description = match enabled {
+                true => t(locale, "settings.random_voices.on"),
+                false => t(locale, "settings.random_voices.off"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "read-topics" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            guild::update_read_topics(database, guild_id.get(), enabled).await?;
+
+            let description = match enabled {
+                true => t(locale, "settings.read_topics.on"),
+                false => t(locale, "settings.read_topics.off"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "read-extras" => {
+            let attachments = subcommand.options.get("attachments").and_then(|v| v.as_bool());
+            let embeds = subcommand.options.get("embeds").and_then(|v| v.as_bool());
+            if let Some(enabled) = attachments {
+                guild::update_read_attachments(database, guild_id.get(), enabled).await?;
+            }
+            if let Some(enabled) = embeds {
+                guild::update_read_embeds(database, guild_id.get(), enabled).await?;
+            }
+            if let Some(enabled) = subcommand.options.get("replies").and_then(|v| v.as_bool()) {
+                guild::update_read_replies(database, guild_id.get(), enabled).await?;
+            }
+            if let Some(enabled) = subcommand.options.get("delays").and_then(|v| v.as_bool()) {
+                guild::update_read_delays(database, guild_id.get(), enabled).await?;
+            }
+            let settings = guild::fetch_by_id(database, guild_id.get()).await?;
+
+            let attachments = match settings.read_attachments {
+                true => t(locale, "settings.read_attachments.on"),
+                false => t(locale, "settings.read_attachments.off"),
+            };
+            let embeds = match settings.read_embeds {
+                true => t(locale, "settings.read_embeds.on"),
+                false => t(locale, "settings.read_embeds.off"),
+            };
+            let replies = match settings.read_replies {
+                true => t(locale, "settings.read_replies.on"),
+                false => t(locale, "settings.read_replies.off"),
+            };
+            let delays = match settings.read_delays {
+                true => t(locale, "settings.read_delays.on"),
+                false => t(locale, "settings.read_delays.off"),
+            };
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(format!("{attachments}\n{embeds}\n{replies}\n{delays}"))
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        "leave-utterance" => {
+            let utterance = subcommand
+                .options
+                .get("text")
+                .and_then(|v| v.as_str())
+                .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default();
+            guild::update_leave_utterance(database, guild_id.get(), &utterance).await?;
+
+            let description = match utterance.is_empty() {
+                true => t(locale, "settings.leave_utterance.empty").to_string(),
+                false => t_with(locale, "settings.leave_utterance.set", &[("utterance", &utterance)]),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "priority-role" => {
+            let role_id = subcommand.options.get("role").and_then(|v| v.as_role_id());
+            guild::update_priority_role_id(database, guild_id.get(), role_id.map(|role_id| role_id.get())).await?;
+
+            let description = match role_id {
+                Some(role_id) => t_with(
+                    locale,
+                    "settings.priority_role.set",
+                    &[("role", &role_id.mention().to_string())],
+                ),
+                None => t(locale, "settings.priority_role.reset").to_string(),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "skip-commands" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            let prefixes = subcommand
+                .options
+                .get("prefixes")
+                .and_then(|v| v.as_str())
+                .map(|prefixes| prefixes.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|prefixes| !prefixes.is_empty());
+            guild::update_command_prefixes(database, guild_id.get(), enabled, prefixes.as_deref()).await?;
+
+            let description = match enabled {
+                true => t(locale, "settings.skip_commands.on"),
+                false => t(locale, "settings.skip_commands.off"),
+            };
+            let prefixes = prefixes.unwrap_or_else(|| DEFAULT_COMMAND_PREFIXES.join(" "));
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .field(
+                        t(locale, "settings.skip_commands.prefixes"),
+                        format!("`{prefixes}`"),
+                        false,
+                    )
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        "name-decorations" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            let patterns = subcommand
+                .options
+                .get("patterns")
+                .and_then(|v| v.as_str())
+                .map(|patterns| patterns.split_whitespace().collect::<Vec<_>>())
+                .filter(|patterns| !patterns.is_empty());
+            if let Some(Err(error)) = patterns.as_deref().map(seitai_converter::name::compile) {
+                let message = CreateInteractionResponseMessage::new()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t(locale, "settings.name_decorations.invalid"))
+                            .field(t(locale, "common.detail"), format!("```\n{error}\n```"), false)
+                            .colour(Colour::RED),
+                    )
+                    .ephemeral(true);
+                respond(context, interaction, &message).await?;
+                return Ok(());
+            }
+            let patterns = patterns.map(|patterns| patterns.join(" "));
+            guild::update_name_decorations(database, guild_id.get(), enabled, patterns.as_deref()).await?;
+
+            let description = match enabled {
+                true => t(locale, "settings.name_decorations.on"),
+                false => t(locale, "settings.name_decorations.off"),
+            };
+            let patterns = patterns.unwrap_or_else(|| seitai_converter::name::DEFAULT_PATTERNS.join(" "));
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .field(
+                        t(locale, "settings.name_decorations.patterns"),
+                        format!("`{patterns}`"),
+                        false,
+                    )
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        "celebration" => {
+            let threshold = subcommand
+                .options
+                .get("threshold")
+                .and_then(|v| v.as_i64())
+                .map(i32::try_from)
+                .transpose()
+                .context("failed to convert threshold to i32")?;
+            let sound = subcommand
+                .options
+                .get("sound")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|sound| !sound.is_empty());
+
+            let name = sound.unwrap_or(DEFAULT_CELEBRATION_SOUND);
+            let embed = match threshold {
+                Some(_) if !sounds.contains_key(&OsString::from(name)) => CreateEmbed::new()
+                    .description(t_with(locale, "settings.celebration.not_found", &[("name", name)]))
+                    .colour(Colour::RED),
+                Some(threshold) => {
+                    guild::update_celebration(database, guild_id.get(), Some(threshold), sound).await?;
+                    CreateEmbed::new()
+                        .description(t_with(
+                            locale,
+                            "settings.celebration.set",
+                            &[("threshold", &threshold.to_string()), ("name", name)],
+                        ))
+                        .colour(Colour::FOOYOO)
+                },
+                None => {
+                    guild::update_celebration(database, guild_id.get(), None, None).await?;
+                    CreateEmbed::new()
+                        .description(t(locale, "settings.celebration.off"))
+                        .colour(Colour::FOOYOO)
+                },
+            };
+            let message = CreateInteractionResponseMessage::new().embed(embed);
+            respond(context, interaction, &message).await?;
+        },
+        "tts-messages" => {
+            let tts_messages = subcommand
+                .options
+                .get("handling")
+                .and_then(|v| v.as_str())
+                .context("no handling option")?
+                .parse::<TtsMessages>()?;
+            guild::update_tts_messages(database, guild_id.get(), tts_messages).await?;
+
+            let description = match tts_messages {
+                TtsMessages::Skip => t(locale, "settings.tts_messages.skip"),
+                TtsMessages::Read => t(locale, "settings.tts_messages.read"),
+                TtsMessages::Guide => t(locale, "settings.tts_messages.guide"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "output" => {
+            let reading_output = subcommand
+                .options
+                .get("target")
+                .and_then(|v| v.as_str())
+                .context("no target option")?
+                .parse::<ReadingOutput>()?;
+            guild::update_reading_output(database, guild_id.get(), reading_output).await?;
+
+            let description = match reading_output {
+                ReadingOutput::Voice => t(locale, "settings.output.voice"),
+                ReadingOutput::Text => t(locale, "settings.output.text"),
+                ReadingOutput::Both => t(locale, "settings.output.both"),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "ducking" => {
+            let ducking = subcommand
+                .options
+                .get("mode")
+                .and_then(|v| v.as_str())
+                .context("no mode option")?
+                .parse::<Ducking>()?;
+            let sensitivity = subcommand
+                .options
+                .get("sensitivity")
+                .and_then(|v| v.as_i64())
+                .map_or(DEFAULT_DUCKING_SENSITIVITY, |sensitivity| sensitivity as i32);
+            guild::update_ducking(database, guild_id.get(), ducking, sensitivity).await?;
+
+            let description = match ducking {
+                Ducking::Off => t(locale, "settings.ducking.off").to_string(),
+                Ducking::Pause => t_with(
+                    locale,
+                    "settings.ducking.pause",
+                    &[("sensitivity", &sensitivity.to_string())],
+                ),
+                Ducking::Lower => t_with(
+                    locale,
+                    "settings.ducking.lower",
+                    &[("sensitivity", &sensitivity.to_string())],
+                ),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "furigana" => {
+            let enabled = subcommand
+                .options
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .context("no enabled option")?;
+            let channel_id = subcommand.options.get("channel").and_then(|v| v.as_channel_id());
+            guild::update_furigana(
+                database,
+                guild_id.get(),
+                enabled,
+                channel_id.map(|channel_id| channel_id.get()),
+            )
+            .await?;
+
+            let description = match (enabled, channel_id) {
+                (true, Some(channel_id)) => t_with(
+                    locale,
+                    "settings.furigana.channel",
+                    &[("channel", &channel_id.mention().to_string())],
+                ),
+                (true, None) => t(locale, "settings.furigana.reply").to_string(),
+                (false, _) => t(locale, "settings.furigana.off").to_string(),
+            };
+            let message = CreateInteractionResponseMessage::new()
+                .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+            respond(context, interaction, &message).await?;
+        },
+        "export" => {
+            // 辞書の取得で応答期限を過ぎないようにする
+            interaction.defer_ephemeral(&context.http).await?;
+
+            let dictionary = get_dictionary(context).await?;
+            let channels = channel_names(context, guild_id).await?;
+            let file = SettingsFile::export(database, &dictionary, guild_id, &channels).await?;
+            let json = serde_json::to_vec_pretty(&file).context("failed to serialize settings")?;
+
+            let embed = CreateEmbed::new()
+                .description(t(locale, "settings.export.exported"))
+                .colour(Colour::FOOYOO);
+            let attachment = CreateAttachment::bytes(json, format!("seitai-settings-{guild_id}.json"));
+            interaction
+                .edit_response(
+                    &context.http,
+                    EditInteractionResponse::new().embed(embed).new_attachment(attachment),
+                )
+                .await?;
+        },
+        "import" => {
+            let attachment = subcommand
+                .options
+                .get("file")
+                .and_then(|v| v.as_attachment_id())
+                .and_then(|id| interaction.data.resolved.attachments.get(&id))
+                .context("no file option")?;
+            if attachment.size > MAX_IMPORT_SIZE {
+                let message = CreateInteractionResponseMessage::new()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t_with(
+                                locale,
+                                "common.file_too_large",
+                                &[("size", &(MAX_IMPORT_SIZE / 1024).to_string())],
+                            ))
+                            .colour(Colour::RED),
+                    )
+                    .ephemeral(true);
+                respond(context, interaction, &message).await?;
+                return Ok(());
+            }
+
+            // 辞書の登録で応答期限を過ぎないようにする
+            interaction.defer_ephemeral(&context.http).await?;
+
+            let bytes = attachment.download().await.context("failed to download settings")?;
+            let validated = match serde_json::from_slice::<SettingsFile>(&bytes) {
+                Ok(file) => {
+                    let channels = channel_names(context, guild_id).await?;
+                    file.validate(&channels, |speaker_id| speaker.get_name(speaker_id).is_ok())
+                },
+                Err(error) => Err(vec![t_with(
+                    locale,
+                    "settings.import.unreadable",
+                    &[("error", &error.to_string())],
+                )]),
+            };
+            let import = match validated {
+                Ok(import) => import,
+                Err(problems) => {
+                    let mut description = format!("{}\n{}", t(locale, "settings.import.rejected"), problems.join("\n"));
+                    truncate_chars(&mut description, MAX_DESCRIPTION_LENGTH);
+                    let embed = CreateEmbed::new().description(description).colour(Colour::RED);
+                    interaction
+                        .edit_response(&context.http, EditInteractionResponse::new().embed(embed))
+                        .await?;
+                    return Ok(());
+                },
+            };
+
+            let missing_channels = import.missing_channels.clone();
+            let dictionary = get_dictionary(context).await?;
+            let applied = import.apply(database, &dictionary, guild_id).await?;
+
+            let mut description = t_with(
+                locale,
+                "settings.import.imported",
+                &[
+                    ("words", &applied.words.to_string()),
+                    ("readings", &applied.readings.to_string()),
+                ],
+            );
+            if !missing_channels.is_empty() {
+                description.push_str(&format!(
+                    "\n{}",
+                    t_with(
+                        locale,
+                        "settings.import.missing_channels",
+                        &[("channels", &missing_channels.join(t(locale, "common.separator")))],
+                    )
+                ));
+            }
+            if !applied.failures.is_empty() {
+                description.push_str(&format!(
+                    "\n{}\n{}",
+                    t(locale, "settings.import.failures"),
+                    applied.failures.join("\n")
+                ));
+            }
+            truncate_chars(&mut description, MAX_DESCRIPTION_LENGTH);
+            let embed = CreateEmbed::new().description(description).colour(Colour::FOOYOO);
+            interaction
+                .edit_response(&context.http, EditInteractionResponse::new().embed(embed))
+                .await?;
+        },
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+pub(super) async fn get_dictionary(context: &Context) -> Result<Dictionary> {
+    let voicevox = get_voicevox(context).await.ok_or(CommandError::EngineUnavailable)?;
+    let voicevox = voicevox.lock().await;
+    Ok(voicevox.dictionary.clone())
+}
+
+pub(super) async fn channel_names(context: &Context, guild_id: GuildId) -> Result<HashMap<ChannelId, String>> {
+    let channels = guild_id
+        .channels(&context.http)
+        .await
+        .with_context(|| format!("failed to get channels of guild ({guild_id})"))?;
+    Ok(channels
+        .into_iter()
+        .map(|(channel_id, channel)| (channel_id, channel.name))
+        .collect())
+}
+
+/// Toggles the setting or sets the volume chosen on the panel and updates the panel.
+pub(crate) async fn handle_component(
+    context: &Context,
+    component: &ComponentInteraction,
+    database: &PgPool,
+    speaker: &Speaker,
+) -> Result<()> {
+    let Some(guild_id) = component.guild_id else {
+        return Ok(());
+    };
+    let locale = Locale::from_tag(&component.locale);
+
+    // パネルはメッセージを見られる誰でも操作できてしまう
+    let can_manage = component
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_guild());
+    if !can_manage {
+        let message = CreateInteractionResponseMessage::new()
+            .embed(
+                CreateEmbed::new()
+                    .description(t(locale, "settings.panel.permission_denied"))
+                    .colour(Colour::RED),
+            )
+            .ephemeral(true);
+        component
+            .create_response(&context.http, CreateInteractionResponse::Message(message))
+            .await
+            .context("failed to respond to settings panel")?;
+        return Ok(());
+    }
+
+    let settings = guild::fetch_by_id(database, guild_id.get()).await?;
+    let name = component
+        .data
+        .custom_id
+        .strip_prefix(PANEL_PREFIX)
+        .context("component is not of settings panel")?;
+    match (name, &component.data.kind) {
+        ("crosspost", ComponentInteractionDataKind::Button) => {
+            guild::update_read_crossposts(database, guild_id.get(), !settings.read_crossposts).await?;
+        },
+        ("join", ComponentInteractionDataKind::Button) => {
+            guild::update_announce_joins(database, guild_id.get(), !settings.announce_joins).await?;
+        },
+        ("activity", ComponentInteractionDataKind::Button) => {
+            guild::update_announce_activities(database, guild_id.get(), !settings.announce_activities).await?;
+        },
+        ("markdown", ComponentInteractionDataKind::Button) => {
+            guild::update_flatten_markdown(database, guild_id.get(), !settings.flatten_markdown).await?;
+        },
+        ("omit-code", ComponentInteractionDataKind::Button) => {
+            guild::update_omit_code(database, guild_id.get(), !settings.omit_code).await?;
+        },
+        ("jitter", ComponentInteractionDataKind::Button) => {
+            guild::update_jitter(database, guild_id.get(), !settings.jitter).await?;
+        },
+        ("random-voices", ComponentInteractionDataKind::Button) => {
+            guild::update_random_voices(database, guild_id.get(), !settings.random_voices).await?;
+        },
+        ("read-topics", ComponentInteractionDataKind::Button) => {
+            guild::update_read_topics(database, guild_id.get(), !settings.read_topics).await?;
+        },
+        ("read-attachments", ComponentInteractionDataKind::Button) => {
+            guild::update_read_attachments(database, guild_id.get(), !settings.read_attachments).await?;
+        },
+        ("read-embeds", ComponentInteractionDataKind::Button) => {
+            guild::update_read_embeds(database, guild_id.get(), !settings.read_embeds).await?;
+        },
+        ("read-replies", ComponentInteractionDataKind::Button) => {
+            guild::update_read_replies(database, guild_id.get(), !settings.read_replies).await?;
+        },
+        ("read-threads", ComponentInteractionDataKind::Button) => {
+            guild::update_read_threads(database, guild_id.get(), !settings.read_threads).await?;
+        },
+        ("read-voice-chat", ComponentInteractionDataKind::Button) => {
+            guild::update_read_voice_chat(database, guild_id.get(), !settings.read_voice_chat).await?;
+        },
+        ("read-delays", ComponentInteractionDataKind::Button) => {
+            guild::update_read_delays(database, guild_id.get(), !settings.read_delays).await?;
+        },
+        ("skip-commands", ComponentInteractionDataKind::Button) => {
+            guild::update_command_prefixes(
+                database,
+                guild_id.get(),
+                !settings.skip_commands,
+                settings.command_prefixes.as_deref(),
+            )
+            .await?;
+        },
+        ("name-decorations", ComponentInteractionDataKind::Button) => {
+            guild::update_name_decorations(
+                database,
+                guild_id.get(),
+                !settings.strip_name_decorations,
+                settings.name_patterns.as_deref(),
+            )
+            .await?;
+        },
+        ("volume", ComponentInteractionDataKind::StringSelect { values }) => {
+            let volume = values
+                .first()
+                .and_then(|volume| volume.parse::<i32>().ok())
+                .filter(|volume| PANEL_VOLUMES.contains(volume))
+                .context("invalid volume is selected")?;
+            set_volume(context, database, guild_id, volume).await?;
+        },
+        _ => return Ok(()),
+    }
+
+    let message = panel(database, speaker, guild_id, locale).await?;
+    component
+        .create_response(&context.http, CreateInteractionResponse::UpdateMessage(message))
+        .await
+        .context("failed to update settings panel")?;
+
+    Ok(())
+}
+
+/// Current settings of the guild with buttons to toggle them and a menu to choose the volume.
+async fn panel(
+    database: &PgPool,
+    speaker: &Speaker,
+    guild_id: GuildId,
+    locale: Locale,
+) -> Result<CreateInteractionResponseMessage> {
+    let settings = guild::fetch_by_id(database, guild_id.get()).await?;
+    let rotation = voice_rotation::fetch_by_guild_id(database, guild_id.get())
+        .await?
+        .into_iter()
+        .filter_map(|speaker_id| u16::try_from(speaker_id).ok())
+        .filter_map(|speaker_id| speaker.get_name(speaker_id).ok())
+        .collect::<Vec<_>>();
+    let default_speaker = settings
+        .default_speaker_id
+        .and_then(|speaker_id| u16::try_from(speaker_id).ok())
+        .and_then(|speaker_id| speaker.get_name(speaker_id).ok())
+        .unwrap_or_else(|| t(locale, "settings.panel.none").to_string());
+    let auto_joins = auto_join::fetch_by_guild_id(database, guild_id.get())
+        .await?
+        .into_iter()
+        .map(|auto_join| {
+            format!(
+                "{} -> {}",
+                ChannelId::new(auto_join.voice_channel_id as u64).mention(),
+                ChannelId::new(auto_join.text_channel_id as u64).mention()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let toggles = [
+        (
+            "crosspost",
+            t(locale, "settings.panel.crosspost"),
+            settings.read_crossposts,
+        ),
+        ("join", t(locale, "settings.panel.join"), settings.announce_joins),
+        (
+            "activity",
+            t(locale, "settings.panel.activity"),
+            settings.announce_activities,
+        ),
+        (
+            "markdown",
+            t(locale, "settings.panel.markdown"),
+            settings.flatten_markdown,
+        ),
+        ("omit-code", t(locale, "settings.panel.omit_code"), settings.omit_code),
+        (
+            "skip-commands",
+            t(locale, "settings.panel.skip_commands"),
+            settings.skip_commands,
+        ),
+        (
+            "name-decorations",
+            t(locale, "settings.panel.name_decorations"),
+            settings.strip_name_decorations,
+        ),
+        ("jitter", t(locale, "settings.panel.jitter"), settings.jitter),
+        (
+            "random-voices",
+            t(locale, "settings.panel.random_voices"),
+            settings.random_voices,
+        ),
+        (
+            "read-topics",
+            t(locale, "settings.panel.read_topics"),
+            settings.read_topics,
+        ),
+        (
+            "read-attachments",
+            t(locale, "settings.panel.read_attachments"),
+            settings.read_attachments,
+        ),
+        (
+            "read-embeds",
+            t(locale, "settings.panel.read_embeds"),
+            settings.read_embeds,
+        ),
+        (
+            "read-replies",
+            t(locale, "settings.panel.read_replies"),
+            settings.read_replies,
+        ),
+        (
+            "read-threads",
+            t(locale, "settings.panel.read_threads"),
+            settings.read_threads,
+        ),
+        (
+            "read-voice-chat",
+            t(locale, "settings.panel.read_voice_chat"),
+            settings.read_voice_chat,
+        ),
+        (
+            "read-delays",
+            t(locale, "settings.panel.read_delays"),
+            settings.read_delays,
+        ),
+    ];
+    let embed = CreateEmbed::new()
+        .title(t(locale, "settings.panel.title"))
+        .fields(toggles.iter().map(|(_, label, enabled)| {
+            let state = match enabled {
+                true => t(locale, "settings.panel.enabled"),
+                false => t(locale, "settings.panel.disabled"),
+            };
+            (*label, state, true)
+        }))
+        .field(
+            t(locale, "settings.panel.volume"),
+            format!("{}%", settings.volume),
+            true,
+        )
+        .field(
+            t(locale, "settings.panel.celebration"),
+            settings
+                .celebration_threshold
+                .map_or(t(locale, "settings.panel.disabled").to_string(), |threshold| {
+                    let sound = settings
+                        .celebration_sound
+                        .as_deref()
+                        .unwrap_or(DEFAULT_CELEBRATION_SOUND);
+                    t_with(
+                        locale,
+                        "settings.panel.celebration_threshold",
+                        &[("threshold", &threshold.to_string()), ("sound", sound)],
+                    )
+                }),
+            true,
+        )
+        .field(
+            t(locale, "settings.panel.tts_messages"),
+            match settings.tts_messages() {
+                TtsMessages::Skip => t(locale, "settings.panel.tts_messages.skip"),
+                TtsMessages::Read => t(locale, "settings.panel.tts_messages.read"),
+                TtsMessages::Guide => t(locale, "settings.panel.tts_messages.guide"),
+            },
+            true,
+        )
+        .field(
+            t(locale, "settings.panel.output"),
+            match settings.reading_output() {
+                ReadingOutput::Voice => t(locale, "settings.panel.output.voice"),
+                ReadingOutput::Text => t(locale, "settings.panel.output.text"),
+                ReadingOutput::Both => t(locale, "settings.panel.output.both"),
+            },
+            true,
+        )
+        .field(
+            t(locale, "settings.panel.ducking"),
+            match settings.ducking() {
+                Ducking::Off => t(locale, "settings.panel.none").to_string(),
+                Ducking::Pause => t_with(
+                    locale,
+                    "settings.panel.ducking.pause",
+                    &[("sensitivity", &settings.ducking_sensitivity.to_string())],
+                ),
+                Ducking::Lower => t_with(
+                    locale,
+                    "settings.panel.ducking.lower",
+                    &[("sensitivity", &settings.ducking_sensitivity.to_string())],
+                ),
+            },
+            true,
+        )
+        .field(
+            t(locale, "settings.panel.furigana"),
+            match (settings.furigana, settings.furigana_channel_id) {
+                (true, Some(channel_id)) => ChannelId::new(channel_id as u64).mention().to_string(),
+                (true, None) => t(locale, "settings.panel.furigana.reply").to_string(),
+                (false, _) => t(locale, "settings.panel.disabled").to_string(),
+            },
+            true,
+        )
+        .field(t(locale, "settings.panel.default_speaker"), default_speaker, true)
+        .field(
+            t(locale, "settings.panel.rotation"),
+            match rotation.is_empty() {
+                true => t(locale, "settings.panel.none").to_string(),
+                false => rotation.join(t(locale, "common.separator")),
+            },
+            false,
+        )
+        .field(
+            t(locale, "settings.panel.auto_joins"),
+            match auto_joins.is_empty() {
+                true => t(locale, "settings.panel.none").to_string(),
+                false => auto_joins.join("\n"),
+            },
+            false,
+        )
+        .footer(CreateEmbedFooter::new(t(locale, "settings.panel.footer")))
+        .colour(Colour::FOOYOO);
+
+    let buttons = toggles
+        .iter()
+        .map(|(name, label, enabled)| {
+            CreateButton::new(format!("{PANEL_PREFIX}{name}"))
+                .label(*label)
+                .style(match enabled {
+                    true => ButtonStyle::Success,
+                    false => ButtonStyle::Secondary,
+                })
+        })
+        .collect::<Vec<_>>();
+    let volumes = PANEL_VOLUMES
+        .iter()
+        .map(|volume| {
+            CreateSelectMenuOption::new(format!("{volume}%"), volume.to_string())
+                .default_selection(*volume == settings.volume)
+        })
+        .collect::<Vec<_>>();
+    let volume = CreateSelectMenu::new(
+        format!("{PANEL_PREFIX}volume"),
+        CreateSelectMenuKind::String { options: volumes },
+    )
+    .placeholder(t_with(
+        locale,
+        "settings.panel.volume_placeholder",
+        &[("volume", &settings.volume.to_string())],
+    ));
+
+    // 1 行に置けるボタンは 5 個まで
+    let mut components = buttons
+        .chunks(5)
+        .map(|buttons| CreateActionRow::Buttons(buttons.to_vec()))
+        .collect::<Vec<_>>();
+    components.push(CreateActionRow::SelectMenu(volume));
+
+    Ok(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(components))
+}
+
+pub fn register() -> CreateCommand {
+    let panel = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "panel",
+        "Shows the settings of this server with buttons to change them",
+    )
+    .description_localized(
+        "ja",
+        "サーバーの設定を表示し、ボタンやメニューで変更できるようにします。",
+    );
+    let crosspost = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to read")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み上げるかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "crosspost",
+            "Reads announcements published from followed channels",
+        )
+        .description_localized("ja", "フォローしているチャンネルから公開されたお知らせを読み上げます。")
+        .add_sub_option(enabled)
+    };
+    let join = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to announce")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み上げるかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "join",
+            "Announces members joining the voice channel",
+        )
+        .description_localized("ja", "ボイスチャンネルに参加したメンバーを読み上げます。")
+        .add_sub_option(enabled)
+    };
+    let activity = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to announce")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み上げるかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "activity",
+            "Appends what joining members are playing to the announcement",
+        )
+        .description_localized("ja", "参加したメンバーがプレイ中のゲームなども読み上げます。")
+        .add_sub_option(enabled)
+    };
+
+    let rotation_add = {
+        let speaker = CreateCommandOption::new(CommandOptionType::Integer, "speaker", "Voice to be added")
+            .name_localized("ja", "ボイス")
+            .description_localized("ja", "追加するボイス")
+            .set_autocomplete(true)
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "rotation-add",
+            "Adds a voice the default voice rotates among day by day",
+        )
+        .description_localized("ja", "既定のボイスを日替わりにするボイスを追加します。")
+        .add_sub_option(speaker)
+    };
+    let rotation_remove = {
+        let speaker = CreateCommandOption::new(CommandOptionType::Integer, "speaker", "Voice to be removed")
+            .name_localized("ja", "ボイス")
+            .description_localized("ja", "削除するボイス")
+            .set_autocomplete(true)
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "rotation-remove",
+            "Removes a voice the default voice rotates among day by day",
+        )
+        .description_localized("ja", "既定のボイスを日替わりにするボイスを削除します。")
+        .add_sub_option(speaker)
+    };
+    let channel_voice = {
+        let channel = CreateCommandOption::new(CommandOptionType::Channel, "channel", "Channel to set voice")
+            .name_localized("ja", "チャンネル")
+            .description_localized("ja", "ボイスを設定するチャンネル")
+            .required(true);
+        let speaker = CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "speaker",
+            "Voice to be used, or none to reset",
+        )
+        .name_localized("ja", "ボイス")
+        .description_localized("ja", "使うボイス（省略するとリセットします）")
+        .set_autocomplete(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "channel-voice",
+            "Reads messages in the channel with the voice regardless of members' voices",
+        )
+        .description_localized(
+            "ja",
+            "チャンネルのメッセージをメンバーの設定に関わらず決まったボイスで読み上げます。",
+        )
+        .add_sub_option(channel)
+        .add_sub_option(speaker)
+    };
+    let keyword_voice = {
+        let keyword = CreateCommandOption::new(CommandOptionType::String, "keyword", "Keyword to set voice")
+            .name_localized("ja", "キーワード")
+            .description_localized("ja", "ボイスを設定するキーワード")
+            .required(true);
+        let speaker = CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "speaker",
+            "Voice to be used, or none to reset",
+        )
+        .name_localized("ja", "ボイス")
+        .description_localized("ja", "使うボイス（省略するとリセットします）")
+        .set_autocomplete(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "keyword-voice",
+            "Reads messages containing the keyword with the voice regardless of members' voices",
+        )
+        .description_localized(
+            "ja",
+            "キーワードを含むメッセージをメンバーの設定に関わらず決まったボイスで読み上げます。",
+        )
+        .add_sub_option(keyword)
+        .add_sub_option(speaker)
+    };
+    let style_shortcut = {
+        let prefix = CreateCommandOption::new(CommandOptionType::String, "prefix", "Prefix of messages")
+            .name_localized("ja", "接頭辞")
+            .description_localized("ja", "メッセージの先頭に付ける文字")
+            .required(true);
+        let style = CreateCommandOption::new(
+            CommandOptionType::String,
+            "style",
+            "Name of the style such as ささやき, or none to reset",
+        )
+        .name_localized("ja", "スタイル")
+        .description_localized("ja", "「ささやき」などのスタイルの名前（省略するとリセットします）");
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "style-shortcut",
+            "Reads messages starting with the prefix with the style of each member's voice",
+        )
+        .description_localized(
+            "ja",
+            "接頭辞で始まるメッセージを、メンバーのボイスのささやきなどのスタイルで読み上げます。",
+        )
+        .add_sub_option(prefix)
+        .add_sub_option(style)
+    };
+    let markdown = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to flatten")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "まとめて読み上げるかどうか");
+        let code = CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "code",
+            "Whether to read code blocks and inline code as \"コード省略\" instead of their content",
+        )
+        .name_localized("ja", "コードの省略")
+        .description_localized(
+            "ja",
+            "コードブロックやインラインコードを「コード省略」と読み上げるかどうか",
+        );
+        let spoilers = CreateCommandOption::new(CommandOptionType::String, "spoilers", "How to read spoilers")
+            .name_localized("ja", "伏せ字")
+            .description_localized("ja", "伏せ字の読み上げ方")
+            .add_string_choice_localized(
+                "Read \"ネタバレ\"",
+                Spoilers::Announce.as_str(),
+                [("ja", "「ネタバレ」と読み上げる")],
+            )
+            .add_string_choice_localized("Skip", Spoilers::Skip.as_str(), [("ja", "読み上げない")])
+            .add_string_choice_localized("Read", Spoilers::Read.as_str(), [("ja", "そのまま読み上げる")]);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "markdown",
+            "Sets how to read lists, tables, code and spoilers, showing the current settings",
+        )
+        .description_localized(
+            "ja",
+            "箇条書きや表、コード、伏せ字の読み上げ方を設定します（省略すると現在の設定を表示します）。",
+        )
+        .add_sub_option(enabled)
+        .add_sub_option(code)
+        .add_sub_option(spoilers)
+    };
+    let jitter = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to vary them")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "揺らすかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "jitter",
+            "Varies the speed and pitch of each utterance slightly so long messages sound natural",
+        )
+        .description_localized(
+            "ja",
+            "発話ごとに速さと高さをわずかに揺らして、長いメッセージを自然に読み上げます。",
+        )
+        .add_sub_option(enabled)
+    };
+    let random_voices = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to assign them")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "割り当てるかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "random-voices",
+            "Assigns a voice picked from the user id to members who have chosen none",
+        )
+        .description_localized(
+            "ja",
+            "ボイスを設定していないメンバーに、メンバーごとに決まったボイスを割り当てます。",
+        )
+        .add_sub_option(enabled)
+    };
+    let read_topics = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to read it")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み上げるかどうか")
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "read-topics",
+            "Reads the topic of the channel on joining and whenever it changes",
+        )
+        .description_localized(
+            "ja",
+            "接続したときと変更されたときに、チャンネルのトピックを読み上げます。",
+        )
+        .add_sub_option(enabled)
+    };
+    let read_extras = {
+        let attachments = CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "attachments",
+            "Whether to read the kinds and numbers of attached files, and the names of files other than media",
+        )
+        .name_localized("ja", "添付ファイル")
+        .description_localized(
+            "ja",
+            "添付ファイルの種類と数、画像や動画でないファイルの名前を読み上げるかどうか",
+        );
+        let embeds = CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "embeds",
+            "Whether to read the titles and descriptions of embeds, including messages of bots with embeds",
+        )
+        .name_localized("ja", "埋め込み")
+        .description_localized(
+            "ja",
+            "埋め込みのタイトルと説明を、bot のメッセージも含めて読み上げるかどうか",
+        );
+        let replies = CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "replies",
+            "Whether to read whom a message replies to before it",
+        )
+        .name_localized("ja", "返信先")
+        .description_localized("ja", "返信の前に「〇〇さんへの返信」と読み上げるかどうか");
+        let delays = CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "delays",
+            "Whether to read how long ago messages read late were sent before them",
+        )
+        .name_localized("ja", "遅れ")
+        .description_localized(
+            "ja",
+            "遅れて読み上げるメッセージの前に「2分前」のように読み上げるかどうか",
+        );
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "read-extras",
+            "Sets whether to read attached files, embeds, replies and delays, showing the current settings",
+        )
+        .description_localized(
+            "ja",
+            "添付ファイルや埋め込み、返信先、遅れを読み上げるかどうかを設定します（省略すると現在の設定を表示します）。",
+        )
+        .add_sub_option(attachments)
+        .add_sub_option(embeds)
+        .add_sub_option(replies)
+        .add_sub_option(delays)
+    };
+    let leave_utterance = {
+        let text = CreateCommandOption::new(
+            CommandOptionType::String,
+            "text",
+            "Text to read, or none to leave silently",
+        )
+        .name_localized("ja", "テキスト")
+        .description_localized("ja", "読み上げるテキスト（省略すると何も読み上げずに切断します）")
+        .max_length(MAX_LEAVE_UTTERANCE_LENGTH);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "leave-utterance",
+            "Sets what is read before leaving the voice channel with `/leave`",
+        )
+        .description_localized("ja", "`/leave` で切断する前に読み上げるテキストを設定します。")
+        .add_sub_option(text)
+    };
+    let priority_role = {
+        let role = CreateCommandOption::new(
+            CommandOptionType::Role,
+            "role",
+            "Role whose messages are read first, or none to read in order",
+        )
+        .name_localized("ja", "ロール")
+        .description_localized("ja", "先に読み上げるロール（省略すると送られた順に読み上げます）");
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "priority-role",
+            "Sets role whose messages are read before the others waiting",
+        )
+        .description_localized(
+            "ja",
+            "ロールのメンバーのメッセージを、読み上げを待っているメッセージより先に読み上げます。",
+        )
+        .add_sub_option(role)
+    };
+    let skip_commands = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to skip")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み上げないかどうか")
+            .required(true);
+        let prefixes = CreateCommandOption::new(
+            CommandOptionType::String,
+            "prefixes",
+            "Space-separated prefixes of commands, or none for the default ones",
+        )
+        .name_localized("ja", "プレフィックス")
+        .description_localized(
+            "ja",
+            "空白区切りのコマンドのプレフィックス（省略すると既定のものを使います）",
+        );
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "skip-commands",
+            "Skips messages starting with prefixes of commands such as `/` or `!`",
+        )
+        .description_localized(
+            "ja",
+            "`/` や `!` などのコマンドのプレフィックスで始まるメッセージを読み上げません。",
+        )
+        .add_sub_option(enabled)
+        .add_sub_option(prefixes)
+    };
+    let name_decorations = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to leave them out")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "装飾を省くかどうか")
+            .required(true);
+        let patterns = CreateCommandOption::new(
+            CommandOptionType::String,
+            "patterns",
+            "Space-separated regular expressions of decorations, or none for the default ones",
+        )
+        .name_localized("ja", "パターン")
+        .description_localized("ja", "空白区切りの装飾の正規表現（省略すると既定のものを使います）");
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "name-decorations",
+            "Leaves out decorations such as pronoun tags and clan tags when names are read",
+        )
+        .description_localized(
+            "ja",
+            "参加のお知らせや名前の読み上げで、代名詞やクランのタグなどの装飾を省きます。",
+        )
+        .add_sub_option(enabled)
+        .add_sub_option(patterns)
+    };
+    let celebration = {
+        let threshold = CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "threshold",
+            "Number of reactions, or none to disable",
+        )
+        .name_localized("ja", "リアクション数")
+        .description_localized("ja", "サウンドを鳴らすリアクションの数（省略すると無効にします）")
+        .min_int_value(1);
+        let sound = CreateCommandOption::new(
+            CommandOptionType::String,
+            "sound",
+            "Name of the sound, or none for the default one",
+        )
+        .name_localized("ja", "サウンド")
+        .description_localized("ja", "鳴らすサウンドの名前（省略すると既定のものを使います）");
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "celebration",
+            "Plays a sound when a message in the reading channel gets enough reactions",
+        )
+        .description_localized(
+            "ja",
+            "読み上げるチャンネルのメッセージに一定数のリアクションが付いたらサウンドを鳴らします。",
+        )
+        .add_sub_option(threshold)
+        .add_sub_option(sound)
+    };
+    let tts_messages = {
+        let handling = CreateCommandOption::new(CommandOptionType::String, "handling", "How to handle them")
+            .name_localized("ja", "扱い")
+            .description_localized("ja", "メッセージの扱い")
+            .add_string_choice_localized("Skip", TtsMessages::Skip.as_str(), [("ja", "読み上げない")])
+            .add_string_choice_localized("Read", TtsMessages::Read.as_str(), [("ja", "読み上げる")])
+            .add_string_choice_localized(
+                "Read and guide",
+                TtsMessages::Guide.as_str(),
+                [("ja", "読み上げて権限の変更を案内する")],
+            )
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "tts-messages",
+            "Chooses how to handle messages sent with the text-to-speech of Discord",
+        )
+        .description_localized("ja", "Discord の読み上げ機能で送られたメッセージの扱いを選びます。")
+        .add_sub_option(handling)
+    };
+
+    let output = {
+        let target = CreateCommandOption::new(CommandOptionType::String, "target", "Where to output")
+            .name_localized("ja", "読み上げ先")
+            .description_localized("ja", "読み上げの出力先")
+            .add_string_choice_localized(
+                "Voice channel",
+                ReadingOutput::Voice.as_str(),
+                [("ja", "ボイスチャンネル")],
+            )
+            .add_string_choice_localized("Text reply", ReadingOutput::Text.as_str(), [("ja", "文章の返信")])
+            .add_string_choice_localized(
+                "Both",
+                ReadingOutput::Both.as_str(),
+                [("ja", "ボイスチャンネルと文章の返信")],
+            )
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "output",
+            "Chooses whether to read messages aloud, post the text to be read, or both",
+        )
+        .description_localized(
+            "ja",
+            "メッセージを読み上げるか、聞こえないメンバーのために読み上げる文章を送るか、その両方かを選びます。",
+        )
+        .add_sub_option(target)
+    };
+
+    let ducking = {
+        let mode = CreateCommandOption::new(
+            CommandOptionType::String,
+            "mode",
+            "What to do while members are talking",
+        )
+        .name_localized("ja", "動作")
+        .description_localized("ja", "話している人がいる間の読み上げの動作")
+        .add_string_choice_localized("Keep reading", Ducking::Off.as_str(), [("ja", "読み上げを続ける")])
+        .add_string_choice_localized("Pause", Ducking::Pause.as_str(), [("ja", "止める")])
+        .add_string_choice_localized("Lower volume", Ducking::Lower.as_str(), [("ja", "音量を下げる")])
+        .required(true);
+        let sensitivity = CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "sensitivity",
+            "How soon to react to talking from 1 to 5, or none for 3",
+        )
+        .name_localized("ja", "感度")
+        .description_localized("ja", "話し声に反応する早さ（1〜5、省略すると3）")
+        .min_int_value(1)
+        .max_int_value(5);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "ducking",
+            "Chooses whether to pause or lower reading while members are talking in the voice channel",
+        )
+        .description_localized(
+            "ja",
+            "ボイスチャンネルで話している人がいる間、読み上げを止めるか音量を下げるかを選びます。",
+        )
+        .add_sub_option(mode)
+        .add_sub_option(sensitivity)
+    };
+
+    let furigana = {
+        let enabled = CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether to post them")
+            .name_localized("ja", "有効")
+            .description_localized("ja", "読み方を送るかどうか")
+            .required(true);
+        let channel = CreateCommandOption::new(
+            CommandOptionType::Channel,
+            "channel",
+            "Channel to post them in, or none to reply to messages with them in spoilers",
+        )
+        .name_localized("ja", "チャンネル")
+        .description_localized("ja", "読み方を送るチャンネル（省略するとメッセージに伏せ字で返信）")
+        .channel_types(vec![ChannelType::Text]);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "furigana",
+            "Posts kana readings of messages read aloud for members learning Japanese",
+        )
+        .description_localized(
+            "ja",
+            "日本語を学んでいるメンバーのために、読み上げたメッセージのカナの読み方を送ります。",
+        )
+        .add_sub_option(enabled)
+        .add_sub_option(channel)
+    };
+
+    let export = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "export",
+        "Exports the settings, dictionary and voices of this server as a JSON file",
+    )
+    .description_localized("ja", "サーバーの設定、辞書、ボイスを JSON のファイルに書き出します。");
+
+    let import = {
+        let file = CreateCommandOption::new(
+            CommandOptionType::Attachment,
+            "file",
+            "File written by /settings export",
+        )
+        .name_localized("ja", "ファイル")
+        .description_localized("ja", "/settings export で書き出したファイル")
+        .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "import",
+            "Imports settings from a JSON file, adding to the dictionary and voices of this server",
+        )
+        .description_localized(
+            "ja",
+            "JSON のファイルから設定を読み込みます。辞書やボイスは今の設定に追加されます。",
+        )
+        .add_sub_option(file)
+    };
+
+    CreateCommand::new("settings")
+        .description("サーバーの読み上げの設定を変更します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![
+            panel,
+            crosspost,
+            join,
+            activity,
+            rotation_add,
+            rotation_remove,
+            channel_voice,
+            keyword_voice,
+            style_shortcut,
+            markdown,
+            jitter,
+            random_voices,
+            read_topics,
+            read_extras,
+            leave_utterance,
+            priority_role,
+            skip_commands,
+            name_decorations,
+            celebration,
+            tts_messages,
+            output,
+            ducking,
+            furigana,
+            export,
+            import,
+        ])
+}
@@ -0,0 +1,58 @@
+use anyhow::{Context as _, Result};
+use serenity::{
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::{
+    i18n::{Locale, t},
+    utils::{get_manager, respond},
+};
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction) -> Result<()> {
+    let guild_id = interaction.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&interaction.locale);
+    let manager = get_manager(context).await?;
+    let call = manager.get_or_insert(guild_id);
+    let call = call.lock().await;
+
+    let queue = call.queue();
+    if queue.current().is_none() {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description(t(locale, "common.nothing_reading"))
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    // 読み上げ中の発話だけを止めて、残りはそのまま読み上げる
+    match queue.skip() {
+        Ok(_) => {
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(t(locale, "skip.skipped"))
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        Err(error) => {
+            tracing::error!("failed to skip current track\nError: {error:?}");
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(t(locale, "skip.failed"))
+                    .field(t(locale, "common.detail"), format!("```\n{}\n```", error), false)
+                    .colour(Colour::RED),
+            );
+            respond(context, interaction, &message).await?;
+        },
+    };
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("skip").description("読み上げ中のメッセージをスキップします。")
+}
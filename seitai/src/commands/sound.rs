@@ -0,0 +1,223 @@
+use std::{ffi::OsString, path::Path, time::Duration};
+
+use anyhow::{Context as _, Result};
+use dashmap::DashMap;
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+use songbird::input::{
+    Input,
+    cached::Memory,
+    codecs::{get_codec_registry, get_probe},
+};
+
+use super::subcommand::Subcommand;
+use crate::utils::{is_owner, respond};
+
+const EXTENSIONS: [&str; 3] = ["mp3", "wav", "opus"];
+// アップロードできるファイルの大きさの上限
+const MAX_SIZE: u32 = 1024 * 1024;
+// 効果音として鳴らすには長すぎるサウンドを弾く
+const MAX_DURATION: Duration = Duration::from_secs(10);
+const MAX_NAME_LENGTH: usize = 32;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    sounds: &DashMap<OsString, Memory>,
+    sound_directory: Option<&Path>,
+) -> Result<()> {
+    if !is_owner(context, interaction.user.id).await? {
+        let message = CreateInteractionResponseMessage::new()
+            .embed(
+                CreateEmbed::new()
+                    .description("このコマンドは bot のオーナーのみ使用できます。")
+                    .colour(Colour::RED),
+            )
+            .ephemeral(true);
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /sound subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+
+    match subcommand.name {
+        "add" => {
+            let name = subcommand
+                .options
+                .get("name")
+                .and_then(|v| v.as_str())
+                .context("no name option")?;
+            let attachment = subcommand
+                .options
+                .get("file")
+                .and_then(|v| v.as_attachment_id())
+                .and_then(|id| interaction.data.resolved.attachments.get(&id))
+                .context("no file option")?;
+            let extension = Path::new(&attachment.filename)
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+                .filter(|extension| EXTENSIONS.contains(&extension.as_str()));
+
+            let error = if !is_valid_name(name) {
+                Some(format!(
+                    "名前は{MAX_NAME_LENGTH}文字以内で、`/` や `\\` を含まず `.` で始まらないものにしてください。"
+                ))
+            } else if sounds.contains_key(&OsString::from(name)) {
+                Some(format!("サウンド「{name}」は既にあります。"))
+            } else if extension.is_none() {
+                Some(format!("{} のファイルを添付してください。", EXTENSIONS.join("、")))
+            } else if attachment.size > MAX_SIZE {
+                Some(format!("ファイルは{}KB以下にしてください。", MAX_SIZE / 1024))
+            } else {
+                None
+            };
+            if let Some(error) = error {
+                let message = CreateInteractionResponseMessage::new()
+                    .embed(CreateEmbed::new().description(error).colour(Colour::RED))
+                    .ephemeral(true);
+                respond(context, interaction, &message).await?;
+                return Ok(());
+            }
+
+            let bytes = attachment.download().await.context("failed to download sound")?;
+            let duration = probe_duration(bytes.clone()).await.unwrap_or_else(|error| {
+                tracing::error!("failed to probe sound {name}\nError: {error:?}");
+                None
+            });
+            let duration = match duration {
+                Some(duration) if duration <= MAX_DURATION => duration,
+                Some(_) => {
+                    let message = CreateInteractionResponseMessage::new()
+                        .embed(
+                            CreateEmbed::new()
+                                .description(format!("サウンドは{}秒以内にしてください。", MAX_DURATION.as_secs()))
+                                .colour(Colour::RED),
+                        )
+                        .ephemeral(true);
+                    respond(context, interaction, &message).await?;
+                    return Ok(());
+                },
+                None => {
+                    let message = CreateInteractionResponseMessage::new()
+                        .embed(
+                            CreateEmbed::new()
+                                .description("サウンドを読み込めませんでした。")
+                                .colour(Colour::RED),
+                        )
+                        .ephemeral(true);
+                    respond(context, interaction, &message).await?;
+                    return Ok(());
+                },
+            };
+
+            // SS_DIRECTORY があれば保存して再起動後も鳴らせるようにする
+            if let (Some(sound_directory), Some(extension)) = (sound_directory, &extension) {
+                let path = sound_directory.join(format!("{name}.{extension}"));
+                tokio::fs::write(&path, &bytes)
+                    .await
+                    .with_context(|| format!("failed to save sound to {}", path.display()))?;
+            }
+            let memory = Memory::new(Input::from(bytes)).await.context("failed to cache sound")?;
+            sounds.insert(OsString::from(name), memory);
+
+            let sound = database::sound::UploadedSound {
+                name: name.to_string(),
+                guild_id: interaction.guild_id.map(|guild_id| guild_id.get()),
+                user_id: interaction.user.id.get(),
+                size: attachment.size,
+                duration_ms: u32::try_from(duration.as_millis()).unwrap_or(u32::MAX),
+            };
+            database::sound::create_uploaded(database, &sound).await?;
+            tracing::info!("sound {name} has been added by {}", interaction.user.id);
+
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .description(format!(
+                        "サウンド「{name}」を追加しました。`/soundboard play` で鳴らせます。"
+                    ))
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+/// Parses the sound to find its duration, which is `None` if the format does not tell it.
+async fn probe_duration(bytes: Vec<u8>) -> Result<Option<Duration>> {
+    let input = Input::from(bytes)
+        .make_playable_async(get_codec_registry(), get_probe())
+        .await
+        .context("failed to parse sound")?;
+    let parsed = input.parsed().context("sound is not parsed")?;
+
+    let duration = parsed
+        .format
+        .tracks()
+        .iter()
+        .find(|track| track.id == parsed.track_id)
+        .and_then(|track| {
+            let frames = track.codec_params.n_frames?;
+            let sample_rate = track.codec_params.sample_rate?;
+            Some(Duration::from_secs_f64(frames as f64 / f64::from(sample_rate)))
+        });
+
+    Ok(duration)
+}
+
+// サウンドの名前はファイル名にもなるので、ディレクトリを辿れる名前を弾く
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_NAME_LENGTH
+        && !name.starts_with('.')
+        && !name.chars().any(|c| c == '/' || c == '\\' || c.is_control())
+}
+
+pub fn register() -> CreateCommand {
+    let add = {
+        let name = CreateCommandOption::new(CommandOptionType::String, "name", "Name of the sound")
+            .name_localized("ja", "名前")
+            .description_localized("ja", "サウンドの名前（ボイスチャンネルのチャットで送ると鳴ります）")
+            .max_length(MAX_NAME_LENGTH as u16)
+            .required(true);
+        let file = CreateCommandOption::new(CommandOptionType::Attachment, "file", "mp3, wav or opus file")
+            .name_localized("ja", "ファイル")
+            .description_localized("ja", "mp3、wav または opus のファイル")
+            .required(true);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "add", "Adds the sound")
+            .description_localized("ja", "サウンドを追加します。")
+            .add_sub_option(name)
+            .add_sub_option(file)
+    };
+
+    CreateCommand::new("sound")
+        .description("bot のオーナー向けにサウンドを管理します。")
+        .add_option(add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_name;
+
+    #[test]
+    fn reject_names_escaping_directory() {
+        assert!(is_valid_name("拍手"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("../seitai"));
+        assert!(!is_valid_name("a\\b"));
+        assert!(!is_valid_name(".hidden"));
+    }
+}
@@ -0,0 +1,187 @@
+use std::ffi::OsString;
+
+use anyhow::{Context as _, Result};
+use dashmap::DashMap;
+use serenity::{
+    all::CommandOptionType,
+    builder::{
+        AutocompleteChoice, CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed,
+        CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+    },
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+use songbird::{input::cached::Memory, tracks::Track};
+
+use super::{error::CommandError, subcommand::Subcommand};
+use crate::utils::{SOUND_GAIN, get_manager, respond};
+
+// 1 ページに表示するサウンドの数
+const PAGE_SIZE: usize = 20;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    sounds: &DashMap<OsString, Memory>,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /soundboard subcommand")?;
+    let subcommand = Subcommand::from_command_data_option(subcommand).unwrap_or_default();
+
+    match subcommand.name {
+        "list" => {
+            let page = subcommand
+                .options
+                .get("page")
+                .and_then(|v| v.as_i64())
+                .and_then(|page| usize::try_from(page).ok())
+                .unwrap_or(1);
+            let mut names = sounds
+                .iter()
+                .map(|sound| sound.key().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            names.sort();
+
+            let embed = match paginate(&names, page) {
+                Some((names, pages)) => CreateEmbed::new()
+                    .title("サウンド一覧")
+                    .description(format!("```\n{}\n```", names.join("\n")))
+                    .footer(CreateEmbedFooter::new(format!("{page} / {pages} ページ")))
+                    .colour(Colour::FOOYOO),
+                None if names.is_empty() => CreateEmbed::new()
+                    .description("サウンドはありません。")
+                    .colour(Colour::RED),
+                None => CreateEmbed::new()
+                    .description(format!(
+                        "ページは 1 〜 {} を指定してください。",
+                        names.len().div_ceil(PAGE_SIZE)
+                    ))
+                    .colour(Colour::RED),
+            };
+            let message = CreateInteractionResponseMessage::new().embed(embed);
+            respond(context, interaction, &message).await?;
+        },
+        "play" => {
+            let name = subcommand
+                .options
+                .get("name")
+                .and_then(|v| v.as_str())
+                .context("no name option")?;
+
+            let manager = get_manager(context).await?;
+            let embed = match (manager.get(guild_id), sounds.get(&OsString::from(name))) {
+                (None, _) => CreateEmbed::new()
+                    .description("ボイスチャンネルに接続していません。")
+                    .colour(Colour::RED),
+                (_, None) => CreateEmbed::new()
+                    .description(format!("サウンド「{name}」が見つかりません。"))
+                    .colour(Colour::RED),
+                (Some(call), Some(sound)) => {
+                    call.lock()
+                        .await
+                        .enqueue(Track::from(sound.value().clone()).volume(SOUND_GAIN))
+                        .await;
+                    CreateEmbed::new()
+                        .description(format!("「{name}」を鳴らします。"))
+                        .colour(Colour::FOOYOO)
+                },
+            };
+            let message = CreateInteractionResponseMessage::new().embed(embed);
+            respond(context, interaction, &message).await?;
+        },
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn autocomplete(
+    context: &Context,
+    interaction: &CommandInteraction,
+    sounds: &DashMap<OsString, Memory>,
+) -> Result<()> {
+    let Some(option) = interaction.data.autocomplete() else {
+        return Ok(());
+    };
+
+    let mut names = sounds
+        .iter()
+        .map(|sound| sound.key().to_string_lossy().into_owned())
+        .filter(|name| name.contains(option.value))
+        .collect::<Vec<_>>();
+    names.sort();
+    let choices = names
+        .into_iter()
+        .take(25)
+        .map(|name| AutocompleteChoice::new(name.clone(), name))
+        .collect::<Vec<_>>();
+    interaction
+        .create_response(
+            &context.http,
+            CreateInteractionResponse::Autocomplete(CreateAutocompleteResponse::new().set_choices(choices)),
+        )
+        .await
+        .context("failed to autocomplete sound names")?;
+
+    Ok(())
+}
+
+/// Names on the page counted from 1 and the number of pages, or `None` if the page is out of range.
+fn paginate(names: &[String], page: usize) -> Option<(&[String], usize)> {
+    let pages = names.len().div_ceil(PAGE_SIZE);
+    if page == 0 || page > pages {
+        return None;
+    }
+    let start = (page - 1) * PAGE_SIZE;
+    let end = (start + PAGE_SIZE).min(names.len());
+    Some((&names[start..end], pages))
+}
+
+pub fn register() -> CreateCommand {
+    let list = {
+        let page = CreateCommandOption::new(CommandOptionType::Integer, "page", "Page to be shown")
+            .name_localized("ja", "ページ")
+            .description_localized("ja", "表示するページ（省略すると最初のページを表示します）")
+            .min_int_value(1);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Lists the sounds")
+            .description_localized("ja", "鳴らせるサウンドを表示します。")
+            .add_sub_option(page)
+    };
+    let play = {
+        let name = CreateCommandOption::new(CommandOptionType::String, "name", "Name of the sound")
+            .name_localized("ja", "名前")
+            .description_localized("ja", "鳴らすサウンドの名前")
+            .set_autocomplete(true)
+            .required(true);
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "play",
+            "Plays the sound after the messages being read",
+        )
+        .description_localized("ja", "読み上げ待ちのメッセージの後にサウンドを鳴らします。")
+        .add_sub_option(name)
+    };
+
+    CreateCommand::new("soundboard")
+        .description("サウンドを表示したり鳴らしたりします。")
+        .set_options(vec![list, play])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PAGE_SIZE, paginate};
+
+    #[test]
+    fn paginate_names() {
+        let names = (0..PAGE_SIZE + 5).map(|index| index.to_string()).collect::<Vec<_>>();
+        assert_eq!(paginate(&names, 1), Some((&names[..PAGE_SIZE], 2)));
+        assert_eq!(paginate(&names, 2), Some((&names[PAGE_SIZE..], 2)));
+        assert_eq!(paginate(&names, 3), None);
+        assert_eq!(paginate(&names, 0), None);
+        assert_eq!(paginate(&[], 1), None);
+    }
+}
@@ -11,15 +11,16 @@ use serenity::{
 };
 use soundboard::{Soundboard, SoundboardExt};
 
-use crate::utils::{parse_soundmoji, respond};
+use crate::{
+    quota,
+    utils::{parse_soundmoji, respond},
+};
 
-use super::subcommand::Subcommand;
+use super::{error::CommandError, subcommand::Subcommand};
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
-    let Some(guild_id) = interaction.guild_id else {
-        return Ok(());
-    };
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
     let subcommand = interaction
         .data
         .options
@@ -53,6 +54,22 @@ pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, dat
             let sticker = sticker_id.to_sticker(&context.http).await?;
             let sound = sound_id.to_soundboard_sound(&context.http, sound_guild_id).await?;
 
+            let count = soundsticker::count_by_guild_id(database, guild_id.get()).await?;
+            let is_linked = !soundsticker::fetch_by_ids(database, vec![sticker.id.get()])
+                .await?
+                .is_empty();
+            if count >= quota::SOUNDSTICKERS && !is_linked {
+                let message = CreateInteractionResponseMessage::new().embed(
+                    CreateEmbed::new()
+                        .title("サウンドを紐づけられるスタンプ数の上限に達しています。")
+                        .description("`/soundsticker delete` で不要な紐づけを解除してください。")
+                        .field("使用量", format!("{count} / {}", quota::SOUNDSTICKERS), false)
+                        .colour(Colour::RED),
+                );
+                respond(context, interaction, &message).await?;
+                return Ok(());
+            }
+
             soundsticker::create(
                 database,
                 &sticker.name,
@@ -75,23 +92,39 @@ pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, dat
             respond(context, interaction, &message).await?;
         },
         "list" => {
-            let stickers = soundsticker::fetch_all(database).await?.into_iter().map(|v| {
-                (
-                    format!(":frame_photo: {}", v.sticker_name),
-                    format!(":sound: {}", v.sound_name),
-                    true,
-                )
-            });
-
-            let message = CreateInteractionResponseMessage::new()
-                .embed(CreateEmbed::new().fields(stickers).colour(Colour::FOOYOO));
+            let soundstickers = soundsticker::fetch_all(database).await?;
+            let embed = match soundstickers.is_empty() {
+                true => CreateEmbed::new()
+                    .description("サウンドが紐づいたスタンプはありません。`/soundsticker link` で紐づけられます。")
+                    .colour(Colour::FOOYOO),
+                false => {
+                    let stickers = soundstickers.into_iter().map(|v| {
+                        (
+                            format!(":frame_photo: {}", v.sticker_name),
+                            format!(":sound: {}", v.sound_name),
+                            true,
+                        )
+                    });
+                    CreateEmbed::new().fields(stickers).colour(Colour::FOOYOO)
+                },
+            };
+
+            let message = CreateInteractionResponseMessage::new().embed(embed);
             respond(context, interaction, &message).await?;
         },
         "delete" => {
             let sticker_id = sticker_id.context("no sticker option")?;
             let deleted_soundsticker = match soundsticker::delete_by_id(database, sticker_id.get()).await? {
                 Some(deleted) => deleted,
-                None => return Ok(()),
+                None => {
+                    let message = CreateInteractionResponseMessage::new().embed(
+                        CreateEmbed::new()
+                            .description("このスタンプにはサウンドが紐づいていません。")
+                            .colour(Colour::RED),
+                    );
+                    respond(context, interaction, &message).await?;
+                    return Ok(());
+                },
             };
 
             let message = CreateInteractionResponseMessage::new().embed(
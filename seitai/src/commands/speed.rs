@@ -0,0 +1,60 @@
+use anyhow::Result;
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::{speaker::Speaker, utils::respond};
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let speed = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "speed")
+        .and_then(|option| option.value.as_f64());
+
+    let embed = match speed {
+        Some(speed) if !(Speaker::min_speed()..=Speaker::max_speed()).contains(&(speed as f32)) => CreateEmbed::new()
+            .description(format!(
+                "速さは{}から{}の間で指定してください。",
+                Speaker::min_speed(),
+                Speaker::max_speed()
+            ))
+            .colour(Colour::RED),
+        Some(speed) => {
+            let speed = database::user_speed::create(database, interaction.user.id.get(), speed as f32).await?;
+            CreateEmbed::new()
+                .title("読み上げの速さを変更しました。")
+                .description(format!("{speed}倍"))
+                .colour(Colour::FOOYOO)
+        },
+        None => {
+            database::user_speed::delete_by_id(database, interaction.user.id.get()).await?;
+            CreateEmbed::new()
+                .title("読み上げの速さをリセットしました。")
+                .description("ボイスごとの速さで読み上げます。")
+                .colour(Colour::FOOYOO)
+        },
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let speed = CreateCommandOption::new(CommandOptionType::Number, "speed", "Speed, or none to reset")
+        .name_localized("ja", "速さ")
+        .description_localized("ja", "読み上げの速さ（省略するとリセットします）")
+        .min_number_value(f64::from(Speaker::min_speed()))
+        .max_number_value(f64::from(Speaker::max_speed()));
+
+    CreateCommand::new("speed")
+        .description("あなたのメッセージを読み上げる速さを設定します。")
+        .add_option(speed)
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::{speaker::Speaker, utils::respond};
+
+// よく使われているボイスとして表示する数
+const TOP_SPEAKERS: usize = 5;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    speaker: &Speaker,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let period = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "period")
+        .and_then(|option| option.value.as_str())
+        .unwrap_or("weekly");
+    let (days, label) = match period {
+        "daily" => (1, "今日"),
+        "monthly" => (30, "直近30日"),
+        _ => (7, "直近7日"),
+    };
+
+    // 直近の分はまだ書き込まれていないことがある
+    let usages = database::usage_statistic::fetch_by_guild_id(database, guild_id.get(), days).await?;
+    if usages.is_empty() {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description(format!("{label}に読み上げたメッセージはありません。"))
+                .colour(Colour::FOOYOO),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    let messages = usages.iter().map(|usage| usage.messages).sum::<i64>();
+    let characters = usages.iter().map(|usage| usage.characters).sum::<i64>();
+    let syntheses = usages.iter().map(|usage| usage.syntheses).sum::<i64>();
+    let cache_hits = usages.iter().map(|usage| usage.cache_hits).sum::<i64>();
+    let cache_hit_rate = match syntheses {
+        0 => "-".to_string(),
+        _ => format!("{:.1}%", cache_hits as f64 / syntheses as f64 * 100.0),
+    };
+    let speakers = usages
+        .iter()
+        .take(TOP_SPEAKERS)
+        .enumerate()
+        .map(|(rank, usage)| {
+            let name = u16::try_from(usage.speaker_id)
+                .ok()
+                .and_then(|speaker_id| speaker.get_name(speaker_id).ok())
+                .unwrap_or_else(|| usage.speaker_id.to_string());
+            format!("{}. {name}（{}件）", rank + 1, usage.messages)
+        })
+        .collect::<Vec<_>>();
+
+    let message = CreateInteractionResponseMessage::new().embed(
+        CreateEmbed::new()
+            .title(format!("読み上げの統計（{label}）"))
+            .field("メッセージ", messages.to_string(), true)
+            .field("文字数", characters.to_string(), true)
+            .field("キャッシュヒット率", cache_hit_rate, true)
+            .field("よく使われているボイス", speakers.join("\n"), false)
+            .colour(Colour::FOOYOO),
+    );
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let period = CreateCommandOption::new(
+        CommandOptionType::String,
+        "period",
+        "Period to sum up, weekly by default",
+    )
+    .name_localized("ja", "期間")
+    .description_localized("ja", "集計する期間（省略すると直近7日）")
+    .add_string_choice_localized("daily", "daily", [("ja", "今日")])
+    .add_string_choice_localized("weekly", "weekly", [("ja", "直近7日")])
+    .add_string_choice_localized("monthly", "monthly", [("ja", "直近30日")]);
+
+    CreateCommand::new("stats")
+        .description("このサーバーでの読み上げの統計を表示します。")
+        .add_option(period)
+}
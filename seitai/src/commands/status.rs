@@ -0,0 +1,161 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use anyhow::Result;
+use database::PgPool;
+use futures::lock::Mutex;
+use hashbrown::HashMap;
+use seitai_audio::CacheStatistics;
+use serenity::{
+    all::{ChannelId, GuildId},
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage, EditInteractionResponse},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use crate::{
+    engine::EnginePool,
+    utils::{get_manager, is_owner, respond},
+    worker::ReadingWorkers,
+};
+
+// 読み上げ待ちを表示するサーバーの最大数
+const QUEUE_DISPLAY_LIMIT: usize = 10;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    connections: &Mutex<HashMap<GuildId, ChannelId>>,
+    reading_workers: &ReadingWorkers,
+    engine_pool: &EnginePool,
+    cache_statistics: &CacheStatistics,
+) -> Result<()> {
+    if !is_owner(context, interaction.user.id).await? {
+        let message = CreateInteractionResponseMessage::new()
+            .embed(
+                CreateEmbed::new()
+                    .description("このコマンドは bot のオーナーのみ使用できます。")
+                    .colour(Colour::RED),
+            )
+            .ephemeral(true);
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    // エンジンへの問い合わせで応答期限を過ぎないようにする
+    interaction.defer_ephemeral(&context.http).await?;
+
+    let connection_count = connections.lock().await.len();
+
+    // 合成中と読み上げ待ちのメッセージ、再生待ちのトラックの数
+    let mut queues = BTreeMap::<GuildId, (usize, usize)>::new();
+    for (guild_id, pending) in reading_workers.pending() {
+        queues.entry(guild_id).or_default().0 = pending;
+    }
+    let manager = get_manager(context).await?;
+    let calls = manager.iter().collect::<Vec<_>>();
+    for (guild_id, call) in calls {
+        let tracks = call.lock().await.queue().len();
+        if tracks > 0 {
+            queues.entry(GuildId::from(guild_id.0)).or_default().1 = tracks;
+        }
+    }
+    let mut queues = queues.into_iter().collect::<Vec<_>>();
+    queues.sort_by_key(|(_, (pending, tracks))| std::cmp::Reverse(pending + tracks));
+    let mut queue_lines = queues
+        .iter()
+        .take(QUEUE_DISPLAY_LIMIT)
+        .map(|(guild_id, (pending, tracks))| {
+            let name = context
+                .cache
+                .guild(*guild_id)
+                .map_or_else(|| guild_id.to_string(), |guild| guild.name.clone());
+            format!("{name}: メッセージ {pending} / トラック {tracks}")
+        })
+        .collect::<Vec<_>>();
+    if queues.len() > QUEUE_DISPLAY_LIMIT {
+        queue_lines.push(format!("ほか {} サーバー", queues.len() - QUEUE_DISPLAY_LIMIT));
+    }
+    let queue_description = match queue_lines.is_empty() {
+        true => "読み上げ待ちはありません。".to_string(),
+        false => queue_lines.join("\n"),
+    };
+
+    let cache_description = match cache_statistics.hit_rate() {
+        Some(hit_rate) => format!(
+            "{:.1}%（{} / {}）",
+            hit_rate * 100.0,
+            cache_statistics.hits(),
+            cache_statistics.hits() + cache_statistics.misses()
+        ),
+        None => "まだ読み上げていません。".to_string(),
+    };
+
+    let engine_description = engine_pool
+        .check()
+        .await
+        .into_iter()
+        .map(|status| {
+            let rotation = match (status.available, status.warmed_up) {
+                (true, true) => "",
+                (true, false) => "（起動中）",
+                (false, _) => "（切り離し中）",
+            };
+            let mode = match &status.mode {
+                Some(mode) if mode.gpu => format!("、GPU（{}Hz）", mode.sampling_rate),
+                Some(mode) => format!("、CPU（{}Hz）", mode.sampling_rate),
+                None => String::new(),
+            };
+            match status.response {
+                Ok((version, latency)) => {
+                    format!("{}{rotation}: {version}、{}ms{mode}", status.host, latency.as_millis())
+                },
+                Err(error) => {
+                    tracing::warn!("engine {} is unreachable\nError: {error:?}", status.host);
+                    format!("{}{rotation}: 応答がありません", status.host)
+                },
+            }
+        })
+        .chain(
+            engine_pool
+                .runs_on_cpu()
+                .then(|| "CPU で合成しているため、短い行をまとめて低い標本化周波数で読み上げます。".to_string()),
+        )
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let started_at = Instant::now();
+    let acquired = database.acquire().await;
+    let database_description = format!(
+        "接続 {}（待機 {}）、{}",
+        database.size(),
+        database.num_idle(),
+        match acquired {
+            Ok(_) => format!("取得に{}ms", started_at.elapsed().as_millis()),
+            Err(error) => {
+                tracing::warn!("failed to acquire database connection for /status\nError: {error:?}");
+                "接続を取得できません".to_string()
+            },
+        }
+    );
+
+    let embed = CreateEmbed::new()
+        .title("ステータス")
+        .field("読み上げ中のサーバー", connection_count.to_string(), true)
+        .field("音声キャッシュのヒット率", cache_description, true)
+        .field("データベース", database_description, false)
+        .field("VOICEVOX ENGINE", engine_description, false)
+        .field("読み上げ待ち", queue_description, false)
+        .colour(Colour::FOOYOO);
+    interaction
+        .edit_response(&context.http, EditInteractionResponse::new().embed(embed))
+        .await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("status")
+        .description("読み上げ待ちやエンジンの応答など、bot の稼働状況を表示します。")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+}
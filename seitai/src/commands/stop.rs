@@ -0,0 +1,66 @@
+use anyhow::{Context as _, Result};
+use serenity::{
+    builder::{CreateCommand, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::{
+    i18n::{Locale, t, t_with},
+    utils::{get_manager, respond},
+    worker::ReadingWorkers,
+};
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    reading_workers: &ReadingWorkers,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.context("failed to get guild")?;
+    let locale = Locale::from_tag(&interaction.locale);
+    let manager = get_manager(context).await?;
+    let call = manager.get_or_insert(guild_id);
+
+    // 合成中のメッセージが後から追加されないように先に取り消す
+    let cancelled = reading_workers.cancel(guild_id);
+    let dropped = {
+        let call = call.lock().await;
+        let queue = call.queue();
+        let dropped = queue.len();
+        queue.stop();
+        dropped
+    };
+
+    if cancelled == 0 && dropped == 0 {
+        let message = CreateInteractionResponseMessage::new().embed(
+            CreateEmbed::new()
+                .description(t(locale, "common.nothing_reading"))
+                .colour(Colour::RED),
+        );
+        respond(context, interaction, &message).await?;
+        return Ok(());
+    }
+
+    let message = CreateInteractionResponseMessage::new().embed(
+        CreateEmbed::new()
+            .description(t(locale, "stop.stopped"))
+            .field(
+                t(locale, "stop.dropped"),
+                t_with(locale, "stop.count", &[("count", &dropped.to_string())]),
+                true,
+            )
+            .field(
+                t(locale, "stop.cancelled"),
+                t_with(locale, "stop.count", &[("count", &cancelled.to_string())]),
+                true,
+            )
+            .colour(Colour::FOOYOO),
+    );
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("stop").description("読み上げ待ちのメッセージを全て取り消します。")
+}
@@ -0,0 +1,64 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::utils::respond;
+
+pub(crate) async fn run(
+    context: &Context,
+    interaction: &CommandInteraction,
+    database: &PgPool,
+    telemetry_enabled: bool,
+) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let subcommand = interaction
+        .data
+        .options
+        .first()
+        .context("cannot get /telemetry subcommand")?;
+
+    let mut description = match subcommand.name.as_str() {
+        "on" => {
+            database::guild::update_share_telemetry(database, guild_id.get(), true).await?;
+            "読み上げたメッセージの数、読み上げにかかった時間、エンジンのエラーの数を匿名で開発者に送ります。メッセージの内容やサーバー、メンバーの ID は送りません。"
+                .to_string()
+        },
+        "off" => {
+            database::guild::update_share_telemetry(database, guild_id.get(), false).await?;
+            "統計を開発者に送らないようにしました。".to_string()
+        },
+        _ => unreachable!(),
+    };
+    // 送信先はデプロイごとに設定するので、設定していなければ何も送らない
+    if !telemetry_enabled {
+        description.push_str("\nなお、この bot では送信先が設定されていないため、統計は送られません。");
+    }
+
+    let message = CreateInteractionResponseMessage::new()
+        .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO));
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let on = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "on",
+        "Shares anonymous statistics of this server with the developer",
+    )
+    .description_localized("ja", "このサーバーの匿名の統計を開発者に送ります。");
+    let off = CreateCommandOption::new(CommandOptionType::SubCommand, "off", "Stops sharing statistics")
+        .description_localized("ja", "統計を開発者に送らないようにします。");
+
+    CreateCommand::new("telemetry")
+        .description("読み上げの匿名の統計を開発者に送るかどうかを切り替えます（既定では送りません）。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .set_options(vec![on, off])
+}
@@ -0,0 +1,48 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::CommandOptionType,
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, application::CommandInteraction},
+};
+
+use crate::utils::respond;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let subcommand = interaction.data.options.first().context("cannot get /tts subcommand")?;
+
+    let description = match subcommand.name.as_str() {
+        "on" => {
+            database::user_mute::delete_by_id(database, interaction.user.id.get()).await?;
+            "あなたのメッセージを読み上げます。"
+        },
+        "off" => {
+            database::user_mute::create(database, interaction.user.id.get()).await?;
+            "あなたのメッセージを読み上げません。`/tts on` で元に戻せます。"
+        },
+        _ => unreachable!(),
+    };
+
+    let message = CreateInteractionResponseMessage::new()
+        .embed(CreateEmbed::new().description(description).colour(Colour::FOOYOO))
+        .ephemeral(true);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let on = CreateCommandOption::new(CommandOptionType::SubCommand, "on", "Reads your messages aloud")
+        .description_localized("ja", "あなたのメッセージを読み上げます。");
+    let off = CreateCommandOption::new(
+        CommandOptionType::SubCommand,
+        "off",
+        "Stops reading your messages aloud",
+    )
+    .description_localized("ja", "あなたのメッセージを読み上げないようにします。");
+
+    CreateCommand::new("tts")
+        .description("あなたのメッセージを読み上げるかどうかを切り替えます。")
+        .set_options(vec![on, off])
+}
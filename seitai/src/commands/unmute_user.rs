@@ -0,0 +1,53 @@
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use serenity::{
+    all::{CommandOptionType, Mentionable},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::utils::respond;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let user_id = interaction
+        .data
+        .options
+        .first()
+        .and_then(|option| option.value.as_user_id())
+        .context("cannot get member to unmute")?;
+
+    let embed = match database::guild_user_mute::delete(database, guild_id.get(), user_id.get()).await? {
+        true => {
+            tracing::info!(
+                "{user_id} has been unmuted in guild {guild_id} by {}",
+                interaction.user.id
+            );
+            CreateEmbed::new()
+                .description(format!("{}のメッセージを読み上げます。", user_id.mention()))
+                .colour(Colour::FOOYOO)
+        },
+        false => CreateEmbed::new()
+            .description(format!("{}は読み上げの対象外になっていません。", user_id.mention()))
+            .colour(Colour::RED),
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed).ephemeral(true);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let member = CreateCommandOption::new(CommandOptionType::User, "member", "Member to read messages of again")
+        .name_localized("ja", "メンバー")
+        .description_localized("ja", "メッセージを再び読み上げるメンバー")
+        .required(true);
+
+    CreateCommand::new("unmute-user")
+        .description("`/mute-user` で読み上げないようにしたメンバーのメッセージを再び読み上げます。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(member)
+}
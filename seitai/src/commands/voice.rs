@@ -1,16 +1,37 @@
+use std::ops::Range;
+
 use anyhow::{Context as _, Result};
 use database::PgPool;
+use seitai_audio::AudioRepository;
 use serenity::{
-    all::{CommandDataOptionValue, CommandOptionType},
+    all::{
+        ButtonStyle, CommandDataOptionValue, CommandOptionType, ComponentInteraction, ComponentInteractionDataKind,
+        Mentionable,
+    },
     builder::{
-        AutocompleteChoice, CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed,
-        CreateInteractionResponse, CreateInteractionResponseMessage,
+        AutocompleteChoice, CreateActionRow, CreateAutocompleteResponse, CreateButton, CreateCommand,
+        CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+        CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
     },
     client::Context,
     model::{Colour, application::CommandInteraction},
 };
+use songbird::input::Input;
+
+use super::{error::CommandError, sample::read_sample};
+use crate::{
+    i18n::{Locale, t, t_with},
+    scheduler::SynthesisScheduler,
+    speaker::{NamePair, Speaker},
+    utils::{get_manager, get_voicevox, respond},
+};
 
-use crate::{speaker::Speaker, utils::respond};
+// ボイス一覧のコンポーネントの custom_id の接頭辞
+pub(crate) const LIST_PREFIX: &str = "voice:list:";
+// 1 ページに表示するキャラクターの最大数
+const LIST_CHARACTERS: usize = 6;
+// セレクトメニューに置ける選択肢の最大数
+const MAX_SELECT_OPTIONS: usize = 25;
 
 pub(crate) async fn run(
     context: &Context,
@@ -19,6 +40,7 @@ pub(crate) async fn run(
     speaker: &Speaker,
 ) -> Result<()> {
     let subcommand = interaction.data.options.first().context("cannot get subcommand")?;
+    let locale = Locale::from_tag(&interaction.locale);
     match subcommand.name.as_str() {
         "use" => {
             let speaker_id = u16::try_from(
@@ -28,35 +50,102 @@ pub(crate) async fn run(
                     .context(format!("{:?} is not integer", subcommand.value))?,
             )?;
 
-            let speaker_id = u16::try_from(
-                database::user::create(database, interaction.user.id.into(), speaker_id)
-                    .await?
-                    .speaker_id,
-            )
-            .context("failed to convert speaker_id to u16")?;
+            let description = match only_in_guild(interaction, &subcommand.value) {
+                Some(guild_id) => {
+                    database::user::create_in_guild(database, guild_id, interaction.user.id.get(), speaker_id).await?;
+                    t(locale, "voice.use.guild")
+                },
+                None => {
+                    database::user::create(database, interaction.user.id.into(), speaker_id).await?;
+                    t(locale, "voice.use.global")
+                },
+            };
             let speaker_name = speaker.get_name(speaker_id)?;
 
             let message = CreateInteractionResponseMessage::new().embed(
                 CreateEmbed::new()
-                    .title("ボイスを変更しました。")
-                    .description(speaker_name)
+                    .title(t(locale, "voice.changed"))
+                    .field(speaker_name, description, false)
                     .colour(Colour::FOOYOO),
             );
             respond(context, interaction, &message).await?;
         },
+        "list" => {
+            let message = list_message(speaker, 1, None, locale).ephemeral(true);
+            respond(context, interaction, &message).await?;
+        },
+        "show" => {
+            let users = database::user::fetch_with_speaker_by_ids(database, &[interaction.user.id.into()]).await?;
+            let mut embed = CreateEmbed::new()
+                .title(t(locale, "voice.show.title"))
+                .colour(Colour::FOOYOO);
+            if let Some(guild_id) = interaction.guild_id
+                && let Some(speaker_id) =
+                    database::user::fetch_voices(database, guild_id.get(), interaction.user.id.get())
+                        .await?
+                        .guild
+            {
+                let speaker_id = u16::try_from(speaker_id).context("failed to convert speaker_id to u16")?;
+                embed = embed.field(t(locale, "voice.show.guild"), speaker.get_name(speaker_id)?, true);
+            }
+            if let Some(morph) = database::user_morph::fetch_by_id(database, interaction.user.id.get()).await? {
+                let base_speaker_id =
+                    u16::try_from(morph.base_speaker_id).context("failed to convert speaker_id to u16")?;
+                let target_speaker_id =
+                    u16::try_from(morph.target_speaker_id).context("failed to convert speaker_id to u16")?;
+                embed = embed.field(
+                    t(locale, "voice.show.morph"),
+                    t_with(
+                        locale,
+                        "voice.show.morph_value",
+                        &[
+                            ("base", &speaker.get_name(base_speaker_id)?),
+                            ("target", &speaker.get_name(target_speaker_id)?),
+                            ("rate", &morph.rate.to_string()),
+                        ],
+                    ),
+                    false,
+                );
+            }
+            embed = match users.first() {
+                Some(user) => {
+                    let speaker_id = u16::try_from(user.speaker_id).context("failed to convert speaker_id to u16")?;
+                    let speed = user.speed.unwrap_or(Speaker::default_speed());
+                    embed.field(
+                        t(locale, "voice.show.global"),
+                        t_with(
+                            locale,
+                            "voice.show.global_value",
+                            &[("name", &speaker.get_name(speaker_id)?), ("speed", &speed.to_string())],
+                        ),
+                        true,
+                    )
+                },
+                None => embed.description(t(locale, "voice.show.unset")),
+            };
+
+            let message = CreateInteractionResponseMessage::new().embed(embed);
+            respond(context, interaction, &message).await?;
+        },
         "reset" => {
-            let speaker_id = u16::try_from(
-                database::user::create(database, interaction.user.id.into(), 1)
-                    .await?
-                    .speaker_id,
-            )
-            .context("failed to convert speaker_id to u16")?;
-            let speaker_name = speaker.get_name(speaker_id)?;
+            let title = match only_in_guild(interaction, &subcommand.value) {
+                Some(guild_id) => {
+                    // このサーバーだけの設定を消して、すべてのサーバーでのボイスに戻す
+                    database::user::delete_in_guild(database, guild_id, interaction.user.id.get()).await?;
+                    t(locale, "voice.reset.guild")
+                },
+                None => {
+                    // 設定を消して、サーバーの既定のボイス（日替わりのボイスなど）に戻す
+                    database::user::delete_by_id(database, interaction.user.id.get()).await?;
+                    database::user_morph::delete_by_id(database, interaction.user.id.get()).await?;
+                    t(locale, "voice.reset.global")
+                },
+            };
 
             let message = CreateInteractionResponseMessage::new().embed(
                 CreateEmbed::new()
-                    .title("ボイスを変更しました。")
-                    .description(speaker_name)
+                    .title(title)
+                    .description(t(locale, "voice.reset.description"))
                     .colour(Colour::FOOYOO),
             );
             respond(context, interaction, &message).await?;
@@ -80,12 +169,140 @@ pub(crate) async fn run(
 
             let message = CreateInteractionResponseMessage::new().embed(
                 CreateEmbed::new()
-                    .title("ボイスを変更しました。")
+                    .title(t(locale, "voice.changed"))
                     .field(name, speed.to_string(), true)
                     .colour(Colour::FOOYOO),
             );
             respond(context, interaction, &message).await?;
         },
+        "morph" => {
+            let base_speaker_id = get_speaker_id(&subcommand.value, "base")?;
+            let target_speaker_id = get_speaker_id(&subcommand.value, "target")?;
+            let rate = get_subcommand_option(&subcommand.value, "rate")
+                .context("cannot get rate from `/voice morph` argument")?
+                .as_f64()
+                .context(format!("{:?} is not float", subcommand.value))? as f32;
+
+            let is_morphable = {
+                let voicevox = get_voicevox(context).await.ok_or(CommandError::EngineUnavailable)?;
+                let voicevox = voicevox.lock().await;
+                voicevox
+                    .audio_generator
+                    .is_morphable(base_speaker_id.into(), target_speaker_id.into())
+                    .await?
+            };
+            let base_name = speaker.get_name(base_speaker_id)?;
+            let target_name = speaker.get_name(target_speaker_id)?;
+            if !is_morphable {
+                let message = CreateInteractionResponseMessage::new().embed(
+                    CreateEmbed::new()
+                        .title(t(locale, "voice.morph.unmorphable"))
+                        .description(t_with(
+                            locale,
+                            "voice.morph.pair",
+                            &[("base", &base_name), ("target", &target_name)],
+                        ))
+                        .colour(Colour::RED),
+                );
+                respond(context, interaction, &message).await?;
+                return Ok(());
+            }
+
+            let morph = database::user_morph::UserMorph {
+                base_speaker_id: base_speaker_id.into(),
+                target_speaker_id: target_speaker_id.into(),
+                rate,
+            };
+            database::user_morph::create(database, interaction.user.id.get(), morph).await?;
+
+            let message = CreateInteractionResponseMessage::new().embed(
+                CreateEmbed::new()
+                    .title(t(locale, "voice.morph.morphed"))
+                    .field(
+                        format!("{base_name} → {target_name}"),
+                        t_with(locale, "voice.morph.rate", &[("rate", &rate.to_string())]),
+                        false,
+                    )
+                    .description(t(locale, "voice.morph.description"))
+                    .colour(Colour::FOOYOO),
+            );
+            respond(context, interaction, &message).await?;
+        },
+        "default" => {
+            let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+            if !can_manage_guild(interaction) {
+                return Err(CommandError::PermissionDenied.into());
+            }
+            let speaker_id = get_subcommand_option(&subcommand.value, "speaker")
+                .and_then(|speaker_id| speaker_id.as_i64())
+                .map(u16::try_from)
+                .transpose()?;
+            let speaker_name = speaker_id.map(|speaker_id| speaker.get_name(speaker_id)).transpose()?;
+            database::guild::update_default_speaker_id(database, guild_id.get(), speaker_id).await?;
+
+            let embed = match speaker_name {
+                Some(speaker_name) => CreateEmbed::new()
+                    .title(t(locale, "voice.default.set"))
+                    .field(speaker_name, t(locale, "voice.default.description"), false)
+                    .colour(Colour::FOOYOO),
+                None => CreateEmbed::new()
+                    .description(t(locale, "voice.default.reset"))
+                    .colour(Colour::FOOYOO),
+            };
+
+            let message = CreateInteractionResponseMessage::new().embed(embed);
+            respond(context, interaction, &message).await?;
+        },
+        "role-default" => {
+            let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+            if !can_manage_guild(interaction) {
+                return Err(CommandError::PermissionDenied.into());
+            }
+            let role_id = get_subcommand_option(&subcommand.value, "role")
+                .and_then(|role| role.as_role_id())
+                .context("cannot get role from `/voice role-default` argument")?;
+            let speaker_id = get_subcommand_option(&subcommand.value, "speaker")
+                .and_then(|speaker_id| speaker_id.as_i64())
+                .map(u16::try_from)
+                .transpose()?;
+
+            let embed = match speaker_id {
+                Some(speaker_id) => {
+                    database::role_voice::create(database, guild_id.get(), role_id.get(), speaker_id).await?;
+                    CreateEmbed::new()
+                        .title(t(locale, "voice.role_default.set"))
+                        .field(
+                            speaker.get_name(speaker_id)?,
+                            t_with(
+                                locale,
+                                "voice.role_default.description",
+                                &[("role", &role_id.mention().to_string())],
+                            ),
+                            false,
+                        )
+                        .colour(Colour::FOOYOO)
+                },
+                None => match database::role_voice::delete(database, guild_id.get(), role_id.get()).await? {
+                    true => CreateEmbed::new()
+                        .description(t_with(
+                            locale,
+                            "voice.role_default.reset",
+                            &[("role", &role_id.mention().to_string())],
+                        ))
+                        .colour(Colour::FOOYOO),
+                    false => CreateEmbed::new()
+                        .description(t_with(
+                            locale,
+                            "voice.role_default.not_set",
+                            &[("role", &role_id.mention().to_string())],
+                        ))
+                        .colour(Colour::RED),
+                },
+            };
+
+            let message = CreateInteractionResponseMessage::new().embed(embed);
+            respond(context, interaction, &message).await?;
+        },
         _ => unreachable!(),
     }
 
@@ -100,13 +317,29 @@ pub fn register() -> CreateCommand {
             .description_localized("ja", "設定するボイス")
             .set_autocomplete(true)
             .required(true);
+        let server = CreateCommandOption::new(CommandOptionType::Boolean, "server", "Whether to use only in this server")
+            .name_localized("ja", "このサーバーだけ")
+            .description_localized("ja", "このサーバーだけで使うかどうか");
         CreateCommandOption::new(CommandOptionType::SubCommand, "use", "Sets voice that read aloud your message.")
             .description_localized("ja", "あなたのメッセージを読み上げるボイスを設定します。")
             .add_sub_option(speaker)
+            .add_sub_option(server)
     };
 
-    let reset = CreateCommandOption::new(CommandOptionType::SubCommand, "reset", "Resets voice that read aloud your message.")
-        .description_localized("ja", "あなたのメッセージを読み上げるボイスをリセットします。");
+    let list = CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Lists voices to hear samples and choose one.")
+        .description_localized("ja", "ボイスの一覧を表示します。試聴したり、そのまま設定したりできます。");
+
+    let show = CreateCommandOption::new(CommandOptionType::SubCommand, "show", "Shows voice that read aloud your message.")
+        .description_localized("ja", "あなたのメッセージを読み上げるボイスを表示します。");
+
+    let reset = {
+        let server = CreateCommandOption::new(CommandOptionType::Boolean, "server", "Whether to reset only in this server")
+            .name_localized("ja", "このサーバーだけ")
+            .description_localized("ja", "このサーバーでのボイスだけをリセットするかどうか");
+        CreateCommandOption::new(CommandOptionType::SubCommand, "reset", "Resets voice that read aloud your message.")
+            .description_localized("ja", "あなたのメッセージを読み上げるボイスをリセットします。")
+            .add_sub_option(server)
+    };
 
     let set_speed = {
         let speaker = CreateCommandOption::new(CommandOptionType::Integer, "speaker", "Voice to be used")
@@ -124,34 +357,296 @@ pub fn register() -> CreateCommand {
             .add_sub_option(speed)
     };
 
+    let morph = {
+        let base = CreateCommandOption::new(CommandOptionType::Integer, "base", "Voice to be morphed")
+            .name_localized("ja", "元のボイス")
+            .description_localized("ja", "モーフィングする元のボイス")
+            .set_autocomplete(true)
+            .required(true);
+        let target = CreateCommandOption::new(CommandOptionType::Integer, "target", "Voice to be morphed with")
+            .name_localized("ja", "相手のボイス")
+            .description_localized("ja", "混ぜ合わせる相手のボイス")
+            .set_autocomplete(true)
+            .required(true);
+        let rate = CreateCommandOption::new(CommandOptionType::Number, "rate", "Rate of target voice from 0.0 to 1.0")
+            .name_localized("ja", "割合")
+            .description_localized("ja", "相手のボイスの割合（0.0〜1.0）")
+            .min_number_value(0.0)
+            .max_number_value(1.0)
+            .required(true);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "morph", "Reads aloud your message with two voices morphed.")
+            .description_localized("ja", "2つのボイスを混ぜ合わせたボイスであなたのメッセージを読み上げます。")
+            .add_sub_option(base)
+            .add_sub_option(target)
+            .add_sub_option(rate)
+    };
+
+    let default = {
+        let speaker = CreateCommandOption::new(CommandOptionType::Integer, "speaker", "Voice to be used, or none to unset")
+            .name_localized("ja", "ボイス")
+            .description_localized("ja", "設定するボイス（省略すると解除します）")
+            .set_autocomplete(true);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "default", "Sets voice of this server for members who have chosen none.")
+            .description_localized("ja", "ボイスを設定していないメンバーを読み上げる、このサーバーの既定のボイスを設定します。")
+            .add_sub_option(speaker)
+    };
+
+    let role_default = {
+        let role = CreateCommandOption::new(CommandOptionType::Role, "role", "Role whose members use the voice")
+            .name_localized("ja", "ロール")
+            .description_localized("ja", "ボイスを設定するロール")
+            .required(true);
+        let speaker = CreateCommandOption::new(CommandOptionType::Integer, "speaker", "Voice to be used, or none to unset")
+            .name_localized("ja", "ボイス")
+            .description_localized("ja", "設定するボイス（省略すると解除します）")
+            .set_autocomplete(true);
+        CreateCommandOption::new(CommandOptionType::SubCommand, "role-default", "Sets voice for members with the role who have chosen none.")
+            .description_localized("ja", "ボイスを設定していない、ロールを持つメンバーのボイスを設定します。")
+            .add_sub_option(role)
+            .add_sub_option(speaker)
+    };
+
     CreateCommand::new("voice")
         .description("ボイスの設定を行います。")
-        .set_options(vec![r#use, reset, set_speed])
+        .set_options(vec![r#use, list, show, reset, set_speed, morph, default, role_default])
 }
 
 pub(crate) async fn autocomplete(context: &Context, interaction: &CommandInteraction, speaker: &Speaker) -> Result<()> {
-    let subcommand = interaction.data.options.first().context("cannot get subcommand")?;
-    let speaker_id = get_subcommand_option(&subcommand.value, "speaker").context("cannot get speaker from argument")?;
-
-    if let CommandDataOptionValue::Autocomplete { value, .. } = &speaker_id {
-        let choices = speaker
-            .pairs()
-            .filter(|(name_pairs, _)| name_pairs.contains(value))
-            .map(|(name_pairs, id)| AutocompleteChoice::new(name_pairs.to_string(), id))
-            .take(25)
-            .collect::<Vec<_>>();
-        let autocomplete =
-            CreateInteractionResponse::Autocomplete(CreateAutocompleteResponse::new().set_choices(choices));
-        let error = format!("failed to create interaction response as autocomplete: {autocomplete:?}");
-        interaction
-            .create_response(&context.http, autocomplete)
-            .await
-            .context(error)?;
+    let option = interaction
+        .data
+        .autocomplete()
+        .context("cannot get speaker from argument")?;
+    if !matches!(option.name, "speaker" | "base" | "target") {
+        return Ok(());
     }
 
+    let choices = speaker
+        .pairs()
+        .filter(|(name_pairs, _)| name_pairs.contains(option.value))
+        .map(|(name_pairs, id)| AutocompleteChoice::new(name_pairs.to_string(), id))
+        .take(25)
+        .collect::<Vec<_>>();
+    let autocomplete = CreateInteractionResponse::Autocomplete(CreateAutocompleteResponse::new().set_choices(choices));
+    let error = format!("failed to create interaction response as autocomplete: {autocomplete:?}");
+    interaction
+        .create_response(&context.http, autocomplete)
+        .await
+        .context(error)?;
+
+    Ok(())
+}
+
+/// Guild to set the voice only in, when the `server` option is true in a guild.
+/// Turns pages of the voice list, sets the voice chosen in the menu, or reads the sample of it.
+pub(crate) async fn handle_component<Repository>(
+    context: &Context,
+    component: &ComponentInteraction,
+    database: &PgPool,
+    speaker: &Speaker,
+    audio_repository: &Repository,
+    synthesis_scheduler: &SynthesisScheduler,
+) -> Result<()>
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let mut parts = component
+        .data
+        .custom_id
+        .strip_prefix(LIST_PREFIX)
+        .context("component is not of voice list")?
+        .split(':');
+    let action = parts.next();
+    let page = parts.next().and_then(|page| page.parse::<usize>().ok()).unwrap_or(1);
+    let selected = parts.next().and_then(|speaker_id| speaker_id.parse::<u16>().ok());
+    let locale = Locale::from_tag(&component.locale);
+
+    let response = match (action, &component.data.kind) {
+        (Some("page"), ComponentInteractionDataKind::Button) => {
+            CreateInteractionResponse::UpdateMessage(list_message(speaker, page, selected, locale))
+        },
+        (Some("select"), ComponentInteractionDataKind::StringSelect { values }) => {
+            let speaker_id = values
+                .first()
+                .and_then(|speaker_id| speaker_id.parse::<u16>().ok())
+                .context("invalid voice is selected")?;
+            speaker.get_name(speaker_id)?;
+            database::user::create(database, component.user.id.get(), speaker_id).await?;
+            CreateInteractionResponse::UpdateMessage(list_message(speaker, page, Some(speaker_id), locale))
+        },
+        (Some("sample"), ComponentInteractionDataKind::Button) => {
+            let (Some(guild_id), Some(speaker_id)) = (component.guild_id, selected) else {
+                return Ok(());
+            };
+            let name = speaker.get_name(speaker_id)?;
+
+            // 試聴のためだけに接続はせず、読み上げ中の通話に流す
+            let manager = get_manager(context).await?;
+            let Some(call) = manager.get(guild_id) else {
+                let message = CreateInteractionResponseMessage::new()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t(locale, "voice.list.not_connected"))
+                            .colour(Colour::RED),
+                    )
+                    .ephemeral(true);
+                component
+                    .create_response(&context.http, CreateInteractionResponse::Message(message))
+                    .await
+                    .context("failed to respond to voice list")?;
+                return Ok(());
+            };
+
+            let message = CreateInteractionResponseMessage::new()
+                .embed(
+                    CreateEmbed::new()
+                        .description(t_with(locale, "voice.list.sample", &[("name", &name)]))
+                        .colour(Colour::FOOYOO),
+                )
+                .ephemeral(true);
+            component
+                .create_response(&context.http, CreateInteractionResponse::Message(message))
+                .await
+                .context("failed to respond to voice list")?;
+            return read_sample(
+                &call,
+                database,
+                audio_repository,
+                synthesis_scheduler,
+                guild_id,
+                speaker_id,
+            )
+            .await;
+        },
+        _ => return Ok(()),
+    };
+    component
+        .create_response(&context.http, response)
+        .await
+        .context("failed to update voice list")?;
+
     Ok(())
 }
 
+/// Page of the voice list, with the styles of each character, a menu to choose a voice, and a button to hear the one
+/// chosen.
+fn list_message(
+    speaker: &Speaker,
+    page: usize,
+    selected: Option<u16>,
+    locale: Locale,
+) -> CreateInteractionResponseMessage {
+    let characters = speaker.characters().collect::<Vec<_>>();
+    let pages = paginate(&characters.iter().map(|(_, styles)| styles.len()).collect::<Vec<_>>());
+    let page_count = pages.len().max(1);
+    let page = page.clamp(1, page_count);
+    let characters = pages.get(page - 1).map_or(&[][..], |range| &characters[range.clone()]);
+
+    let fields = characters.iter().map(|(name, styles)| {
+        let styles = styles
+            .iter()
+            .map(|(style, speaker_id)| format!("`{speaker_id}` {style}"))
+            .collect::<Vec<_>>();
+        (*name, styles.join("\n"), true)
+    });
+    let mut embed = CreateEmbed::new()
+        .title(t(locale, "voice.list.title"))
+        .description(t(locale, "voice.list.description"))
+        .fields(fields)
+        .footer(CreateEmbedFooter::new(t_with(
+            locale,
+            "common.page",
+            &[("page", &page.to_string()), ("pages", &page_count.to_string())],
+        )))
+        .colour(Colour::FOOYOO);
+    if let Some(name) = selected.and_then(|speaker_id| speaker.get_name(speaker_id).ok()) {
+        embed = embed.field(t(locale, "voice.list.selected"), name, false);
+    }
+
+    let options = characters
+        .iter()
+        .flat_map(|(name, styles)| {
+            styles.iter().map(|(style, speaker_id)| {
+                CreateSelectMenuOption::new(NamePair(name, style).to_string(), speaker_id.to_string())
+                    .default_selection(selected == Some(*speaker_id))
+            })
+        })
+        .take(MAX_SELECT_OPTIONS)
+        .collect::<Vec<_>>();
+    let selected_id = selected.map_or_else(|| "none".to_string(), |speaker_id| speaker_id.to_string());
+    let buttons = vec![
+        CreateButton::new(format!("{LIST_PREFIX}page:{}:{selected_id}", page - 1))
+            .label(t(locale, "common.previous"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 1),
+        CreateButton::new(format!("{LIST_PREFIX}page:{}:{selected_id}", page + 1))
+            .label(t(locale, "common.next"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page == page_count),
+        CreateButton::new(format!("{LIST_PREFIX}sample:{page}:{selected_id}"))
+            .label(t(locale, "voice.list.listen"))
+            .style(ButtonStyle::Primary)
+            .disabled(selected.is_none()),
+    ];
+
+    let mut components = Vec::new();
+    if !options.is_empty() {
+        let menu = CreateSelectMenu::new(
+            format!("{LIST_PREFIX}select:{page}"),
+            CreateSelectMenuKind::String { options },
+        )
+        .placeholder(t(locale, "voice.list.placeholder"));
+        components.push(CreateActionRow::SelectMenu(menu));
+    }
+    components.push(CreateActionRow::Buttons(buttons));
+
+    CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(components)
+}
+
+/// Splits characters into pages so that the styles on each page fit in a select menu.
+fn paginate(style_counts: &[usize]) -> Vec<Range<usize>> {
+    let mut pages = Vec::new();
+    let mut start = 0;
+    let mut styles = 0;
+    for (index, count) in style_counts.iter().enumerate() {
+        if index > start && (index - start >= LIST_CHARACTERS || styles + count > MAX_SELECT_OPTIONS) {
+            pages.push(start..index);
+            start = index;
+            styles = 0;
+        }
+        styles += count;
+    }
+    if start < style_counts.len() {
+        pages.push(start..style_counts.len());
+    }
+    pages
+}
+
+fn can_manage_guild(interaction: &CommandInteraction) -> bool {
+    interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_guild())
+}
+
+fn only_in_guild(interaction: &CommandInteraction, value: &CommandDataOptionValue) -> Option<u64> {
+    let server = get_subcommand_option(value, "server").and_then(|server| server.as_bool());
+    match server {
+        Some(true) => interaction.guild_id.map(|guild_id| guild_id.get()),
+        _ => None,
+    }
+}
+
+fn get_speaker_id(value: &CommandDataOptionValue, name: &str) -> Result<u16> {
+    let speaker_id = get_subcommand_option(value, name)
+        .with_context(|| format!("cannot get {name} from `/voice morph` argument"))?
+        .as_i64()
+        .context(format!("{value:?} is not integer"))?;
+    Ok(u16::try_from(speaker_id)?)
+}
+
 fn get_subcommand_option<'a>(value: &'a CommandDataOptionValue, name: &str) -> Option<&'a CommandDataOptionValue> {
     match value {
         CommandDataOptionValue::SubCommand(options) => options
@@ -161,3 +656,15 @@ fn get_subcommand_option<'a>(value: &'a CommandDataOptionValue, name: &str) -> O
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::paginate;
+
+    #[test]
+    fn fit_styles_of_page_in_select_menu() {
+        assert!(paginate(&[]).is_empty());
+        assert_eq!(paginate(&[4, 4, 4, 4, 4, 4, 4]), [0..6, 6..7]);
+        assert_eq!(paginate(&[10, 10, 6, 30, 1]), [0..2, 2..3, 3..4, 4..5]);
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use database::PgPool;
+use serenity::{
+    all::{CommandOptionType, GuildId},
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage},
+    client::Context,
+    model::{Colour, Permissions, application::CommandInteraction},
+};
+
+use super::error::CommandError;
+use crate::utils::{gain, get_manager, respond};
+
+const DEFAULT_VOLUME: i32 = 100;
+pub(crate) const MAX_VOLUME: i32 = 200;
+
+pub(crate) async fn run(context: &Context, interaction: &CommandInteraction, database: &PgPool) -> Result<()> {
+    let guild_id = interaction.guild_id.ok_or(CommandError::GuildOnly)?;
+    let volume = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == "volume")
+        .and_then(|option| option.value.as_i64());
+
+    let embed = match volume.map(i32::try_from) {
+        Some(Ok(volume)) if (0..=MAX_VOLUME).contains(&volume) => {
+            set_volume(context, database, guild_id, volume).await?;
+            CreateEmbed::new()
+                .title("読み上げの音量を変更しました。")
+                .description(format!("{volume}%"))
+                .colour(Colour::FOOYOO)
+        },
+        Some(_) => CreateEmbed::new()
+            .description(format!("音量は0から{MAX_VOLUME}の間で指定してください。"))
+            .colour(Colour::RED),
+        None => {
+            set_volume(context, database, guild_id, DEFAULT_VOLUME).await?;
+            CreateEmbed::new()
+                .title("読み上げの音量をリセットしました。")
+                .description(format!("{DEFAULT_VOLUME}%"))
+                .colour(Colour::FOOYOO)
+        },
+    };
+
+    let message = CreateInteractionResponseMessage::new().embed(embed);
+    respond(context, interaction, &message).await?;
+
+    Ok(())
+}
+
+pub(crate) async fn set_volume(context: &Context, database: &PgPool, guild_id: GuildId, volume: i32) -> Result<()> {
+    database::guild::update_volume(database, guild_id.get(), volume).await?;
+
+    // 読み上げ待ちのメッセージにも反映する
+    let manager = get_manager(context).await?;
+    if let Some(call) = manager.get(guild_id) {
+        for track in call.lock().await.queue().current_queue() {
+            let _ = track.set_volume(gain(volume));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let volume = CreateCommandOption::new(
+        CommandOptionType::Integer,
+        "volume",
+        "Volume in percent, or none to reset",
+    )
+    .name_localized("ja", "音量")
+    .description_localized("ja", "読み上げの音量（%、省略するとリセットします）")
+    .min_int_value(0)
+    .max_int_value(MAX_VOLUME as u64);
+
+    CreateCommand::new("volume")
+        .description("サーバーの読み上げの音量を設定します。")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(volume)
+}
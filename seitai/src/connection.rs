@@ -0,0 +1,128 @@
+use std::{sync::Arc, time::Duration};
+
+use database::guild::Guild;
+use seitai_audio::AudioRepository;
+use seitai_converter::ReadContext;
+use serenity::async_trait;
+use songbird::{Call, Event, EventContext, EventHandler, TrackEvent, input::Input, tracks::TrackHandle};
+use tokio::sync::{Mutex, Notify};
+
+use crate::{
+    utils::{enqueue, system_audio},
+    utterance::PredefinedUtterance,
+};
+
+// 読み上げ中の発話を待つ最大時間
+const CURRENT_UTTERANCE_TIMEOUT: Duration = Duration::from_secs(10);
+// 移動や切断のアナウンスを待つ最大時間
+const ANNOUNCEMENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct TrackEndNotifier {
+    notify: Arc<Notify>,
+}
+
+#[async_trait]
+impl EventHandler for TrackEndNotifier {
+    async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+        self.notify.notify_one();
+        None
+    }
+}
+
+/// Drains the call before it moves to another voice channel in the same guild.
+///
+/// Pending utterances are dropped, the one being read is allowed to finish within a bound, and the move is announced in
+/// the channel being left. The call is locked only to enqueue the announcement, so that messages and commands of the
+/// guild are not held up while waiting.
+pub(crate) async fn drain<Repository>(call: &Mutex<Call>, audio_repository: &Repository, settings: &Guild)
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let queue = call.lock().await.queue().clone();
+    queue.modify_queue(|queue| {
+        for queued in queue.drain(1..) {
+            let _ = queued.stop();
+        }
+    });
+
+    if let Some(current) = queue.current()
+        && !wait_for_end(&current, CURRENT_UTTERANCE_TIMEOUT).await
+    {
+        queue.stop();
+    }
+
+    let Some(input) = synthesize(audio_repository, PredefinedUtterance::Moving.as_ref(), settings).await else {
+        return;
+    };
+    let announcement = enqueue(&mut *call.lock().await, input, settings.volume).await;
+    if !wait_for_end(&announcement, ANNOUNCEMENT_TIMEOUT).await {
+        queue.stop();
+    }
+}
+
+/// Clears the call before leaving the voice channel, and reads the utterance of the guild on leaving unless it is empty.
+pub(crate) async fn farewell<Repository>(call: &mut Call, audio_repository: &Repository, settings: &Guild)
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    call.queue().stop();
+    if settings.leave_utterance.trim().is_empty() {
+        return;
+    }
+    let text = seitai_converter::convert(&ReadContext::default(), &settings.leave_utterance);
+    let Some(input) = synthesize(audio_repository, &text, settings).await else {
+        return;
+    };
+    let announcement = enqueue(call, input, settings.volume).await;
+    if !wait_for_end(&announcement, ANNOUNCEMENT_TIMEOUT).await {
+        call.queue().stop();
+    }
+}
+
+/// Synthesizes the text with the default voice of the guild.
+async fn synthesize<Repository>(audio_repository: &Repository, text: &str, settings: &Guild) -> Option<Input>
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let audio = system_audio(text.to_string(), settings.default_speaker_id);
+    match audio_repository.get(audio).await {
+        Ok(input) => Some(input),
+        Err(error) => {
+            tracing::error!("failed to get audio source to announce\nError: {error:?}");
+            None
+        },
+    }
+}
+
+/// Waits for the track to end and returns `false` on timeout.
+async fn wait_for_end(track: &TrackHandle, timeout: Duration) -> bool {
+    let notify = Arc::new(Notify::new());
+    let notifier = TrackEndNotifier { notify: notify.clone() };
+
+    // 既に終了しているトラックにはイベントを登録できない
+    if track.add_event(Event::Track(TrackEvent::End), notifier).is_err() {
+        return true;
+    }
+
+    tokio::time::timeout(timeout, notify.notified()).await.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use songbird::{Config, Driver, input::Input};
+
+    use super::wait_for_end;
+
+    #[tokio::test]
+    async fn give_up_waiting_for_track_after_timeout() {
+        // 接続していないドライバーではトラックが終わらない
+        let mut driver = Driver::new(Config::default());
+        let track = driver.play_input(Input::from(vec![0_u8; 1024]));
+
+        let started = Instant::now();
+        assert!(!wait_for_end(&track, Duration::from_millis(100)).await);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,204 @@
+use std::{fmt, str::FromStr};
+
+// 時刻は日本時間で解釈する
+const UTC_OFFSET_SECS: u64 = 9 * 60 * 60;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Schedule in the five fields of cron, minute, hour, day of month, month and day of week, read in Japan time.
+///
+/// Each field is `*`, a number, a range such as `1-5`, any of them followed by a step such as `*/15`, or a list of
+/// them separated with commas. Sunday is either 0 or 7 in the day of week, and when both days are restricted, the
+/// schedule matches either of them as cron does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // 日と曜日のどちらかが * のときは両方を満たす必要がある
+    any_day: bool,
+}
+
+/// Error of a schedule which is not in the syntax of cron.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Minute of a day in Japan time, broken into the fields of cron.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LocalTime {
+    pub(crate) minute: u32,
+    pub(crate) hour: u32,
+    pub(crate) day: u32,
+    pub(crate) month: u32,
+    /// Day of week from 0 for Sunday.
+    pub(crate) weekday: u32,
+}
+
+impl LocalTime {
+    pub(crate) fn from_unix(secs: u64) -> Self {
+        let secs = secs + UTC_OFFSET_SECS;
+        let days = secs / SECS_PER_DAY;
+        let secs_of_day = secs % SECS_PER_DAY;
+        let (_, month, day) = civil_from_days(days);
+
+        Self {
+            minute: (secs_of_day / 60 % 60) as u32,
+            hour: (secs_of_day / 3600) as u32,
+            day,
+            month,
+            // 1970-01-01 は木曜日
+            weekday: ((days + 4) % 7) as u32,
+        }
+    }
+}
+
+// 1970-01-01 からの日数を年月日にする
+fn civil_from_days(days: u64) -> (u64, u32, u32) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+impl Schedule {
+    pub(crate) fn matches(&self, time: &LocalTime) -> bool {
+        let day = self.days & (1 << time.day) != 0;
+        let weekday = self.weekdays & (1 << time.weekday) != 0;
+        let day = if self.any_day { day && weekday } else { day || weekday };
+
+        self.minutes & (1 << time.minute) != 0
+            && self.hours & (1 << time.hour) != 0
+            && self.months & (1 << time.month) != 0
+            && day
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(ParseError(format!(
+                "expected 5 fields of minute, hour, day, month and day of week, but got {}",
+                fields.len()
+            )));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, "day of week")?;
+        // 7 も日曜日として扱う
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days: parse_field(day, 1, 31, "day")?,
+            months: parse_field(month, 1, 12, "month")?,
+            weekdays,
+            any_day: day == "*" || weekday == "*",
+        })
+    }
+}
+
+// 値ごとのビットを立てたマスクにする
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, ParseError> {
+    let parse = |value: &str| {
+        value
+            .parse::<u32>()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(|| ParseError(format!("{name} must be between {min} and {max}, but got `{value}`")))
+    };
+
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step =
+                    step.parse::<u32>().ok().filter(|step| *step > 0).ok_or_else(|| {
+                        ParseError(format!("step of {name} must be a positive number, but got `{step}`"))
+                    })?;
+                (range, step)
+            },
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (parse(start)?, parse(end)?),
+                // 5/15 は 5 から最後まで 15 ごと
+                None if step > 1 => (parse(range)?, max),
+                None => {
+                    let value = parse(range)?;
+                    (value, value)
+                },
+            },
+        };
+        if start > end {
+            return Err(ParseError(format!("range of {name} is reversed in `{range}`")));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalTime, Schedule};
+
+    #[test]
+    fn match_schedule_in_japan_time() {
+        // 2026-10-15 (木) 12:30 JST
+        let noon = LocalTime::from_unix(1_792_035_000);
+        assert_eq!(
+            noon,
+            LocalTime {
+                minute: 30,
+                hour: 12,
+                day: 15,
+                month: 10,
+                weekday: 4,
+            }
+        );
+
+        let schedule = |s: &str| s.parse::<Schedule>().unwrap();
+        assert!(schedule("* * * * *").matches(&noon));
+        assert!(schedule("*/15 9-18 * * 1-5").matches(&noon));
+        assert!(schedule("30 12 1,15 * *").matches(&noon));
+        assert!(!schedule("0 12 * * *").matches(&noon));
+        assert!(!schedule("30 12 * * 0,6").matches(&noon));
+        // 日と曜日の両方を指定したらどちらかに合えばよい
+        assert!(schedule("30 12 1 * 4").matches(&noon));
+        assert!(!schedule("30 12 1 * 7").matches(&noon));
+
+        assert!("* * * *".parse::<Schedule>().is_err());
+        assert!("60 * * * *".parse::<Schedule>().is_err());
+        assert!("* * * * 8".parse::<Schedule>().is_err());
+        assert!("*/0 * * * *".parse::<Schedule>().is_err());
+        assert!("5-1 * * * *".parse::<Schedule>().is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use std::env;
+
+use database::PgConnectOptions;
+
+/// Postgres credentials bundled into the binary built with the `self-host` feature, which are the same as in
+/// compose.yaml.
+const POSTGRES: [(&str, &str); 3] = [("PGUSER", "seitai"), ("PGPASSWORD", "seitai"), ("PGDATABASE", "seitai")];
+
+/// Fills the options to connect to Postgres with the bundled defaults for what is not given in the environment.
+pub(crate) fn postgres(mut options: PgConnectOptions) -> PgConnectOptions {
+    for (key, default) in POSTGRES {
+        if env::var(key).is_ok() {
+            continue;
+        }
+        options = match key {
+            "PGUSER" => options.username(default),
+            "PGPASSWORD" => options.password(default),
+            _ => options.database(default),
+        };
+    }
+    options
+}
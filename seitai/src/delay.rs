@@ -0,0 +1,50 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serenity::model::Timestamp;
+
+// これより遅れて読み上げるメッセージには、いつ送られたかを前置きする
+const THRESHOLD: Duration = Duration::from_secs(60);
+
+/// Time since the message was sent, which is zero if the clock of the bot is behind Discord.
+pub(crate) fn since(timestamp: Timestamp) -> Duration {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let sent_at = Duration::from_secs(u64::try_from(timestamp.unix_timestamp()).unwrap_or_default());
+    now.saturating_sub(sent_at)
+}
+
+/// How long ago a message read late, e.g. after the bot reconnects, was sent, such as `2分前`, or `None` if the
+/// message is read soon enough.
+pub(crate) fn relative_time(elapsed: Duration) -> Option<String> {
+    if elapsed < THRESHOLD {
+        return None;
+    }
+
+    let minutes = elapsed.as_secs() / 60;
+    Some(match minutes {
+        ..60 => format!("{minutes}分前"),
+        60..1440 => format!("{}時間前", minutes / 60),
+        _ => format!("{}日前", minutes / 1440),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::relative_time;
+
+    #[test]
+    fn read_how_long_ago_late_messages_were_sent() {
+        assert_eq!(relative_time(Duration::from_secs(59)), None);
+        assert_eq!(relative_time(Duration::from_secs(60)).as_deref(), Some("1分前"));
+        assert_eq!(relative_time(Duration::from_secs(150)).as_deref(), Some("2分前"));
+        assert_eq!(
+            relative_time(Duration::from_secs(2 * 60 * 60 + 59)).as_deref(),
+            Some("2時間前")
+        );
+        assert_eq!(
+            relative_time(Duration::from_secs(3 * 24 * 60 * 60)).as_deref(),
+            Some("3日前")
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use std::fmt;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use database::{PgPool, dictionary_word};
+use indexmap::IndexMap;
+use seitai_converter::{regex, to_full_width, to_katakana};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use voicevox::dictionary::{
+    Dictionary,
+    response::{DeleteUserDictWordResult, GetUserDictResult, Item, PostUserDictWordResult, PutUserDictWordResult},
+};
+
+use crate::quota;
+
+const WORD_TYPES: [&str; 5] = ["PROPER_NOUN", "COMMON_NOUN", "VERB", "ADJECTIVE", "SUFFIX"];
+const DEFAULT_ACCENT_TYPE: i64 = 0;
+const MAX_PRIORITY: i64 = 10;
+
+/// Word asked to be registered into the user dictionary of VOICEVOX ENGINE, from `/dictionary add` or the API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct WordRequest {
+    pub(crate) surface: String,
+    pub(crate) pronunciation: String,
+    pub(crate) accent_type: Option<i64>,
+    pub(crate) word_type: Option<String>,
+    pub(crate) priority: Option<i64>,
+}
+
+/// Word which passed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Word {
+    pub(crate) surface: String,
+    pub(crate) pronunciation: String,
+    accent_type: i64,
+    word_type: Option<String>,
+    priority: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Upsert {
+    Registered(Uuid),
+    Updated(Uuid),
+}
+
+/// Numbers of the words imported at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Imported {
+    pub(crate) registered: usize,
+    pub(crate) updated: usize,
+}
+
+#[derive(Debug)]
+pub(crate) enum DictionaryError {
+    Invalid(&'static str),
+    /// The guild has registered as many words as the quota.
+    QuotaExceeded(i64),
+    NotFound,
+    /// VOICEVOX ENGINE refused the word.
+    Rejected(String),
+    Failed(anyhow::Error),
+}
+
+impl WordRequest {
+    pub(crate) fn validate(self) -> Result<Word, DictionaryError> {
+        let surface = normalize_surface(self.surface.trim());
+        if surface.is_empty() {
+            return Err(DictionaryError::Invalid("単語が空です。"));
+        }
+        let pronunciation = to_katakana(self.pronunciation.trim()).into_owned();
+        if pronunciation.is_empty() {
+            return Err(DictionaryError::Invalid("ヨミが空です。"));
+        }
+        let accent_type = self.accent_type.unwrap_or(DEFAULT_ACCENT_TYPE);
+        if accent_type < 0 {
+            return Err(DictionaryError::Invalid("音が下がる位置は 0 以上にしてください。"));
+        }
+        let priority = self.priority.unwrap_or(MAX_PRIORITY);
+        if !(0..=MAX_PRIORITY).contains(&priority) {
+            return Err(DictionaryError::Invalid("優先度は 0 〜 10 にしてください。"));
+        }
+        if let Some(word_type) = &self.word_type
+            && !WORD_TYPES.contains(&word_type.as_str())
+        {
+            return Err(DictionaryError::Invalid(
+                "単語の種類は PROPER_NOUN、COMMON_NOUN、VERB、ADJECTIVE、SUFFIX のいずれかにしてください。",
+            ));
+        }
+
+        Ok(Word {
+            surface,
+            pronunciation,
+            accent_type,
+            word_type: self.word_type,
+            priority,
+        })
+    }
+}
+
+impl Word {
+    fn parameters(&self) -> Vec<(&str, String)> {
+        let mut parameters = vec![
+            ("surface", self.surface.clone()),
+            ("pronunciation", self.pronunciation.clone()),
+            ("accent_type", self.accent_type.to_string()),
+            ("priority", self.priority.to_string()),
+        ];
+        if let Some(word_type) = &self.word_type {
+            parameters.push(("word_type", word_type.clone()));
+        }
+        parameters
+    }
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(reason) => f.write_str(reason),
+            Self::QuotaExceeded(count) => write!(
+                f,
+                "辞書に登録できる単語数の上限に達しています。（{count} / {}）",
+                quota::DICTIONARY_WORDS
+            ),
+            Self::NotFound => f.write_str("単語は登録されていません。"),
+            Self::Rejected(detail) => f.write_str(detail),
+            Self::Failed(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl From<anyhow::Error> for DictionaryError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Failed(error)
+    }
+}
+
+pub(crate) async fn list(dictionary: &Dictionary) -> Result<IndexMap<Uuid, Item>> {
+    let GetUserDictResult::Ok(list) = dictionary.list().await.context("failed to get dictionary")?;
+    Ok(list)
+}
+
+/// Registers the word into the dictionary on behalf of the guild, or updates it if it has already been registered.
+pub(crate) async fn upsert(
+    dictionary: &Dictionary,
+    database: &PgPool,
+    guild_id: u64,
+    word: &Word,
+) -> Result<Upsert, DictionaryError> {
+    let parameters = word.parameters();
+    let parameters = parameters
+        .iter()
+        .map(|(key, value)| (*key, value.as_str()))
+        .collect::<Vec<_>>();
+
+    if let Some(uuid) = find(dictionary, &word.surface).await? {
+        return match dictionary.update_word(&uuid, &parameters).await? {
+            PutUserDictWordResult::NoContent => Ok(Upsert::Updated(uuid)),
+            PutUserDictWordResult::UnprocessableEntity(error) => Err(DictionaryError::Rejected(error.detail)),
+        };
+    }
+
+    let count = dictionary_word::count_by_guild_id(database, guild_id).await?;
+    if count >= quota::DICTIONARY_WORDS {
+        return Err(DictionaryError::QuotaExceeded(count));
+    }
+
+    let uuid = match dictionary.register_word(&parameters).await? {
+        PostUserDictWordResult::Ok(uuid) => uuid,
+        PostUserDictWordResult::UnprocessableEntity(error) => return Err(DictionaryError::Rejected(error.detail)),
+    };
+    if let Err(error) = dictionary_word::create(database, uuid, guild_id).await {
+        tracing::error!(
+            "failed to record {} registered by guild {guild_id}\nError: {error:?}",
+            word.surface
+        );
+    }
+
+    Ok(Upsert::Registered(uuid))
+}
+
+/// Registers or updates all the words on behalf of the guild, or none of them if any fails.
+///
+/// VOICEVOX ENGINE has no transaction, so the words registered or updated so far are put back on failure.
+pub(crate) async fn import(
+    dictionary: &Dictionary,
+    database: &PgPool,
+    guild_id: u64,
+    words: &[Word],
+) -> Result<Imported, DictionaryError> {
+    let list = list(dictionary).await?;
+    let mut new_words = Vec::new();
+    let mut existing_words = Vec::new();
+    for word in words {
+        let surface = to_full_width(&word.surface);
+        let mut uuids = list.iter().filter(|(_uuid, item)| item.surface == surface);
+        match (uuids.next(), uuids.next()) {
+            (None, _) => new_words.push(word),
+            (Some((uuid, item)), None) => existing_words.push((word, *uuid, item)),
+            (Some(_), Some(_)) => return Err(anyhow!("{surface} is registered in more than one").into()),
+        }
+    }
+
+    let count = dictionary_word::count_by_guild_id(database, guild_id).await?;
+    if count + new_words.len() as i64 > quota::DICTIONARY_WORDS {
+        return Err(DictionaryError::QuotaExceeded(count));
+    }
+
+    let mut registered = Vec::with_capacity(new_words.len());
+    let mut updated = Vec::with_capacity(existing_words.len());
+    let result = async {
+        for word in &new_words {
+            let parameters = word.parameters();
+            let parameters = parameters
+                .iter()
+                .map(|(key, value)| (*key, value.as_str()))
+                .collect::<Vec<_>>();
+            match dictionary.register_word(&parameters).await? {
+                PostUserDictWordResult::Ok(uuid) => registered.push(uuid),
+                PostUserDictWordResult::UnprocessableEntity(error) => {
+                    return Err(DictionaryError::Rejected(format!("{}: {}", word.surface, error.detail)));
+                },
+            }
+        }
+        for (word, uuid, item) in &existing_words {
+            let parameters = word.parameters();
+            let parameters = parameters
+                .iter()
+                .map(|(key, value)| (*key, value.as_str()))
+                .collect::<Vec<_>>();
+            match dictionary.update_word(uuid, &parameters).await? {
+                PutUserDictWordResult::NoContent => updated.push((*uuid, *item)),
+                PutUserDictWordResult::UnprocessableEntity(error) => {
+                    return Err(DictionaryError::Rejected(format!("{}: {}", word.surface, error.detail)));
+                },
+            }
+        }
+        dictionary_word::create_all(database, &registered, guild_id).await?;
+        Ok::<_, DictionaryError>(())
+    }
+    .await;
+
+    if let Err(error) = result {
+        roll_back(dictionary, &registered, &updated).await;
+        return Err(error);
+    }
+
+    Ok(Imported {
+        registered: registered.len(),
+        updated: updated.len(),
+    })
+}
+
+async fn roll_back(dictionary: &Dictionary, registered: &[Uuid], updated: &[(Uuid, &Item)]) {
+    for uuid in registered {
+        if let Err(error) = dictionary.delete_word(uuid).await {
+            tracing::error!("failed to delete {uuid} to roll back import\nError: {error:?}");
+        }
+    }
+    for (uuid, item) in updated {
+        // 単語の種類は品詞から戻せないので省く
+        let accent_type = item.accent_type.to_string();
+        let priority = item.priority.to_string();
+        let parameters = [
+            ("surface", item.surface.as_str()),
+            ("pronunciation", item.pronunciation.as_str()),
+            ("accent_type", accent_type.as_str()),
+            ("priority", priority.as_str()),
+        ];
+        if let Err(error) = dictionary.update_word(uuid, &parameters).await {
+            tracing::error!("failed to restore {uuid} to roll back import\nError: {error:?}");
+        }
+    }
+}
+
+/// Deletes the word registered with the surface.
+pub(crate) async fn delete(dictionary: &Dictionary, database: &PgPool, surface: &str) -> Result<Uuid, DictionaryError> {
+    let surface = normalize_surface(surface);
+    let Some(uuid) = find(dictionary, &surface).await? else {
+        return Err(DictionaryError::NotFound);
+    };
+
+    match dictionary.delete_word(&uuid).await? {
+        DeleteUserDictWordResult::NoContent => {},
+        DeleteUserDictWordResult::UnprocessableEntity(error) => return Err(DictionaryError::Rejected(error.detail)),
+    };
+    if let Err(error) = dictionary_word::delete_by_id(database, uuid).await {
+        tracing::error!("failed to forget which guild registered {surface}\nError: {error:?}");
+    }
+
+    Ok(uuid)
+}
+
+async fn find(dictionary: &Dictionary, surface: &str) -> Result<Option<Uuid>> {
+    let list = list(dictionary).await?;
+    let surface = to_full_width(surface);
+    let uuids = list
+        .into_iter()
+        .filter(|(_uuid, item)| item.surface == surface)
+        .map(|(uuid, _item)| uuid)
+        .collect::<Vec<_>>();
+    if uuids.len() > 1 {
+        bail!("{surface} is registered in more than one");
+    }
+
+    Ok(uuids.into_iter().next())
+}
+
+// カスタム絵文字は名前だけを登録する
+fn normalize_surface(surface: &str) -> String {
+    regex::EMOJI.replace_all(surface, ":$1:").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DictionaryError, WordRequest};
+
+    fn request(surface: &str, pronunciation: &str) -> WordRequest {
+        WordRequest {
+            surface: surface.to_string(),
+            pronunciation: pronunciation.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fill_defaults() {
+        let word = request("seitai", "せいたい").validate().unwrap();
+        assert_eq!(word.surface, "seitai");
+        assert_eq!(word.pronunciation, "セイタイ");
+        assert_eq!(word.accent_type, 0);
+        assert_eq!(word.priority, 10);
+    }
+
+    #[test]
+    fn register_name_of_custom_emoji() {
+        let word = request("<:sunny:123456789012345678>", "ハレ").validate().unwrap();
+        assert_eq!(word.surface, ":sunny:");
+    }
+
+    #[test]
+    fn reject_invalid_word() {
+        assert!(matches!(
+            request(" ", "セイタイ").validate(),
+            Err(DictionaryError::Invalid(_))
+        ));
+        assert!(matches!(
+            request("seitai", "").validate(),
+            Err(DictionaryError::Invalid(_))
+        ));
+        assert!(matches!(
+            WordRequest {
+                priority: Some(11),
+                ..request("seitai", "セイタイ")
+            }
+            .validate(),
+            Err(DictionaryError::Invalid(_))
+        ));
+        assert!(matches!(
+            WordRequest {
+                accent_type: Some(-1),
+                ..request("seitai", "セイタイ")
+            }
+            .validate(),
+            Err(DictionaryError::Invalid(_))
+        ));
+        assert!(matches!(
+            WordRequest {
+                word_type: Some("NOUN".to_string()),
+                ..request("seitai", "セイタイ")
+            }
+            .validate(),
+            Err(DictionaryError::Invalid(_))
+        ));
+    }
+}
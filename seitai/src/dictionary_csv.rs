@@ -0,0 +1,161 @@
+use indexmap::IndexMap;
+use seitai_converter::to_half_width;
+use voicevox::dictionary::response::Item;
+
+use crate::dictionary::{Word, WordRequest};
+
+const HEADER: [&str; 3] = ["word", "reading", "priority"];
+// 問題として表示する最大数
+const MAX_PROBLEMS: usize = 20;
+
+/// Reads words from CSV with the columns `word,reading,priority`, where the header and the priority are optional.
+///
+/// A word appearing more than once is registered with the last row. Returns the problems of rows if any row is invalid.
+pub(crate) fn parse(text: &str) -> Result<Vec<Word>, Vec<String>> {
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+    let mut records = records(text)?;
+    if let Some((_line, fields)) = records.first()
+        && fields.first().is_some_and(|field| {
+            let field = field.trim();
+            field.eq_ignore_ascii_case(HEADER[0]) || field == "単語"
+        })
+    {
+        records.remove(0);
+    }
+
+    let mut words = IndexMap::new();
+    let mut problems = Vec::new();
+    for (line, fields) in records {
+        if fields.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        match word(&fields) {
+            Ok(word) => {
+                words.insert(word.surface.clone(), word);
+            },
+            Err(problem) => problems.push(format!("{line}行目: {problem}")),
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(words.into_values().collect())
+    } else {
+        if problems.len() > MAX_PROBLEMS {
+            let rest = problems.len() - MAX_PROBLEMS;
+            problems.truncate(MAX_PROBLEMS);
+            problems.push(format!("ほか {rest} 行"));
+        }
+        Err(problems)
+    }
+}
+
+/// Writes the words as CSV which [`parse`] can read.
+pub(crate) fn write<'a>(items: impl IntoIterator<Item = &'a Item>) -> String {
+    let mut csv = format!("{}\r\n", HEADER.join(","));
+    for item in items {
+        let fields = [
+            escape(&to_half_width(&item.surface)),
+            escape(&item.pronunciation),
+            item.priority.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn word(fields: &[String]) -> Result<Word, String> {
+    if !(2..=HEADER.len()).contains(&fields.len()) {
+        return Err("列は「単語,ヨミ,優先度」にしてください。".to_string());
+    }
+    let priority = match fields.get(2).map(|priority| priority.trim()) {
+        Some(priority) if !priority.is_empty() => Some(
+            priority
+                .parse()
+                .map_err(|_| "優先度は 0 〜 10 の整数にしてください。".to_string())?,
+        ),
+        _ => None,
+    };
+    let request = WordRequest {
+        surface: fields[0].clone(),
+        pronunciation: fields[1].clone(),
+        priority,
+        ..Default::default()
+    };
+    request.validate().map_err(|error| error.to_string())
+}
+
+/// Splits the text into records with the line each of them starts at.
+fn records(text: &str) -> Result<Vec<(usize, Vec<String>)>, Vec<String>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut start = 1;
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if quoted => {
+                if chars.next_if_eq(&'"').is_some() {
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            },
+            '"' if field.is_empty() => quoted = true,
+            '\n' => {
+                line += 1;
+                if quoted {
+                    field.push(char);
+                } else {
+                    fields.push(std::mem::take(&mut field));
+                    records.push((start, std::mem::take(&mut fields)));
+                    start = line;
+                }
+            },
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {},
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(char),
+        }
+    }
+    if quoted {
+        return Err(vec![format!("{start}行目: 引用符が閉じられていません。")]);
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn parse_rows_with_header_quotes_and_duplicates() {
+        let words =
+            parse("\u{FEFF}word,reading,priority\r\nseitai,せいたい,5\r\n\"a,b\",エービー\r\n\r\nseitai,セイタイ,\n")
+                .unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].surface, "seitai");
+        assert_eq!(words[0].pronunciation, "セイタイ");
+        assert_eq!(words[1].surface, "a,b");
+
+        let problems = parse("seitai,セイタイ,11\nseitai\n\"open,ヨミ").unwrap_err();
+        assert_eq!(problems, ["3行目: 引用符が閉じられていません。"]);
+        let problems = parse("seitai,セイタイ,11\nseitai\n").unwrap_err();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("1行目"));
+        assert!(problems[1].starts_with("2行目"));
+    }
+}
@@ -0,0 +1,64 @@
+use std::env;
+
+use anyhow::{Context as _, Result, bail};
+use songbird::{
+    Config,
+    driver::{CryptoMode, DecodeMode},
+};
+
+/// Configuration of the voice driver of every call, tuned with the environment variables for busy hosts.
+///
+/// - `SONGBIRD_CRYPTO_MODE`: `aes256gcm` or `xchacha20poly1305`
+/// - `SONGBIRD_PREALLOCATED_TRACKS`: number of tracks to allocate for each call in advance
+/// - `SONGBIRD_SOFTCLIP`: `false` to skip soft clipping of mixed audio
+pub(crate) fn config_from_env() -> Result<Config> {
+    // 話しているかどうかしか見ないので受け取った音声は復号しない
+    let mut config = Config::default().decode_mode(DecodeMode::Pass);
+
+    if let Some(crypto_mode) = var("SONGBIRD_CRYPTO_MODE") {
+        config = config.crypto_mode(parse_crypto_mode(&crypto_mode)?);
+    }
+    if let Some(preallocated_tracks) = var("SONGBIRD_PREALLOCATED_TRACKS") {
+        let preallocated_tracks = preallocated_tracks
+            .parse::<usize>()
+            .context("failed to parse environment variable SONGBIRD_PREALLOCATED_TRACKS")?;
+        config = config.preallocated_tracks(preallocated_tracks);
+    }
+    if let Some(use_softclip) = var("SONGBIRD_SOFTCLIP") {
+        let use_softclip = use_softclip
+            .parse::<bool>()
+            .context("failed to parse environment variable SONGBIRD_SOFTCLIP")?;
+        config = config.use_softclip(use_softclip);
+    }
+
+    Ok(config)
+}
+
+fn var(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+fn parse_crypto_mode(value: &str) -> Result<CryptoMode> {
+    match value.to_ascii_lowercase().as_str() {
+        "aes256gcm" => Ok(CryptoMode::Aes256Gcm),
+        "xchacha20poly1305" => Ok(CryptoMode::XChaCha20Poly1305),
+        _ => bail!("unknown crypto mode {value} in environment variable SONGBIRD_CRYPTO_MODE"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use songbird::driver::CryptoMode;
+
+    use super::parse_crypto_mode;
+
+    #[test]
+    fn parse_crypto_modes() {
+        assert_eq!(parse_crypto_mode("aes256gcm").unwrap(), CryptoMode::Aes256Gcm);
+        assert_eq!(
+            parse_crypto_mode("XChaCha20Poly1305").unwrap(),
+            CryptoMode::XChaCha20Poly1305
+        );
+        assert!(parse_crypto_mode("xsalsa20poly1305").is_err());
+    }
+}
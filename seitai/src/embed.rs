@@ -0,0 +1,49 @@
+use crate::{utils::truncate_chars, utterance::PredefinedUtterance};
+
+// 読み上げる埋め込みの最大文字数（すべての埋め込みの合計）
+const MAX_EMBED_CHARS: usize = 200;
+
+/// Text read for the titles and descriptions of the embeds of a message, one line each and truncated, or `None` if the
+/// embeds have nothing to read.
+pub(crate) fn text_of<'a>(embeds: impl IntoIterator<Item = (Option<&'a str>, Option<&'a str>)>) -> Option<String> {
+    let mut text = embeds
+        .into_iter()
+        .flat_map(|(title, description)| [title, description])
+        .flatten()
+        .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        return None;
+    }
+    if truncate_chars(&mut text, MAX_EMBED_CHARS) {
+        text.push('\n');
+        text.push_str(PredefinedUtterance::Omitted.as_ref());
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_EMBED_CHARS, text_of};
+
+    #[test]
+    fn read_titles_and_descriptions() {
+        assert_eq!(text_of([]), None);
+        assert_eq!(text_of([(None, Some(" \n "))]), None);
+        assert_eq!(
+            text_of([
+                (Some("新しいリリース"), Some("v1.0.0 を\n公開しました")),
+                (None, Some("説明だけ"))
+            ])
+            .as_deref(),
+            Some("新しいリリース\nv1.0.0 を 公開しました\n説明だけ")
+        );
+
+        let long = "あ".repeat(MAX_EMBED_CHARS * 2);
+        let text = text_of([(Some(long.as_str()), None)]).unwrap();
+        assert!(text.starts_with(&"あ".repeat(MAX_EMBED_CHARS)));
+        assert!(text.ends_with("\n以下省略"));
+    }
+}
@@ -0,0 +1,602 @@
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    num::NonZeroUsize,
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{Result, anyhow};
+use lru::LruCache;
+use metrics::counter;
+use seitai_audio::AudioGenerator;
+use seitai_converter::blacklist::BEEP;
+use serenity::{builder::CreateEmbed, model::Colour};
+use tokio::sync::watch;
+use voicevox::{Bytes, Voicevox, audio::AudioQuery};
+
+use crate::{metrics::QUERY_CACHE_HITS, notifier::OwnerNotifier, timing::Stage};
+
+// 発話の種類はそれほど多くないので、よく読まれるものが残れば十分
+const QUERY_CACHE_CAPACITY: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+// 起動中のホストの準備を確かめる回数の上限
+const MAX_WARM_UP_ATTEMPTS: usize = 40;
+// CPU で合成するホストに頼む標本化周波数
+const CPU_SAMPLING_RATE: u32 = 16000;
+// CPU で合成するときにまとめて読み上げる最大の文字数
+const CPU_BATCH_CHARS: usize = 80;
+// まとめた行の間に読点を挟まなくてよい末尾の文字
+const PAUSES: [char; 7] = ['。', '、', '！', '？', '!', '?', '…'];
+
+/// Tolerated error rate of an engine host before it is taken out of rotation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ErrorBudget {
+    // エラー率を計算する時間枠
+    window: Duration,
+    // エラー率を判定するのに必要な最小リクエスト数
+    min_requests: usize,
+    // 許容するエラー率
+    max_error_rate: f32,
+    // ローテーションから外す時間
+    cooldown: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    outcomes: VecDeque<(Instant, bool)>,
+    degraded_until: Option<Instant>,
+}
+
+struct Engine {
+    host: String,
+    voicevox: Voicevox,
+    health: Mutex<Health>,
+    // ボイスのモデルを読み込み終えたか
+    warmed_up: AtomicBool,
+    // 準備ができてから確かめた合成の仕方
+    mode: OnceLock<EngineMode>,
+}
+
+struct Inner {
+    engines: Vec<Engine>,
+    next: AtomicUsize,
+    budget: ErrorBudget,
+    notifier: OwnerNotifier,
+    // 話者に関わらず使い回すため、テキストごとに倍率を適用する前のクエリを保持する
+    queries: Mutex<LruCache<String, AudioQuery>>,
+    // いずれかのホストの準備ができたか
+    ready: watch::Sender<bool>,
+}
+
+/// How an engine host synthesizes, detected from its manifest and supported devices once it has warmed up.
+#[derive(Debug, Clone)]
+pub(crate) struct EngineMode {
+    /// Whether the host is able to synthesize on a GPU, which makes synthesis far faster than on CPU.
+    pub(crate) gpu: bool,
+    /// Sampling rate requested from the host, lowered from its default on CPU.
+    pub(crate) sampling_rate: u32,
+}
+
+/// Reachability of an engine host at the time it was checked.
+pub(crate) struct EngineStatus {
+    pub(crate) host: String,
+    /// Whether the host is in rotation rather than taken out for exceeding the error budget.
+    pub(crate) available: bool,
+    /// Whether the host has loaded the models of the voices warmed up at startup.
+    pub(crate) warmed_up: bool,
+    /// How the host synthesizes, or `None` until it has been detected.
+    pub(crate) mode: Option<EngineMode>,
+    /// Version of the engine and how long it took to respond.
+    pub(crate) response: Result<(String, Duration)>,
+}
+
+/// Distributes synthesis over several VOICEVOX ENGINE hosts, failing over to the next host on errors.
+#[derive(Clone)]
+pub(crate) struct EnginePool {
+    inner: Arc<Inner>,
+}
+
+impl ErrorBudget {
+    pub(crate) fn new(window_secs: u64, min_requests: usize, max_error_rate: f32, cooldown_secs: u64) -> Self {
+        Self {
+            window: Duration::from_secs(window_secs),
+            min_requests,
+            max_error_rate,
+            cooldown: Duration::from_secs(cooldown_secs),
+        }
+    }
+}
+
+impl Health {
+    fn is_available(&self) -> bool {
+        self.degraded_until.is_none()
+    }
+
+    /// Records an outcome and returns the error rate when it has just exhausted the budget.
+    fn record(&mut self, budget: &ErrorBudget, now: Instant, succeeded: bool) -> Option<f32> {
+        self.outcomes.push_back((now, succeeded));
+        while self
+            .outcomes
+            .front()
+            .is_some_and(|(time, _)| now.duration_since(*time) > budget.window)
+        {
+            self.outcomes.pop_front();
+        }
+
+        if !self.is_available() || self.outcomes.len() < budget.min_requests {
+            return None;
+        }
+
+        let errors = self.outcomes.iter().filter(|(_, succeeded)| !succeeded).count();
+        let error_rate = errors as f32 / self.outcomes.len() as f32;
+        if error_rate <= budget.max_error_rate {
+            return None;
+        }
+
+        self.degraded_until = Some(now + budget.cooldown);
+        Some(error_rate)
+    }
+
+    fn is_due_to_probe(&self, now: Instant) -> bool {
+        self.degraded_until.is_some_and(|until| now >= until)
+    }
+
+    fn restore(&mut self) {
+        self.outcomes.clear();
+        self.degraded_until = None;
+    }
+
+    fn extend(&mut self, budget: &ErrorBudget, now: Instant) {
+        self.degraded_until = Some(now + budget.cooldown);
+    }
+}
+
+impl EnginePool {
+    pub(crate) fn build(hosts: &[&str], budget: ErrorBudget, notifier: OwnerNotifier) -> Result<Self> {
+        let engines = hosts
+            .iter()
+            .map(|host| {
+                Ok(Engine {
+                    host: host.to_string(),
+                    voicevox: Voicevox::build(host)?,
+                    health: Mutex::new(Health::default()),
+                    warmed_up: AtomicBool::new(false),
+                    mode: OnceLock::new(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                engines,
+                next: AtomicUsize::new(0),
+                budget,
+                notifier,
+                queries: Mutex::new(LruCache::new(QUERY_CACHE_CAPACITY)),
+                ready: watch::Sender::new(false),
+            }),
+        })
+    }
+
+    pub(crate) fn engine_count(&self) -> usize {
+        self.inner.engines.len()
+    }
+
+    /// Loads the models of the voices on every host in the background, retrying hosts which are still starting up.
+    pub(crate) fn spawn_warm_up(&self, speakers: Vec<String>, interval: Duration) {
+        for index in 0..self.inner.engines.len() {
+            let pool = self.clone();
+            let speakers = speakers.clone();
+            tokio::spawn(async move {
+                pool.warm_up(index, &speakers, interval).await;
+            });
+        }
+    }
+
+    async fn warm_up(&self, index: usize, speakers: &[String], interval: Duration) {
+        let engine = &self.inner.engines[index];
+        let started_at = Instant::now();
+        for speaker in speakers {
+            let mut attempts = 1;
+            while let Err(error) = engine.voicevox.engine.initialize_speaker(speaker).await {
+                if attempts >= MAX_WARM_UP_ATTEMPTS {
+                    // 読み込めないボイスのために読み上げを止め続けないようにする
+                    tracing::error!(
+                        "engine {} failed to load voice {speaker}, giving up warming it up\nError: {error:?}",
+                        engine.host
+                    );
+                    break;
+                }
+                tracing::warn!("engine {} is still starting up\nError: {error:?}", engine.host);
+                attempts += 1;
+                tokio::time::sleep(interval).await;
+            }
+        }
+
+        engine.detect_mode().await;
+        engine.warmed_up.store(true, Ordering::Relaxed);
+        let warmed_up = self
+            .inner
+            .engines
+            .iter()
+            .filter(|engine| engine.warmed_up.load(Ordering::Relaxed))
+            .count();
+        tracing::info!(
+            "engine {} warmed up in {:?} ({warmed_up}/{} engines)",
+            engine.host,
+            started_at.elapsed(),
+            self.inner.engines.len()
+        );
+        self.inner.ready.send_replace(true);
+    }
+
+    /// Whether every host whose mode has been detected synthesizes on CPU, which makes synthesis slow.
+    pub(crate) fn runs_on_cpu(&self) -> bool {
+        let mut modes = self
+            .inner
+            .engines
+            .iter()
+            .filter_map(|engine| engine.mode.get())
+            .peekable();
+        modes.peek().is_some() && modes.all(|mode| !mode.gpu)
+    }
+
+    /// Texts to synthesize one by one, joined into longer utterances when synthesis is slow on CPU.
+    pub(crate) fn utterances<'a>(&self, texts: impl IntoIterator<Item = &'a str>) -> Vec<Cow<'a, str>> {
+        match self.runs_on_cpu() {
+            true => batch(texts, CPU_BATCH_CHARS),
+            false => texts.into_iter().map(Cow::Borrowed).collect(),
+        }
+    }
+
+    /// Whether no host has warmed up yet since startup.
+    pub(crate) fn is_warming_up(&self) -> bool {
+        !*self.inner.ready.borrow()
+    }
+
+    /// Waits until any host has warmed up, giving up after the timeout to let the synthesis fail as usual.
+    pub(crate) async fn wait_until_warmed_up(&self, timeout: Duration) {
+        let mut ready = self.inner.ready.subscribe();
+        let _ = tokio::time::timeout(timeout, ready.wait_for(|ready| *ready)).await;
+    }
+
+    /// Periodically probes degraded hosts and puts them back into rotation once they respond.
+    pub(crate) fn spawn_prober(&self, interval: Duration) {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                pool.probe().await;
+            }
+        });
+    }
+
+    async fn probe(&self) {
+        for engine in &self.inner.engines {
+            if !engine.health().is_due_to_probe(Instant::now()) {
+                continue;
+            }
+
+            match engine.voicevox.engine.version().await {
+                Ok(version) => {
+                    engine.health().restore();
+                    tracing::info!("engine {} ({version}) is back in rotation", engine.host);
+                    self.inner
+                        .notifier
+                        .notify(
+                            CreateEmbed::new()
+                                .title("VOICEVOX ENGINE が復旧しました。")
+                                .field("ホスト", &engine.host, true)
+                                .field("バージョン", version, true)
+                                .colour(Colour::FOOYOO),
+                        )
+                        .await;
+                },
+                Err(error) => {
+                    engine.health().extend(&self.inner.budget, Instant::now());
+                    tracing::warn!("engine {} is still unavailable\nError: {error:?}", engine.host);
+                },
+            }
+        }
+    }
+
+    /// Asks every host for its version to check whether it is reachable and how fast it responds.
+    pub(crate) async fn check(&self) -> Vec<EngineStatus> {
+        let checks = self.inner.engines.iter().map(|engine| async move {
+            let started_at = Instant::now();
+            let response = engine
+                .voicevox
+                .engine
+                .version()
+                .await
+                .map(|version| (version, started_at.elapsed()));
+            EngineStatus {
+                host: engine.host.clone(),
+                available: engine.health().is_available(),
+                warmed_up: engine.warmed_up.load(Ordering::Relaxed),
+                mode: engine.mode.get().cloned(),
+                response,
+            }
+        });
+        futures::future::join_all(checks).await
+    }
+
+    /// Engines in round-robin order, preferring those within their error budget.
+    fn candidates(&self) -> Vec<&Engine> {
+        let engines = &self.inner.engines;
+        let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+        let rotated = (0..engines.len()).map(|offset| &engines[(start + offset) % engines.len()]);
+
+        let available = rotated
+            .clone()
+            .filter(|engine| engine.health().is_available())
+            .collect::<Vec<_>>();
+        match available.is_empty() {
+            // 全て切り離されている場合は失敗覚悟で全てに問い合わせる
+            true => rotated.collect(),
+            false => available,
+        }
+    }
+
+    /// Length of the audio of `text` read at `speed`, if its audio query is still cached.
+    pub(crate) fn expected_duration(&self, text: &str, speed: f32) -> Option<Duration> {
+        let mut query = self.cached_query(text)?;
+        query.scale(speed, query.pitch_scale, query.intonation_scale);
+        Some(query.duration())
+    }
+
+    fn cached_query(&self, text: &str) -> Option<AudioQuery> {
+        self.queries().get(text).cloned()
+    }
+
+    fn queries(&self) -> std::sync::MutexGuard<'_, LruCache<String, AudioQuery>> {
+        self.inner.queries.lock().expect("audio query cache has been poisoned")
+    }
+
+    // モーフィングする場合は相手の話者と割合を渡す
+    async fn synthesize_with(&self, speaker: &str, morph: Option<(&str, f32)>, query: &AudioQuery) -> Result<Bytes> {
+        let started_at = Instant::now();
+        let mut last_error = anyhow!("no engine is configured");
+        for engine in self.candidates() {
+            let audio_generator = &engine.voicevox.audio_generator;
+            let query = engine.adapt(query);
+            let query = query.as_ref();
+            let synthesized = match morph {
+                Some((target, rate)) => {
+                    audio_generator
+                        .synthesize_morphing_query(speaker, target, rate, query)
+                        .await
+                },
+                None => audio_generator.synthesize_query(speaker, query).await,
+            };
+            match synthesized {
+                Ok(audio) => {
+                    let elapsed = started_at.elapsed();
+                    Stage::Synthesis.record(elapsed);
+                    tracing::debug!("engine {} synthesized audio in {elapsed:?}", engine.host);
+                    self.record(engine, true).await;
+                    return Ok(audio);
+                },
+                Err(error) => {
+                    tracing::warn!("engine {} failed to synthesize audio\nError: {error:?}", engine.host);
+                    self.record(engine, false).await;
+                    last_error = error;
+                },
+            }
+        }
+
+        Err(last_error)
+    }
+
+    async fn record(&self, engine: &Engine, succeeded: bool) {
+        let exhausted = engine.health().record(&self.inner.budget, Instant::now(), succeeded);
+        let Some(error_rate) = exhausted else {
+            return;
+        };
+
+        tracing::warn!(
+            "engine {} exceeded error budget with error rate {error_rate:.2}, taking it out of rotation",
+            engine.host
+        );
+        self.inner
+            .notifier
+            .notify(
+                CreateEmbed::new()
+                    .title("VOICEVOX ENGINE を一時的に切り離しました。")
+                    .field("ホスト", &engine.host, true)
+                    .field("エラー率", format!("{:.0}%", error_rate * 100.0), true)
+                    .field(
+                        "再確認まで",
+                        format!("{}秒", self.inner.budget.cooldown.as_secs()),
+                        true,
+                    )
+                    .colour(Colour::ORANGE),
+            )
+            .await;
+    }
+}
+
+impl Engine {
+    fn health(&self) -> std::sync::MutexGuard<'_, Health> {
+        self.health.lock().expect("engine health has been poisoned")
+    }
+
+    async fn detect_mode(&self) {
+        let detected = futures::try_join!(
+            self.voicevox.engine.manifest(),
+            self.voicevox.engine.supported_devices()
+        );
+        match detected {
+            Ok((manifest, devices)) => {
+                let mode = EngineMode {
+                    gpu: devices.has_gpu(),
+                    sampling_rate: match devices.has_gpu() {
+                        true => manifest.default_sampling_rate,
+                        false => manifest.default_sampling_rate.min(CPU_SAMPLING_RATE),
+                    },
+                };
+                tracing::info!("engine {} ({}) synthesizes as {mode:?}", self.host, manifest.name);
+                let _ = self.mode.set(mode);
+            },
+            Err(error) => {
+                tracing::warn!("failed to detect mode of engine {}\nError: {error:?}", self.host);
+            },
+        }
+    }
+
+    /// Lowers the sampling rate of the query for hosts synthesizing on CPU.
+    fn adapt<'a>(&self, query: &'a AudioQuery) -> Cow<'a, AudioQuery> {
+        match self.mode.get() {
+            Some(mode) if !mode.gpu && query.output_sampling_rate != mode.sampling_rate => {
+                let mut query = query.clone();
+                query.output_sampling_rate = mode.sampling_rate;
+                Cow::Owned(query)
+            },
+            _ => Cow::Borrowed(query),
+        }
+    }
+}
+
+/// Joins consecutive texts into utterances of up to `max_chars` characters, keeping beeps on their own.
+fn batch<'a>(texts: impl IntoIterator<Item = &'a str>, max_chars: usize) -> Vec<Cow<'a, str>> {
+    let mut batches = Vec::<Cow<'a, str>>::new();
+    let mut joinable = false;
+    for text in texts.into_iter().map(str::trim).filter(|text| !text.is_empty()) {
+        if text == BEEP {
+            batches.push(Cow::Borrowed(text));
+            joinable = false;
+            continue;
+        }
+        if joinable
+            && let Some(last) = batches.last_mut()
+            && last.chars().count() + text.chars().count() < max_chars
+        {
+            let last = last.to_mut();
+            if !last.ends_with(PAUSES) {
+                last.push('、');
+            }
+            last.push_str(text);
+            continue;
+        }
+        batches.push(Cow::Borrowed(text));
+        joinable = true;
+    }
+    batches
+}
+
+impl AudioGenerator for EnginePool {
+    type Query = AudioQuery;
+    type Raw = Bytes;
+
+    async fn generate_query(
+        &self,
+        speaker: &str,
+        text: &str,
+        speed: f32,
+        pitch: f32,
+        intonation: f32,
+    ) -> Result<Self::Query> {
+        // 同じテキストのアクセント句は話者を切り替えても使い回し、合成だけをやり直す
+        if let Some(mut query) = self.cached_query(text) {
+            counter!(QUERY_CACHE_HITS).increment(1);
+            query.scale(speed, pitch, intonation);
+            return Ok(query);
+        }
+
+        // フェイルオーバーにかかった時間も含める
+        let started_at = Instant::now();
+        let mut last_error = anyhow!("no engine is configured");
+        for engine in self.candidates() {
+            match engine.voicevox.audio_generator.accent_phrases(speaker, text).await {
+                Ok(mut query) => {
+                    let elapsed = started_at.elapsed();
+                    Stage::Query.record(elapsed);
+                    tracing::debug!("engine {} generated audio query in {elapsed:?}", engine.host);
+                    self.record(engine, true).await;
+                    self.queries().put(text.to_string(), query.clone());
+                    query.scale(speed, pitch, intonation);
+                    return Ok(query);
+                },
+                Err(error) => {
+                    tracing::warn!(
+                        "engine {} failed to generate audio query\nError: {error:?}",
+                        engine.host
+                    );
+                    self.record(engine, false).await;
+                    last_error = error;
+                },
+            }
+        }
+
+        Err(last_error)
+    }
+
+    async fn synthesize(&self, speaker: &str, query: &Self::Query) -> Result<Self::Raw> {
+        self.synthesize_with(speaker, None, query).await
+    }
+
+    async fn synthesize_morphing(
+        &self,
+        speaker: &str,
+        target: &str,
+        rate: f32,
+        query: &Self::Query,
+    ) -> Result<Self::Raw> {
+        self.synthesize_with(speaker, Some((target, rate)), query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use seitai_converter::blacklist::BEEP;
+
+    use super::{ErrorBudget, Health, batch};
+
+    #[test]
+    fn exhaust_error_budget() {
+        let budget = ErrorBudget::new(60, 4, 0.5, 30);
+        let mut health = Health::default();
+        let now = Instant::now();
+
+        assert_eq!(health.record(&budget, now, false), None);
+        assert_eq!(health.record(&budget, now, true), None);
+        assert_eq!(health.record(&budget, now, false), None);
+        assert_eq!(health.record(&budget, now, false), Some(0.75));
+        assert!(!health.is_available());
+        assert!(!health.is_due_to_probe(now + Duration::from_secs(10)));
+        assert!(health.is_due_to_probe(now + Duration::from_secs(30)));
+
+        health.restore();
+        assert!(health.is_available());
+    }
+
+    #[test]
+    fn forget_outcomes_outside_window() {
+        let budget = ErrorBudget::new(60, 2, 0.5, 30);
+        let mut health = Health::default();
+        let now = Instant::now();
+
+        assert_eq!(health.record(&budget, now, false), None);
+        assert_eq!(health.record(&budget, now + Duration::from_secs(61), true), None);
+        assert!(health.is_available());
+    }
+
+    #[test]
+    fn batch_short_texts() {
+        let texts = ["こんにちは", "今日は", "", "いい天気ですね。", BEEP, "またね"];
+        assert_eq!(
+            batch(texts, 80),
+            ["こんにちは、今日は、いい天気ですね。", BEEP, "またね"]
+        );
+        assert_eq!(
+            batch(texts, 10),
+            ["こんにちは、今日は", "いい天気ですね。", BEEP, "またね"]
+        );
+    }
+}
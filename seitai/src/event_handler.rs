@@ -1,9 +1,22 @@
-use std::{borrow::Cow, error::Error, ffi::OsString, pin::Pin, sync::Arc, time::Duration};
+use std::{
+    borrow::Cow,
+    error::Error,
+    ffi::OsString,
+    path::PathBuf,
+    pin::Pin,
+    slice,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context as _, Result};
-use dashmap::DashMap;
-use database::PgPool;
-use futures::{StreamExt, future::join_all, lock::Mutex, stream};
+use dashmap::{DashMap, DashSet};
+use database::{
+    PgPool,
+    guild::{ReadingOutput, TtsMessages},
+    usage_statistic::Usage,
+};
+use futures::lock::Mutex;
 use hashbrown::HashMap;
 use http_body_util::BodyExt;
 use hyper::{
@@ -11,13 +24,27 @@ use hyper::{
     body::{Body, Buf},
 };
 use hyper_util::rt::TokioIo;
-use lazy_regex::Regex;
+use logging::LogFilter;
 use ordered_float::NotNan;
+use seitai_audio::{Audio, AudioGenerator, AudioRepository, CacheStatistics};
+use seitai_converter::{
+    ReadContext,
+    blacklist::{BEEP, BEEP_LINE, BEEP_READING},
+};
 use serde::de::DeserializeOwned;
 use serenity::{
-    all::{ChannelId as SerenityChannelId, ChannelType, GuildId, VoiceState},
+    all::{
+        ChannelId as SerenityChannelId, ChannelType, GuildChannel, GuildId, GuildMemberUpdateEvent, Member,
+        Mentionable, MessageType, User, VoiceState,
+    },
+    builder::{CreateEmbed, CreateMessage},
     client::{Context, EventHandler},
-    model::{application::Interaction, channel::Message, gateway::Ready},
+    model::{
+        Colour,
+        application::Interaction,
+        channel::{Message, MessageFlags, Reaction},
+        gateway::{Activity, ActivityType, Ready},
+    },
 };
 use songbird::{
     Call,
@@ -28,17 +55,40 @@ use soundboard::sound::SoundId;
 use tokio::net::TcpStream;
 use tracing::instrument;
 use url::Url;
-use voicevox::dictionary::response::GetUserDictResult;
-use wana_kana::ConvertJapanese;
-use whatlang::{Lang, detect_lang};
 
 use crate::{
-    audio::{Audio, AudioRepository, cache::PredefinedUtterance},
-    character_converter::to_half_width,
-    commands, regex,
+    announcement::Announcer,
+    attachment,
+    authors::LastAuthors,
+    celebration::{self, Celebrations, DEFAULT_CELEBRATION_SOUND},
+    classification::{DEFAULT_COMMAND_PREFIXES, is_command_like},
+    commands, delay, embed,
+    engine::EnginePool,
+    fallback::TextFallbacks,
+    jitter::Jitter,
+    member_names::MemberNames,
+    metrics,
+    output::{Furigana, TextSummary},
+    presence::Presence,
+    priority::PriorityTracks,
+    recent::RecentAudios,
+    resolver::{Voice, VoiceResolver},
+    scheduler::SynthesisScheduler,
+    settings_resolver::{Readings, SettingsResolver},
     speaker::Speaker,
+    statistics::UsageStatistics,
+    style,
+    telemetry::Telemetry,
     time_keepr::TimeKeeper,
-    utils::{RateLimiter, get_manager, get_voicevox, normalize},
+    timing::{Stage, Timings},
+    topic::{self, Topics},
+    utils::{
+        RateLimiter, SOUND_GAIN, core_name, enqueue, get_manager, hide_spoilers, normalize, read_context, system_audio,
+        users_in,
+    },
+    utterance::PredefinedUtterance,
+    watchdog::Watchdog,
+    worker::ReadingWorkers,
 };
 
 pub(crate) struct Handler<Repository> {
@@ -50,25 +100,54 @@ pub(crate) struct Handler<Repository> {
     pub(crate) kanatrans_host: String,
     pub(crate) kanatrans_port: u16,
     pub(crate) sounds: Arc<DashMap<OsString, Memory>>,
+    pub(crate) sound_directory: Option<PathBuf>,
     pub(crate) rate_limiter: RateLimiter,
+    pub(crate) started_at: Instant,
+    pub(crate) support_server_url: Option<String>,
+    pub(crate) log_filter: LogFilter,
+    pub(crate) reading_workers: ReadingWorkers,
+    pub(crate) voice_resolver: VoiceResolver,
+    /// Settings read for each message, which are served from the last ones read while the database is unavailable.
+    pub(crate) settings: Arc<SettingsResolver>,
+    pub(crate) member_names: Arc<MemberNames>,
+    pub(crate) celebrations: Celebrations,
+    pub(crate) presence: Arc<Presence>,
+    pub(crate) engine_pool: EnginePool,
+    pub(crate) cache_statistics: Arc<CacheStatistics>,
+    pub(crate) synthesis_scheduler: SynthesisScheduler,
+    pub(crate) tts_guides: Arc<Mutex<TimeKeeper<GuildId>>>,
+    pub(crate) last_authors: LastAuthors,
+    pub(crate) recent_audios: Arc<RecentAudios>,
+    pub(crate) warm_up_notices: DashSet<GuildId>,
+    /// Guilds where reading is paused by `/pause`, whose tracks wait in the queue until `/resume`.
+    pub(crate) paused_guilds: Arc<DashSet<GuildId>>,
+    pub(crate) watchdog: Arc<Watchdog>,
+    pub(crate) announcer: Arc<Announcer>,
+    /// Topics of the text channels last read, to read them again when changed.
+    pub(crate) topics: Topics,
+    /// Text channels read as text while the bot cannot join voice channels.
+    pub(crate) text_fallbacks: TextFallbacks,
+    pub(crate) priority_tracks: PriorityTracks,
+    pub(crate) usage_statistics: Arc<UsageStatistics>,
+    /// Telemetry of the guilds opting in, or `None` when the deployment sends none.
+    pub(crate) telemetry: Option<Arc<Telemetry>>,
 }
 
-enum Replacement {
-    General(&'static Regex, &'static str),
-    Katakana(&'static Regex),
-}
-
-const SYSTEM_SPEAKER: &str = "1";
+const ANNOUNCEMENT_PREFIX: &str = "お知らせ：";
+// Discord の読み上げ機能の権限について案内する間隔
+const TTS_GUIDE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// エンジンの起動を待つ時間の上限
+const WARM_UP_TIMEOUT: Duration = Duration::from_secs(3 * 60);
 
 impl<Repository> EventHandler for Handler<Repository>
 where
-    Repository: AudioRepository<Input = Input> + Send + Sync,
+    Repository: AudioRepository<Input = Input> + Clone + Send + Sync + 'static,
 {
     fn interaction_create<'s, 'async_trait>(
         &'s self,
         context: Context,
         interaction: Interaction,
-    ) -> Pin<Box<(dyn Future<Output = ()> + Send + 'async_trait)>>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
     where
         Self: 'async_trait,
         's: 'async_trait,
@@ -76,41 +155,194 @@ where
         Box::pin(async move {
             match interaction {
                 Interaction::Command(command) => {
+                    let started_at = Instant::now();
                     let result = match command.data.name.as_str() {
-                        "dictionary" => commands::dictionary::run(&context, &self.audio_repository, &command).await,
+                        "about" => {
+                            commands::about::run(
+                                &context,
+                                &command,
+                                self.started_at,
+                                self.support_server_url.as_deref(),
+                            )
+                            .await
+                        },
+                        "admin" => commands::admin::run(&context, &command, &self.log_filter).await,
+                        "dictionary" => {
+                            commands::dictionary::run(&context, &self.audio_repository, &command, &self.database).await
+                        },
                         "help" => commands::help::run(&context, &command).await,
                         "join" => {
                             commands::join::run(
                                 &context,
+                                &self.database,
                                 &self.audio_repository,
-                                &mut *self.connections.lock().await,
+                                &self.connections,
+                                &self.topics,
+                                &self.text_fallbacks,
                                 &command,
                             )
                             .await
                         },
-                        "leave" => commands::leave::run(&context, &command).await,
+                        "rejoin" => commands::rejoin::run(&context, &command, &self.database, &self.connections).await,
+                        "config" => commands::config::run(&context, &command, &self.database).await,
+                        "announce" => commands::announce::run(&context, &command, &self.database).await,
+                        "leave" => {
+                            commands::leave::run(
+                                &context,
+                                &command,
+                                &self.database,
+                                &self.audio_repository,
+                                &self.connections,
+                                &self.reading_workers,
+                                &self.paused_guilds,
+                            )
+                            .await
+                        },
+                        "skip" => commands::skip::run(&context, &command).await,
+                        "stop" => commands::stop::run(&context, &command, &self.reading_workers).await,
+                        "pause" => commands::pause::run(&context, &command, &self.paused_guilds).await,
+                        "resume" => commands::resume::run(&context, &command, &self.paused_guilds).await,
                         "voice" => commands::voice::run(&context, &command, &self.database, &self.speaker).await,
+                        "quota" => commands::quota::run(&context, &command, &self.database).await,
+                        "limit" => commands::limit::run(&context, &command, &self.database).await,
+                        "sample" => {
+                            commands::sample::run(
+                                &context,
+                                &command,
+                                &self.database,
+                                &self.speaker,
+                                &self.audio_repository,
+                                &self.synthesis_scheduler,
+                            )
+                            .await
+                        },
+                        "replay" => {
+                            commands::replay::run(
+                                &context,
+                                &command,
+                                &self.database,
+                                &self.audio_repository,
+                                &self.recent_audios,
+                                &self.synthesis_scheduler,
+                            )
+                            .await
+                        },
+                        "reset" => commands::reset::run(&context, &command, &self.database).await,
+                        "readname" => commands::readname::run(&context, &command, &self.database).await,
+                        "emoji" => commands::emoji::run(&context, &command, &self.database).await,
+                        "session" => commands::session::run(&context, &command, &self.usage_statistics).await,
+                        "blacklist" => commands::blacklist::run(&context, &command, &self.database, &self.sounds).await,
+                        "autojoin" => commands::autojoin::run(&context, &command, &self.database).await,
+                        "channels" => commands::channels::run(&context, &command, &self.database).await,
+                        "settings" => {
+                            commands::settings::run(&context, &command, &self.database, &self.speaker, &self.sounds)
+                                .await
+                        },
+                        "read" => commands::read::run(&context, &command, &self.database).await,
+                        "ratelimit" => {
+                            commands::ratelimit::run(&context, &command, &self.database, &self.rate_limiter).await
+                        },
+                        "sound" => {
+                            commands::sound::run(
+                                &context,
+                                &command,
+                                &self.database,
+                                &self.sounds,
+                                self.sound_directory.as_deref(),
+                            )
+                            .await
+                        },
+                        "soundboard" => commands::soundboard::run(&context, &command, &self.sounds).await,
                         "soundsticker" => commands::soundsticker::run(&context, &command, &self.database).await,
+                        "speed" => commands::speed::run(&context, &command, &self.database).await,
+                        "stats" => commands::stats::run(&context, &command, &self.database, &self.speaker).await,
+                        "telemetry" => {
+                            commands::telemetry::run(&context, &command, &self.database, self.telemetry.is_some()).await
+                        },
+                        "tts" => commands::tts::run(&context, &command, &self.database).await,
+                        "mute-user" => commands::mute_user::run(&context, &command, &self.database).await,
+                        "unmute-user" => commands::unmute_user::run(&context, &command, &self.database).await,
+                        "status" => {
+                            commands::status::run(
+                                &context,
+                                &command,
+                                &self.database,
+                                &self.connections,
+                                &self.reading_workers,
+                                &self.engine_pool,
+                                &self.cache_statistics,
+                            )
+                            .await
+                        },
+                        "pitch" => commands::pitch::run(&context, &command, &self.database).await,
+                        "intonation" => commands::intonation::run(&context, &command, &self.database).await,
+                        "volume" => commands::volume::run(&context, &command, &self.database).await,
                         _ => Ok(()),
                     }
                     .with_context(|| format!("failed to execute /{}", command.data.name));
+                    metrics::record_interaction("command", &command.data.name, started_at.elapsed(), result.is_ok());
 
                     if let Err(error) = result {
-                        tracing::error!("failed to handle slash command\nError: {error:?}");
+                        commands::error::report(&context, &command, error).await;
                     }
                 },
                 Interaction::Autocomplete(command) => {
+                    let started_at = Instant::now();
                     let result = match command.data.name.as_str() {
-                        "voice" => commands::voice::autocomplete(&context, &command, &self.speaker).await,
+                        "voice" | "settings" | "sample" => {
+                            commands::voice::autocomplete(&context, &command, &self.speaker).await
+                        },
+                        "help" => commands::help::autocomplete(&context, &command).await,
+                        "soundboard" => commands::soundboard::autocomplete(&context, &command, &self.sounds).await,
                         "soundsticker" => commands::soundsticker::autocomplete(&context, &command).await,
                         _ => Ok(()),
                     }
                     .with_context(|| format!("failed to autocomplete /{}", command.data.name));
+                    metrics::record_interaction(
+                        "autocomplete",
+                        &command.data.name,
+                        started_at.elapsed(),
+                        result.is_ok(),
+                    );
 
                     if let Err(error) = result {
                         tracing::error!("failed to handle autocomplete of slash command\nError: {error:?}");
                     }
                 },
+                Interaction::Component(component) => {
+                    let result = match component.data.custom_id.as_str() {
+                        custom_id if custom_id.starts_with(commands::settings::PANEL_PREFIX) => {
+                            commands::settings::handle_component(&context, &component, &self.database, &self.speaker)
+                                .await
+                        },
+                        custom_id if custom_id.starts_with(commands::help::PAGE_PREFIX) => {
+                            commands::help::handle_component(&context, &component).await
+                        },
+                        custom_id if custom_id.starts_with(commands::dictionary::READINGS_PREFIX) => {
+                            commands::dictionary::handle_component(&context, &component, &self.database).await
+                        },
+                        custom_id if custom_id.starts_with(commands::voice::LIST_PREFIX) => {
+                            commands::voice::handle_component(
+                                &context,
+                                &component,
+                                &self.database,
+                                &self.speaker,
+                                &self.audio_repository,
+                                &self.synthesis_scheduler,
+                            )
+                            .await
+                        },
+                        custom_id if custom_id.starts_with(commands::join::FALLBACK_PREFIX) => {
+                            commands::join::handle_component(&context, &component, &self.text_fallbacks).await
+                        },
+                        _ => Ok(()),
+                    }
+                    .with_context(|| format!("failed to handle component {}", component.data.custom_id));
+
+                    if let Err(error) = result {
+                        tracing::error!("failed to handle message component\nError: {error:?}");
+                    }
+                },
                 _ => {},
             }
         })
@@ -120,13 +352,22 @@ where
         &'s self,
         context: Context,
         message: Message,
-    ) -> Pin<Box<(dyn Future<Output = ()> + Send + 'async_trait)>>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
     where
         Self: 'async_trait,
         's: 'async_trait,
     {
         Box::pin(async move {
-            if message.author.bot {
+            // フォローしているチャンネルのお知らせは Webhook から投稿される
+            let is_crosspost = message
+                .flags
+                .is_some_and(|flags| flags.contains(MessageFlags::IS_CROSSPOST));
+            // 埋め込みを読み上げるサーバーのために、埋め込みのある bot のメッセージは設定を確かめるまで残す
+            let is_bot_embed = message.author.bot
+                && !is_crosspost
+                && !message.embeds.is_empty()
+                && message.author.id != context.cache.current_user().id;
+            if message.author.bot && !is_crosspost && !is_bot_embed {
                 return;
             }
 
@@ -141,53 +382,72 @@ where
                     return;
                 },
             };
+            // 通話のロックを握ったまま接続の一覧を待つと、/join と互いに待ち合ってしまう
+            let joined_channel_id = self.connections.lock().await.get(&guild_id).copied();
             let call = manager.get_or_insert(guild_id);
-            let mut call = call.lock().await;
+            let mut call_guard = call.lock().await;
 
-            let (Some(_), Some(channel_id_bot_at)) = (call.current_connection(), call.current_channel()) else {
-                return;
+            let channel_id_bot_at = match (call_guard.current_connection(), call_guard.current_channel()) {
+                (Some(_), Some(channel_id)) => Some(SerenityChannelId::from(channel_id.0)),
+                _ => None,
             };
-            let channel_id_bot_at = SerenityChannelId::from(channel_id_bot_at.0);
-
-            let is_voice_channel_bot_at = {
-                let connections = self.connections.lock().await;
-                connections
-                    .get(&guild_id)
-                    .is_some_and(|channel_id| &message.channel_id == channel_id)
+            // ボイスチャンネルに接続できない間は、テキストで読み上げるチャンネルだけを読む
+            let text_only =
+                channel_id_bot_at.is_none() && self.text_fallbacks.channel(guild_id) == Some(message.channel_id);
+            let Some(channel_id_bot_at) = channel_id_bot_at.or(text_only.then_some(message.channel_id)) else {
+                return;
             };
-            let is_text_channel_binded_to_bot = message.channel_id == channel_id_bot_at;
 
-            if !is_voice_channel_bot_at && !is_text_channel_binded_to_bot {
-                return;
+            let settings = self.settings.guild(guild_id).await;
+            // ボイスチャンネルのチャットは、設定で外していなければ登録しなくても読み上げる
+            let is_text_channel_binded_to_bot =
+                message.channel_id == channel_id_bot_at && (text_only || settings.read_voice_chat);
+            if !is_text_channel_binded_to_bot {
+                let read_channels = self.settings.read_channels(guild_id).await;
+                // 読み上げるチャンネルが登録されていなければ /join を使ったチャンネルを読み上げる
+                let is_channel_read = |channel_id: SerenityChannelId| match read_channels.is_empty() {
+                    true => joined_channel_id == Some(channel_id),
+                    false => read_channels.contains(&(channel_id.get() as i64)),
+                };
+                if !is_channel_read(message.channel_id) {
+                    // スレッドは、設定していれば親のチャンネルを読み上げるかどうかに従う
+                    let parent_id = match settings.read_threads {
+                        true => thread_parent_id(&context, guild_id, message.channel_id).await,
+                        false => None,
+                    };
+                    if !parent_id.is_some_and(is_channel_read) {
+                        return;
+                    }
+                }
             }
 
-            let channel_bot_at = match channel_id_bot_at.to_channel(&context.http).await {
-                Ok(channel_bot_at) => channel_bot_at,
-                Err(error) => {
-                    tracing::error!("failed to get channel: {channel_id_bot_at:?}\nError: {error:?}");
+            if is_crosspost {
+                if !settings.read_crossposts {
                     return;
-                },
-            };
-
-            let serenity::all::Channel::Guild(channel_bot_at) = channel_bot_at else {
+                }
+            } else if is_bot_embed {
+                if !settings.read_embeds {
+                    return;
+                }
+            } else if !text_only && !users_in(&context, guild_id, channel_id_bot_at).contains(&message.author.id) {
                 return;
-            };
+            }
 
-            let members = match channel_bot_at.members(&context.cache) {
-                Ok(members) => members,
-                Err(error) => {
-                    tracing::error!("failed to get members in channel: {channel_bot_at:?}\nError: {error:?}");
-                    return;
-                },
-            };
-            if !members
-                .into_iter()
-                .map(|member| member.user)
-                .any(|user| message.author == user)
-            {
+            if self.settings.is_muted(guild_id, message.author.id).await {
                 return;
             }
 
+            // Discord の読み上げ機能と二重に読み上げないようにする
+            if message.tts {
+                match settings.tts_messages() {
+                    TtsMessages::Skip => return,
+                    TtsMessages::Read => {},
+                    TtsMessages::Guide => {
+                        guide_tts_messages(&context, guild_id, message.channel_id, &self.tts_guides).await;
+                    },
+                }
+            }
+
             let channel_message_at = match message.channel_id.to_channel(&context.http).await {
                 Ok(channel_at) => channel_at,
                 Err(error) => {
@@ -200,18 +460,18 @@ where
                 return;
             };
 
-            if channel_message_at.kind == ChannelType::Voice && self.sounds.len() > 0 {
-                if !self.rate_limiter.check_rate_limit(message.author.id).await {
+            if channel_message_at.kind == ChannelType::Voice && !text_only && !self.sounds.is_empty() {
+                if !self.rate_limiter.check_rate_limit(guild_id, message.author.id).await {
                     return;
                 }
                 let os_string: OsString = message.content.clone().into();
                 if let Some(sound) = self.sounds.get(&os_string) {
-                    call.play(Track::from(sound.value().clone()).volume(0.02));
+                    call_guard.play(Track::from(sound.value().clone()).volume(SOUND_GAIN));
                     return;
                 }
             }
 
-            if !message.sticker_items.is_empty() {
+            if !message.sticker_items.is_empty() && !text_only {
                 let sticker_ids = message.sticker_items.into_iter().map(|v| v.id.get());
                 let soundstickers =
                     match database::soundsticker::fetch_by_ids(&self.database, sticker_ids.clone()).await {
@@ -248,102 +508,361 @@ where
                 return;
             }
 
-            let ids: Vec<i64> = vec![message.author.id.into()];
-            let speaker = match database::user::fetch_by_ids(&self.database, &ids).await {
-                Ok(users) => users
-                    .first()
-                    .unwrap_or(&database::user::User::default())
-                    .speaker_id
-                    .to_string(),
-                Err(error) => {
-                    tracing::error!("failed to fetch users by ids: {ids:?}\nError: {error:?}");
+            if settings.skip_commands {
+                let is_command = match settings.command_prefixes.as_deref() {
+                    Some(prefixes) => is_command_like(&message.content, prefixes.split_whitespace()),
+                    None => is_command_like(&message.content, DEFAULT_COMMAND_PREFIXES.iter().copied()),
+                };
+                if is_command {
                     return;
-                },
-            };
+                }
+            }
 
-            let default = database::user::UserSpeaker::default();
-            let speed =
-                match database::user::fetch_with_speaker_by_ids(&self.database, &[message.author.id.into()]).await {
-                    Ok(speakers) => speakers
-                        .first()
-                        .unwrap_or(&default)
-                        .speed
-                        .or(default.speed)
-                        .unwrap_or(1.2),
-                    Err(error) => {
-                        tracing::error!("failed to fetch speakers\nError: {error:?}");
-                        return;
-                    },
-                };
+            // 同じメンバーが続けて話している間は名前を読み上げない
+            let name_interval = Duration::from_secs(settings.name_interval.max(0) as u64);
+            let is_new_author = self
+                .last_authors
+                .record(guild_id, message.author.id, Instant::now(), name_interval);
+            let read_name = settings.read_names && is_new_author && !is_crosspost;
 
-            {
-                let dictionary = {
-                    let voicevox = get_voicevox(&context)
-                        .await
-                        .context("failed to get voicevox client for /dictionary command")
-                        .unwrap();
-                    let voicevox = voicevox.lock().await;
-                    voicevox.dictionary.clone()
-                };
-                let dictionary_words = dictionary
-                    .list()
-                    .await
-                    .map(|GetUserDictResult::Ok(list)| {
-                        list.values()
-                            .map(|item| to_half_width(&item.surface).into_owned())
-                            .collect::<Vec<_>>()
-                    })
-                    .unwrap_or_default();
-
-                let replaced = replace_message(
-                    &context,
+            let resolution = self
+                .settings
+                .voice(
+                    &self.voice_resolver,
+                    &self.speaker,
+                    guild_id,
                     &message,
-                    &self.kanatrans_host,
-                    self.kanatrans_port,
-                    &dictionary_words,
+                    settings.random_voices,
                 )
                 .await;
+            let Voice {
+                speaker,
+                speed,
+                pitch,
+                intonation,
+                morph,
+            } = resolution.voice;
+            let styles = speaker
+                .parse::<u16>()
+                .map(|speaker_id| self.speaker.styles_of(speaker_id))
+                .unwrap_or_default();
 
-                let truncated = truncate_message(&replaced, 150, "、以下省略");
+            // 合成中に他のメッセージの処理を止めないようにロックを外す
+            drop(call_guard);
 
-                for text in truncated.split('\n') {
-                    let text = text.trim();
+            // 起動直後はエラーにせず、サーバーごとに一度だけ知らせて読み上げを待たせる
+            if self.engine_pool.is_warming_up() && self.warm_up_notices.insert(guild_id) {
+                let embed = CreateEmbed::new()
+                    .description("エンジン起動中です。準備ができたら順に読み上げます。")
+                    .colour(Colour::FOOYOO);
+                if let Err(error) = message
+                    .channel_id
+                    .send_message(&context.http, CreateMessage::new().embed(embed))
+                    .await
+                {
+                    tracing::error!("failed to notify that engine is warming up\nError: {error:?}");
+                }
+            }
 
-                    if text.is_empty() {
-                        continue;
-                    }
+            let settings_resolver = self.settings.clone();
+            let paused_guilds = self.paused_guilds.clone();
+            let watchdog = self.watchdog.clone();
+            let audio_repository = self.audio_repository.clone();
+            let synthesis_scheduler = self.synthesis_scheduler.clone();
+            let recent_audios = self.recent_audios.clone();
+            let usage_statistics = self.usage_statistics.clone();
+            let telemetry = self.telemetry.clone().filter(|_| settings.share_telemetry);
+            let engine_pool = self.engine_pool.clone();
+            let member_names = self.member_names.clone();
+            let volume = settings.volume;
+            let priority_tracks = self.priority_tracks.clone();
+            let priority = settings.priority_role_id.is_some_and(|priority_role_id| {
+                message.member.as_ref().is_some_and(|member| {
+                    member
+                        .roles
+                        .iter()
+                        .any(|role_id| role_id.get() as i64 == priority_role_id)
+                })
+            });
+            let reading_output = match text_only {
+                true => ReadingOutput::Text,
+                false => settings.reading_output(),
+            };
+            let furigana = settings.furigana;
+            let strip_name_decorations = settings.strip_name_decorations;
+            let name_patterns = settings.name_patterns.clone();
+            let jitter = settings.jitter;
+            let read_attachments = settings.read_attachments;
+            let read_replies = settings.read_replies;
+            let read_delays = settings.read_delays;
+            let spoilers = settings.spoilers();
+            let embeds = match settings.read_embeds {
+                true => embed::text_of(
+                    message
+                        .embeds
+                        .iter()
+                        .map(|embed| (embed.title.as_deref(), embed.description.as_deref())),
+                ),
+                false => None,
+            };
+            let beep = settings
+                .beep_sound
+                .as_ref()
+                .and_then(|name| self.sounds.get(&OsString::from(name)))
+                .map(|sound| sound.value().clone());
+            let furigana_channel_id = settings
+                .furigana_channel_id
+                .map(|channel_id| SerenityChannelId::new(channel_id as u64));
+            let read_context = read_context(&settings);
+            let kanatrans_host = self.kanatrans_host.clone();
+            let kanatrans_port = self.kanatrans_port;
+            self.reading_workers.submit(
+                guild_id,
+                Box::pin(async move {
+                    engine_pool.wait_until_warmed_up(WARM_UP_TIMEOUT).await;
 
-                    let audio = Audio {
-                        text: text.to_string(),
-                        speaker: speaker.clone(),
-                        speed: NotNan::new(speed).or(NotNan::new(Speaker::default_speed())).unwrap(),
-                    };
-                    match self.audio_repository.get(audio).await {
-                        Ok(input) => {
-                            call.enqueue_input(input).await;
+                    let mut timings = Timings::new();
+                    let readings = timings
+                        .measure_async(Stage::Dictionary, settings_resolver.readings(guild_id))
+                        .await;
+
+                    let mut message = message;
+                    // 先頭のショートカットで、このメッセージだけボイスのスタイルを変える
+                    let mut speaker = speaker;
+                    if let Some((style, text)) = style::apply(&message.content, &readings.style_shortcuts, &styles) {
+                        if let Some(style) = style {
+                            speaker = style.to_string();
+                        }
+                        message.content = text.to_string();
+                    }
+                    // 埋め込みも本文と同じように辞書や変換を通す
+                    if let Some(embeds) = &embeds {
+                        if !message.content.is_empty() {
+                            message.content.push('\n');
+                        }
+                        message.content.push_str(embeds);
+                    }
+                    // 伏せ字は辞書より先に置き換えて、読みを登録した単語からも漏れないようにする
+                    if let Cow::Owned(hidden) =
+                        timings.measure(Stage::Normalize, || hide_spoilers(&message.content, spoilers))
+                    {
+                        message.content = hidden;
+                    }
+                    message.mentions = timings
+                        .measure_async(
+                            Stage::Normalize,
+                            member_names.resolve(&context, guild_id, &message.mentions),
+                        )
+                        .await;
+                    let name = match read_name {
+                        true => {
+                            let name = spoken_name(
+                                &context,
+                                &member_names,
+                                guild_id,
+                                &message.author,
+                                strip_name_decorations,
+                                name_patterns.as_deref(),
+                                &read_context,
+                            )
+                            .await;
+                            Some(format!("{name}さん"))
                         },
-                        Err(error) => {
-                            tracing::error!("failed to get audio source\nError: {error:?}");
+                        false => None,
+                    };
+                    let reply = match read_replies && message.kind == MessageType::InlineReply {
+                        true => match replied_author(&context, &message).await {
+                            Some(author) => {
+                                let name = spoken_name(
+                                    &context,
+                                    &member_names,
+                                    guild_id,
+                                    &author,
+                                    strip_name_decorations,
+                                    name_patterns.as_deref(),
+                                    &read_context,
+                                )
+                                .await;
+                                Some(format!("{name}さんへの返信"))
+                            },
+                            // 返信先が削除されているなどで分からなければ、返信であることだけを読み上げる
+                            None => Some(PredefinedUtterance::Reply.as_ref().to_string()),
                         },
+                        false => None,
                     };
-                }
-
-                if !message.attachments.is_empty() {
-                    let audio = Audio {
-                        text: PredefinedUtterance::Attachment.as_ref().to_string(),
-                        speaker: speaker.clone(),
-                        speed: NotNan::new(speed).or(NotNan::new(Speaker::default_speed())).unwrap(),
+                    let mut replaced = replace_message(
+                        &context,
+                        &message,
+                        &read_context,
+                        &kanatrans_host,
+                        kanatrans_port,
+                        &readings,
+                        &mut timings,
+                    );
+                    if is_crosspost {
+                        replaced.insert_str(0, ANNOUNCEMENT_PREFIX);
+                    }
+                    let attachments = match read_attachments {
+                        true => attachment::describe(
+                            message
+                                .attachments
+                                .iter()
+                                .map(|attachment| (attachment.filename.as_str(), attachment.content_type.as_deref())),
+                        )
+                        .map(|text| seitai_converter::convert(&read_context, &text)),
+                        false => (!message.attachments.is_empty())
+                            .then(|| PredefinedUtterance::Attachment.as_ref().to_string()),
                     };
-                    match self.audio_repository.get(audio).await {
-                        Ok(input) => {
-                            call.enqueue_input(input).await;
-                        },
-                        Err(error) => {
-                            tracing::error!("failed to get audio source\nError: {error:?}");
-                        },
+
+                    // 再接続の後などに遅れて読み上げる場合は、いつ送られたかを先に読む
+                    let delay = read_delays
+                        .then(|| delay::relative_time(delay::since(message.timestamp)))
+                        .flatten();
+                    let texts = resolution
+                        .announcement
+                        .iter()
+                        .map(String::as_str)
+                        .chain(delay.as_deref())
+                        .chain(name.as_deref())
+                        .chain(reply.as_deref())
+                        .chain(replaced.split('\n'))
+                        .chain(attachments.as_deref());
+                    // CPU で合成している場合は短い行をまとめて合成の回数を減らす
+                    let texts = engine_pool.utterances(texts);
+                    let mut summary = TextSummary::new();
+                    let mut read = Vec::new();
+                    let mut usage = Usage {
+                        messages: 1,
+                        ..Default::default()
                     };
-                }
-            }
+                    let mut jitter = jitter.then(Jitter::new);
+                    for text in &texts {
+                        let text = text.trim();
+
+                        if text.is_empty() {
+                            continue;
+                        }
+                        if text == BEEP
+                            && let Some(beep) = &beep
+                        {
+                            if reading_output.posts_text() {
+                                summary.push(BEEP_READING);
+                            }
+                            if reading_output.plays_voice() {
+                                let mut call = call.lock().await;
+                                call.enqueue(Track::from(beep.clone()).volume(SOUND_GAIN)).await;
+                                if paused_guilds.contains(&guild_id)
+                                    && let Err(error) = call.queue().pause()
+                                {
+                                    tracing::error!("failed to pause track\nError: {error:?}");
+                                }
+                            }
+                            continue;
+                        }
+                        let text = match text {
+                            BEEP => BEEP_READING,
+                            text => text,
+                        };
+                        if reading_output.posts_text() {
+                            summary.push(text);
+                        }
+                        if !reading_output.plays_voice() {
+                            continue;
+                        }
+
+                        let (speed, pitch) = match &mut jitter {
+                            Some(jitter) => jitter.apply(speed, pitch),
+                            None => (speed, pitch),
+                        };
+                        let audio = Audio {
+                            text: text.to_string(),
+                            speaker: speaker.clone(),
+                            speed: NotNan::new(speed).or(NotNan::new(Speaker::default_speed())).unwrap(),
+                            pitch: NotNan::new(pitch).or(NotNan::new(Speaker::default_pitch())).unwrap(),
+                            intonation: NotNan::new(intonation)
+                                .or(NotNan::new(Speaker::default_intonation()))
+                                .unwrap(),
+                            morph: morph.clone(),
+                        };
+                        let slot = synthesis_scheduler.acquire(guild_id).await;
+                        let input = audio_repository.fetch(audio.clone()).await;
+                        drop(slot);
+                        match input {
+                            Ok((input, cached)) => {
+                                usage.characters += audio.text.chars().count() as i64;
+                                usage.syntheses += 1;
+                                usage.cache_hits += i64::from(cached);
+                                let expected = engine_pool.expected_duration(&audio.text, speed);
+                                read.push(audio);
+                                timings
+                                    .measure_async(Stage::Enqueue, async {
+                                        let mut call = call.lock().await;
+                                        let track = priority_tracks
+                                            .enqueue(&mut call, guild_id, input, volume, priority)
+                                            .await;
+                                        if let Some(expected) = expected {
+                                            watchdog.expect(track.uuid(), expected);
+                                        }
+                                        // 空のキューに追加した発話はすぐに再生されるので止め直す
+                                        if paused_guilds.contains(&guild_id)
+                                            && let Err(error) = call.queue().pause()
+                                        {
+                                            tracing::error!("failed to pause track\nError: {error:?}");
+                                        }
+                                    })
+                                    .await;
+                            },
+                            Err(error) => {
+                                tracing::error!("failed to get audio source\nError: {error:?}");
+                                if let Some(telemetry) = &telemetry {
+                                    telemetry.record_engine_error().await;
+                                }
+                            },
+                        };
+                    }
+
+                    if let Err(error) = summary.post(&context, &message).await {
+                        tracing::error!("failed to post reading of message {}\nError: {error:?}", message.id);
+                    }
+
+                    if furigana {
+                        let mut readings = Furigana::new();
+                        for text in replaced
+                            .split('\n')
+                            .map(str::trim)
+                            .filter(|text| !text.is_empty() && *text != BEEP)
+                        {
+                            // 読み上げと同じテキストなのでアクセント句はキャッシュが使われる
+                            match engine_pool
+                                .generate_query(&speaker, text, speed, pitch, intonation)
+                                .await
+                            {
+                                Ok(query) => readings.push(text, &query),
+                                Err(error) => {
+                                    tracing::error!("failed to generate audio query for furigana\nError: {error:?}");
+                                },
+                            }
+                        }
+                        if let Err(error) = readings.post(&context, &message, furigana_channel_id).await {
+                            tracing::error!("failed to post furigana of message {}\nError: {error:?}", message.id);
+                        }
+                    }
+
+                    if let Some(telemetry) = &telemetry
+                        && !read.is_empty()
+                    {
+                        telemetry.record_message(timings.total()).await;
+                    }
+                    recent_audios.record(guild_id, read);
+                    if let Ok(speaker_id) = speaker.parse::<i32>() {
+                        usage_statistics
+                            .record(guild_id, message.author.id, speaker_id, usage)
+                            .await;
+                    }
+
+                    tracing::debug!("read message {}: {timings}", message.id);
+                }),
+            );
         })
     }
 
@@ -352,7 +871,7 @@ where
         &'s self,
         context: Context,
         ready: Ready,
-    ) -> Pin<Box<(dyn Future<Output = ()> + Send + 'async_trait)>>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
     where
         Self: 'async_trait,
         's: 'async_trait,
@@ -360,21 +879,19 @@ where
         tracing::info!("{} is ready", ready.user.name);
 
         Box::pin(async move {
+            self.presence.update(&context).await;
+            self.presence.start_refreshing(context.clone());
+            self.watchdog.spawn(context.clone(), self.paused_guilds.clone());
+            self.announcer.spawn(
+                context.clone(),
+                self.database.clone(),
+                self.settings.clone(),
+                self.paused_guilds.clone(),
+                self.audio_repository.clone(),
+            );
+
             for guild in ready.guilds {
-                let commands = guild
-                    .id
-                    .set_commands(
-                        &context.http,
-                        vec![
-                            commands::dictionary::register(),
-                            commands::help::register(),
-                            commands::join::register(),
-                            commands::leave::register(),
-                            commands::voice::register(),
-                            commands::soundsticker::register(),
-                        ],
-                    )
-                    .await;
+                let commands = guild.id.set_commands(&context.http, commands::register_all()).await;
 
                 if let Err(error) = commands {
                     tracing::error!("failed to regeister slash commands\nError: {error:?}");
@@ -383,12 +900,162 @@ where
         })
     }
 
+    fn cache_ready<'s, 'async_trait>(
+        &'s self,
+        context: Context,
+        guild_ids: Vec<GuildId>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        's: 'async_trait,
+    {
+        Box::pin(async move {
+            restore_connections(&context, &self.database, &guild_ids, &self.connections).await;
+        })
+    }
+
+    fn guild_member_update<'s, 'async_trait>(
+        &'s self,
+        _context: Context,
+        _old_if_available: Option<Member>,
+        _new: Option<Member>,
+        event: GuildMemberUpdateEvent,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        's: 'async_trait,
+    {
+        Box::pin(async move {
+            // ニックネームの変更を次のメンションから反映する
+            self.member_names.forget(event.guild_id, event.user.id);
+        })
+    }
+
+    fn channel_update<'s, 'async_trait>(
+        &'s self,
+        context: Context,
+        old: Option<GuildChannel>,
+        new: GuildChannel,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        's: 'async_trait,
+    {
+        Box::pin(async move {
+            // 名前や権限の変更ではトピックを読み直さない
+            if old.is_some_and(|old| old.topic == new.topic) {
+                return;
+            }
+            let is_bound = {
+                let connections = self.connections.lock().await;
+                connections
+                    .get(&new.guild_id)
+                    .is_some_and(|channel_id| channel_id == &new.id)
+            };
+            if !is_bound {
+                return;
+            }
+
+            let settings = self.settings.guild(new.guild_id).await;
+            let topic = new.topic.unwrap_or_default();
+            if !settings.read_topics || !self.topics.update(new.id, &topic) {
+                return;
+            }
+
+            let manager = match get_manager(&context).await {
+                Ok(manager) => manager,
+                Err(error) => {
+                    tracing::error!("{error:?}");
+                    return;
+                },
+            };
+            let Some(call) = manager.get(new.guild_id) else {
+                return;
+            };
+            topic::read(&mut *call.lock().await, &self.audio_repository, &topic, &settings).await;
+        })
+    }
+
+    fn reaction_add<'s, 'async_trait>(
+        &'s self,
+        context: Context,
+        reaction: Reaction,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        's: 'async_trait,
+    {
+        Box::pin(async move {
+            let Some(guild_id) = reaction.guild_id else {
+                return;
+            };
+
+            let manager = match get_manager(&context).await {
+                Ok(manager) => manager,
+                Err(error) => {
+                    tracing::error!("{error:?}");
+                    return;
+                },
+            };
+            let Some(call) = manager.get(guild_id) else {
+                return;
+            };
+
+            let is_bound = {
+                let connections = self.connections.lock().await;
+                connections
+                    .get(&guild_id)
+                    .is_some_and(|channel_id| &reaction.channel_id == channel_id)
+            };
+            let is_voice_channel_bot_at = call
+                .lock()
+                .await
+                .current_channel()
+                .is_some_and(|channel_id| channel_id.0 == reaction.channel_id.into());
+            if !is_bound && !is_voice_channel_bot_at {
+                return;
+            }
+
+            let settings = self.settings.guild(guild_id).await;
+            let Some(threshold) = settings.celebration_threshold else {
+                return;
+            };
+
+            let message = match reaction.message(&context.http).await {
+                Ok(message) => message,
+                Err(error) => {
+                    tracing::error!(
+                        "failed to get message {} to count reactions\nError: {error:?}",
+                        reaction.message_id
+                    );
+                    return;
+                },
+            };
+            let counts = message.reactions.iter().map(|reaction| reaction.count);
+            if !celebration::reaches(counts, threshold) || !self.celebrations.mark(message.id) {
+                return;
+            }
+
+            let name = settings
+                .celebration_sound
+                .as_deref()
+                .unwrap_or(DEFAULT_CELEBRATION_SOUND);
+            let Some(sound) = self.sounds.get(&OsString::from(name)) else {
+                tracing::warn!("celebration sound {name} is not found");
+                return;
+            };
+            call.lock()
+                .await
+                .play(Track::from(sound.value().clone()).volume(SOUND_GAIN));
+        })
+    }
+
     fn voice_state_update<'s, 'async_trait>(
         &'s self,
         context: Context,
         old_state: Option<VoiceState>,
         new_state: VoiceState,
-    ) -> Pin<Box<(dyn Future<Output = ()> + Send + 'async_trait)>>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
     where
         Self: 'async_trait,
         's: 'async_trait,
@@ -423,7 +1090,14 @@ where
                 if is_disconnected {
                     let mut connections = self.connections.lock().await;
                     connections.remove(&guild_id);
+                    self.paused_guilds.remove(&guild_id);
+                    if let Err(error) = database::last_connection::disconnect(&self.database, guild_id.get()).await {
+                        tracing::error!("failed to record disconnection of guild {guild_id}\nError: {error:?}");
+                    }
+                } else {
+                    self.text_fallbacks.recover(guild_id);
                 }
+                self.presence.update(&context).await;
                 return;
             }
 
@@ -434,11 +1108,34 @@ where
                 Some(old_state) => old_state.channel_id != new_state.channel_id,
                 None => true,
             };
+
+            if channel_id_bot_at.is_none() && !is_disconnected && newly_connected {
+                // 接続中は通話をロックできない
+                drop(call);
+                handle_auto_join(
+                    &context,
+                    &self.database,
+                    &self.audio_repository,
+                    &new_state,
+                    &self.connections,
+                    &self.topics,
+                )
+                .await;
+                return;
+            }
             let is_connected_bot_at = new_state.channel_id == channel_id_bot_at;
 
             if !is_disconnected && newly_connected && is_connected_bot_at {
-                let mut connections = self.connections.lock().await;
-                handle_connect(&self.audio_repository, &new_state, &mut call, is_bot, &mut connections).await;
+                handle_connect(
+                    &context,
+                    &self.database,
+                    &self.audio_repository,
+                    &new_state,
+                    &mut call,
+                    is_bot,
+                    &self.connections,
+                )
+                .await;
                 return;
             }
 
@@ -456,16 +1153,7 @@ where
                 if channel.kind != ChannelType::Voice {
                     return;
                 }
-                let members = match channel.members(&context.cache) {
-                    Ok(members) => members,
-                    Err(error) => {
-                        tracing::error!(
-                            "failed to get members in channel {channel_id_bot_at} to check alone\nError: {error:?}"
-                        );
-                        return;
-                    },
-                };
-                let ids = members.iter().map(|v| v.user.id).collect::<Vec<_>>();
+                let ids = users_in(&context, guild_id, channel_id_bot_at);
                 let is_alone = ids != vec![bot_id];
                 if is_alone {
                     return;
@@ -479,114 +1167,339 @@ where
     }
 }
 
-async fn replace_message<'a>(
+/// Rejoins the voice channels the bot had not left before a restart or a reconnection to the gateway cut it off.
+async fn restore_connections(
     context: &Context,
-    message: &'a Message,
-    _kanatrans_host: &str,
-    _kanatrans_port: u16,
-    _dictionary_words: &[String],
-) -> Cow<'a, str> {
-    let Some(guild_id) = message.guild_id else {
-        return Cow::Borrowed(&message.content);
+    database: &PgPool,
+    guild_ids: &[GuildId],
+    connections: &Mutex<HashMap<GuildId, SerenityChannelId>>,
+) {
+    let last_connections = match database::last_connection::fetch_connected(database).await {
+        Ok(last_connections) => last_connections,
+        Err(error) => {
+            tracing::error!("failed to fetch connections to restore\nError: {error:?}");
+            return;
+        },
+    };
+    let manager = match get_manager(context).await {
+        Ok(manager) => manager,
+        Err(error) => {
+            tracing::error!("{error:?}");
+            return;
+        },
     };
 
-    let replacements = [
-        Replacement::General(&regex::CODE, "\nコード省略\n"),
-        Replacement::General(&regex::URL, "\nURL\n"),
-        Replacement::General(&regex::WW, "$1ワラワラ$2"),
-        Replacement::General(&regex::W, "$1ワラ$2"),
-        Replacement::General(&regex::IDEOGRAPHIC_FULL_STOP, "。\n"),
-        Replacement::General(&regex::EMOJI, ""), // 絵文字は読み上げない
-        Replacement::Katakana(&regex::WORD),
-    ];
-
-    let text = normalize(context, &guild_id, &message.mentions, &message.content);
-    stream::iter(replacements.into_iter())
-        .fold(text, |accumulator, replacement| async move {
-            match replacement {
-                Replacement::General(regex, replacer) => match regex.replace_all(&accumulator, replacer) {
-                    Cow::Borrowed(borrowed) if borrowed.len() == accumulator.len() => accumulator,
-                    Cow::Borrowed(borrowed) => Cow::Owned(borrowed.to_owned()),
-                    Cow::Owned(owned) => Cow::Owned(owned),
-                },
-                Replacement::Katakana(_regex) => {
-                    let cloned = accumulator.into_owned();
-                    let text_opt = detect_lang(&cloned);
-                    Cow::Owned(
-                        text_opt
-                            .filter(|&opt| opt == Lang::Jpn)
-                            .map_or_else(|| cloned.to_hiragana(), |_| cloned.to_string()),
-                    )
-                },
+    for last_connection in last_connections {
+        let guild_id = GuildId::new(last_connection.guild_id as u64);
+        if !guild_ids.contains(&guild_id) {
+            continue;
+        }
+        if let Some(call) = manager.get(guild_id)
+            && call.lock().await.current_connection().is_some()
+        {
+            continue;
+        }
+
+        let voice_channel_id = SerenityChannelId::new(last_connection.voice_channel_id as u64);
+        let text_channel_id = SerenityChannelId::new(last_connection.text_channel_id as u64);
+        // 誰もいなくなったチャンネルには戻らない
+        if users_in(context, guild_id, voice_channel_id).is_empty() {
+            if let Err(error) = database::last_connection::disconnect(database, guild_id.get()).await {
+                tracing::error!("failed to record disconnection of guild {guild_id}\nError: {error:?}");
             }
-        })
+            continue;
+        }
+
+        match commands::join::connect(
+            context,
+            database,
+            guild_id,
+            voice_channel_id,
+            text_channel_id,
+            connections,
+        )
         .await
+        {
+            Ok(_) => tracing::info!("restored connection to channel {voice_channel_id} in guild {guild_id}"),
+            Err(error) => {
+                tracing::error!("failed to restore connection to channel {voice_channel_id}\nError: {error:?}");
+            },
+        }
+    }
 }
 
-fn truncate_str(s: &str, max_chars: usize) -> &str {
-    match s.char_indices().nth(max_chars) {
-        None => s,
-        Some((idx, _)) => &s[..idx],
+/// Joins the voice channel bound by `/autojoin` in the same way as `/join` when the first human enters it.
+async fn handle_auto_join<Repository>(
+    context: &Context,
+    database: &PgPool,
+    audio_repository: &Repository,
+    state: &VoiceState,
+    connections: &Mutex<HashMap<GuildId, SerenityChannelId>>,
+    topics: &Topics,
+) where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let (Some(guild_id), Some(channel_id)) = (state.guild_id, state.channel_id) else {
+        return;
+    };
+    if state.member.as_ref().is_some_and(|member| member.user.bot) {
+        return;
+    }
+
+    let text_channel_id = match database::auto_join::fetch_by_voice_channel_id(database, channel_id.get()).await {
+        Ok(Some(text_channel_id)) => SerenityChannelId::new(text_channel_id as u64),
+        Ok(None) => return,
+        Err(error) => {
+            tracing::error!("failed to fetch auto join of channel {channel_id}\nError: {error:?}");
+            return;
+        },
+    };
+
+    // キャッシュにメンバーがいない場合は人として数える
+    let humans = context.cache.guild(guild_id).map_or(0, |guild| {
+        guild
+            .voice_states
+            .values()
+            .filter(|voice_state| voice_state.channel_id == Some(channel_id))
+            .filter(|voice_state| !voice_state.member.as_ref().is_some_and(|member| member.user.bot))
+            .count()
+    });
+    if humans != 1 {
+        return;
+    }
+
+    let connected =
+        commands::join::connect(context, database, guild_id, channel_id, text_channel_id, connections).await;
+    if let Err(error) = connected {
+        tracing::error!("failed to join channel {channel_id} automatically\nError: {error:?}");
+        return;
+    }
+    if let Err(error) =
+        commands::join::read_topic(context, database, audio_repository, topics, guild_id, text_channel_id).await
+    {
+        tracing::error!("failed to read topic of channel {text_channel_id}\nError: {error:?}");
     }
 }
 
-fn truncate_message<'a>(message: &'a str, limit: usize, suffix: &str) -> Cow<'a, str> {
-    if message.len() > limit {
-        Cow::Owned(format!("{}{}", truncate_str(message, limit), suffix))
-    } else {
-        Cow::Borrowed(message)
+/// Channel which the thread was created in, or `None` if the channel is not a thread.
+async fn thread_parent_id(
+    context: &Context,
+    guild_id: GuildId,
+    channel_id: SerenityChannelId,
+) -> Option<SerenityChannelId> {
+    if let Some(guild) = context.cache.guild(guild_id) {
+        if guild.channels.contains_key(&channel_id) {
+            return None;
+        }
+        if let Some(thread) = guild.threads.iter().find(|thread| thread.id == channel_id) {
+            return parent_of_thread(thread);
+        }
+    }
+    match channel_id.to_channel(&context.http).await {
+        Ok(serenity::all::Channel::Guild(channel)) => parent_of_thread(&channel),
+        Ok(_) => None,
+        Err(error) => {
+            tracing::error!("failed to get channel {channel_id} to find its parent\nError: {error:?}");
+            None
+        },
+    }
+}
+
+// チャンネルの親はカテゴリーなので、スレッドのときだけ親を返す
+fn parent_of_thread(channel: &GuildChannel) -> Option<SerenityChannelId> {
+    match channel.kind {
+        ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread => channel.parent_id,
+        _ => None,
     }
 }
 
+/// Name of the user read aloud, resolved into the name in the guild and without decorations if the guild chooses.
+async fn spoken_name(
+    context: &Context,
+    member_names: &MemberNames,
+    guild_id: GuildId,
+    user: &User,
+    strip_decorations: bool,
+    patterns: Option<&str>,
+    read_context: &ReadContext,
+) -> String {
+    let users = member_names.resolve(context, guild_id, slice::from_ref(user)).await;
+    let mention = user.mention().to_string();
+    let name = normalize(context, &guild_id, &users, &mention);
+    let name = core_name(name.trim_start_matches('@'), strip_decorations, patterns);
+    seitai_converter::convert(read_context, &name)
+}
+
+/// Author of the message which the message replies to, looked up in the cache and then over HTTP unless Discord sends
+/// it along.
+async fn replied_author(context: &Context, message: &Message) -> Option<User> {
+    if let Some(referenced) = &message.referenced_message {
+        return Some(referenced.author.clone());
+    }
+    let reference = message.message_reference.as_ref()?;
+    let message_id = reference.message_id?;
+    if let Some(referenced) = context.cache.message(reference.channel_id, message_id) {
+        return Some(referenced.author.clone());
+    }
+    match reference.channel_id.message(&context.http, message_id).await {
+        Ok(referenced) => Some(referenced.author),
+        Err(error) => {
+            tracing::debug!("failed to fetch message {message_id} replied to\nError: {error:?}");
+            None
+        },
+    }
+}
+
+/// Suggests denying the permission to send text-to-speech messages, at most once in the interval for each guild.
+async fn guide_tts_messages(
+    context: &Context,
+    guild_id: GuildId,
+    channel_id: SerenityChannelId,
+    tts_guides: &Mutex<TimeKeeper<GuildId>>,
+) {
+    {
+        let mut tts_guides = tts_guides.lock().await;
+        if tts_guides.is_elapsed(&guild_id, TTS_GUIDE_INTERVAL) {
+            return;
+        }
+        tts_guides.record(guild_id);
+    }
+
+    let embed = CreateEmbed::new()
+        .description(
+            "Discord の読み上げ機能で送られたメッセージは二重に読み上げられます。\n\
+             サーバー設定の「ロール」や「チャンネルの編集」で「テキスト読み上げメッセージを送信」の権限を外してください。",
+        )
+        .colour(Colour::FOOYOO);
+    if let Err(error) = channel_id
+        .send_message(&context.http, CreateMessage::new().embed(embed))
+        .await
+    {
+        tracing::error!("failed to guide tts messages in channel {channel_id}\nError: {error:?}");
+    }
+}
+
+fn replace_message(
+    context: &Context,
+    message: &Message,
+    read_context: &ReadContext,
+    _kanatrans_host: &str,
+    _kanatrans_port: u16,
+    readings: &Readings,
+    timings: &mut Timings,
+) -> String {
+    let text = timings.measure(Stage::Normalize, || match message.guild_id {
+        Some(guild_id) => normalize(context, &guild_id, &message.mentions, &message.content),
+        None => Cow::Borrowed(message.content.as_str()),
+    });
+    let text = timings.measure(Stage::Dictionary, || {
+        // 禁止された単語は読みを登録していても読み上げない
+        let text = seitai_converter::blacklist::mute(&text, &readings.blacklist, BEEP_LINE);
+        let emojis = readings
+            .emojis
+            .iter()
+            .map(|emoji| (emoji.emoji_id as u64, emoji.reading.as_str()))
+            .collect::<Vec<_>>();
+        let text = seitai_converter::emoji::apply(&text, &emojis);
+        let words = readings
+            .words
+            .iter()
+            .map(|reading| (reading.surface.as_str(), reading.reading.as_str()))
+            .collect::<Vec<_>>();
+        seitai_converter::reading::apply(&text, &words).into_owned()
+    });
+    timings.measure(Stage::Convert, || seitai_converter::convert(read_context, &text))
+}
+
 async fn handle_connect<Repository>(
+    context: &Context,
+    database: &PgPool,
     audio_repository: &Repository,
     state: &VoiceState,
     call: &mut Call,
     is_bot: bool,
-    connections: &mut HashMap<GuildId, SerenityChannelId>,
+    connections: &Mutex<HashMap<GuildId, SerenityChannelId>>,
 ) where
     Repository: AudioRepository<Input = Input> + Send + Sync,
 {
+    let Some(guild_id) = state.guild_id else {
+        return;
+    };
+
     if is_bot {
-        let (Some(guild_id), Some(channel_id)) = (state.guild_id, state.channel_id) else {
+        if let Some(channel_id) = state.channel_id {
+            connections.lock().await.insert(guild_id, channel_id);
+        }
+        return;
+    }
+
+    let settings = match database::guild::fetch_by_id(database, guild_id.get()).await {
+        Ok(settings) => settings,
+        Err(error) => {
+            tracing::error!("failed to fetch settings of guild {guild_id}\nError: {error:?}");
             return;
-        };
-        connections.insert(guild_id, channel_id);
+        },
+    };
+    if !settings.announce_joins {
+        return;
     }
 
-    let user_is = (!is_bot)
-        .then(|| {
-            let member = state.member.as_ref()?;
-            let user = &member.user;
-            let name = member.nick.as_ref().or(user.global_name.as_ref()).unwrap_or(&user.name);
-            Some(format!("{name}さんが"))
-        })
-        .flatten();
+    let user_is = state.member.as_ref().map(|member| {
+        let user = &member.user;
+        let name = member.nick.as_ref().or(user.global_name.as_ref()).unwrap_or(&user.name);
+        let name = core_name(name, settings.strip_name_decorations, settings.name_patterns.as_deref());
+        format!("{name}さんが")
+    });
     let connected = Some(PredefinedUtterance::Connected.as_ref().to_string());
-
-    /*
-    let inputs = stream::iter([user_is, connected].into_iter().flatten())
-        .map(async |text| {
-            let audio = Audio {
-                text,
-                speaker: SYSTEM_SPEAKER.to_string(),
-                speed: NotNan::new(Speaker::default_speed()).unwrap(),
-            };
-            match audio_repository.get(audio).await {
-                Ok(input) => Some(input),
-                Err(error) => {
-                    tracing::error!("failed to get audio source\nError: {error:?}");
-                    None
-                },
-            }
+    // プレゼンスは GUILD_PRESENCES インテントが有効な場合にのみキャッシュされる
+    let activity = settings
+        .announce_activities
+        .then(|| {
+            let guild = context.cache.guild(guild_id)?;
+            let presence = guild.presences.get(&state.user_id)?;
+            describe_activities(&presence.activities)
         })
-        .collect::<Vec<_>>()
-        .await;
+        .flatten()
+        .map(|activity| seitai_converter::convert(&ReadContext::default(), &activity));
 
-    for input in join_all(inputs).await.into_iter().flatten() {
-        call.enqueue_input(input).await;
+    for text in [user_is, connected, activity].into_iter().flatten() {
+        let audio = system_audio(text, settings.default_speaker_id);
+        match audio_repository.get(audio).await {
+            Ok(input) => {
+                enqueue(call, input, settings.volume).await;
+            },
+            Err(error) => {
+                tracing::error!("failed to get audio source to announce join\nError: {error:?}");
+                return;
+            },
+        }
     }
-    */
+}
+
+/// Describes what the member is doing, preferring games and the like over the custom status.
+fn describe_activities(activities: &[Activity]) -> Option<String> {
+    let describe = |activity: &Activity| {
+        let name = &activity.name;
+        match activity.kind {
+            ActivityType::Playing => Some(format!("{name}をプレイ中です")),
+            ActivityType::Streaming => Some(format!("{name}を配信中です")),
+            ActivityType::Listening => Some(format!("{name}を聴いています")),
+            ActivityType::Watching => Some(format!("{name}を視聴中です")),
+            ActivityType::Competing => Some(format!("{name}に参加中です")),
+            _ => None,
+        }
+    };
+    let custom = |activity: &Activity| {
+        (activity.kind == ActivityType::Custom)
+            .then(|| activity.state.clone())
+            .flatten()
+            .filter(|state| !state.is_empty())
+    };
+
+    activities
+        .iter()
+        .find_map(describe)
+        .or_else(|| activities.iter().find_map(custom))
 }
 
 async fn _request<RequestBody, Response>(url: Url, request: Request<RequestBody>) -> Result<(StatusCode, Response)>
@@ -614,3 +1527,36 @@ where
 
     Ok((status, json))
 }
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::{ChannelId, ChannelType, GuildChannel};
+
+    use super::parent_of_thread;
+
+    fn channel(kind: ChannelType, parent_id: u64) -> GuildChannel {
+        let mut channel = GuildChannel::default();
+        channel.kind = kind;
+        channel.parent_id = Some(ChannelId::new(parent_id));
+        channel
+    }
+
+    #[test]
+    fn find_parent_only_of_threads() {
+        assert_eq!(
+            parent_of_thread(&channel(ChannelType::PublicThread, 1)),
+            Some(ChannelId::new(1))
+        );
+        assert_eq!(
+            parent_of_thread(&channel(ChannelType::PrivateThread, 2)),
+            Some(ChannelId::new(2))
+        );
+        assert_eq!(
+            parent_of_thread(&channel(ChannelType::NewsThread, 3)),
+            Some(ChannelId::new(3))
+        );
+        // カテゴリーに属するチャンネルはスレッドではない
+        assert_eq!(parent_of_thread(&channel(ChannelType::Text, 4)), None);
+        assert_eq!(parent_of_thread(&channel(ChannelType::Voice, 5)), None);
+    }
+}
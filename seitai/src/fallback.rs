@@ -0,0 +1,71 @@
+use dashmap::DashMap;
+use serenity::all::{ChannelId, GuildId};
+
+// 何回続けて接続に失敗したらテキストでの読み上げを提案するか
+const FAILURES_TO_OFFER: u32 = 2;
+
+/// Text channels read as text while the bot cannot join the voice channel, and how many times in a row it has failed.
+#[derive(Debug, Default)]
+pub(crate) struct TextFallbacks {
+    failures: DashMap<GuildId, u32>,
+    channels: DashMap<GuildId, ChannelId>,
+}
+
+impl TextFallbacks {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a failure to join and returns whether failures have repeated enough to offer reading as text.
+    pub(crate) fn record_failure(&self, guild_id: GuildId) -> bool {
+        let mut failures = self.failures.entry(guild_id).or_default();
+        *failures += 1;
+        *failures >= FAILURES_TO_OFFER
+    }
+
+    /// Reads messages in the text channel as text until the bot joins a voice channel.
+    pub(crate) fn start(&self, guild_id: GuildId, channel_id: ChannelId) {
+        self.channels.insert(guild_id, channel_id);
+    }
+
+    /// Stops reading as text and returns whether it was.
+    pub(crate) fn stop(&self, guild_id: GuildId) -> bool {
+        self.channels.remove(&guild_id).is_some()
+    }
+
+    /// Forgets the failures and stops reading as text once the bot has joined a voice channel.
+    pub(crate) fn recover(&self, guild_id: GuildId) {
+        self.failures.remove(&guild_id);
+        if self.stop(guild_id) {
+            tracing::info!("voice of guild {guild_id} has recovered, stopping reading as text");
+        }
+    }
+
+    /// Text channel read as text in the guild, if any.
+    pub(crate) fn channel(&self, guild_id: GuildId) -> Option<ChannelId> {
+        self.channels.get(&guild_id).map(|channel_id| *channel_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::{ChannelId, GuildId};
+
+    use super::TextFallbacks;
+
+    #[test]
+    fn offer_after_repeated_failures_until_recovered() {
+        let fallbacks = TextFallbacks::new();
+        let guild_id = GuildId::new(1);
+        let channel_id = ChannelId::new(2);
+
+        assert!(!fallbacks.record_failure(guild_id));
+        assert!(fallbacks.record_failure(guild_id));
+        fallbacks.start(guild_id, channel_id);
+        assert_eq!(fallbacks.channel(guild_id), Some(channel_id));
+
+        fallbacks.recover(guild_id);
+        assert_eq!(fallbacks.channel(guild_id), None);
+        assert!(!fallbacks.record_failure(guild_id));
+    }
+}
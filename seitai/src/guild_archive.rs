@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use database::PgPool;
+use serde::Serialize;
+use serenity::all::{ChannelId, GuildId};
+use voicevox::dictionary::Dictionary;
+
+use crate::settings_file::SettingsFile;
+
+// 形式を変えたら上げる
+const VERSION: u32 = 1;
+// 書き出す利用状況の日数
+const USAGE_DAYS: i32 = 365;
+
+/// Everything stored about a guild, written by `/config export-all` for the owner of the guild.
+///
+/// The settings are in the same form as `/settings export`, so that they can be read by `/settings import` on another
+/// instance.
+#[derive(Debug, Serialize)]
+pub(crate) struct GuildArchive {
+    version: u32,
+    guild_id: u64,
+    settings: SettingsFile,
+    share_telemetry: bool,
+    priority_role_id: Option<u64>,
+    role_voices: Vec<RoleVoiceEntry>,
+    user_voices: Vec<UserVoiceEntry>,
+    muted_users: Vec<MutedUserEntry>,
+    emoji_readings: Vec<EmojiReadingEntry>,
+    blacklisted_words: Vec<String>,
+    style_shortcuts: Vec<StyleShortcutEntry>,
+    scheduled_announcements: Vec<ScheduledAnnouncementEntry>,
+    auto_joins: Vec<AutoJoinEntry>,
+    last_connection: Option<ConnectionEntry>,
+    rate_limit: Option<RateLimitEntry>,
+    uploaded_sounds: Vec<UploadedSoundEntry>,
+    soundstickers: Vec<SoundstickerEntry>,
+    usage_days: i32,
+    usage: Vec<UsageEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct RoleVoiceEntry {
+    role_id: u64,
+    speaker_id: u16,
+}
+
+#[derive(Debug, Serialize)]
+struct UserVoiceEntry {
+    user_id: u64,
+    speaker_id: u16,
+}
+
+#[derive(Debug, Serialize)]
+struct MutedUserEntry {
+    user_id: u64,
+    muted_by: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct EmojiReadingEntry {
+    emoji_id: u64,
+    name: String,
+    reading: String,
+}
+
+#[derive(Debug, Serialize)]
+struct StyleShortcutEntry {
+    prefix: String,
+    style: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ScheduledAnnouncementEntry {
+    schedule: String,
+    text: String,
+    created_by: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct AutoJoinEntry {
+    voice_channel_id: u64,
+    text_channel_id: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ConnectionEntry {
+    voice_channel_id: u64,
+    text_channel_id: u64,
+    connected: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct RateLimitEntry {
+    max_messages: i32,
+    time_window: i32,
+    base_cooldown: i32,
+    max_cooldown: i32,
+    cooldown_multiplier: f32,
+    violation_reset_time: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct UploadedSoundEntry {
+    name: String,
+    user_id: u64,
+    size: u32,
+    duration_ms: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct SoundstickerEntry {
+    sticker_id: u64,
+    sticker_name: String,
+    sound_id: u64,
+    sound_name: String,
+    sound_guild_id: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct UsageEntry {
+    speaker_id: u16,
+    messages: i64,
+    characters: i64,
+    syntheses: i64,
+    cache_hits: i64,
+}
+
+impl GuildArchive {
+    /// Reads everything stored about the guild, naming the channels in the settings with `channels`.
+    pub(crate) async fn export(
+        database: &PgPool,
+        dictionary: &Dictionary,
+        guild_id: GuildId,
+        channels: &HashMap<ChannelId, String>,
+    ) -> Result<Self> {
+        let settings = SettingsFile::export(database, dictionary, guild_id, channels).await?;
+        let guild = database::guild::fetch_by_id(database, guild_id.get()).await?;
+
+        let role_voices = database::role_voice::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|role_voice| RoleVoiceEntry {
+                role_id: role_voice.role_id as u64,
+                speaker_id: role_voice.speaker_id as u16,
+            })
+            .collect();
+        let user_voices = database::user::fetch_in_guild(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|user_voice| UserVoiceEntry {
+                user_id: user_voice.user_id as u64,
+                speaker_id: user_voice.speaker_id as u16,
+            })
+            .collect();
+        let muted_users = database::guild_user_mute::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|mute| MutedUserEntry {
+                user_id: mute.user_id as u64,
+                muted_by: mute.muted_by.map(|user_id| user_id as u64),
+            })
+            .collect();
+        let emoji_readings = database::emoji_reading::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|emoji_reading| EmojiReadingEntry {
+                emoji_id: emoji_reading.emoji_id as u64,
+                name: emoji_reading.name,
+                reading: emoji_reading.reading,
+            })
+            .collect();
+        let blacklisted_words = database::blacklisted_word::fetch_by_guild_id(database, guild_id.get()).await?;
+        let style_shortcuts = database::style_shortcut::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|shortcut| StyleShortcutEntry {
+                prefix: shortcut.prefix,
+                style: shortcut.style,
+            })
+            .collect();
+        let scheduled_announcements = database::scheduled_announcement::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|announcement| ScheduledAnnouncementEntry {
+                schedule: announcement.schedule,
+                text: announcement.text,
+                created_by: announcement.created_by as u64,
+            })
+            .collect();
+        let auto_joins = database::auto_join::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|auto_join| AutoJoinEntry {
+                voice_channel_id: auto_join.voice_channel_id as u64,
+                text_channel_id: auto_join.text_channel_id as u64,
+            })
+            .collect();
+        let last_connection = database::last_connection::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .map(|connection| ConnectionEntry {
+                voice_channel_id: connection.voice_channel_id as u64,
+                text_channel_id: connection.text_channel_id as u64,
+                connected: connection.connected,
+            });
+        let rate_limit = database::guild_rate_limit::fetch_all(database)
+            .await?
+            .into_iter()
+            .find(|rate_limit| rate_limit.id as u64 == guild_id.get())
+            .map(|rate_limit| RateLimitEntry {
+                max_messages: rate_limit.max_messages,
+                time_window: rate_limit.time_window,
+                base_cooldown: rate_limit.base_cooldown,
+                max_cooldown: rate_limit.max_cooldown,
+                cooldown_multiplier: rate_limit.cooldown_multiplier,
+                violation_reset_time: rate_limit.violation_reset_time,
+            });
+        let uploaded_sounds = database::sound::fetch_uploaded_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|sound| UploadedSoundEntry {
+                name: sound.name,
+                user_id: sound.user_id,
+                size: sound.size,
+                duration_ms: sound.duration_ms,
+            })
+            .collect();
+        // スタンプがこのサーバーのものだけを書き出す
+        let soundstickers = database::soundsticker::fetch_all(database)
+            .await?
+            .into_iter()
+            .filter(|soundsticker| soundsticker.sticker_guild_id == Some(guild_id.get()))
+            .map(|soundsticker| SoundstickerEntry {
+                sticker_id: soundsticker.sticker_id,
+                sticker_name: soundsticker.sticker_name,
+                sound_id: soundsticker.sound_id,
+                sound_name: soundsticker.sound_name,
+                sound_guild_id: soundsticker.sound_guild_id,
+            })
+            .collect();
+        let usage = database::usage_statistic::fetch_by_guild_id(database, guild_id.get(), USAGE_DAYS)
+            .await?
+            .into_iter()
+            .map(|usage| UsageEntry {
+                speaker_id: usage.speaker_id as u16,
+                messages: usage.messages,
+                characters: usage.characters,
+                syntheses: usage.syntheses,
+                cache_hits: usage.cache_hits,
+            })
+            .collect();
+
+        Ok(Self {
+            version: VERSION,
+            guild_id: guild_id.get(),
+            settings,
+            share_telemetry: guild.share_telemetry,
+            priority_role_id: guild.priority_role_id.map(|role_id| role_id as u64),
+            role_voices,
+            user_voices,
+            muted_users,
+            emoji_readings,
+            blacklisted_words,
+            style_shortcuts,
+            scheduled_announcements,
+            auto_joins,
+            last_connection,
+            rate_limit,
+            uploaded_sounds,
+            soundstickers,
+            usage_days: USAGE_DAYS,
+            usage,
+        })
+    }
+}
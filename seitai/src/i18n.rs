@@ -0,0 +1,116 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use serde::Deserialize;
+use serenity::builder::CreateCommand;
+
+// 英語の説明を付ける Discord のロケール
+const ENGLISH_LOCALES: [&str; 2] = ["en-US", "en-GB"];
+
+static JA: LazyLock<Catalog> = LazyLock::new(|| load(include_str!("../locales/ja.json")));
+static EN: LazyLock<Catalog> = LazyLock::new(|| load(include_str!("../locales/en.json")));
+
+/// Messages of a locale, loaded from `locales/*.json`.
+#[derive(Debug, Default, Deserialize)]
+struct Catalog {
+    // コマンドの説明は日本語で登録するので、ほかのロケールにだけある
+    #[serde(default)]
+    commands: HashMap<String, String>,
+    #[serde(default)]
+    messages: HashMap<String, String>,
+}
+
+fn load(json: &str) -> Catalog {
+    serde_json::from_str(json).expect("locale file should be valid")
+}
+
+/// Language to respond in, resolved from the locale of the client of the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Locale {
+    Ja,
+    En,
+}
+
+impl Locale {
+    /// Resolves the locale from a Discord locale such as `ja` or `en-US`, in Japanese unless it is English.
+    pub(crate) fn from_tag(tag: &str) -> Self {
+        match tag.starts_with("en") {
+            true => Self::En,
+            false => Self::Ja,
+        }
+    }
+
+    fn catalog(self) -> &'static Catalog {
+        match self {
+            Self::Ja => &JA,
+            Self::En => &EN,
+        }
+    }
+}
+
+/// Message of the key in the locale, falling back to Japanese and then to the key itself.
+pub(crate) fn t(locale: Locale, key: &str) -> &str {
+    locale
+        .catalog()
+        .messages
+        .get(key)
+        .or_else(|| JA.messages.get(key))
+        .map_or(key, String::as_str)
+}
+
+/// Message of the key with `{name}` placeholders replaced by the arguments.
+pub(crate) fn t_with(locale: Locale, key: &str, arguments: &[(&str, &str)]) -> String {
+    arguments
+        .iter()
+        .fold(t(locale, key).to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Adds the descriptions of the command in other languages to the one registered in Japanese.
+pub(crate) fn localize_command(command: CreateCommand) -> CreateCommand {
+    let name = serde_json::to_value(&command)
+        .ok()
+        .and_then(|value| value.get("name")?.as_str().map(str::to_string));
+    match name.and_then(|name| EN.commands.get(&name)) {
+        Some(description) => ENGLISH_LOCALES.iter().fold(command, |command, locale| {
+            command.description_localized(*locale, description)
+        }),
+        None => command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{EN, JA, Locale, t, t_with};
+    use crate::commands::register_all;
+
+    #[test]
+    fn translate_every_message_and_command() {
+        let ja = JA.messages.keys().collect::<BTreeSet<_>>();
+        let en = EN.messages.keys().collect::<BTreeSet<_>>();
+        assert_eq!(ja, en);
+
+        for command in register_all() {
+            let command = serde_json::to_value(&command).unwrap();
+            let name = command["name"].as_str().unwrap();
+            assert!(EN.commands.contains_key(name), "{name} has no English description");
+            assert_eq!(
+                command["description_localizations"]["en-US"].as_str(),
+                Some(EN.commands[name].as_str())
+            );
+            // Discord に登録できるオプションは 25 個まで
+            assert!(
+                command["options"].as_array().map_or(0, Vec::len) <= 25,
+                "{name} has too many options"
+            );
+        }
+
+        assert_eq!(Locale::from_tag("en-GB"), Locale::En);
+        assert_eq!(Locale::from_tag("ko"), Locale::Ja);
+        assert_eq!(t(Locale::En, "common.detail"), "Details");
+        assert_eq!(t(Locale::En, "missing.key"), "missing.key");
+        assert_eq!(t_with(Locale::Ja, "stop.count", &[("count", "3")]), "3件");
+    }
+}
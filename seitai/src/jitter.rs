@@ -0,0 +1,63 @@
+use std::hash::{BuildHasher, RandomState};
+
+use crate::speaker::Speaker;
+
+// 揺らぎの幅（速さは割合、高さは VOICEVOX の pitchScale の値で、どちらも約 ±2.5%）
+const MAX_SPEED_RATE: f32 = 0.025;
+const MAX_PITCH_SHIFT: f32 = 0.025;
+
+/// Slight random variation of the speed and pitch of each utterance, so that long monologues sound less monotonous.
+#[derive(Debug)]
+pub(crate) struct Jitter {
+    state: u64,
+}
+
+impl Jitter {
+    pub(crate) fn new() -> Self {
+        Self::with_seed(RandomState::new().hash_one(0_u8))
+    }
+
+    /// Jitter which varies the same way every time for the same seed.
+    pub(crate) fn with_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Varies the speed and pitch of an utterance within the range the voices can be read with.
+    pub(crate) fn apply(&mut self, speed: f32, pitch: f32) -> (f32, f32) {
+        let speed = speed * (1.0 + self.next_unit() * MAX_SPEED_RATE);
+        let pitch = pitch + self.next_unit() * MAX_PITCH_SHIFT;
+        (
+            speed.clamp(Speaker::min_speed(), Speaker::max_speed()),
+            pitch.clamp(Speaker::min_pitch(), Speaker::max_pitch()),
+        )
+    }
+
+    // -1.0 以上 1.0 以下の値 (SplitMix64)
+    fn next_unit(&mut self) -> f32 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^= value >> 31;
+
+        ((value >> 40) as f32 / (1_u64 << 24) as f32) * 2.0 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Jitter, MAX_PITCH_SHIFT, MAX_SPEED_RATE};
+
+    #[test]
+    fn vary_within_range_deterministically() {
+        let mut jitter = Jitter::with_seed(42);
+        let varied = (0..100).map(|_| jitter.apply(1.2, 0.0)).collect::<Vec<_>>();
+        assert!(varied.iter().all(|(speed, pitch)| {
+            (speed - 1.2).abs() <= 1.2 * MAX_SPEED_RATE + f32::EPSILON && pitch.abs() <= MAX_PITCH_SHIFT
+        }));
+        assert!(varied.windows(2).any(|pair| pair[0] != pair[1]));
+
+        let mut jitter = Jitter::with_seed(42);
+        assert_eq!(jitter.apply(1.2, 0.0), varied[0]);
+    }
+}
@@ -1,41 +1,123 @@
-use std::{env, ffi::OsString, path::Path, process::exit, sync::Arc, time::Duration};
+use std::{
+    env,
+    ffi::OsString,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process::exit,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context as _, Error, Result};
 use cli::Application;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use database::{ConnectOptions, PgConnectOptions, PgPool, PgPoolOptions};
 use futures::lock::Mutex;
 use hashbrown::HashMap;
 use jwalk::WalkDir;
-use logging::initialize_logging;
-use serenity::{client::Client, model::gateway::GatewayIntents, prelude::TypeMapKey};
+use logging::{LogFilter, initialize_logging};
+use seitai_audio::{ConstCacheable, SongbirdAudioProcessor, VoicevoxAudioRepository};
+use serenity::{
+    all::{ChannelId, GuildId, Http},
+    cache::Settings as CacheSettings,
+    client::Client,
+    model::gateway::GatewayIntents,
+    prelude::TypeMapKey,
+};
 use songbird::{
     SerenityInit,
     input::{File, cached::Memory},
 };
 use time_keepr::TimeKeeper;
 use tracing::log::LevelFilter;
-use utils::RateLimiter;
+use url::Url;
+use utils::{RateLimit, RateLimiter};
 use voicevox::Voicevox;
 
 use crate::{
-    audio::{
-        VoicevoxAudioRepository,
-        cache::{ConstCacheable, PredefinedUtterance},
-        processor::SongbirdAudioProcessor,
-    },
+    announcement::Announcer,
+    api::Api,
+    authors::LastAuthors,
+    celebration::Celebrations,
+    engine::{EnginePool, ErrorBudget},
+    fallback::TextFallbacks,
+    member_names::MemberNames,
+    notifier::OwnerNotifier,
+    presence::Presence,
+    priority::PriorityTracks,
+    recent::RecentAudios,
+    resolver::VoiceResolver,
+    scheduler::SynthesisScheduler,
+    settings_resolver::SettingsResolver,
     speaker::Speaker,
+    statistics::UsageStatistics,
+    telemetry::Telemetry,
+    topic::Topics,
+    utterance::PredefinedUtterance,
+    voice_activity::VoiceActivity,
+    watchdog::Watchdog,
+    worker::ReadingWorkers,
 };
 
-mod audio;
-mod character_converter;
+mod announcement;
+mod api;
+mod attachment;
+mod authors;
+mod build_info;
+mod celebration;
+mod classification;
 mod cli;
 mod commands;
+mod connection;
+mod cron;
+#[cfg(feature = "self-host")]
+mod defaults;
+mod delay;
+mod dictionary;
+mod dictionary_csv;
+mod driver;
+mod embed;
+mod engine;
 mod event_handler;
-mod regex;
+mod fallback;
+mod guild_archive;
+mod i18n;
+mod jitter;
+mod member_names;
+mod metrics;
+mod notifier;
+mod output;
+mod presence;
+mod priority;
+mod quota;
+mod recent;
+mod replay;
+mod resolver;
+mod scheduler;
+mod settings_file;
+mod settings_resolver;
+mod sounds;
 mod speaker;
+mod statistics;
+mod style;
+mod telemetry;
 mod time_keepr;
+mod timing;
+mod topic;
 mod utils;
+mod utterance;
+mod voice_activity;
+mod watchdog;
+mod worker;
+
+// 省メモリモードで一時的なキャッシュを捨てるまでの時間
+const SLIM_CACHE_TIME_TO_LIVE: Duration = Duration::from_secs(10 * 60);
+// エンジン 1 台で同時に合成する数
+const SYNTHESIS_SLOTS_PER_ENGINE: usize = 2;
+// 読み上げの統計をまとめてデータベースに書き込む間隔
+const USAGE_STATISTICS_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+// 匿名の統計を送る間隔
+const TELEMETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 struct VoicevoxClient;
 
@@ -45,15 +127,16 @@ impl TypeMapKey for VoicevoxClient {
 
 #[tokio::main]
 async fn main() {
-    initialize_logging();
+    let log_filter = initialize_logging();
+    worker::install_panic_hook();
 
-    if let Err(err) = Application::start().await {
+    if let Err(err) = Application::start(log_filter).await {
         tracing::error!("failed to start application\nError: {err:?}");
         exit(1);
     };
 }
 
-pub async fn start_bot() {
+pub async fn start_bot(log_filter: LogFilter) {
     let token = match env::var("DISCORD_TOKEN") {
         Ok(token) => token,
         Err(error) => {
@@ -89,6 +172,89 @@ pub async fn start_bot() {
         },
     };
 
+    let owner_error_channel_id = match env::var("OWNER_ERROR_CHANNEL_ID")
+        .ok()
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse::<u64>())
+    {
+        Some(Ok(id)) => Some(ChannelId::new(id)),
+        Some(Err(error)) => {
+            tracing::error!("failed to parse environment variable OWNER_ERROR_CHANNEL_ID\nError: {error:?}");
+            exit(1);
+        },
+        None => None,
+    };
+    let support_server_url = env::var("SUPPORT_SERVER_URL").ok().filter(|url| !url.is_empty());
+    let bot_status = env::var("BOT_STATUS").ok().filter(|status| !status.is_empty());
+    // Developer Portal で Presence Intent を有効にしている場合のみ指定できる
+    let presence_intent = env::var("PRESENCE_INTENT").is_ok_and(|enabled| enabled == "true" || enabled == "1");
+    // メモリの少ないホスト向けに、読み上げに必要なインテントとキャッシュだけを使う
+    let slim_mode = env::var("SLIM_MODE").is_ok_and(|enabled| enabled == "true" || enabled == "1");
+
+    match env::var("METRICS_ADDR")
+        .ok()
+        .filter(|addr| !addr.is_empty())
+        .map(|addr| addr.parse::<SocketAddr>())
+    {
+        Some(Ok(addr)) => {
+            if let Err(error) = metrics::install(addr) {
+                tracing::error!("failed to set up metrics\nError: {error:?}");
+                exit(1);
+            }
+        },
+        Some(Err(error)) => {
+            tracing::error!("failed to parse environment variable METRICS_ADDR\nError: {error:?}");
+            exit(1);
+        },
+        None => {},
+    };
+
+    // 送信先を指定したデプロイでのみ、オプトインしたサーバーの匿名の統計を送る
+    let telemetry = match env::var("TELEMETRY_ENDPOINT")
+        .ok()
+        .filter(|endpoint| !endpoint.is_empty())
+        .map(|endpoint| Url::parse(&endpoint))
+    {
+        Some(Ok(endpoint)) => Some(Arc::new(Telemetry::new(endpoint))),
+        Some(Err(error)) => {
+            tracing::error!("failed to parse environment variable TELEMETRY_ENDPOINT\nError: {error:?}");
+            exit(1);
+        },
+        None => None,
+    };
+    if let Some(telemetry) = &telemetry {
+        telemetry.spawn_sending(TELEMETRY_INTERVAL);
+    }
+
+    let api_addr = match env::var("API_ADDR")
+        .ok()
+        .filter(|addr| !addr.is_empty())
+        .map(|addr| addr.parse::<SocketAddr>())
+    {
+        Some(Ok(addr)) => Some(addr),
+        Some(Err(error)) => {
+            tracing::error!("failed to parse environment variable API_ADDR\nError: {error:?}");
+            exit(1);
+        },
+        None => None,
+    };
+    // 辞書を誰でも編集できないように、API を有効にするならトークンを必須にする
+    let api_token = env::var("API_TOKEN").ok().filter(|token| !token.is_empty());
+    if api_addr.is_some() && api_token.is_none() {
+        tracing::error!("environment variable API_TOKEN is required to serve api");
+        exit(1);
+    }
+
+    let driver_config = match driver::config_from_env() {
+        Ok(driver_config) => driver_config,
+        Err(error) => {
+            tracing::error!("failed to configure voice driver\nError: {error:?}");
+            exit(1);
+        },
+    };
+
+    let notifier = OwnerNotifier::new(Arc::new(Http::new(&token)), owner_error_channel_id);
+
     let pool = match set_up_database().await {
         Ok(pool) => pool,
         Err(error) => {
@@ -96,6 +262,12 @@ pub async fn start_bot() {
             exit(1);
         },
     };
+    // 1 つのバイナリだけで動かせるように、組み込んだマイグレーションを起動時に適用する
+    #[cfg(feature = "self-host")]
+    if let Err(error) = apply_migrations(&pool).await {
+        tracing::error!("failed to apply migrations\nError: {error:?}");
+        exit(1);
+    }
 
     let voicevox = match set_up_voicevox().await {
         Ok(voicevox) => voicevox,
@@ -105,6 +277,20 @@ pub async fn start_bot() {
         },
     };
 
+    if let (Some(addr), Some(token)) = (api_addr, api_token) {
+        let api = Api {
+            dictionary: voicevox.dictionary.clone(),
+            database: pool.clone(),
+            token,
+        };
+        tokio::spawn(async move {
+            if let Err(error) = api::serve(addr, api).await {
+                tracing::error!("failed to serve api\nError: {error:?}");
+                exit(1);
+            }
+        });
+    }
+
     let speaker = match Speaker::build(&voicevox).await {
         Ok(speaker) => speaker,
         Err(error) => {
@@ -113,11 +299,31 @@ pub async fn start_bot() {
         },
     };
 
+    let engine_pool = match set_up_engine_pool(notifier.clone()) {
+        Ok(engine_pool) => engine_pool,
+        Err(error) => {
+            tracing::error!("failed to set up voicevox engine pool\nError: {error:?}");
+            exit(1);
+        },
+    };
+    engine_pool.spawn_prober(Duration::from_secs(15));
+    // 既定のボイスは最初の読み上げで使われやすいので、先にモデルを読み込んでおく
+    engine_pool.spawn_warm_up(
+        vec![database::user::DEFAULT_SPEAKER_ID.to_string()],
+        Duration::from_secs(15),
+    );
+
+    let synthesis_scheduler = SynthesisScheduler::new(engine_pool.engine_count() * SYNTHESIS_SLOTS_PER_ENGINE);
+
     let audio_repository = VoicevoxAudioRepository::new(
-        voicevox.audio_generator.clone(),
+        engine_pool.clone(),
         SongbirdAudioProcessor,
         ConstCacheable::<PredefinedUtterance>::new(),
     );
+    let cache_statistics = audio_repository.statistics();
+
+    let usage_statistics = Arc::new(UsageStatistics::new());
+    usage_statistics.spawn_flushing(pool.clone(), USAGE_STATISTICS_FLUSH_INTERVAL);
 
     if !ss_direcotry.is_empty() && !Path::new(&ss_direcotry).exists() {
         tracing::error!("{} is not exists.", ss_direcotry);
@@ -125,29 +331,66 @@ pub async fn start_bot() {
     };
     let sounds: DashMap<OsString, Memory> = DashMap::new();
     if !ss_direcotry.is_empty() {
-        for entry in WalkDir::new(ss_direcotry).into_iter().flatten() {
+        for entry in WalkDir::new(&ss_direcotry).into_iter().flatten() {
             let path = entry.path();
-            if let Some(ext) = path.extension() {
-                if ext == "mp3" || ext == "wav" || ext == "opus" || path.file_stem().is_some() {
-                    let file = File::new(path.clone());
-                    match Memory::new(file.into()).await {
-                        Ok(memory) => {
-                            sounds.insert(path.file_stem().unwrap().to_owned(), memory);
-                        },
-                        Err(error) => {
-                            tracing::error!("{error:?}");
-                            continue;
-                        },
-                    };
-                }
+            if let Some(ext) = path.extension()
+                && (ext == "mp3" || ext == "wav" || ext == "opus" || path.file_stem().is_some())
+            {
+                let file = File::new(path.clone());
+                match Memory::new(file.into()).await {
+                    Ok(memory) => {
+                        sounds.insert(path.file_stem().unwrap().to_owned(), memory);
+                    },
+                    Err(error) => {
+                        tracing::error!("{error:?}");
+                        continue;
+                    },
+                };
             }
         }
 
         tracing::info!("{} files found!", sounds.len());
     };
 
-    let intents = GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT;
+    let mut intents = match slim_mode {
+        true => {
+            GatewayIntents::GUILDS
+                | GatewayIntents::GUILD_VOICE_STATES
+                | GatewayIntents::GUILD_MESSAGES
+                | GatewayIntents::GUILD_MESSAGE_REACTIONS
+                | GatewayIntents::MESSAGE_CONTENT
+        },
+        false => GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT,
+    };
+    if presence_intent {
+        intents |= GatewayIntents::GUILD_PRESENCES;
+    }
+    let mut cache_settings = CacheSettings::default();
+    if slim_mode {
+        // メンバーは一括で取得せず、必要になったものだけを REST で取得する
+        cache_settings.max_messages = 0;
+        cache_settings.cache_users = false;
+        cache_settings.time_to_live = SLIM_CACHE_TIME_TO_LIVE;
+    }
+    let rate_limiter = RateLimiter::new(RateLimit::new(2, 3, 20, 60, 1.5, 1));
+    match database::guild_rate_limit::fetch_all(&pool).await {
+        Ok(rate_limits) => {
+            for rate_limit in rate_limits {
+                rate_limiter.set_limit(GuildId::new(rate_limit.id as u64), rate_limit.into());
+            }
+        },
+        Err(error) => {
+            tracing::error!("failed to fetch rate limits of guilds\nError: {error:?}");
+        },
+    }
+    let settings = Arc::new(SettingsResolver::new(pool.clone(), notifier.clone()));
+    let paused_guilds = Arc::new(DashSet::new());
+    let voice_activity = VoiceActivity {
+        settings: settings.clone(),
+        paused_guilds: paused_guilds.clone(),
+    };
     let mut client = match Client::builder(token, intents)
+        .cache_settings(cache_settings)
         .event_handler(event_handler::Handler {
             database: pool,
             speaker,
@@ -157,9 +400,34 @@ pub async fn start_bot() {
             kanatrans_host,
             kanatrans_port,
             sounds: Arc::new(sounds),
-            rate_limiter: RateLimiter::new(2, 3, 20, 60, 1.5, 1),
+            sound_directory: (!ss_direcotry.is_empty()).then(|| PathBuf::from(&ss_direcotry)),
+            rate_limiter,
+            started_at: Instant::now(),
+            support_server_url,
+            log_filter,
+            reading_workers: ReadingWorkers::new(notifier.clone()),
+            voice_resolver: VoiceResolver::new(),
+            settings,
+            member_names: Arc::new(MemberNames::new()),
+            celebrations: Celebrations::new(),
+            presence: Arc::new(Presence::new(bot_status)),
+            engine_pool,
+            cache_statistics,
+            synthesis_scheduler,
+            tts_guides: Arc::new(Mutex::new(TimeKeeper::new())),
+            last_authors: LastAuthors::new(),
+            recent_audios: Arc::new(RecentAudios::new()),
+            warm_up_notices: DashSet::new(),
+            paused_guilds,
+            watchdog: Arc::new(Watchdog::new()),
+            announcer: Arc::new(Announcer::new()),
+            topics: Topics::new(),
+            text_fallbacks: TextFallbacks::new(),
+            priority_tracks: PriorityTracks::new(),
+            usage_statistics,
+            telemetry,
         })
-        .register_songbird()
+        .register_songbird_from_config(driver_config)
         .await
     {
         Ok(client) => client,
@@ -173,6 +441,7 @@ pub async fn start_bot() {
         let mut data = client.data.write().await;
 
         data.insert::<VoicevoxClient>(Arc::new(Mutex::new(voicevox)));
+        data.insert::<VoiceActivity>(voice_activity);
     }
 
     tokio::spawn(async move {
@@ -189,6 +458,8 @@ pub async fn set_up_database() -> Result<PgPool> {
     let pg_options = PgConnectOptions::new()
         .log_statements(LevelFilter::Debug)
         .log_slow_statements(LevelFilter::Warn, Duration::from_millis(500));
+    #[cfg(feature = "self-host")]
+    let pg_options = defaults::postgres(pg_options);
 
     PgPoolOptions::new()
         .max_connections(5)
@@ -198,9 +469,24 @@ pub async fn set_up_database() -> Result<PgPool> {
         .context("failed to set up database")
 }
 
+#[cfg(feature = "self-host")]
+async fn apply_migrations(pool: &PgPool) -> Result<()> {
+    let mut connection = pool.acquire().await?;
+    database::migrations::Migrator::new().apply_all(&mut connection).await
+}
+
 async fn set_up_voicevox() -> Result<Voicevox> {
-    let voicevox_host = env::var("VOICEVOX_HOST").context("failed to fetch environment variable VOICEVOX_HOST")?;
-    Voicevox::build(&voicevox_host).context("failed to build voicevox client")
+    let voicevox_hosts = env::var("VOICEVOX_HOST").context("failed to fetch environment variable VOICEVOX_HOST")?;
+    // 辞書や話者の取得には先頭のホストを使う
+    let voicevox_host = voicevox_hosts.split(',').next().unwrap_or_default().trim();
+    Voicevox::build(voicevox_host).context("failed to build voicevox client")
+}
+
+fn set_up_engine_pool(notifier: OwnerNotifier) -> Result<EnginePool> {
+    let voicevox_hosts = env::var("VOICEVOX_HOST").context("failed to fetch environment variable VOICEVOX_HOST")?;
+    let voicevox_hosts = voicevox_hosts.split(',').map(str::trim).collect::<Vec<_>>();
+    EnginePool::build(&voicevox_hosts, ErrorBudget::new(60, 5, 0.5, 60), notifier)
+        .context("failed to build voicevox engine pool")
 }
 
 pub(crate) async fn wait_for_signal() {
@@ -0,0 +1,73 @@
+use std::num::NonZeroUsize;
+
+use dashmap::DashMap;
+use lru::LruCache;
+use serenity::{
+    all::{GuildId, User, UserId},
+    client::Context,
+};
+
+// サーバーごとに覚えておくメンバーの数
+const CAPACITY: NonZeroUsize = NonZeroUsize::new(256).unwrap();
+
+/// Display names of members missing from the cache, e.g. right after startup, so mentions of them are read as names.
+pub(crate) struct MemberNames {
+    guilds: DashMap<GuildId, LruCache<UserId, String>>,
+}
+
+impl MemberNames {
+    pub(crate) fn new() -> Self {
+        Self { guilds: DashMap::new() }
+    }
+
+    /// Replaces the names of the users with their display names in the guild, fetching members missing from the cache.
+    ///
+    /// Members in the cache are left as they are since their display names are taken from the cache.
+    pub(crate) async fn resolve(&self, context: &Context, guild_id: GuildId, users: &[User]) -> Vec<User> {
+        let mut resolved = Vec::with_capacity(users.len());
+        for user in users {
+            let mut user = user.clone();
+            let is_cached = context
+                .cache
+                .guild(guild_id)
+                .is_some_and(|guild| guild.members.contains_key(&user.id));
+            if !is_cached && let Some(name) = self.name(context, guild_id, user.id).await {
+                user.name = name;
+            }
+            resolved.push(user);
+        }
+        resolved
+    }
+
+    /// Forgets the name of the member, e.g. when the nickname is changed.
+    pub(crate) fn forget(&self, guild_id: GuildId, user_id: UserId) {
+        if let Some(mut names) = self.guilds.get_mut(&guild_id) {
+            names.pop(&user_id);
+        }
+    }
+
+    async fn name(&self, context: &Context, guild_id: GuildId, user_id: UserId) -> Option<String> {
+        if let Some(name) = self
+            .guilds
+            .get_mut(&guild_id)
+            .and_then(|mut names| names.get(&user_id).cloned())
+        {
+            return Some(name);
+        }
+
+        let member = match guild_id.member(&context.http, user_id).await {
+            Ok(member) => member,
+            Err(error) => {
+                tracing::warn!("failed to fetch member {user_id} in guild {guild_id}\nError: {error:?}");
+                return None;
+            },
+        };
+        let name = member.display_name().to_string();
+        self.guilds
+            .entry(guild_id)
+            .or_insert_with(|| LruCache::new(CAPACITY))
+            .put(user_id, name.clone());
+
+        Some(name)
+    }
+}
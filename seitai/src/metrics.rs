@@ -0,0 +1,84 @@
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::{Context as _, Result};
+use metrics::{Unit, counter, describe_counter, describe_histogram, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+
+pub(crate) const WORKER_PANICS: &str = "seitai_reading_worker_panics_total";
+pub(crate) const PIPELINE_STAGE_SECONDS: &str = "seitai_pipeline_stage_seconds";
+pub(crate) const QUERY_CACHE_HITS: &str = "seitai_audio_query_cache_hits_total";
+pub(crate) const SYNTHESIS_WAIT_SECONDS: &str = "seitai_synthesis_wait_seconds";
+const INTERACTIONS: &str = "seitai_interactions_total";
+const INTERACTION_SECONDS: &str = "seitai_interaction_seconds";
+
+// Discord が最初の応答を待つ時間
+const INTERACTION_DEADLINE: Duration = Duration::from_secs(3);
+
+// 正規化などの数 ms から合成の数秒までを区別できるようにする
+const PIPELINE_STAGE_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+// 応答期限の 3 秒の前後を区別できるようにする
+const INTERACTION_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0, 30.0];
+
+/// Serves metrics for Prometheus to scrape at `addr`.
+pub(crate) fn install(addr: SocketAddr) -> Result<()> {
+    PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .set_buckets_for_metric(
+            Matcher::Full(PIPELINE_STAGE_SECONDS.to_string()),
+            PIPELINE_STAGE_BUCKETS,
+        )
+        .context("failed to set buckets")?
+        .set_buckets_for_metric(
+            Matcher::Full(SYNTHESIS_WAIT_SECONDS.to_string()),
+            PIPELINE_STAGE_BUCKETS,
+        )
+        .context("failed to set buckets")?
+        .set_buckets_for_metric(Matcher::Full(INTERACTION_SECONDS.to_string()), INTERACTION_BUCKETS)
+        .context("failed to set buckets")?
+        .install()
+        .context("failed to install prometheus exporter")?;
+
+    describe_counter!(WORKER_PANICS, Unit::Count, "Panics caught in per-guild reading workers");
+    describe_counter!(
+        QUERY_CACHE_HITS,
+        Unit::Count,
+        "Audio queries reused from the cache instead of asking the engine"
+    );
+    describe_histogram!(
+        PIPELINE_STAGE_SECONDS,
+        Unit::Seconds,
+        "Time spent in each stage of turning a message into audio"
+    );
+    describe_counter!(
+        INTERACTIONS,
+        Unit::Count,
+        "Interactions handled, by kind, command and whether they succeeded"
+    );
+    describe_histogram!(
+        INTERACTION_SECONDS,
+        Unit::Seconds,
+        "Time spent handling each interaction, by kind and command"
+    );
+    describe_histogram!(
+        SYNTHESIS_WAIT_SECONDS,
+        Unit::Seconds,
+        "Time each guild waited for its turn to synthesize"
+    );
+
+    Ok(())
+}
+
+/// Records how long the interaction of the command took, and warns when it took longer than Discord waits for the
+/// first response.
+///
+/// `kind` is either `command` or `autocomplete`, which are the interactions with the deadline.
+pub(crate) fn record_interaction(kind: &'static str, command: &str, elapsed: Duration, succeeded: bool) {
+    let outcome = if succeeded { "ok" } else { "error" };
+    counter!(INTERACTIONS, "kind" => kind, "command" => command.to_string(), "outcome" => outcome).increment(1);
+    histogram!(INTERACTION_SECONDS, "kind" => kind, "command" => command.to_string()).record(elapsed.as_secs_f64());
+
+    // 応答を保留したコマンドは期限を過ぎても失敗しないが、保留し忘れていないか確かめられるようにする
+    if elapsed > INTERACTION_DEADLINE {
+        tracing::warn!("{kind} of /{command} took {elapsed:?}, longer than the deadline of {INTERACTION_DEADLINE:?}");
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use serenity::{
+    all::{ChannelId, Http},
+    builder::{CreateEmbed, CreateMessage},
+};
+
+/// Posts operational alerts to the channel configured by `OWNER_ERROR_CHANNEL_ID`.
+#[derive(Clone)]
+pub(crate) struct OwnerNotifier {
+    http: Arc<Http>,
+    channel_id: Option<ChannelId>,
+}
+
+impl OwnerNotifier {
+    pub(crate) fn new(http: Arc<Http>, channel_id: Option<ChannelId>) -> Self {
+        Self { http, channel_id }
+    }
+
+    pub(crate) async fn notify(&self, embed: CreateEmbed) {
+        let Some(channel_id) = self.channel_id else {
+            return;
+        };
+
+        if let Err(error) = channel_id
+            .send_message(&self.http, CreateMessage::new().embed(embed))
+            .await
+        {
+            tracing::error!("failed to notify owner error channel {channel_id}\nError: {error:?}");
+        }
+    }
+}
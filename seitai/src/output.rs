@@ -0,0 +1,214 @@
+use anyhow::{Context as _, Result};
+use serenity::{
+    all::{ChannelId, Message},
+    builder::{CreateAllowedMentions, CreateMessage},
+    client::Context,
+};
+use voicevox::audio::AudioQuery;
+
+use crate::utils::truncate_chars;
+
+// Discord のメッセージの文字数の上限
+const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// Text sink of the reading, which collects what is read and posts it in reply to the message.
+#[derive(Debug, Default)]
+pub(crate) struct TextSummary {
+    lines: Vec<String>,
+}
+
+impl TextSummary {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, text: &str) {
+        self.lines.push(text.to_string());
+    }
+
+    /// Posts the collected text in reply to `message` without mentioning anyone, unless nothing is collected.
+    pub(crate) async fn post(self, context: &Context, message: &Message) -> Result<()> {
+        let Some(content) = self.content() else {
+            return Ok(());
+        };
+
+        let reply = CreateMessage::new()
+            .content(content)
+            .reference_message(message)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false));
+        message
+            .channel_id
+            .send_message(&context.http, reply)
+            .await
+            .context("failed to post text summary")?;
+
+        Ok(())
+    }
+
+    fn content(&self) -> Option<String> {
+        if self.lines.is_empty() {
+            return None;
+        }
+
+        let mut content = self
+            .lines
+            .iter()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        truncate_chars(&mut content, MAX_MESSAGE_LENGTH);
+        Some(content)
+    }
+}
+
+/// Sink of the kana readings of the message, for members learning Japanese to follow the pronunciations.
+#[derive(Debug, Default)]
+pub(crate) struct Furigana {
+    lines: Vec<(String, String)>,
+}
+
+impl Furigana {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, text: &str, query: &AudioQuery) {
+        let reading = reading(query);
+        if !reading.is_empty() {
+            self.lines.push((text.to_string(), reading));
+        }
+    }
+
+    /// Posts the readings in `channel_id` with a link to `message`, or in spoilers in reply to `message` if `None`.
+    pub(crate) async fn post(self, context: &Context, message: &Message, channel_id: Option<ChannelId>) -> Result<()> {
+        let Some(content) = self.content(channel_id.is_none()) else {
+            return Ok(());
+        };
+
+        let (channel_id, post) = match channel_id {
+            Some(channel_id) => (
+                channel_id,
+                CreateMessage::new().content(format!("{}\n{content}", message.link())),
+            ),
+            None => (
+                message.channel_id,
+                CreateMessage::new().content(content).reference_message(message),
+            ),
+        };
+        channel_id
+            .send_message(
+                &context.http,
+                post.allowed_mentions(CreateAllowedMentions::new().replied_user(false)),
+            )
+            .await
+            .context("failed to post furigana")?;
+
+        Ok(())
+    }
+
+    fn content(&self, spoiler: bool) -> Option<String> {
+        if self.lines.is_empty() {
+            return None;
+        }
+
+        let mut content = self
+            .lines
+            .iter()
+            .map(|(text, reading)| match spoiler {
+                true => format!("> {text}\n||{reading}||"),
+                false => format!("> {text}\n{reading}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        // 途中で切ると伏せ字が閉じなくなるので、収まらない行は省く
+        while content.chars().count() > MAX_MESSAGE_LENGTH {
+            match content.rfind("\n> ") {
+                Some(end) => content.truncate(end),
+                None => return None,
+            }
+        }
+        Some(content)
+    }
+}
+
+// アクセント句ごとにモーラのカナを空白で区切って並べる
+fn reading(query: &AudioQuery) -> String {
+    query
+        .accent_phrases
+        .iter()
+        .map(|accent_phrase| {
+            accent_phrase
+                .moras
+                .iter()
+                .chain(Some(&accent_phrase.pause_mora))
+                .filter_map(|mora| mora.get("text")?.as_str())
+                .collect::<String>()
+        })
+        .filter(|phrase| !phrase.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use voicevox::audio::AudioQuery;
+
+    use super::{Furigana, MAX_MESSAGE_LENGTH, TextSummary, reading};
+
+    #[test]
+    fn quote_lines_within_limit() {
+        assert_eq!(TextSummary::new().content(), None);
+
+        let mut summary = TextSummary::new();
+        summary.push("ほげさん");
+        summary.push("こんにちは");
+        assert_eq!(summary.content().as_deref(), Some("> ほげさん\n> こんにちは"));
+
+        let mut summary = TextSummary::new();
+        summary.push(&"あ".repeat(MAX_MESSAGE_LENGTH));
+        assert_eq!(summary.content().unwrap().chars().count(), MAX_MESSAGE_LENGTH);
+    }
+
+    #[test]
+    fn read_moras_by_accent_phrase() {
+        let query: AudioQuery = serde_json::from_value(json!({
+            "accent_phrases": [
+                {
+                    "moras": [{"text": "キョ"}, {"text": "オ"}, {"text": "ワ"}],
+                    "accent": 1.0,
+                    "pause_mora": {"text": "、"},
+                    "is_interrogative": false,
+                },
+                {
+                    "moras": [{"text": "ハ"}, {"text": "レ"}],
+                    "accent": 2.0,
+                    "pause_mora": null,
+                    "is_interrogative": false,
+                },
+            ],
+            "speedScale": 1.0,
+            "pitchScale": 0.0,
+            "intonationScale": 1.0,
+            "volumeScale": 1.0,
+            "prePhonemeLength": 0.1,
+            "postPhonemeLength": 0.1,
+            "outputSamplingRate": 24000,
+            "outputStereo": false,
+            "kana": null,
+        }))
+        .unwrap();
+        assert_eq!(reading(&query), "キョオワ、 ハレ");
+
+        let mut furigana = Furigana::new();
+        furigana.push("今日は、晴れ", &query);
+        assert_eq!(
+            furigana.content(true).as_deref(),
+            Some("> 今日は、晴れ\n||キョオワ、 ハレ||")
+        );
+        assert_eq!(
+            furigana.content(false).as_deref(),
+            Some("> 今日は、晴れ\nキョオワ、 ハレ")
+        );
+    }
+}
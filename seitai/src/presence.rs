@@ -0,0 +1,101 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use serenity::{client::Context, gateway::ActivityData};
+
+use crate::utils::get_manager;
+
+// 接続や切断を取りこぼしても表示がずれたままにならないように更新する間隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Activity of the bot, which shows the number of guilds it is reading aloud in unless a custom status is configured.
+pub(crate) struct Presence {
+    custom_status: Option<String>,
+    refreshing: AtomicBool,
+}
+
+impl Presence {
+    pub(crate) fn new(custom_status: Option<String>) -> Self {
+        Self {
+            custom_status,
+            refreshing: AtomicBool::new(false),
+        }
+    }
+
+    /// Sets the activity from the voice channels the bot is currently connected to.
+    pub(crate) async fn update(&self, context: &Context) {
+        let connections = match &self.custom_status {
+            Some(_) => 0,
+            None => match count_connections(context).await {
+                Some(connections) => connections,
+                None => return,
+            },
+        };
+        let status = describe(self.custom_status.as_deref(), connections);
+        context.set_activity(Some(ActivityData::custom(status)));
+    }
+
+    /// Keeps the activity up to date periodically, which is started only once even if the bot reconnects.
+    pub(crate) fn start_refreshing(self: &Arc<Self>, context: Context) {
+        if self.refreshing.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let presence = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                presence.update(&context).await;
+            }
+        });
+    }
+}
+
+async fn count_connections(context: &Context) -> Option<usize> {
+    let manager = match get_manager(context).await {
+        Ok(manager) => manager,
+        Err(error) => {
+            tracing::error!("failed to get manager to update presence\nError: {error:?}");
+            return None;
+        },
+    };
+
+    let calls = manager.iter().map(|(_, call)| call).collect::<Vec<_>>();
+    let mut connections = 0;
+    for call in calls {
+        if call.lock().await.current_channel().is_some() {
+            connections += 1;
+        }
+    }
+    Some(connections)
+}
+
+fn describe(custom_status: Option<&str>, connections: usize) -> String {
+    match custom_status {
+        Some(status) => status.to_string(),
+        None if connections == 0 => "待機中".to_string(),
+        None => format!("{connections} サーバーで読み上げ中"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe;
+
+    #[test]
+    fn describe_connections() {
+        assert_eq!(describe(None, 0), "待機中");
+        assert_eq!(describe(None, 3), "3 サーバーで読み上げ中");
+    }
+
+    #[test]
+    fn prefer_custom_status() {
+        assert_eq!(describe(Some("メンテナンス中"), 3), "メンテナンス中");
+    }
+}
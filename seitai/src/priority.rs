@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use hashbrown::HashSet;
+use serenity::all::GuildId;
+use songbird::{Call, input::Input, tracks::TrackHandle};
+use uuid::Uuid;
+
+use crate::utils::enqueue;
+
+/// Tracks of messages from the priority speakers of each guild, which are read before the others waiting in the queue
+/// of songbird.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PriorityTracks {
+    tracks: Arc<DashMap<GuildId, HashSet<Uuid>>>,
+}
+
+impl PriorityTracks {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enqueues the input at the volume of the guild in percent, and moves it ahead of the tracks waiting in the queue
+    /// if it has priority.
+    ///
+    /// The track being read is not interrupted, and tracks with priority are read in the order they are enqueued.
+    pub(crate) async fn enqueue(
+        &self,
+        call: &mut Call,
+        guild_id: GuildId,
+        input: Input,
+        volume: i32,
+        priority: bool,
+    ) -> TrackHandle {
+        let track = enqueue(call, input, volume).await;
+        if !priority {
+            return track;
+        }
+
+        let mut tracks = self.tracks.entry(guild_id).or_default();
+        call.queue().modify_queue(|queue| {
+            // 読み終わったトラックは忘れる
+            tracks.retain(|uuid| queue.iter().any(|queued| queued.uuid() == *uuid));
+            // 既に読み終わっていれば、後ろにあるのは別のトラック
+            if queue.back().is_none_or(|queued| queued.uuid() != track.uuid()) {
+                return;
+            }
+
+            let priorities = queue
+                .iter()
+                .take(queue.len().saturating_sub(1))
+                .map(|queued| tracks.contains(&queued.uuid()))
+                .collect::<Vec<_>>();
+            let position = position(&priorities);
+            if let Some(queued) = queue.pop_back() {
+                queue.insert(position, queued);
+            }
+        });
+        tracks.insert(track.uuid());
+
+        track
+    }
+}
+
+/// Position in the queue to insert a track with priority, behind the track being read and the other tracks with
+/// priority at the head of the queue.
+fn position(priorities: &[bool]) -> usize {
+    match priorities.split_first() {
+        Some((_current, waiting)) => 1 + waiting.iter().take_while(|priority| **priority).count(),
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::position;
+
+    #[test]
+    fn insert_behind_current_and_other_priorities() {
+        assert_eq!(position(&[]), 0);
+        assert_eq!(position(&[false]), 1);
+        assert_eq!(position(&[true, false, false]), 1);
+        assert_eq!(position(&[false, true, true, false, true]), 3);
+    }
+}
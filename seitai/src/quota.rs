@@ -0,0 +1,34 @@
+use anyhow::Result;
+use database::{PgPool, blacklisted_word, dictionary_word, emoji_reading, soundsticker};
+use serenity::all::GuildId;
+
+/// Maximum number of words a guild can register into the dictionary.
+pub(crate) const DICTIONARY_WORDS: i64 = 200;
+/// Maximum number of words a guild can register readings of.
+pub(crate) const READINGS: i64 = 200;
+/// Maximum number of custom emoji a guild can register readings of.
+pub(crate) const EMOJI_READINGS: i64 = 200;
+/// Maximum number of words a guild can ban from being read.
+pub(crate) const BLACKLISTED_WORDS: i64 = 200;
+/// Maximum number of stickers of a guild linked to sounds.
+pub(crate) const SOUNDSTICKERS: i64 = 50;
+/// Maximum number of announcements a guild can schedule.
+pub(crate) const SCHEDULED_ANNOUNCEMENTS: i64 = 10;
+
+pub(crate) struct Usage {
+    pub(crate) dictionary_words: i64,
+    pub(crate) readings: i64,
+    pub(crate) emoji_readings: i64,
+    pub(crate) blacklisted_words: i64,
+    pub(crate) soundstickers: i64,
+}
+
+pub(crate) async fn usage(database: &PgPool, guild_id: GuildId) -> Result<Usage> {
+    Ok(Usage {
+        dictionary_words: dictionary_word::count_by_guild_id(database, guild_id.get()).await?,
+        readings: dictionary_word::count_readings_by_guild_id(database, guild_id.get()).await?,
+        emoji_readings: emoji_reading::count_by_guild_id(database, guild_id.get()).await?,
+        blacklisted_words: blacklisted_word::count_by_guild_id(database, guild_id.get()).await?,
+        soundstickers: soundsticker::count_by_guild_id(database, guild_id.get()).await?,
+    })
+}
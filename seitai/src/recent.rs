@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+use dashmap::DashMap;
+use seitai_audio::Audio;
+use serenity::all::GuildId;
+
+// サーバーごとに覚えておくメッセージの数
+const CAPACITY: usize = 5;
+
+/// Audio of the messages recently read aloud in each guild, to read the last one again with `/replay`.
+#[derive(Debug, Default)]
+pub(crate) struct RecentAudios {
+    guilds: DashMap<GuildId, VecDeque<Vec<Audio>>>,
+}
+
+impl RecentAudios {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the audio read for a message, forgetting the oldest message if too many are recorded.
+    pub(crate) fn record(&self, guild_id: GuildId, audios: Vec<Audio>) {
+        if audios.is_empty() {
+            return;
+        }
+
+        let mut messages = self.guilds.entry(guild_id).or_default();
+        if messages.len() >= CAPACITY {
+            messages.pop_front();
+        }
+        messages.push_back(audios);
+    }
+
+    pub(crate) fn latest(&self, guild_id: GuildId) -> Option<Vec<Audio>> {
+        self.guilds.get(&guild_id).and_then(|messages| messages.back().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ordered_float::NotNan;
+    use seitai_audio::Audio;
+    use serenity::all::GuildId;
+
+    use super::{CAPACITY, RecentAudios};
+
+    fn audio(text: &str) -> Audio {
+        Audio {
+            text: text.to_string(),
+            speaker: database::user::DEFAULT_SPEAKER_ID.to_string(),
+            speed: NotNan::new(1.0).unwrap(),
+            pitch: NotNan::new(0.0).unwrap(),
+            intonation: NotNan::new(1.0).unwrap(),
+            morph: None,
+        }
+    }
+
+    #[test]
+    fn keep_recent_messages() {
+        let recent = RecentAudios::new();
+        let guild_id = GuildId::new(1);
+        assert_eq!(recent.latest(guild_id), None);
+
+        for index in 0..=CAPACITY {
+            recent.record(guild_id, vec![audio(&index.to_string())]);
+        }
+        recent.record(guild_id, Vec::new());
+        assert_eq!(recent.latest(guild_id), Some(vec![audio(&CAPACITY.to_string())]));
+        assert_eq!(recent.guilds.get(&guild_id).unwrap().len(), CAPACITY);
+        assert_eq!(recent.latest(GuildId::new(2)), None);
+    }
+}
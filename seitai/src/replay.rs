@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result};
+use database::PgPool;
+use seitai_converter::blacklist::BEEP_READING;
+
+use crate::{
+    set_up_voicevox,
+    speaker::Speaker,
+    utils::{hide_spoilers, read_context},
+};
+
+/// Reproduces how a message is read in a guild, writing the audio query and the audio of each line.
+#[derive(clap::Args)]
+pub(crate) struct ReplayCommand {
+    /// Guild whose readings and settings are applied
+    #[arg(long)]
+    guild_id: u64,
+    /// Speaker to synthesize with, which defaults to the default voice of the guild
+    #[arg(long)]
+    speaker: Option<String>,
+    #[arg(long, default_value_t = Speaker::default_speed())]
+    speed: f32,
+    #[arg(long, default_value_t = Speaker::default_pitch())]
+    pitch: f32,
+    #[arg(long, default_value_t = Speaker::default_intonation())]
+    intonation: f32,
+    /// Directory to write `<n>.json` and `<n>.wav` into
+    #[arg(long, default_value = ".")]
+    output: PathBuf,
+    /// Raw message text as it was sent
+    text: String,
+}
+
+impl ReplayCommand {
+    pub(crate) async fn run(&self, database: &PgPool) -> Result<()> {
+        let settings = database::guild::fetch_by_id(database, self.guild_id).await?;
+        let readings = database::dictionary_word::fetch_readings_by_guild_id(database, self.guild_id).await?;
+        let emoji_readings = database::emoji_reading::fetch_by_guild_id(database, self.guild_id).await?;
+        let blacklist = database::blacklisted_word::fetch_by_guild_id(database, self.guild_id).await?;
+        let read_context = read_context(&settings);
+        let speaker = self
+            .speaker
+            .clone()
+            .or_else(|| settings.default_speaker_id.map(|speaker_id| speaker_id.to_string()))
+            .unwrap_or_else(|| database::user::DEFAULT_SPEAKER_ID.to_string());
+
+        // メンションは解決できないので、送られたテキストのまま読みから先を再現する
+        let emoji_readings = emoji_readings
+            .iter()
+            .map(|emoji| (emoji.emoji_id as u64, emoji.reading.as_str()))
+            .collect::<Vec<_>>();
+        let readings = readings
+            .iter()
+            .map(|reading| (reading.surface.as_str(), reading.reading.as_str()))
+            .collect::<Vec<_>>();
+        let read = hide_spoilers(&self.text, settings.spoilers());
+        let read = seitai_converter::blacklist::mute(&read, &blacklist, BEEP_READING);
+        let read = seitai_converter::emoji::apply(&read, &emoji_readings);
+        let read = seitai_converter::reading::apply(&read, &readings).into_owned();
+        println!("input: {:?}", self.text);
+        if read != self.text.as_str() {
+            println!("reading: {read:?}");
+        }
+        let stages = seitai_converter::convert_with_stages(&read_context, &read);
+        for stage in &stages {
+            println!("{}: {:?}", stage.name, stage.text);
+        }
+        let converted = stages.last().map_or(&*read, |stage| stage.text.as_str());
+
+        let voicevox = set_up_voicevox().await?;
+        std::fs::create_dir_all(&self.output).with_context(|| format!("failed to create {}", self.output.display()))?;
+        let lines = converted.split('\n').map(str::trim).filter(|line| !line.is_empty());
+        for (index, line) in lines.enumerate() {
+            let audio_query = voicevox
+                .audio_generator
+                .query(&speaker, line, self.speed, self.pitch, self.intonation)
+                .await?;
+            let audio = voicevox
+                .audio_generator
+                .synthesize_query(&speaker, &audio_query)
+                .await?;
+
+            let query_path = self.output.join(format!("{index}.json"));
+            std::fs::write(&query_path, serde_json::to_string_pretty(&audio_query)?)
+                .with_context(|| format!("failed to write {}", query_path.display()))?;
+            let audio_path = self.output.join(format!("{index}.wav"));
+            std::fs::write(&audio_path, &audio).with_context(|| format!("failed to write {}", audio_path.display()))?;
+            println!("{line:?} -> {}, {}", query_path.display(), audio_path.display());
+        }
+
+        Ok(())
+    }
+}
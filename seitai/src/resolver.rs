@@ -0,0 +1,443 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use database::{PgPool, keyword_voice::KeywordVoice, role_voice::RoleVoice, user::UserVoices};
+use ordered_float::NotNan;
+use seitai_audio::Morph;
+use serenity::all::{GuildId, Message, RoleId};
+
+use crate::speaker::Speaker;
+
+// 日付の区切りは日本時間に合わせる
+const UTC_OFFSET_SECS: u64 = 9 * 60 * 60;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Voice a message is read aloud with.
+#[derive(Debug, Clone)]
+pub(crate) struct Voice {
+    pub(crate) speaker: String,
+    pub(crate) speed: f32,
+    pub(crate) pitch: f32,
+    pub(crate) intonation: f32,
+    pub(crate) morph: Option<Morph>,
+}
+
+/// Voice resolved for a message, with an utterance to be read before it.
+#[derive(Debug, Clone)]
+pub(crate) struct Resolution {
+    pub(crate) voice: Voice,
+    pub(crate) announcement: Option<String>,
+}
+
+/// Where the voice of a member comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VoiceSource {
+    /// Voice the member chose only for the guild.
+    Guild(i32),
+    /// Voice the member chose for every guild.
+    Global(i32),
+    /// Voice of the day of the guild.
+    GuildDefault(i32),
+    BotDefault,
+}
+
+impl Voice {
+    pub(crate) fn new(speaker_id: i32, speed: f32) -> Self {
+        Self {
+            speaker: speaker_id.to_string(),
+            speed,
+            pitch: Speaker::default_pitch(),
+            intonation: Speaker::default_intonation(),
+            morph: None,
+        }
+    }
+
+    /// Voice of members who have chosen none in a guild without the voice of the day, when the speed of the default
+    /// voice of the guild is unknown.
+    pub(crate) fn guild_default(default_speaker_id: Option<i32>) -> Self {
+        Self::new(
+            default_speaker_id.unwrap_or(database::user::DEFAULT_SPEAKER_ID),
+            Speaker::default_speed(),
+        )
+    }
+}
+
+/// Resolves the voice of messages from the preference of the author and the settings of the guild.
+#[derive(Debug, Default)]
+pub(crate) struct VoiceResolver {
+    // 今日の声を最後に知らせた日
+    announced_on: DashMap<GuildId, u64>,
+}
+
+impl VoiceResolver {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves the voice in order of the voices for the channel and keywords, the morphing, the voice the author chose,
+    /// the voice for the roles of the author and the voice of the day of the guild, which is read at the speed, pitch
+    /// and intonation the author chose if any.
+    ///
+    /// With `random_voices`, an author who has chosen no voice is given one picked from the user id instead of the voice
+    /// of the day, which is saved as the voice of the author in the guild.
+    pub(crate) async fn resolve(
+        &self,
+        database: &PgPool,
+        speaker: &Speaker,
+        guild_id: GuildId,
+        message: &Message,
+        random_voices: bool,
+    ) -> Result<Resolution> {
+        let mut resolution = self
+            .resolve_voice(database, speaker, guild_id, message, random_voices)
+            .await?;
+        // 速さはボイスに関わらずメンバーの設定を優先する
+        if let Some(speed) = database::user_speed::fetch_by_id(database, message.author.id.get()).await? {
+            resolution.voice.speed = speed;
+        }
+        let tone = database::user_tone::fetch_by_id(database, message.author.id.get())
+            .await?
+            .unwrap_or_default();
+        resolution.voice.pitch = tone.pitch.unwrap_or(Speaker::default_pitch());
+        resolution.voice.intonation = tone.intonation.unwrap_or(Speaker::default_intonation());
+
+        Ok(resolution)
+    }
+
+    async fn resolve_voice(
+        &self,
+        database: &PgPool,
+        speaker: &Speaker,
+        guild_id: GuildId,
+        message: &Message,
+        random_voices: bool,
+    ) -> Result<Resolution> {
+        // サーバーで決めたボイスはメンバーの設定より優先する
+        if let Some(speaker_id) = Self::override_of(database, guild_id, message).await? {
+            return Ok(Resolution {
+                voice: Self::voice_of(database, speaker_id).await?,
+                announcement: None,
+            });
+        }
+
+        // モーフィングはメンバーが選んだボイスの代わりに使う
+        if let Some(morph) = database::user_morph::fetch_by_id(database, message.author.id.get()).await? {
+            let mut voice = Self::voice_of(database, morph.base_speaker_id).await?;
+            voice.morph = NotNan::new(morph.rate).ok().map(|rate| Morph {
+                target: morph.target_speaker_id.to_string(),
+                rate,
+            });
+            return Ok(Resolution {
+                voice,
+                announcement: None,
+            });
+        }
+
+        let voices = database::user::fetch_voices(database, guild_id.get(), message.author.id.get()).await?;
+        if voices.guild.is_none()
+            && voices.global.is_none()
+            && let Some(member) = &message.member
+            && !member.roles.is_empty()
+        {
+            let role_voices = database::role_voice::fetch_by_guild_id(database, guild_id.get()).await?;
+            if let Some(speaker_id) = role_voice(&role_voices, &member.roles) {
+                return Ok(Resolution {
+                    voice: Self::voice_of(database, speaker_id).await?,
+                    announcement: None,
+                });
+            }
+        }
+        // 一度割り当てたボイスは保存して、ボイスの一覧が変わっても同じボイスのままにする
+        if random_voices
+            && voices.guild.is_none()
+            && voices.global.is_none()
+            && let Some(speaker_id) = random_voice(
+                &speaker.pairs().map(|(_, id)| id).collect::<Vec<_>>(),
+                message.author.id.get(),
+            )
+        {
+            database::user::create_in_guild(database, guild_id.get(), message.author.id.get(), speaker_id).await?;
+            return Ok(Resolution {
+                voice: Self::voice_of(database, speaker_id.into()).await?,
+                announcement: None,
+            });
+        }
+        let today = today();
+        let source = choose_voice(voices, async || {
+            let rotation = database::voice_rotation::fetch_by_guild_id(database, guild_id.get()).await?;
+            Ok(voice_of_day(&rotation, guild_id.get(), today))
+        })
+        .await?;
+
+        let speaker_id = match source {
+            VoiceSource::Guild(speaker_id) | VoiceSource::Global(speaker_id) => {
+                return Ok(Resolution {
+                    voice: Self::voice_of(database, speaker_id).await?,
+                    announcement: None,
+                });
+            },
+            VoiceSource::GuildDefault(speaker_id) => speaker_id,
+            VoiceSource::BotDefault => {
+                // サーバーの既定のボイスがあれば bot の既定のボイスの代わりに使う
+                let voice = match database::guild::fetch_by_id(database, guild_id.get())
+                    .await?
+                    .default_speaker_id
+                {
+                    Some(speaker_id) => Self::voice_of(database, speaker_id).await?,
+                    None => Voice::guild_default(None),
+                };
+                return Ok(Resolution {
+                    voice,
+                    announcement: None,
+                });
+            },
+        };
+
+        let voice = Self::voice_of(database, speaker_id).await?;
+        let announcement = match self.announced_on.insert(guild_id, today) {
+            Some(day) if day == today => None,
+            _ => u16::try_from(speaker_id)
+                .ok()
+                .and_then(|speaker_id| speaker.get_name(speaker_id).ok())
+                .map(|name| format!("今日の声は{name}です")),
+        };
+
+        Ok(Resolution { voice, announcement })
+    }
+
+    async fn override_of(database: &PgPool, guild_id: GuildId, message: &Message) -> Result<Option<i32>> {
+        if let Some(speaker_id) =
+            database::channel_voice::fetch_by_channel_id(database, message.channel_id.get()).await?
+        {
+            return Ok(Some(speaker_id));
+        }
+
+        let keywords = database::keyword_voice::fetch_by_guild_id(database, guild_id.get()).await?;
+        Ok(match_keyword(&keywords, &message.content))
+    }
+
+    async fn voice_of(database: &PgPool, speaker_id: i32) -> Result<Voice> {
+        let speed = database::speaker::fetch_by_ids(database, &[speaker_id])
+            .await?
+            .first()
+            .map_or(Speaker::default_speed(), |speaker| speaker.speed);
+
+        Ok(Voice::new(speaker_id, speed))
+    }
+}
+
+/// Chooses the voice of a member in order of the voice the member chose for the guild, the voice the member chose for
+/// every guild, the voice of the day of the guild and the default voice, which is the one set by `/voice default` if
+/// any.
+///
+/// The voice of the day is looked up only when the member has chosen no voice.
+async fn choose_voice<GuildDefault>(voices: UserVoices, guild_default: GuildDefault) -> Result<VoiceSource>
+where
+    GuildDefault: AsyncFnOnce() -> Result<Option<i32>>,
+{
+    if let Some(speaker_id) = voices.guild {
+        return Ok(VoiceSource::Guild(speaker_id));
+    }
+    if let Some(speaker_id) = voices.global {
+        return Ok(VoiceSource::Global(speaker_id));
+    }
+    Ok(match guild_default().await? {
+        Some(speaker_id) => VoiceSource::GuildDefault(speaker_id),
+        None => VoiceSource::BotDefault,
+    })
+}
+
+/// Finds the voice of the longest keyword the text contains, ignoring case.
+fn match_keyword(keywords: &[KeywordVoice], text: &str) -> Option<i32> {
+    let text = text.to_lowercase();
+    keywords
+        .iter()
+        .filter(|keyword| !keyword.keyword.is_empty() && text.contains(&keyword.keyword.to_lowercase()))
+        .max_by_key(|keyword| keyword.keyword.chars().count())
+        .map(|keyword| keyword.speaker_id)
+}
+
+/// Finds the voice of the oldest role the member has among the roles with voices, so that the voice does not depend on
+/// the order of the roles.
+fn role_voice(role_voices: &[RoleVoice], roles: &[RoleId]) -> Option<i32> {
+    role_voices
+        .iter()
+        .filter(|role_voice| roles.iter().any(|role_id| role_id.get() as i64 == role_voice.role_id))
+        .min_by_key(|role_voice| role_voice.role_id)
+        .map(|role_voice| role_voice.speaker_id)
+}
+
+/// Days since the Unix epoch in Japan.
+fn today() -> u64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    (secs + UTC_OFFSET_SECS) / SECS_PER_DAY
+}
+
+/// Picks the voice of the day from the rotation, which stays the same throughout the day.
+fn voice_of_day(rotation: &[i32], guild_id: u64, day: u64) -> Option<i32> {
+    if rotation.is_empty() {
+        return None;
+    }
+
+    // 同じ日でもサーバーごとに違う声になるように混ぜる
+    let seed = mix(day ^ guild_id.rotate_left(32));
+    rotation.get((seed % rotation.len() as u64) as usize).copied()
+}
+
+/// Picks a voice for a member from the user id, which is the same as long as the voices are the same.
+fn random_voice(speaker_ids: &[u16], user_id: u64) -> Option<u16> {
+    if speaker_ids.is_empty() {
+        return None;
+    }
+
+    let seed = mix(user_id);
+    speaker_ids.get((seed % speaker_ids.len() as u64) as usize).copied()
+}
+
+// 近い値からも散らばった値を得る (SplitMix64)
+fn mix(mut seed: u64) -> u64 {
+    seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    seed ^ (seed >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use anyhow::bail;
+    use database::{keyword_voice::KeywordVoice, role_voice::RoleVoice, user::UserVoices};
+    use serenity::all::RoleId;
+
+    use super::{VoiceSource, choose_voice, match_keyword, random_voice, role_voice, voice_of_day};
+
+    #[test]
+    fn keep_voice_throughout_day() {
+        let rotation = [1, 3, 8, 14];
+        assert_eq!(
+            voice_of_day(&rotation, 1234567890, 20000),
+            voice_of_day(&rotation, 1234567890, 20000)
+        );
+    }
+
+    #[test]
+    fn rotate_among_voices() {
+        let rotation = [1, 3, 8, 14];
+        let voices = (20000..20030)
+            .filter_map(|day| voice_of_day(&rotation, 1234567890, day))
+            .collect::<HashSet<_>>();
+        assert!(voices.len() > 1);
+        assert!(voices.iter().all(|voice| rotation.contains(voice)));
+    }
+
+    #[test]
+    fn no_voice_without_rotation() {
+        assert_eq!(voice_of_day(&[], 1234567890, 20000), None);
+    }
+
+    #[test]
+    fn spread_random_voices_over_users() {
+        let speaker_ids = [1, 3, 8, 14];
+        assert_eq!(
+            random_voice(&speaker_ids, 1234567890),
+            random_voice(&speaker_ids, 1234567890)
+        );
+        let voices = (1234567890..1234567920)
+            .filter_map(|user_id| random_voice(&speaker_ids, user_id))
+            .collect::<HashSet<_>>();
+        assert!(voices.len() > 1);
+        assert_eq!(random_voice(&[], 1234567890), None);
+    }
+
+    #[test]
+    fn match_longest_keyword() {
+        let keywords = [
+            KeywordVoice {
+                keyword: "お知らせ".to_string(),
+                speaker_id: 13,
+            },
+            KeywordVoice {
+                keyword: "重要なお知らせ".to_string(),
+                speaker_id: 21,
+            },
+        ];
+        assert_eq!(match_keyword(&keywords, "明日のお知らせです"), Some(13));
+        assert_eq!(match_keyword(&keywords, "重要なお知らせがあります"), Some(21));
+        assert_eq!(match_keyword(&keywords, "こんにちは"), None);
+    }
+
+    #[test]
+    fn match_keyword_ignoring_case() {
+        let keywords = [KeywordVoice {
+            keyword: "News".to_string(),
+            speaker_id: 13,
+        }];
+        assert_eq!(match_keyword(&keywords, "今日のNEWSです"), Some(13));
+    }
+
+    #[test]
+    fn prefer_voice_of_oldest_role() {
+        let role_voices = [
+            RoleVoice {
+                role_id: 200,
+                speaker_id: 13,
+            },
+            RoleVoice {
+                role_id: 100,
+                speaker_id: 21,
+            },
+        ];
+        assert_eq!(role_voice(&role_voices, &[RoleId::new(200)]), Some(13));
+        assert_eq!(
+            role_voice(&role_voices, &[RoleId::new(200), RoleId::new(100)]),
+            Some(21)
+        );
+        assert_eq!(role_voice(&role_voices, &[RoleId::new(300)]), None);
+    }
+
+    #[tokio::test]
+    async fn choose_voice_in_order() {
+        let guild_default = async || Ok(Some(8));
+        let voices = UserVoices {
+            guild: Some(3),
+            global: Some(1),
+        };
+        assert_eq!(
+            choose_voice(voices, guild_default).await.unwrap(),
+            VoiceSource::Guild(3)
+        );
+
+        let voices = UserVoices {
+            guild: None,
+            global: Some(1),
+        };
+        assert_eq!(
+            choose_voice(voices, guild_default).await.unwrap(),
+            VoiceSource::Global(1)
+        );
+
+        let voices = UserVoices::default();
+        assert_eq!(
+            choose_voice(voices, guild_default).await.unwrap(),
+            VoiceSource::GuildDefault(8)
+        );
+        assert_eq!(
+            choose_voice(voices, async || Ok(None)).await.unwrap(),
+            VoiceSource::BotDefault
+        );
+    }
+
+    #[tokio::test]
+    async fn skip_guild_default_for_chosen_voice() {
+        let voices = UserVoices {
+            guild: None,
+            global: Some(1),
+        };
+        let source = choose_voice(voices, async || bail!("voice of the day is looked up")).await;
+        assert_eq!(source.unwrap(), VoiceSource::Global(1));
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use hashbrown::HashMap;
+use metrics::histogram;
+use serenity::all::GuildId;
+use tokio::sync::oneshot;
+
+use crate::metrics::SYNTHESIS_WAIT_SECONDS;
+
+/// Hands out a limited number of synthesis slots, taking turns among the guilds waiting for one so that a busy guild
+/// cannot keep the others waiting behind all of its messages.
+#[derive(Clone)]
+pub(crate) struct SynthesisScheduler {
+    inner: Arc<Mutex<State>>,
+}
+
+/// Slot which goes to the next guild in turn when dropped.
+pub(crate) struct Slot {
+    inner: Option<Arc<Mutex<State>>>,
+}
+
+#[derive(Default)]
+struct State {
+    available: usize,
+    // 順番を待っているサーバー
+    turns: VecDeque<GuildId>,
+    waiters: HashMap<GuildId, VecDeque<oneshot::Sender<Slot>>>,
+}
+
+impl SynthesisScheduler {
+    pub(crate) fn new(slots: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(State {
+                available: slots,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Waits for a slot in turn with the other guilds and records how long the guild waited.
+    pub(crate) async fn acquire(&self, guild_id: GuildId) -> Slot {
+        let started_at = Instant::now();
+        let slot = self.wait(guild_id).await;
+        histogram!(SYNTHESIS_WAIT_SECONDS, "guild_id" => guild_id.to_string())
+            .record(started_at.elapsed().as_secs_f64());
+        slot
+    }
+
+    async fn wait(&self, guild_id: GuildId) -> Slot {
+        let receiver = {
+            let mut state = self.state();
+            if state.available > 0 && state.turns.is_empty() {
+                state.available -= 1;
+                return Slot {
+                    inner: Some(self.inner.clone()),
+                };
+            }
+
+            let state = &mut *state;
+            let (sender, receiver) = oneshot::channel();
+            let waiters = state.waiters.entry(guild_id).or_default();
+            if waiters.is_empty() {
+                state.turns.push_back(guild_id);
+            }
+            waiters.push_back(sender);
+            receiver
+        };
+
+        // 送る側は渡せなかったスロットを次に回すので、受け取れないことはない
+        receiver.await.expect("synthesis scheduler has dropped waiter")
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.lock().expect("synthesis scheduler has been poisoned")
+    }
+}
+
+impl State {
+    /// Passes the slot to the first waiter of the next guild in turn, or makes it available if nobody is waiting.
+    fn release(&mut self, inner: &Arc<Mutex<State>>) {
+        while let Some(guild_id) = self.turns.pop_front() {
+            let Some(waiters) = self.waiters.get_mut(&guild_id) else {
+                continue;
+            };
+            let Some(waiter) = waiters.pop_front() else {
+                self.waiters.remove(&guild_id);
+                continue;
+            };
+            match waiters.is_empty() {
+                true => {
+                    self.waiters.remove(&guild_id);
+                },
+                false => self.turns.push_back(guild_id),
+            }
+
+            // 待つのをやめたリクエストには渡さない
+            let slot = Slot {
+                inner: Some(inner.clone()),
+            };
+            match waiter.send(slot) {
+                Ok(()) => return,
+                Err(mut slot) => {
+                    slot.inner = None;
+                },
+            }
+        }
+        self.available += 1;
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take()
+            && let Ok(mut state) = inner.lock()
+        {
+            state.release(&inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::GuildId;
+
+    use super::SynthesisScheduler;
+
+    #[tokio::test]
+    async fn take_turns_among_guilds() {
+        let scheduler = SynthesisScheduler::new(1);
+        let busy = GuildId::new(1);
+        let quiet = GuildId::new(2);
+
+        let slot = scheduler.acquire(busy).await;
+        let mut busy_waiters = (0..3)
+            .map(|_| {
+                let scheduler = scheduler.clone();
+                tokio::spawn(async move { scheduler.acquire(busy).await })
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+        tokio::task::yield_now().await;
+        let quiet_waiter = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move { scheduler.acquire(quiet).await })
+        };
+        tokio::task::yield_now().await;
+
+        assert_eq!(scheduler.state().turns, [busy, quiet]);
+        drop(slot);
+        // 先に待っていた busy の残りより quiet を先にする
+        assert_eq!(scheduler.state().turns, [quiet, busy]);
+        drop(busy_waiters.next().unwrap().await.unwrap());
+        assert_eq!(scheduler.state().turns, [busy]);
+        drop(quiet_waiter.await.unwrap());
+
+        for waiter in busy_waiters {
+            drop(waiter.await.unwrap());
+        }
+        assert_eq!(scheduler.state().available, 1);
+        assert!(scheduler.state().turns.is_empty());
+    }
+
+    #[tokio::test]
+    async fn skip_cancelled_waiter() {
+        let scheduler = SynthesisScheduler::new(1);
+        let guild_id = GuildId::new(1);
+
+        let slot = scheduler.acquire(guild_id).await;
+        let cancelled = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move { scheduler.acquire(guild_id).await })
+        };
+        tokio::task::yield_now().await;
+        cancelled.abort();
+        let _ = cancelled.await;
+
+        drop(slot);
+        assert_eq!(scheduler.state().available, 1);
+    }
+}
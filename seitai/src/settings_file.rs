@@ -0,0 +1,589 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use database::{
+    PgPool,
+    guild::{Guild, ReadingOutput, Spoilers, TtsMessages},
+};
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, GuildId};
+use voicevox::dictionary::Dictionary;
+
+use crate::{
+    commands::{limit, volume},
+    dictionary::{self, Word, WordRequest},
+    quota,
+};
+
+// 形式を変えたら上げる
+const VERSION: u32 = 1;
+
+/// Settings of a guild written by `/settings export` and read by `/settings import`, to carry them to another server or
+/// instance.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SettingsFile {
+    version: u32,
+    settings: GuildSettings,
+    #[serde(default)]
+    words: Vec<WordRequest>,
+    #[serde(default)]
+    readings: Vec<ReadingEntry>,
+    #[serde(default)]
+    read_channels: Vec<ChannelEntry>,
+    #[serde(default)]
+    channel_voices: Vec<ChannelVoiceEntry>,
+    #[serde(default)]
+    keyword_voices: Vec<KeywordVoiceEntry>,
+    #[serde(default)]
+    voice_rotation: Vec<u16>,
+}
+
+// 同意に関わる統計の共有は引き継がない
+#[derive(Debug, Serialize, Deserialize)]
+struct GuildSettings {
+    read_crossposts: bool,
+    announce_joins: bool,
+    announce_activities: bool,
+    skip_commands: bool,
+    command_prefixes: Option<String>,
+    flatten_markdown: bool,
+    celebration_threshold: Option<i32>,
+    celebration_sound: Option<String>,
+    volume: i32,
+    tts_messages: String,
+    read_names: bool,
+    max_length: Option<i32>,
+    announce_truncation: bool,
+    reading_output: String,
+    furigana: bool,
+    furigana_channel: Option<ChannelEntry>,
+    // 後から加えた設定は、古いファイルでは既定の設定にする
+    #[serde(default = "default_strip_name_decorations")]
+    strip_name_decorations: bool,
+    #[serde(default)]
+    name_patterns: Option<String>,
+    #[serde(default)]
+    jitter: bool,
+    #[serde(default)]
+    random_voices: bool,
+    #[serde(default)]
+    read_topics: bool,
+    #[serde(default = "default_name_interval")]
+    name_interval: i32,
+    #[serde(default)]
+    beep_sound: Option<String>,
+    #[serde(default = "default_leave_utterance")]
+    leave_utterance: String,
+    #[serde(default = "default_ducking")]
+    ducking: String,
+    #[serde(default = "default_ducking_sensitivity")]
+    ducking_sensitivity: i32,
+    #[serde(default)]
+    default_speaker_id: Option<u16>,
+    #[serde(default)]
+    read_attachments: bool,
+    #[serde(default)]
+    read_embeds: bool,
+    #[serde(default = "default_omit_code")]
+    omit_code: bool,
+    #[serde(default = "default_spoilers")]
+    spoilers: String,
+    #[serde(default)]
+    read_replies: bool,
+    #[serde(default)]
+    read_threads: bool,
+    #[serde(default = "default_read_voice_chat")]
+    read_voice_chat: bool,
+    #[serde(default)]
+    read_delays: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ReadingEntry {
+    surface: String,
+    reading: String,
+}
+
+/// Channel written with its name, to find the channel of the same name in another server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChannelEntry {
+    id: u64,
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChannelVoiceEntry {
+    channel: ChannelEntry,
+    speaker_id: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeywordVoiceEntry {
+    keyword: String,
+    speaker_id: u16,
+}
+
+/// Settings which passed validation, with the channels resolved in the importing server.
+#[derive(Debug)]
+pub(crate) struct Import {
+    guild: Guild,
+    words: Vec<Word>,
+    readings: Vec<(String, String)>,
+    read_channels: Vec<ChannelId>,
+    channel_voices: Vec<(ChannelId, u16)>,
+    keyword_voices: Vec<(String, u16)>,
+    voice_rotation: Vec<u16>,
+    /// Names of the channels not found in the importing server, whose settings are skipped.
+    pub(crate) missing_channels: Vec<String>,
+}
+
+/// Result of applying the settings.
+#[derive(Debug, Default)]
+pub(crate) struct Applied {
+    pub(crate) words: usize,
+    pub(crate) readings: usize,
+    /// Surfaces of the words or readings which could not be registered, with the reasons.
+    pub(crate) failures: Vec<String>,
+}
+
+impl SettingsFile {
+    /// Reads the settings of the guild, naming the channels with `channels`.
+    pub(crate) async fn export(
+        database: &PgPool,
+        dictionary: &Dictionary,
+        guild_id: GuildId,
+        channels: &HashMap<ChannelId, String>,
+    ) -> Result<Self> {
+        let entry = |channel_id: i64| ChannelEntry {
+            id: channel_id as u64,
+            name: channels
+                .get(&ChannelId::new(channel_id as u64))
+                .cloned()
+                .unwrap_or_default(),
+        };
+
+        let guild = database::guild::fetch_by_id(database, guild_id.get()).await?;
+        let word_ids = database::dictionary_word::fetch_by_guild_id(database, guild_id.get()).await?;
+        let words = dictionary::list(dictionary)
+            .await?
+            .into_iter()
+            .filter(|(uuid, _)| word_ids.contains(uuid))
+            .map(|(_, item)| WordRequest {
+                surface: item.surface,
+                pronunciation: item.pronunciation,
+                accent_type: Some(i64::from(item.accent_type)),
+                word_type: None,
+                priority: Some(i64::from(item.priority)),
+            })
+            .collect();
+        let readings = database::dictionary_word::fetch_readings_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|reading| ReadingEntry {
+                surface: reading.surface,
+                reading: reading.reading,
+            })
+            .collect();
+        let read_channels = database::read_channel::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(entry)
+            .collect();
+        let channel_voices = database::channel_voice::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|channel_voice| ChannelVoiceEntry {
+                channel: entry(channel_voice.channel_id),
+                speaker_id: channel_voice.speaker_id as u16,
+            })
+            .collect();
+        let keyword_voices = database::keyword_voice::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|keyword_voice| KeywordVoiceEntry {
+                keyword: keyword_voice.keyword,
+                speaker_id: keyword_voice.speaker_id as u16,
+            })
+            .collect();
+        let voice_rotation = database::voice_rotation::fetch_by_guild_id(database, guild_id.get())
+            .await?
+            .into_iter()
+            .map(|speaker_id| speaker_id as u16)
+            .collect();
+
+        Ok(Self {
+            version: VERSION,
+            settings: GuildSettings {
+                read_crossposts: guild.read_crossposts,
+                announce_joins: guild.announce_joins,
+                announce_activities: guild.announce_activities,
+                skip_commands: guild.skip_commands,
+                command_prefixes: guild.command_prefixes,
+                flatten_markdown: guild.flatten_markdown,
+                celebration_threshold: guild.celebration_threshold,
+                celebration_sound: guild.celebration_sound,
+                volume: guild.volume,
+                tts_messages: guild.tts_messages,
+                read_names: guild.read_names,
+                max_length: guild.max_length,
+                announce_truncation: guild.announce_truncation,
+                reading_output: guild.reading_output,
+                furigana: guild.furigana,
+                furigana_channel: guild.furigana_channel_id.map(entry),
+                strip_name_decorations: guild.strip_name_decorations,
+                name_patterns: guild.name_patterns,
+                jitter: guild.jitter,
+                random_voices: guild.random_voices,
+                read_topics: guild.read_topics,
+                name_interval: guild.name_interval,
+                beep_sound: guild.beep_sound,
+                leave_utterance: guild.leave_utterance,
+                ducking: guild.ducking,
+                ducking_sensitivity: guild.ducking_sensitivity,
+                default_speaker_id: guild
+                    .default_speaker_id
+                    .and_then(|speaker_id| u16::try_from(speaker_id).ok()),
+                read_attachments: guild.read_attachments,
+                read_embeds: guild.read_embeds,
+                omit_code: guild.omit_code,
+                spoilers: guild.spoilers,
+                read_replies: guild.read_replies,
+                read_threads: guild.read_threads,
+                read_voice_chat: guild.read_voice_chat,
+                read_delays: guild.read_delays,
+            },
+            words,
+            readings,
+            read_channels,
+            channel_voices,
+            keyword_voices,
+            voice_rotation,
+        })
+    }
+
+    /// Validates every setting before anything is applied, and returns all the problems found if any.
+    ///
+    /// Channels are looked up by ID and then by name in `channels` of the importing server.
+    pub(crate) fn validate(
+        self,
+        channels: &HashMap<ChannelId, String>,
+        is_speaker: impl Fn(u16) -> bool,
+    ) -> Result<Import, Vec<String>> {
+        let mut problems = Vec::new();
+        if self.version != VERSION {
+            return Err(vec![format!("対応していない形式です。（version: {}）", self.version)]);
+        }
+
+        let settings = self.settings;
+        if !(0..=volume::MAX_VOLUME).contains(&settings.volume) {
+            problems.push(format!("音量は0から{}の間にしてください。", volume::MAX_VOLUME));
+        }
+        if settings
+            .max_length
+            .is_some_and(|max_length| !(1..=limit::MAX_LENGTH as i32).contains(&max_length))
+        {
+            problems.push(format!("文字数の上限は1から{}の間にしてください。", limit::MAX_LENGTH));
+        }
+        if settings.celebration_threshold.is_some_and(|threshold| threshold < 1) {
+            problems.push("リアクションの数は1以上にしてください。".to_string());
+        }
+        if settings.tts_messages.parse::<TtsMessages>().is_err() {
+            problems.push(format!("tts_messages「{}」は使えません。", settings.tts_messages));
+        }
+        if settings.reading_output.parse::<ReadingOutput>().is_err() {
+            problems.push(format!("reading_output「{}」は使えません。", settings.reading_output));
+        }
+        if settings.spoilers.parse::<Spoilers>().is_err() {
+            problems.push(format!("spoilers「{}」は使えません。", settings.spoilers));
+        }
+        if let Some(Err(error)) = settings
+            .name_patterns
+            .as_deref()
+            .map(|patterns| seitai_converter::name::compile(&patterns.split_whitespace().collect::<Vec<_>>()))
+        {
+            problems.push(format!("名前の装飾のパターンを読み込めません。（{error}）"));
+        }
+        if self.words.len() as i64 > quota::DICTIONARY_WORDS {
+            problems.push(format!("辞書の単語は{}個までにしてください。", quota::DICTIONARY_WORDS));
+        }
+        if self.readings.len() as i64 > quota::READINGS {
+            problems.push(format!("読みは{}個までにしてください。", quota::READINGS));
+        }
+
+        let words = self
+            .words
+            .into_iter()
+            .filter_map(|word| {
+                let surface = word.surface.clone();
+                word.validate()
+                    .map_err(|error| problems.push(format!("単語「{surface}」: {error}")))
+                    .ok()
+            })
+            .collect();
+        let readings = self
+            .readings
+            .into_iter()
+            .filter_map(|entry| {
+                let surface = entry.surface.trim();
+                let reading = entry.reading.trim();
+                if surface.is_empty() || reading.is_empty() {
+                    problems.push("読みの単語と読み方は空にできません。".to_string());
+                    return None;
+                }
+                Some((surface.to_string(), reading.to_string()))
+            })
+            .collect();
+        let speakers = self
+            .channel_voices
+            .iter()
+            .map(|channel_voice| channel_voice.speaker_id)
+            .chain(self.keyword_voices.iter().map(|keyword_voice| keyword_voice.speaker_id))
+            .chain(self.voice_rotation.iter().copied())
+            .chain(settings.default_speaker_id);
+        for speaker_id in speakers {
+            if !is_speaker(speaker_id) {
+                problems.push(format!("ボイス {speaker_id} はありません。"));
+            }
+        }
+        let keyword_voices = self
+            .keyword_voices
+            .into_iter()
+            .filter_map(|entry| {
+                let keyword = entry.keyword.trim();
+                if keyword.is_empty() {
+                    problems.push("キーワードは空にできません。".to_string());
+                    return None;
+                }
+                Some((keyword.to_string(), entry.speaker_id))
+            })
+            .collect();
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+
+        let mut missing_channels = Vec::new();
+        let mut resolve = |entry: &ChannelEntry| {
+            let channel_id = resolve_channel(entry, channels);
+            if channel_id.is_none() {
+                missing_channels.push(entry.name.clone());
+            }
+            channel_id
+        };
+        let furigana_channel_id = settings.furigana_channel.as_ref().and_then(&mut resolve);
+        let read_channels = self.read_channels.iter().filter_map(&mut resolve).collect();
+        let channel_voices = self
+            .channel_voices
+            .iter()
+            .filter_map(|entry| Some((resolve(&entry.channel)?, entry.speaker_id)))
+            .collect();
+
+        let guild = Guild {
+            read_crossposts: settings.read_crossposts,
+            announce_joins: settings.announce_joins,
+            announce_activities: settings.announce_activities,
+            skip_commands: settings.skip_commands,
+            command_prefixes: settings.command_prefixes,
+            flatten_markdown: settings.flatten_markdown,
+            celebration_threshold: settings.celebration_threshold,
+            celebration_sound: settings.celebration_sound,
+            volume: settings.volume,
+            tts_messages: settings.tts_messages,
+            read_names: settings.read_names,
+            max_length: settings.max_length,
+            announce_truncation: settings.announce_truncation,
+            reading_output: settings.reading_output,
+            furigana: settings.furigana,
+            furigana_channel_id: furigana_channel_id.map(|channel_id| channel_id.get() as i64),
+            strip_name_decorations: settings.strip_name_decorations,
+            name_patterns: settings.name_patterns,
+            jitter: settings.jitter,
+            random_voices: settings.random_voices,
+            read_topics: settings.read_topics,
+            name_interval: settings.name_interval,
+            beep_sound: settings.beep_sound,
+            leave_utterance: settings.leave_utterance,
+            ducking: settings.ducking,
+            ducking_sensitivity: settings.ducking_sensitivity,
+            default_speaker_id: settings.default_speaker_id.map(i32::from),
+            read_attachments: settings.read_attachments,
+            read_embeds: settings.read_embeds,
+            omit_code: settings.omit_code,
+            spoilers: settings.spoilers,
+            read_replies: settings.read_replies,
+            read_threads: settings.read_threads,
+            read_voice_chat: settings.read_voice_chat,
+            read_delays: settings.read_delays,
+            ..Default::default()
+        };
+
+        Ok(Import {
+            guild,
+            words,
+            readings,
+            read_channels,
+            channel_voices,
+            keyword_voices,
+            voice_rotation: self.voice_rotation,
+            missing_channels,
+        })
+    }
+}
+
+impl Import {
+    /// Adds the settings to the guild, keeping the readings, channels and voices the guild already has.
+    pub(crate) async fn apply(self, database: &PgPool, dictionary: &Dictionary, guild_id: GuildId) -> Result<Applied> {
+        let mut applied = Applied::default();
+        let current = database::guild::fetch_by_id(database, guild_id.get()).await?;
+        database::guild::update(
+            database,
+            guild_id.get(),
+            // ロールはサーバーごとに異なるので引き継がない
+            &Guild {
+                share_telemetry: current.share_telemetry,
+                priority_role_id: current.priority_role_id,
+                ..self.guild
+            },
+        )
+        .await?;
+
+        for word in &self.words {
+            match dictionary::upsert(dictionary, database, guild_id.get(), word).await {
+                Ok(_) => applied.words += 1,
+                Err(error) => applied.failures.push(format!("単語「{}」: {error}", word.surface)),
+            }
+        }
+
+        let registered = database::dictionary_word::fetch_readings_by_guild_id(database, guild_id.get()).await?;
+        let mut count = registered.len() as i64;
+        for (surface, reading) in &self.readings {
+            let is_registered = registered.iter().any(|registered| &registered.surface == surface);
+            if !is_registered && count >= quota::READINGS {
+                applied
+                    .failures
+                    .push(format!("読み「{surface}」: 登録できる読みの数の上限に達しています。"));
+                continue;
+            }
+            database::dictionary_word::create_reading(database, guild_id.get(), surface, reading).await?;
+            applied.readings += 1;
+            if !is_registered {
+                count += 1;
+            }
+        }
+
+        for channel_id in &self.read_channels {
+            database::read_channel::create(database, channel_id.get(), guild_id.get()).await?;
+        }
+        for (channel_id, speaker_id) in &self.channel_voices {
+            database::channel_voice::create(database, channel_id.get(), guild_id.get(), *speaker_id).await?;
+        }
+        for (keyword, speaker_id) in &self.keyword_voices {
+            database::keyword_voice::create(database, guild_id.get(), keyword, *speaker_id).await?;
+        }
+        for speaker_id in &self.voice_rotation {
+            database::voice_rotation::add(database, guild_id.get(), *speaker_id).await?;
+        }
+
+        Ok(applied)
+    }
+}
+
+fn default_strip_name_decorations() -> bool {
+    Guild::default().strip_name_decorations
+}
+
+fn default_name_interval() -> i32 {
+    Guild::default().name_interval
+}
+
+fn default_leave_utterance() -> String {
+    Guild::default().leave_utterance
+}
+
+fn default_ducking() -> String {
+    Guild::default().ducking
+}
+
+fn default_ducking_sensitivity() -> i32 {
+    Guild::default().ducking_sensitivity
+}
+
+fn default_omit_code() -> bool {
+    Guild::default().omit_code
+}
+
+fn default_spoilers() -> String {
+    Guild::default().spoilers
+}
+
+fn default_read_voice_chat() -> bool {
+    Guild::default().read_voice_chat
+}
+
+// 同じサーバーなら ID で、別のサーバーなら同じ名前のチャンネルを探す
+fn resolve_channel(entry: &ChannelEntry, channels: &HashMap<ChannelId, String>) -> Option<ChannelId> {
+    let channel_id = ChannelId::new(entry.id);
+    if channels.contains_key(&channel_id) {
+        return Some(channel_id);
+    }
+    channels
+        .iter()
+        .find(|(_, name)| !entry.name.is_empty() && **name == entry.name)
+        .map(|(channel_id, _)| *channel_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+    use serenity::all::ChannelId;
+
+    use super::SettingsFile;
+
+    fn file(volume: i32, speaker_id: u16) -> SettingsFile {
+        serde_json::from_value(json!({
+            "version": 1,
+            "settings": {
+                "read_crossposts": true,
+                "announce_joins": false,
+                "announce_activities": false,
+                "skip_commands": true,
+                "command_prefixes": null,
+                "flatten_markdown": true,
+                "celebration_threshold": null,
+                "celebration_sound": null,
+                "volume": volume,
+                "tts_messages": "skip",
+                "read_names": false,
+                "max_length": null,
+                "announce_truncation": true,
+                "reading_output": "voice",
+                "furigana": true,
+                "furigana_channel": {"id": 1, "name": "ふりがな"},
+            },
+            "read_channels": [{"id": 2, "name": "雑談"}, {"id": 3, "name": "消えたチャンネル"}],
+            "voice_rotation": [speaker_id],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_channels_by_name_in_another_server() {
+        let channels = HashMap::from([
+            (ChannelId::new(10), "ふりがな".to_string()),
+            (ChannelId::new(20), "雑談".to_string()),
+        ]);
+        let import = file(100, 1).validate(&channels, |_| true).unwrap();
+        assert_eq!(import.guild.furigana_channel_id, Some(10));
+        assert_eq!(import.read_channels, vec![ChannelId::new(20)]);
+        assert_eq!(import.missing_channels, vec!["消えたチャンネル".to_string()]);
+    }
+
+    #[test]
+    fn reject_invalid_settings_before_applying() {
+        let problems = file(300, 9999)
+            .validate(&HashMap::new(), |speaker_id| speaker_id != 9999)
+            .unwrap_err();
+        assert_eq!(problems.len(), 2);
+    }
+}
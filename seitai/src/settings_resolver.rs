@@ -0,0 +1,323 @@
+use std::{
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use database::{
+    PgPool, dictionary_word::Reading, emoji_reading::EmojiReading, guild::Guild, style_shortcut::StyleShortcut,
+};
+use lru::LruCache;
+use serenity::{
+    all::{GuildId, Message, UserId},
+    builder::CreateEmbed,
+    model::Colour,
+};
+
+use crate::{
+    notifier::OwnerNotifier,
+    resolver::{Resolution, Voice, VoiceResolver},
+    speaker::Speaker,
+};
+
+// データベースに再び問い合わせるまでの待ち時間の初期値と上限
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// サーバーごとに覚えておくメンバーの数
+const MEMBER_CAPACITY: NonZeroUsize = NonZeroUsize::new(256).unwrap();
+
+/// Period in which the database has kept failing.
+#[derive(Debug)]
+struct Outage {
+    since: Instant,
+    failures: u32,
+    retry_at: Instant,
+}
+
+/// Last values read for members, which keeps only the members who spoke recently in each guild.
+struct MemberCache<V> {
+    guilds: DashMap<GuildId, LruCache<UserId, V>>,
+}
+
+/// Readings of words and custom emoji registered by a guild, words it bans from being read and prefixes to switch the
+/// style of the voice.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Readings {
+    pub(crate) words: Vec<Reading>,
+    pub(crate) emojis: Vec<EmojiReading>,
+    pub(crate) blacklist: Vec<String>,
+    pub(crate) style_shortcuts: Vec<StyleShortcut>,
+}
+
+/// Settings read for each message, which keeps the last values read to serve them while the database is unavailable.
+///
+/// After a failure, the database is not asked again until the backoff passes, so that messages are not held up by the
+/// timeout of every query.
+pub(crate) struct SettingsResolver {
+    database: PgPool,
+    notifier: OwnerNotifier,
+    outage: Mutex<Option<Outage>>,
+    guilds: DashMap<GuildId, Guild>,
+    read_channels: DashMap<GuildId, Vec<i64>>,
+    mutes: MemberCache<bool>,
+    readings: DashMap<GuildId, Readings>,
+    voices: MemberCache<Voice>,
+}
+
+trait Cache<K, V> {
+    fn get(&self, key: &K) -> Option<V>;
+
+    fn insert(&self, key: K, value: V);
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> for DashMap<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        DashMap::get(self, key).map(|value| value.clone())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        DashMap::insert(self, key, value);
+    }
+}
+
+impl<V> MemberCache<V> {
+    fn new() -> Self {
+        Self { guilds: DashMap::new() }
+    }
+}
+
+impl<V: Clone> Cache<(GuildId, UserId), V> for MemberCache<V> {
+    fn get(&self, (guild_id, user_id): &(GuildId, UserId)) -> Option<V> {
+        self.guilds
+            .get_mut(guild_id)
+            .and_then(|mut values| values.get(user_id).cloned())
+    }
+
+    fn insert(&self, (guild_id, user_id): (GuildId, UserId), value: V) {
+        self.guilds
+            .entry(guild_id)
+            .or_insert_with(|| LruCache::new(MEMBER_CAPACITY))
+            .put(user_id, value);
+    }
+}
+
+impl Outage {
+    fn new(now: Instant) -> Self {
+        Self {
+            since: now,
+            failures: 1,
+            retry_at: now + INITIAL_BACKOFF,
+        }
+    }
+
+    fn fail(&mut self, now: Instant) {
+        self.failures += 1;
+        self.retry_at = now + backoff(self.failures);
+    }
+}
+
+impl SettingsResolver {
+    pub(crate) fn new(database: PgPool, notifier: OwnerNotifier) -> Self {
+        Self {
+            database,
+            notifier,
+            outage: Mutex::new(None),
+            guilds: DashMap::new(),
+            read_channels: DashMap::new(),
+            mutes: MemberCache::new(),
+            readings: DashMap::new(),
+            voices: MemberCache::new(),
+        }
+    }
+
+    pub(crate) async fn guild(&self, guild_id: GuildId) -> Guild {
+        let fetch = database::guild::fetch_by_id(&self.database, guild_id.get());
+        self.read(&self.guilds, guild_id, fetch).await.unwrap_or_else(|| Guild {
+            id: guild_id.get() as i64,
+            ..Default::default()
+        })
+    }
+
+    /// Channels registered to be read, which are none when unknown so that the channel `/join` was used in is read.
+    pub(crate) async fn read_channels(&self, guild_id: GuildId) -> Vec<i64> {
+        let fetch = database::read_channel::fetch_by_guild_id(&self.database, guild_id.get());
+        self.read(&self.read_channels, guild_id, fetch)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Whether the user is muted in every guild or only in the guild.
+    pub(crate) async fn is_muted(&self, guild_id: GuildId, user_id: UserId) -> bool {
+        let fetch = async {
+            Ok(database::user_mute::is_muted(&self.database, user_id.get()).await?
+                || database::guild_user_mute::is_muted(&self.database, guild_id.get(), user_id.get()).await?)
+        };
+        self.read(&self.mutes, (guild_id, user_id), fetch)
+            .await
+            .unwrap_or_default()
+    }
+
+    pub(crate) async fn readings(&self, guild_id: GuildId) -> Readings {
+        let fetch = async {
+            Ok(Readings {
+                words: database::dictionary_word::fetch_readings_by_guild_id(&self.database, guild_id.get()).await?,
+                emojis: database::emoji_reading::fetch_by_guild_id(&self.database, guild_id.get()).await?,
+                blacklist: database::blacklisted_word::fetch_by_guild_id(&self.database, guild_id.get()).await?,
+                style_shortcuts: database::style_shortcut::fetch_by_guild_id(&self.database, guild_id.get()).await?,
+            })
+        };
+        self.read(&self.readings, guild_id, fetch).await.unwrap_or_default()
+    }
+
+    /// Resolves the voice of the message, which falls back to the voice last resolved for the author and then to the
+    /// default voice of the guild.
+    pub(crate) async fn voice(
+        &self,
+        voice_resolver: &VoiceResolver,
+        speaker: &Speaker,
+        guild_id: GuildId,
+        message: &Message,
+        random_voices: bool,
+    ) -> Resolution {
+        // 今日の声の案内は一度だけ読み上げるので、覚えておくのはボイスだけにする
+        let mut announcement = None;
+        let fetch = async {
+            let resolution = voice_resolver
+                .resolve(&self.database, speaker, guild_id, message, random_voices)
+                .await?;
+            announcement = resolution.announcement;
+            Ok(resolution.voice)
+        };
+        let voice = self
+            .read(&self.voices, (guild_id, message.author.id), fetch)
+            .await
+            .unwrap_or_else(|| {
+                Voice::guild_default(self.guilds.get(&guild_id).and_then(|guild| guild.default_speaker_id))
+            });
+
+        Resolution { voice, announcement }
+    }
+
+    async fn read<K, V>(&self, cache: &impl Cache<K, V>, key: K, fetch: impl Future<Output = Result<V>>) -> Option<V>
+    where
+        V: Clone,
+    {
+        if !self.is_due(Instant::now()) {
+            return cache.get(&key);
+        }
+
+        match fetch.await {
+            Ok(value) => {
+                self.recover().await;
+                cache.insert(key, value.clone());
+                Some(value)
+            },
+            Err(error) => {
+                tracing::error!("failed to read settings, serving the last ones read\nError: {error:?}");
+                self.fail(&error).await;
+                cache.get(&key)
+            },
+        }
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.outage().as_ref().is_none_or(|outage| now >= outage.retry_at)
+    }
+
+    async fn fail(&self, error: &anyhow::Error) {
+        let now = Instant::now();
+        let started = {
+            let mut outage = self.outage();
+            match outage.as_mut() {
+                Some(outage) => {
+                    outage.fail(now);
+                    false
+                },
+                None => {
+                    *outage = Some(Outage::new(now));
+                    true
+                },
+            }
+        };
+        if !started {
+            return;
+        }
+
+        self.notifier
+            .notify(
+                CreateEmbed::new()
+                    .title("データベースに接続できません。")
+                    .description("復旧するまで、最後に読み込んだ設定か既定の設定で読み上げます。")
+                    .field("エラー", format!("```\n{error}\n```"), false)
+                    .colour(Colour::ORANGE),
+            )
+            .await;
+    }
+
+    async fn recover(&self) {
+        let Some(outage) = self.outage().take() else {
+            return;
+        };
+
+        let duration = outage.since.elapsed();
+        tracing::info!("database is back after {}s", duration.as_secs());
+        self.notifier
+            .notify(
+                CreateEmbed::new()
+                    .title("データベースが復旧しました。")
+                    .field("停止していた時間", format!("{}秒", duration.as_secs()), true)
+                    .colour(Colour::FOOYOO),
+            )
+            .await;
+    }
+
+    fn outage(&self) -> std::sync::MutexGuard<'_, Option<Outage>> {
+        self.outage.lock().expect("database outage has been poisoned")
+    }
+}
+
+// 失敗が続くほど間隔を倍にする
+fn backoff(failures: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2_u32.saturating_pow(failures.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use serenity::all::{GuildId, UserId};
+
+    use super::{Cache, MAX_BACKOFF, MEMBER_CAPACITY, MemberCache, Outage, backoff};
+
+    #[test]
+    fn double_backoff_up_to_limit() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(100), MAX_BACKOFF);
+
+        let now = Instant::now();
+        let mut outage = Outage::new(now);
+        outage.fail(now);
+        outage.fail(now);
+        assert_eq!(outage.retry_at, now + Duration::from_secs(4));
+    }
+
+    #[test]
+    fn keep_members_who_spoke_recently() {
+        let cache = MemberCache::new();
+        let guild_id = GuildId::new(1);
+        for user_id in 1..=MEMBER_CAPACITY.get() as u64 + 1 {
+            cache.insert((guild_id, UserId::new(user_id)), user_id);
+        }
+
+        assert_eq!(cache.get(&(guild_id, UserId::new(1))), None);
+        assert_eq!(cache.get(&(guild_id, UserId::new(2))), Some(2));
+        assert_eq!(cache.get(&(GuildId::new(2), UserId::new(2))), None);
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::{Context as _, Result, bail};
+use jwalk::WalkDir;
+use serde::Serialize;
+use songbird::input::codecs::{get_codec_registry, get_probe};
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, errors::Error as SymphoniaError, formats::FormatOptions,
+    io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+
+const EXTENSIONS: [&str; 3] = ["mp3", "wav", "opus"];
+// 正規化しても音割れしないように残すピークの上限（-1 dBFS）
+const MAX_PEAK: f32 = 0.891;
+const MANIFEST: &str = "manifest.json";
+
+/// Maintains the sounds in SS_DIRECTORY.
+#[derive(clap::Args)]
+pub(crate) struct SoundsCommand {
+    #[command(subcommand)]
+    subcommand: SoundsSubcommand,
+}
+
+#[derive(clap::Subcommand)]
+enum SoundsSubcommand {
+    /// Re-encodes every sound into 16-bit PCM WAV at the same loudness, and writes a manifest of them
+    Normalize(NormalizeCommand),
+}
+
+#[derive(clap::Args)]
+struct NormalizeCommand {
+    /// Directory to scan, which defaults to SS_DIRECTORY
+    #[arg(long)]
+    directory: Option<PathBuf>,
+    /// Directory to write the normalized sounds and the manifest into, outside of the scanned directory
+    #[arg(long)]
+    output: PathBuf,
+    /// Loudness to normalize to in dBFS RMS
+    #[arg(long, default_value_t = -20.0, allow_negative_numbers = true)]
+    target: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestEntry {
+    name: String,
+    source: PathBuf,
+    sample_rate: u32,
+    channels: usize,
+    duration_ms: u64,
+    gain_db: f32,
+}
+
+struct Decoded {
+    // チャンネルごとに交互に並んだサンプル
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channels: usize,
+}
+
+impl SoundsCommand {
+    pub(crate) fn run(&self) -> Result<()> {
+        match &self.subcommand {
+            SoundsSubcommand::Normalize(normalize) => normalize.run(),
+        }
+    }
+}
+
+impl NormalizeCommand {
+    fn run(&self) -> Result<()> {
+        let directory = match &self.directory {
+            Some(directory) => directory.clone(),
+            None => match env::var("SS_DIRECTORY") {
+                Ok(directory) if !directory.is_empty() => PathBuf::from(directory),
+                _ => bail!("pass --directory or set environment variable SS_DIRECTORY"),
+            },
+        };
+        let directory = directory
+            .canonicalize()
+            .with_context(|| format!("failed to find {}", directory.display()))?;
+        fs::create_dir_all(&self.output).with_context(|| format!("failed to create {}", self.output.display()))?;
+        let output = self.output.canonicalize()?;
+        // 出力先を読み込み元に置くと、起動時に同じ名前のサウンドが二重に読み込まれる
+        if output.starts_with(&directory) {
+            bail!("{} is inside of {}", output.display(), directory.display());
+        }
+
+        let mut paths = WalkDir::new(&directory)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| EXTENSIONS.contains(&&*extension.to_string_lossy().to_ascii_lowercase()))
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut manifest = Vec::new();
+        let mut problems = Vec::new();
+        let mut sources = HashMap::<String, PathBuf>::new();
+        for path in paths {
+            let Some(name) = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            if let Some(source) = sources.get(&name) {
+                problems.push((path, format!("same name as {}", source.display())));
+                continue;
+            }
+
+            let decoded = match decode(&path) {
+                Ok(decoded) => decoded,
+                Err(error) => {
+                    problems.push((path, format!("{error:#}")));
+                    continue;
+                },
+            };
+            let Some(gain) = loudness_gain(&decoded.samples, self.target) else {
+                problems.push((path, "silent".to_string()));
+                continue;
+            };
+
+            let samples = decoded.samples.iter().map(|sample| sample * gain).collect::<Vec<_>>();
+            let destination = output.join(format!("{name}.wav"));
+            fs::write(
+                &destination,
+                encode_wav(&samples, decoded.sample_rate, decoded.channels),
+            )
+            .with_context(|| format!("failed to write {}", destination.display()))?;
+
+            let frames = (decoded.samples.len() / decoded.channels) as u64;
+            manifest.push(ManifestEntry {
+                name: name.clone(),
+                source: path.strip_prefix(&directory).unwrap_or(&path).to_path_buf(),
+                sample_rate: decoded.sample_rate,
+                channels: decoded.channels,
+                duration_ms: frames * 1000 / u64::from(decoded.sample_rate),
+                gain_db: 20.0 * gain.log10(),
+            });
+            sources.insert(name, path);
+        }
+
+        let manifest_path = output.join(MANIFEST);
+        fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)
+            .with_context(|| format!("failed to write {}", manifest_path.display()))?;
+
+        println!(
+            "normalized {} sounds into {}, {} problems",
+            manifest.len(),
+            output.display(),
+            problems.len()
+        );
+        for (path, problem) in &problems {
+            println!("{}: {problem}", path.display());
+        }
+        // 問題のあるファイルがあれば運用のスクリプトで気づけるようにする
+        if !problems.is_empty() {
+            process::exit(1);
+        }
+
+        Ok(())
+    }
+}
+
+fn decode(path: &Path) -> Result<Decoded> {
+    let file = fs::File::open(path).context("failed to open")?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .context("unsupported format")?;
+    let mut format = probed.format;
+    let track = format.default_track().context("no audio track")?;
+    let track_id = track.id;
+    let mut decoder = get_codec_registry()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .context("unsupported codec")?;
+
+    let mut samples = Vec::new();
+    let mut spec = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(error)) if error.kind() == ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error).context("failed to read packet"),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let buffer = match decoder.decode(&packet) {
+            Ok(buffer) => buffer,
+            // 壊れたパケットは飛ばして続きを読む
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(error) => return Err(error).context("failed to decode"),
+        };
+        spec.get_or_insert(*buffer.spec());
+        let mut sample_buffer = SampleBuffer::<f32>::new(buffer.capacity() as u64, *buffer.spec());
+        sample_buffer.copy_interleaved_ref(buffer);
+        samples.extend_from_slice(sample_buffer.samples());
+    }
+
+    let spec = spec.context("no audio")?;
+    Ok(Decoded {
+        samples,
+        sample_rate: spec.rate,
+        channels: spec.channels.count(),
+    })
+}
+
+/// Gain to bring the RMS of the samples to `target` dBFS without the peak exceeding -1 dBFS, or `None` if silent.
+fn loudness_gain(samples: &[f32], target: f32) -> Option<f32> {
+    let peak = samples.iter().fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+    if peak == 0.0 {
+        return None;
+    }
+    let rms = (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt();
+    let gain = 10.0_f32.powf(target / 20.0) / rms;
+    Some(gain.min(MAX_PEAK / peak))
+}
+
+fn encode_wav(samples: &[f32], sample_rate: u32, channels: usize) -> Vec<u8> {
+    let channels = channels as u16;
+    let block_align = channels * 2;
+    let data_size = (samples.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + samples.len() * 2);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16_u32.to_le_bytes());
+    // リニア PCM
+    wav.extend_from_slice(&1_u16.to_le_bytes());
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&16_u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        let sample = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_PEAK, encode_wav, loudness_gain};
+
+    #[test]
+    fn gain_reaches_target_within_peak() {
+        let quiet = [0.01, -0.01, 0.01, -0.01];
+        let gain = loudness_gain(&quiet, -20.0).unwrap();
+        assert!((0.01 * gain - 0.1).abs() < 1e-4);
+
+        // ピークが大きい音は目標の音量より上限を優先する
+        let spiky = [0.5, 0.0, 0.0, 0.0];
+        let gain = loudness_gain(&spiky, 0.0).unwrap();
+        assert!((0.5 * gain - MAX_PEAK).abs() < 1e-4);
+
+        assert_eq!(loudness_gain(&[0.0; 4], -20.0), None);
+    }
+
+    #[test]
+    fn encode_pcm_wav() {
+        let wav = encode_wav(&[0.0, 1.0, -1.0, 0.5], 48000, 2);
+        assert_eq!(wav.len(), 44 + 8);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 36 + 8);
+        assert_eq!(u16::from_le_bytes(wav[22..24].try_into().unwrap()), 2);
+        assert_eq!(i16::from_le_bytes(wav[46..48].try_into().unwrap()), i16::MAX);
+    }
+}
@@ -46,15 +46,74 @@ impl Speaker {
         Ok(format!("{name_pair}"))
     }
 
-    pub(crate) fn pairs(&self) -> impl Iterator<Item = (NamePair, u16)> + '_ {
+    pub(crate) fn pairs(&self) -> impl Iterator<Item = (NamePair<'_>, u16)> + '_ {
         Self::to_speaker_tuples(&self.speakers)
     }
 
+    /// Characters with the names and IDs of their styles.
+    pub(crate) fn characters(&self) -> impl Iterator<Item = (&str, Vec<(&str, u16)>)> + '_ {
+        self.speakers.iter().map(|speaker| {
+            let styles = speaker
+                .styles
+                .iter()
+                .map(|style| (style.name.as_str(), style.id))
+                .collect();
+            (speaker.name.as_str(), styles)
+        })
+    }
+
+    /// Names and IDs of the styles of the character the style belongs to.
+    pub(crate) fn styles_of(&self, speaker_id: u16) -> Vec<(String, u16)> {
+        self.speakers
+            .iter()
+            .find(|speaker| speaker.styles.iter().any(|style| style.id == speaker_id))
+            .map(|speaker| {
+                speaker
+                    .styles
+                    .iter()
+                    .map(|style| (style.name.clone(), style.id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub(crate) fn default_speed() -> f32 {
         1.2
     }
 
-    fn to_speaker_tuples(speakers: &[VoicevoxSpeaker]) -> impl Iterator<Item = (NamePair, u16)> + '_ {
+    pub(crate) fn min_speed() -> f32 {
+        0.5
+    }
+
+    pub(crate) fn max_speed() -> f32 {
+        2.0
+    }
+
+    pub(crate) fn default_pitch() -> f32 {
+        0.0
+    }
+
+    pub(crate) fn min_pitch() -> f32 {
+        -0.15
+    }
+
+    pub(crate) fn max_pitch() -> f32 {
+        0.15
+    }
+
+    pub(crate) fn default_intonation() -> f32 {
+        1.0
+    }
+
+    pub(crate) fn min_intonation() -> f32 {
+        0.0
+    }
+
+    pub(crate) fn max_intonation() -> f32 {
+        2.0
+    }
+
+    fn to_speaker_tuples(speakers: &[VoicevoxSpeaker]) -> impl Iterator<Item = (NamePair<'_>, u16)> + '_ {
         speakers.iter().flat_map(|speaker| {
             speaker
                 .styles
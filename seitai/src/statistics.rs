@@ -0,0 +1,176 @@
+use std::{
+    collections::HashMap,
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use database::{PgPool, usage_statistic::Usage};
+use serenity::all::{GuildId, UserId};
+use tokio::sync::Mutex;
+
+/// Usage of the voices counted in memory, to be written to the database in batches instead of once per message.
+#[derive(Debug, Default)]
+pub(crate) struct UsageStatistics {
+    pending: Mutex<HashMap<(GuildId, i32), Usage>>,
+    sessions: Mutex<HashMap<GuildId, Session>>,
+}
+
+/// Usage counted from `/session start` to `/session stop` in a guild.
+#[derive(Debug)]
+pub(crate) struct Session {
+    pub(crate) name: Option<String>,
+    pub(crate) started_at: Instant,
+    pub(crate) usage: Usage,
+    // メンバーごとの読み上げたメッセージ数
+    messages_by_author: HashMap<UserId, i64>,
+}
+
+impl Session {
+    fn new(name: Option<String>) -> Self {
+        Self {
+            name,
+            started_at: Instant::now(),
+            usage: Usage::default(),
+            messages_by_author: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, author: UserId, usage: Usage) {
+        add(&mut self.usage, usage);
+        *self.messages_by_author.entry(author).or_default() += usage.messages;
+    }
+
+    /// Members who had the most messages read, with the number of the messages.
+    pub(crate) fn top_speakers(&self, count: usize) -> Vec<(UserId, i64)> {
+        let mut speakers = self
+            .messages_by_author
+            .iter()
+            .map(|(author, messages)| (*author, *messages))
+            .collect::<Vec<_>>();
+        speakers.sort_by(|(a_author, a_messages), (b_author, b_messages)| {
+            b_messages.cmp(a_messages).then(a_author.cmp(b_author))
+        });
+        speakers.truncate(count);
+        speakers
+    }
+}
+
+impl UsageStatistics {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) async fn record(&self, guild_id: GuildId, author: UserId, speaker_id: i32, usage: Usage) {
+        {
+            let mut pending = self.pending.lock().await;
+            add(pending.entry((guild_id, speaker_id)).or_default(), usage);
+        }
+        if let Some(session) = self.sessions.lock().await.get_mut(&guild_id) {
+            session.record(author, usage);
+        }
+    }
+
+    /// Starts counting the usage of the guild, unless a session is already going on.
+    pub(crate) async fn start_session(&self, guild_id: GuildId, name: Option<String>) -> bool {
+        let mut sessions = self.sessions.lock().await;
+        if sessions.contains_key(&guild_id) {
+            return false;
+        }
+        sessions.insert(guild_id, Session::new(name));
+        true
+    }
+
+    pub(crate) async fn stop_session(&self, guild_id: GuildId) -> Option<Session> {
+        self.sessions.lock().await.remove(&guild_id)
+    }
+
+    /// Writes the counted usage every `interval`.
+    pub(crate) fn spawn_flushing(self: &Arc<Self>, database: PgPool, interval: Duration) {
+        let statistics = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                statistics.flush(&database).await;
+            }
+        });
+    }
+
+    async fn flush(&self, database: &PgPool) {
+        let pending = mem::take(&mut *self.pending.lock().await);
+        let usages = pending
+            .into_iter()
+            .map(|((guild_id, speaker_id), usage)| (guild_id.get(), speaker_id, usage))
+            .collect::<Vec<_>>();
+        if let Err(error) = database::usage_statistic::add_all(database, &usages).await {
+            // 書き込めなかった分は捨てる（統計のために読み上げを止めない）
+            tracing::error!("failed to write {} usage statistics\nError: {error:?}", usages.len());
+        }
+    }
+}
+
+fn add(total: &mut Usage, usage: Usage) {
+    total.messages += usage.messages;
+    total.characters += usage.characters;
+    total.syntheses += usage.syntheses;
+    total.cache_hits += usage.cache_hits;
+}
+
+#[cfg(test)]
+mod tests {
+    use database::usage_statistic::Usage;
+    use serenity::all::{GuildId, UserId};
+
+    use super::UsageStatistics;
+
+    #[tokio::test]
+    async fn merge_usage_of_same_voice() {
+        let statistics = UsageStatistics::new();
+        let usage = Usage {
+            messages: 1,
+            characters: 5,
+            syntheses: 2,
+            cache_hits: 1,
+        };
+        statistics.record(GuildId::new(1), UserId::new(1), 3, usage).await;
+        statistics.record(GuildId::new(1), UserId::new(1), 3, usage).await;
+        statistics.record(GuildId::new(2), UserId::new(1), 3, usage).await;
+
+        let pending = statistics.pending.lock().await;
+        assert_eq!(pending.len(), 2);
+        assert_eq!(
+            pending[&(GuildId::new(1), 3)],
+            Usage {
+                messages: 2,
+                characters: 10,
+                syntheses: 4,
+                cache_hits: 2,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn count_usage_during_session() {
+        let statistics = UsageStatistics::new();
+        let usage = Usage {
+            messages: 1,
+            characters: 5,
+            syntheses: 2,
+            cache_hits: 0,
+        };
+        statistics.record(GuildId::new(1), UserId::new(1), 3, usage).await;
+        assert!(statistics.start_session(GuildId::new(1), None).await);
+        assert!(!statistics.start_session(GuildId::new(1), None).await);
+        statistics.record(GuildId::new(1), UserId::new(1), 3, usage).await;
+        statistics.record(GuildId::new(1), UserId::new(2), 3, usage).await;
+        statistics.record(GuildId::new(1), UserId::new(2), 4, usage).await;
+        statistics.record(GuildId::new(2), UserId::new(1), 3, usage).await;
+
+        let session = statistics.stop_session(GuildId::new(1)).await.unwrap();
+        assert_eq!(session.usage.messages, 3);
+        assert_eq!(session.usage.characters, 15);
+        assert_eq!(session.top_speakers(1), [(UserId::new(2), 2)]);
+        assert!(statistics.stop_session(GuildId::new(1)).await.is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use database::style_shortcut::StyleShortcut;
+
+/// Prefix of messages to read them with a style of the voice, followed by the name of the style as in `;;ささやき`.
+pub(crate) const STYLE_PREFIX: &str = ";;";
+
+// 英語でも指定できるスタイルの別名
+const ALIASES: [(&str, &[&str]); 8] = [
+    ("normal", &["ノーマル"]),
+    ("whisper", &["ささやき", "ヒソヒソ", "内緒話"]),
+    ("sweet", &["あまあま"]),
+    ("tsun", &["ツンツン"]),
+    ("sexy", &["セクシー"]),
+    ("happy", &["喜び", "うきうき", "楽々"]),
+    ("angry", &["怒り", "ツンギレ"]),
+    ("sad", &["悲しみ", "なみだめ", "しょんぼり"]),
+];
+
+/// Whether the name of a style is the one asked for, ignoring case or by its English alias.
+pub(crate) fn matches(name: &str, query: &str) -> bool {
+    name.eq_ignore_ascii_case(query)
+        || ALIASES
+            .iter()
+            .any(|(alias, names)| alias.eq_ignore_ascii_case(query) && names.contains(&name))
+}
+
+/// Splits the shortcut at the head of the text, returning the style chosen among `styles` of the voice, if it has one,
+/// and the text to be read.
+///
+/// A registered prefix is taken off even if the voice lacks the style, but a name after [`STYLE_PREFIX`] which is none
+/// of the styles is left in the text not to mistake text such as emoticons for shortcuts.
+pub(crate) fn apply<'a>(
+    text: &'a str,
+    shortcuts: &[StyleShortcut],
+    styles: &[(String, u16)],
+) -> Option<(Option<u16>, &'a str)> {
+    let find = |query: &str| styles.iter().find(|(name, _)| matches(name, query)).map(|(_, id)| *id);
+
+    let head = text.trim_start();
+    if let Some(shortcut) = shortcuts
+        .iter()
+        .filter(|shortcut| !shortcut.prefix.is_empty() && head.starts_with(&shortcut.prefix))
+        .max_by_key(|shortcut| shortcut.prefix.len())
+    {
+        return Some((find(&shortcut.style), head[shortcut.prefix.len()..].trim_start()));
+    }
+
+    let rest = head.strip_prefix(STYLE_PREFIX)?;
+    let (query, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let style = find(query)?;
+    Some((Some(style), text.trim_start()))
+}
+
+#[cfg(test)]
+mod tests {
+    use database::style_shortcut::StyleShortcut;
+
+    use super::apply;
+
+    #[test]
+    fn switch_style_for_message() {
+        let styles = [
+            ("ノーマル".to_string(), 3),
+            ("あまあま".to_string(), 1),
+            ("ささやき".to_string(), 22),
+        ];
+        let shortcuts = [StyleShortcut {
+            prefix: "(ひそ)".to_string(),
+            style: "ささやき".to_string(),
+        }];
+
+        assert_eq!(
+            apply(";;whisper こんにちは", &[], &styles),
+            Some((Some(22), "こんにちは"))
+        );
+        assert_eq!(apply(";;あまあま\nおはよう", &[], &styles), Some((Some(1), "おはよう")));
+        assert_eq!(
+            apply("(ひそ) 内緒です", &shortcuts, &styles),
+            Some((Some(22), "内緒です"))
+        );
+        assert_eq!(
+            apply("(ひそ)内緒です", &shortcuts, &styles[..2]),
+            Some((None, "内緒です"))
+        );
+
+        // スタイルでなければそのまま読み上げる
+        assert_eq!(apply(";;) 笑", &[], &styles), None);
+        assert_eq!(apply(";;sexy どうも", &[], &styles), None);
+        assert_eq!(apply("こんにちは ;;whisper", &[], &styles), None);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::build_info;
+
+// 1 回の送信までに覚えておく遅延の数（多すぎる分は捨てる）
+const MAX_LATENCIES: usize = 10_000;
+
+/// Anonymous usage of the guilds opting in, sent to the endpoint of the deployment when it is configured.
+///
+/// Only counts and durations are sent, never contents of messages nor IDs of guilds or users.
+#[derive(Debug)]
+pub(crate) struct Telemetry {
+    endpoint: Url,
+    client: reqwest::Client,
+    report: Mutex<Report>,
+}
+
+#[derive(Debug)]
+struct Report {
+    started_at: Instant,
+    messages: u64,
+    engine_errors: u64,
+    latencies: Vec<Duration>,
+}
+
+#[derive(Debug, Serialize)]
+struct Payload {
+    version: &'static str,
+    period_secs: u64,
+    messages: u64,
+    engine_errors: u64,
+    latency_ms: Option<Percentiles>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Percentiles {
+    p50: u64,
+    p90: u64,
+    p99: u64,
+}
+
+impl Telemetry {
+    pub(crate) fn new(endpoint: Url) -> Self {
+        Self {
+            endpoint,
+            client: reqwest::Client::new(),
+            report: Mutex::new(Report::new()),
+        }
+    }
+
+    /// Records a message read in `latency`.
+    pub(crate) async fn record_message(&self, latency: Duration) {
+        let mut report = self.report.lock().await;
+        report.messages += 1;
+        if report.latencies.len() < MAX_LATENCIES {
+            report.latencies.push(latency);
+        }
+    }
+
+    pub(crate) async fn record_engine_error(&self) {
+        self.report.lock().await.engine_errors += 1;
+    }
+
+    /// Sends what is recorded every `interval`.
+    pub(crate) fn spawn_sending(self: &Arc<Self>, interval: Duration) {
+        let telemetry = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            // 起動直後は何も記録されていないので 1 周期待つ
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(error) = telemetry.send().await {
+                    tracing::warn!("failed to send telemetry\nError: {error:?}");
+                }
+            }
+        });
+    }
+
+    async fn send(&self) -> Result<()> {
+        let report = mem::replace(&mut *self.report.lock().await, Report::new());
+        if report.messages == 0 && report.engine_errors == 0 {
+            return Ok(());
+        }
+
+        let payload = report.into_payload();
+        self.client
+            .post(self.endpoint.clone())
+            .header("content-type", "application/json")
+            .body(serde_json::to_vec(&payload)?)
+            .send()
+            .await
+            .context("failed to post telemetry")?
+            .error_for_status()
+            .context("telemetry endpoint returned error")?;
+
+        Ok(())
+    }
+}
+
+impl Report {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            messages: 0,
+            engine_errors: 0,
+            latencies: Vec::new(),
+        }
+    }
+
+    fn into_payload(mut self) -> Payload {
+        Payload {
+            version: build_info::VERSION,
+            period_secs: self.started_at.elapsed().as_secs(),
+            messages: self.messages,
+            engine_errors: self.engine_errors,
+            latency_ms: percentiles(&mut self.latencies),
+        }
+    }
+}
+
+fn percentiles(latencies: &mut [Duration]) -> Option<Percentiles> {
+    if latencies.is_empty() {
+        return None;
+    }
+
+    latencies.sort_unstable();
+    let at = |percentile: usize| {
+        let index = (latencies.len() * percentile).div_ceil(100).saturating_sub(1);
+        latencies[index].as_millis() as u64
+    };
+    Some(Percentiles {
+        p50: at(50),
+        p90: at(90),
+        p99: at(99),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Percentiles, percentiles};
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let mut latencies = (1..=100).rev().map(Duration::from_millis).collect::<Vec<_>>();
+        assert_eq!(
+            percentiles(&mut latencies),
+            Some(Percentiles {
+                p50: 50,
+                p90: 90,
+                p99: 99,
+            })
+        );
+
+        let mut latencies = vec![Duration::from_millis(7)];
+        assert_eq!(
+            percentiles(&mut latencies),
+            Some(Percentiles { p50: 7, p90: 7, p99: 7 })
+        );
+        assert_eq!(percentiles(&mut []), None);
+    }
+}
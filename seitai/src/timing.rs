@@ -0,0 +1,88 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use metrics::histogram;
+
+use crate::metrics::PIPELINE_STAGE_SECONDS;
+
+/// Stage of the pipeline which turns a message into audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Stage {
+    Normalize,
+    Dictionary,
+    Convert,
+    Query,
+    Synthesis,
+    Enqueue,
+}
+
+/// Time spent in each stage while reading a message.
+#[derive(Debug, Default)]
+pub(crate) struct Timings {
+    stages: Vec<(Stage, Duration)>,
+}
+
+impl Stage {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Normalize => "normalize",
+            Stage::Dictionary => "dictionary",
+            Stage::Convert => "convert",
+            Stage::Query => "query",
+            Stage::Synthesis => "synthesis",
+            Stage::Enqueue => "enqueue",
+        }
+    }
+
+    /// Records the time spent in the stage to the histogram.
+    pub(crate) fn record(self, duration: Duration) {
+        histogram!(PIPELINE_STAGE_SECONDS, "stage" => self.as_str()).record(duration.as_secs_f64());
+    }
+}
+
+impl Timings {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn measure<T>(&mut self, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let started_at = Instant::now();
+        let output = f();
+        self.record(stage, started_at.elapsed());
+        output
+    }
+
+    pub(crate) async fn measure_async<T>(&mut self, stage: Stage, future: impl Future<Output = T>) -> T {
+        let started_at = Instant::now();
+        let output = future.await;
+        self.record(stage, started_at.elapsed());
+        output
+    }
+
+    /// Time spent in all the stages.
+    pub(crate) fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    // 複数行のメッセージでは同じ段階の時間を合算する
+    fn record(&mut self, stage: Stage, duration: Duration) {
+        stage.record(duration);
+        match self.stages.iter_mut().find(|(recorded, _)| *recorded == stage) {
+            Some((_, total)) => *total += duration,
+            None => self.stages.push((stage, duration)),
+        }
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages = self
+            .stages
+            .iter()
+            .map(|(stage, duration)| format!("{}={duration:?}", stage.as_str()))
+            .collect::<Vec<_>>();
+        write!(f, "{}", stages.join(" "))
+    }
+}
@@ -0,0 +1,92 @@
+use dashmap::DashMap;
+use database::guild::Guild;
+use seitai_audio::AudioRepository;
+use seitai_converter::ReadContext;
+use serenity::all::ChannelId;
+use songbird::{Call, input::Input};
+
+use crate::{
+    utils::{enqueue, system_audio, truncate_chars},
+    utterance::PredefinedUtterance,
+};
+
+// 読み上げるトピックの最大文字数
+const MAX_TOPIC_CHARS: usize = 100;
+
+/// Topics of text channels last read, so that each one is read again only when it changes.
+#[derive(Debug, Default)]
+pub(crate) struct Topics {
+    read: DashMap<ChannelId, String>,
+}
+
+impl Topics {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers the topic of the channel and returns whether it differs from the one last read.
+    pub(crate) fn update(&self, channel_id: ChannelId, topic: &str) -> bool {
+        self.read
+            .insert(channel_id, topic.to_string())
+            .is_none_or(|read| read != topic)
+    }
+}
+
+/// Text to read for the topic, on one line and truncated, or `None` if there is nothing to read.
+pub(crate) fn orientation(topic: &str) -> Option<String> {
+    let mut text = topic.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    if truncate_chars(&mut text, MAX_TOPIC_CHARS) {
+        text = format!("{text}、{}", PredefinedUtterance::Omitted.as_ref());
+    }
+    Some(text)
+}
+
+/// Reads the topic of the channel with the default voice of the guild.
+pub(crate) async fn read<Repository>(call: &mut Call, audio_repository: &Repository, topic: &str, settings: &Guild)
+where
+    Repository: AudioRepository<Input = Input> + Send + Sync,
+{
+    let Some(text) = orientation(topic) else {
+        return;
+    };
+    let audio = system_audio(
+        seitai_converter::convert(&ReadContext::default(), &text),
+        settings.default_speaker_id,
+    );
+    match audio_repository.get(audio).await {
+        Ok(input) => {
+            enqueue(call, input, settings.volume).await;
+        },
+        Err(error) => {
+            tracing::error!("failed to get audio source to read topic\nError: {error:?}");
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::ChannelId;
+
+    use super::{MAX_TOPIC_CHARS, Topics, orientation};
+
+    #[test]
+    fn read_topic_once_until_changed() {
+        assert_eq!(orientation(" \n "), None);
+        assert_eq!(
+            orientation("雑談用\nのチャンネル").as_deref(),
+            Some("雑談用 のチャンネル")
+        );
+        let long = orientation(&"あ".repeat(MAX_TOPIC_CHARS * 2)).unwrap();
+        assert!(long.starts_with(&"あ".repeat(MAX_TOPIC_CHARS)));
+        assert!(long.ends_with("以下省略"));
+
+        let topics = Topics::new();
+        let channel_id = ChannelId::new(1);
+        assert!(topics.update(channel_id, "雑談用"));
+        assert!(!topics.update(channel_id, "雑談用"));
+        assert!(topics.update(channel_id, "作業用"));
+    }
+}
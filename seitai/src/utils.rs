@@ -5,23 +5,32 @@ use std::{
 };
 
 use anyhow::{Context as _, Result};
+use dashmap::DashMap;
+use database::{guild::Spoilers, guild_rate_limit::GuildRateLimit};
 use futures::lock::Mutex;
 use hashbrown::HashMap;
+use ordered_float::NotNan;
+use seitai_audio::Audio;
+use seitai_converter::{ReadContext, regex};
 use serenity::{
-    all::{GuildId, User, UserId},
+    all::{ChannelId, GuildId, User, UserId},
     builder::{CreateInteractionResponse, CreateInteractionResponseMessage},
     client::Context,
     model::{application::CommandInteraction, guild::Guild},
     utils::{ContentSafeOptions, content_safe},
 };
-use songbird::Songbird;
+use songbird::{
+    Call, Songbird,
+    input::Input,
+    tracks::{Track, TrackHandle},
+};
 use soundboard::sound::SoundId;
 use voicevox::Voicevox;
 
-use crate::{
-    VoicevoxClient,
-    regex::{self, SOUNDMOJI},
-};
+use crate::{VoicevoxClient, speaker::Speaker, utterance::PredefinedUtterance};
+
+// 効果音は読み上げより大きく聞こえるので小さめに鳴らす
+pub(crate) const SOUND_GAIN: f32 = 0.02;
 
 pub(crate) async fn get_manager(context: &Context) -> Result<Arc<Songbird>> {
     songbird::get(context)
@@ -29,21 +38,37 @@ pub(crate) async fn get_manager(context: &Context) -> Result<Arc<Songbird>> {
         .context("failed to get songbird voice client: it placed in at initialisation")
 }
 
+/// Enqueues the input into the call at the volume of the guild in percent.
+pub(crate) async fn enqueue(call: &mut Call, input: Input, volume: i32) -> TrackHandle {
+    call.enqueue(Track::from(input).volume(gain(volume))).await
+}
+
+/// Converts the volume in percent into the gain of tracks.
+pub(crate) fn gain(volume: i32) -> f32 {
+    volume as f32 / 100.0
+}
+
+/// Users connected to the voice channel, which are taken from voice states so that members missing from the cache are
+/// included.
+pub(crate) fn users_in(context: &Context, guild_id: GuildId, channel_id: ChannelId) -> Vec<UserId> {
+    context.cache.guild(guild_id).map_or_else(Vec::new, |guild| {
+        guild
+            .voice_states
+            .values()
+            .filter(|state| state.channel_id == Some(channel_id))
+            .map(|state| state.user_id)
+            .collect()
+    })
+}
+
 pub(crate) fn get_guild(context: &Context, interaction: &CommandInteraction) -> Option<Guild> {
     let guild_id = interaction.guild_id?;
     guild_id.to_guild_cached(&context.cache).map(|guild| guild.to_owned())
 }
 
 pub(crate) fn parse_soundmoji(value: impl AsRef<str>) -> Result<(Option<SoundId>, Option<GuildId>)> {
-    let value = value.as_ref();
-
-    match SOUNDMOJI.captures(value) {
-        Some(caps) => {
-            let sound_id = caps.name("sound_id").map(|m| m.as_str().parse::<u64>()).transpose()?;
-            let guild_id = caps.name("guild_id").map(|m| m.as_str().parse::<u64>()).transpose()?;
-
-            Ok((sound_id.map(SoundId::new), guild_id.map(GuildId::new)))
-        },
+    match seitai_converter::soundmoji::parse(value.as_ref())? {
+        Some((guild_id, sound_id)) => Ok((Some(SoundId::new(sound_id.get())), Some(GuildId::from(guild_id)))),
         None => Ok((None, None)),
     }
 }
@@ -81,6 +106,102 @@ pub(crate) fn normalize<'a>(context: &Context, guild_id: &GuildId, users: &[User
     }
 }
 
+/// Replaces text hidden as spoilers as the guild chooses.
+pub(crate) fn hide_spoilers(text: &str, spoilers: Spoilers) -> Cow<'_, str> {
+    match spoilers {
+        Spoilers::Announce => seitai_converter::spoiler::replace(text, Some(PredefinedUtterance::Spoiler.as_ref())),
+        Spoilers::Skip => seitai_converter::spoiler::replace(text, None),
+        Spoilers::Read => Cow::Borrowed(text),
+    }
+}
+
+/// Audio of an utterance of the bot, read with the default voice of the guild.
+pub(crate) fn system_audio(text: String, default_speaker_id: Option<i32>) -> Audio {
+    Audio {
+        text,
+        speaker: default_speaker_id
+            .unwrap_or(database::user::DEFAULT_SPEAKER_ID)
+            .to_string(),
+        speed: NotNan::new(Speaker::default_speed()).unwrap(),
+        pitch: NotNan::new(Speaker::default_pitch()).unwrap(),
+        intonation: NotNan::new(Speaker::default_intonation()).unwrap(),
+        morph: None,
+    }
+}
+
+/// Context to convert the messages of the guild with.
+pub(crate) fn read_context(settings: &database::guild::Guild) -> ReadContext {
+    let default = ReadContext::default();
+    let limit = settings
+        .max_length
+        .and_then(|max_length| usize::try_from(max_length).ok());
+    ReadContext {
+        limit: limit.unwrap_or(default.limit),
+        // 上限を設定していなければ既定の上限で省略を読み上げる
+        announce_omission: limit.is_none() || settings.announce_truncation,
+        flatten_markdown: settings.flatten_markdown,
+        omit_code: settings.omit_code,
+    }
+}
+
+/// Removes decorations such as pronoun tags from the name with the whitespace-separated patterns of the guild, or the
+/// default ones if `None`.
+pub(crate) fn core_name<'a>(name: &'a str, enabled: bool, patterns: Option<&str>) -> Cow<'a, str> {
+    if !enabled {
+        return Cow::Borrowed(name);
+    }
+    let Some(patterns) = patterns else {
+        return seitai_converter::name::strip_decorations(name, None);
+    };
+
+    let patterns = patterns.split_whitespace().collect::<Vec<_>>();
+    match seitai_converter::name::compile(&patterns) {
+        Ok(patterns) => seitai_converter::name::strip_decorations(name, Some(&patterns)),
+        Err(error) => {
+            // 登録時に確かめているので、ここで失敗するのは正規表現の実装が変わったときだけ
+            tracing::warn!("failed to compile name patterns {patterns:?}\nError: {error:?}");
+            Cow::Borrowed(name)
+        },
+    }
+}
+
+/// Truncates the text to the number of characters and returns whether anything is cut off.
+pub(crate) fn truncate_chars(text: &mut String, max_chars: usize) -> bool {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => {
+            text.truncate(end);
+            true
+        },
+        None => false,
+    }
+}
+
+/// Duration in days, hours and minutes in Japanese, such as `1日2時間3分`.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    match (days, hours) {
+        (0, 0) => format!("{minutes}分"),
+        (0, _) => format!("{hours}時間{minutes}分"),
+        _ => format!("{days}日{hours}時間{minutes}分"),
+    }
+}
+
+pub(crate) async fn is_owner(context: &Context, user_id: UserId) -> Result<bool> {
+    let application = context
+        .http
+        .get_current_application_info()
+        .await
+        .context("failed to get application info to check owner")?;
+
+    let is_owner = match application.team {
+        Some(team) => team.members.iter().any(|member| member.user.id == user_id),
+        None => application.owner.is_some_and(|owner| owner.id == user_id),
+    };
+
+    Ok(is_owner)
+}
+
 pub(crate) async fn get_voicevox(context: &Context) -> Option<Arc<Mutex<Voicevox>>> {
     let data = context.data.read().await;
     data.get::<VoicevoxClient>().cloned()
@@ -93,24 +214,24 @@ struct UserState {
     cooldown_until: Option<Instant>,
 }
 
-pub struct RateLimiter {
-    // ユーザーごとの状態を保持
-    users: Mutex<HashMap<UserId, UserState>>,
+/// Tuning of the limit of consecutive messages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
     // 制限時間内に許可するメッセージ数
-    max_messages: usize,
+    pub max_messages: usize,
     // 制限を判定する時間枠
-    time_window: Duration,
+    pub time_window: Duration,
     // 基本のクールダウン時間
-    base_cooldown: Duration,
+    pub base_cooldown: Duration,
     // クールダウンの最大時間
-    max_cooldown: Duration,
+    pub max_cooldown: Duration,
     // 違反回数に応じたクールダウン時間の乗数
-    cooldown_multiplier: f32,
+    pub cooldown_multiplier: f32,
     // 違反カウントがリセットされるまでの時間
-    violation_reset_time: Duration,
+    pub violation_reset_time: Duration,
 }
 
-impl RateLimiter {
+impl RateLimit {
     pub fn new(
         max_messages: usize,
         time_window_secs: u64,
@@ -120,7 +241,6 @@ impl RateLimiter {
         violation_reset_hours: u64,
     ) -> Self {
         Self {
-            users: Mutex::new(HashMap::new()),
             max_messages,
             time_window: Duration::from_secs(time_window_secs),
             base_cooldown: Duration::from_secs(base_cooldown_secs),
@@ -130,10 +250,67 @@ impl RateLimiter {
         }
     }
 
-    pub async fn check_rate_limit(&self, user_id: UserId) -> bool {
+    pub(crate) fn to_database(self, guild_id: GuildId) -> GuildRateLimit {
+        GuildRateLimit {
+            id: guild_id.get() as i64,
+            max_messages: self.max_messages as i32,
+            time_window: self.time_window.as_secs() as i32,
+            base_cooldown: self.base_cooldown.as_secs() as i32,
+            max_cooldown: self.max_cooldown.as_secs() as i32,
+            cooldown_multiplier: self.cooldown_multiplier,
+            violation_reset_time: self.violation_reset_time.as_secs() as i32,
+        }
+    }
+}
+
+impl From<GuildRateLimit> for RateLimit {
+    fn from(rate_limit: GuildRateLimit) -> Self {
+        Self {
+            max_messages: rate_limit.max_messages.max(1) as usize,
+            time_window: Duration::from_secs(rate_limit.time_window.max(0) as u64),
+            base_cooldown: Duration::from_secs(rate_limit.base_cooldown.max(0) as u64),
+            max_cooldown: Duration::from_secs(rate_limit.max_cooldown.max(0) as u64),
+            cooldown_multiplier: rate_limit.cooldown_multiplier,
+            violation_reset_time: Duration::from_secs(rate_limit.violation_reset_time.max(0) as u64),
+        }
+    }
+}
+
+pub struct RateLimiter {
+    // サーバーとユーザーごとの状態を保持
+    users: Mutex<HashMap<(GuildId, UserId), UserState>>,
+    // サーバーごとに設定がなければ使う制限
+    default_limit: RateLimit,
+    limits: DashMap<GuildId, RateLimit>,
+}
+
+impl RateLimiter {
+    pub fn new(default_limit: RateLimit) -> Self {
+        Self {
+            users: Mutex::new(HashMap::new()),
+            default_limit,
+            limits: DashMap::new(),
+        }
+    }
+
+    /// Limit applied in the guild.
+    pub fn limit(&self, guild_id: GuildId) -> RateLimit {
+        self.limits.get(&guild_id).map_or(self.default_limit, |limit| *limit)
+    }
+
+    pub fn is_default(&self, guild_id: GuildId) -> bool {
+        !self.limits.contains_key(&guild_id)
+    }
+
+    pub fn set_limit(&self, guild_id: GuildId, limit: RateLimit) {
+        self.limits.insert(guild_id, limit);
+    }
+
+    pub async fn check_rate_limit(&self, guild_id: GuildId, user_id: UserId) -> bool {
+        let limit = self.limit(guild_id);
         let now = Instant::now();
         let mut users = self.users.lock().await;
-        let user_state = users.entry(user_id).or_insert_with(|| UserState {
+        let user_state = users.entry((guild_id, user_id)).or_insert_with(|| UserState {
             messages: Vec::new(),
             violation_count: 0,
             cooldown_until: None,
@@ -145,30 +322,30 @@ impl RateLimiter {
                 return false;
             }
             // クールダウンが終了したら、violation_countをリセットするかチェック
-            if let Some(last_message) = user_state.messages.last() {
-                if now.duration_since(*last_message) >= self.violation_reset_time {
-                    user_state.violation_count = 0;
-                }
+            if let Some(last_message) = user_state.messages.last()
+                && now.duration_since(*last_message) >= limit.violation_reset_time
+            {
+                user_state.violation_count = 0;
             }
         }
 
         // 古いメッセージを削除
         user_state
             .messages
-            .retain(|time| now.duration_since(*time) <= self.time_window);
+            .retain(|time| now.duration_since(*time) <= limit.time_window);
 
         // メッセージ数をチェック
-        if user_state.messages.len() >= self.max_messages {
+        if user_state.messages.len() >= limit.max_messages {
             // 違反回数を増やしてクールダウンを設定
             user_state.violation_count += 1;
 
             // クールダウン時間を計算（基本時間 × 乗数^違反回数）
             let cooldown_duration = Duration::from_secs_f32(
-                self.base_cooldown.as_secs_f32() * self.cooldown_multiplier.powi(user_state.violation_count as i32),
+                limit.base_cooldown.as_secs_f32() * limit.cooldown_multiplier.powi(user_state.violation_count as i32),
             );
 
             // 最大クールダウン時間を超えないように調整
-            let cooldown_duration = cooldown_duration.min(self.max_cooldown);
+            let cooldown_duration = cooldown_duration.min(limit.max_cooldown);
             user_state.cooldown_until = Some(now + cooldown_duration);
 
             return false;
@@ -180,17 +357,103 @@ impl RateLimiter {
     }
 
     // 特定ユーザーの現在の状態を取得するメソッド
-    pub async fn get_user_state(&self, user_id: UserId) -> Option<(usize, Option<Duration>)> {
+    pub async fn snapshot(&self, guild_id: GuildId, user_id: UserId) -> Option<RateLimitSnapshot> {
+        let time_window = self.limit(guild_id).time_window;
+        let now = Instant::now();
         let users = self.users.lock().await;
-        users.get(&user_id).map(|state| {
-            let remaining_cooldown = state.cooldown_until.map(|until| {
-                if Instant::now() < until {
-                    until - Instant::now()
-                } else {
-                    Duration::from_secs(0)
-                }
-            });
-            (state.violation_count, remaining_cooldown)
+        users.get(&(guild_id, user_id)).map(|state| RateLimitSnapshot {
+            recent_messages: state
+                .messages
+                .iter()
+                .filter(|time| now.duration_since(**time) <= time_window)
+                .count(),
+            violation_count: state.violation_count,
+            remaining_cooldown: state
+                .cooldown_until
+                .and_then(|until| until.checked_duration_since(now))
+                .filter(|remaining| !remaining.is_zero()),
         })
     }
+
+    // 誤ってかかった制限を解除するため、特定ユーザーの状態を消去するメソッド
+    pub async fn reset_user(&self, guild_id: GuildId, user_id: UserId) -> bool {
+        self.users.lock().await.remove(&(guild_id, user_id)).is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitSnapshot {
+    // 時間枠内に送られたメッセージ数
+    pub recent_messages: usize,
+    pub violation_count: usize,
+    // クールダウン中であれば残り時間
+    pub remaining_cooldown: Option<Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::{GuildId, UserId};
+
+    use super::{RateLimit, RateLimiter, read_context};
+
+    #[tokio::test]
+    async fn reset_user_lifts_cooldown() {
+        let rate_limiter = RateLimiter::new(RateLimit::new(1, 60, 60, 60, 1.0, 1));
+        let guild_id = GuildId::new(1);
+        let user_id = UserId::new(1);
+
+        assert!(rate_limiter.check_rate_limit(guild_id, user_id).await);
+        assert!(!rate_limiter.check_rate_limit(guild_id, user_id).await);
+        let snapshot = rate_limiter.snapshot(guild_id, user_id).await.unwrap();
+        assert_eq!(snapshot.recent_messages, 1);
+        assert_eq!(snapshot.violation_count, 1);
+        assert!(snapshot.remaining_cooldown.is_some());
+
+        assert!(rate_limiter.reset_user(guild_id, user_id).await);
+        assert_eq!(rate_limiter.snapshot(guild_id, user_id).await, None);
+        assert!(rate_limiter.check_rate_limit(guild_id, user_id).await);
+        assert!(!rate_limiter.reset_user(guild_id, UserId::new(2)).await);
+    }
+
+    #[tokio::test]
+    async fn apply_limit_of_each_guild() {
+        let rate_limiter = RateLimiter::new(RateLimit::new(1, 60, 60, 60, 1.0, 1));
+        let (strict, loose) = (GuildId::new(1), GuildId::new(2));
+        let user_id = UserId::new(1);
+        rate_limiter.set_limit(loose, RateLimit::new(3, 60, 60, 60, 1.0, 1));
+
+        assert!(rate_limiter.check_rate_limit(strict, user_id).await);
+        assert!(!rate_limiter.check_rate_limit(strict, user_id).await);
+        for _ in 0..3 {
+            assert!(rate_limiter.check_rate_limit(loose, user_id).await);
+        }
+        assert!(!rate_limiter.check_rate_limit(loose, user_id).await);
+        assert!(rate_limiter.is_default(strict));
+        assert!(!rate_limiter.is_default(loose));
+    }
+
+    #[test]
+    fn read_up_to_limit_of_guild() {
+        let text = "あ".repeat(300);
+        let mut settings = database::guild::Guild {
+            max_length: Some(200),
+            announce_truncation: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            seitai_converter::convert(&read_context(&settings), &text),
+            "あ".repeat(200)
+        );
+
+        settings.announce_truncation = true;
+        assert_eq!(
+            seitai_converter::convert(&read_context(&settings), &text),
+            format!("{}、以下省略", "あ".repeat(200))
+        );
+
+        // 上限がなければ既定の上限で省略を読み上げる
+        settings.max_length = None;
+        settings.announce_truncation = false;
+        assert!(seitai_converter::convert(&read_context(&settings), &text).ends_with("、以下省略"));
+    }
 }
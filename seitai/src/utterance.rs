@@ -0,0 +1,25 @@
+use strum::{AsRefStr, EnumString};
+
+#[derive(Debug, Clone, EnumString, AsRefStr)]
+pub(crate) enum PredefinedUtterance {
+    #[strum(serialize = "コード省略")]
+    Code,
+    #[strum(serialize = "ネタバレ")]
+    Spoiler,
+    #[strum(serialize = "URL")]
+    Url,
+    #[strum(serialize = "接続しました")]
+    Connected,
+    #[strum(serialize = "添付ファイル")]
+    Attachment,
+    #[strum(serialize = "を登録しました")]
+    Registered,
+    #[strum(serialize = "別のチャンネルに移動します")]
+    Moving,
+    #[strum(serialize = "返信")]
+    Reply,
+    #[strum(serialize = "以下省略")]
+    Omitted,
+    #[strum(serialize = "こんにちは、この声で読み上げます")]
+    Sample,
+}
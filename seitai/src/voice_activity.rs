@@ -0,0 +1,270 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context as _, Result};
+use dashmap::DashSet;
+use database::guild::Ducking;
+use serenity::{all::GuildId, async_trait, client::Context, prelude::TypeMapKey};
+use songbird::{
+    Event, EventContext, EventHandler,
+    tracks::{PlayMode, TrackQueue},
+};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::settings_resolver::SettingsResolver;
+
+// 話し声が途切れてから読み上げを戻すまでのティック数（1ティックは 20ms）
+const RESUME_TICKS: u32 = 40;
+// 音量を下げるときに掛ける倍率
+const LOWERED_GAIN: f32 = 0.25;
+// サーバーの設定を読み直す間隔
+const SETTINGS_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transition {
+    Duck,
+    Resume,
+}
+
+/// Detects that members start and stop talking from the ticks of the voice channel.
+#[derive(Debug, Default)]
+struct Detector {
+    speaking_ticks: u32,
+    silent_ticks: u32,
+    ducked: bool,
+}
+
+impl Detector {
+    fn tick(&mut self, speaking: bool, trigger_ticks: u32) -> Option<Transition> {
+        if speaking {
+            self.speaking_ticks += 1;
+            self.silent_ticks = 0;
+        } else {
+            self.silent_ticks += 1;
+            // 息継ぎ程度の途切れでは話し続けているとみなす
+            if self.silent_ticks >= RESUME_TICKS {
+                self.speaking_ticks = 0;
+            }
+        }
+
+        match self.ducked {
+            false if self.speaking_ticks >= trigger_ticks => {
+                self.ducked = true;
+                Some(Transition::Duck)
+            },
+            true if self.silent_ticks >= RESUME_TICKS => {
+                self.ducked = false;
+                Some(Transition::Resume)
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Number of ticks members have to talk for before the reading ducks, from 5 ticks (100ms) at the sensitivity of 5 to
+/// 45 ticks (900ms) at 1.
+fn trigger_ticks(sensitivity: i32) -> u32 {
+    5 + (5 - sensitivity.clamp(1, 5)) as u32 * 10
+}
+
+/// Track which the handler paused or lowered the volume of.
+#[derive(Debug, Clone, Copy)]
+enum Ducked {
+    Paused(Uuid),
+    Lowered { uuid: Uuid, volume: f32 },
+}
+
+impl Ducked {
+    fn uuid(self) -> Uuid {
+        match self {
+            Self::Paused(uuid) | Self::Lowered { uuid, .. } => uuid,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    detector: Detector,
+    ducking: Ducking,
+    sensitivity: i32,
+    fetched_at: Option<Instant>,
+    ducked: Option<Ducked>,
+}
+
+/// Settings and the guilds paused by `/pause`, which the handlers of every guild read.
+#[derive(Clone)]
+pub(crate) struct VoiceActivity {
+    pub(crate) settings: Arc<SettingsResolver>,
+    pub(crate) paused_guilds: Arc<DashSet<GuildId>>,
+}
+
+impl TypeMapKey for VoiceActivity {
+    type Value = VoiceActivity;
+}
+
+pub(crate) async fn get(context: &Context) -> Result<VoiceActivity> {
+    let data = context.data.read().await;
+    data.get::<VoiceActivity>()
+        .cloned()
+        .context("failed to get voice activity: it placed in at initialisation")
+}
+
+/// Pauses or lowers the reading while members are talking in the voice channel, and resumes it in the gaps, as set
+/// for the guild with `/settings ducking`.
+pub(crate) struct VoiceActivityHandler {
+    voice_activity: VoiceActivity,
+    guild_id: GuildId,
+    queue: TrackQueue,
+    state: Mutex<State>,
+}
+
+impl VoiceActivityHandler {
+    pub(crate) fn new(voice_activity: VoiceActivity, guild_id: GuildId, queue: TrackQueue) -> Self {
+        Self {
+            voice_activity,
+            guild_id,
+            queue,
+            state: Mutex::new(State {
+                detector: Detector::default(),
+                ducking: Ducking::Off,
+                sensitivity: 3,
+                fetched_at: None,
+                ducked: None,
+            }),
+        }
+    }
+
+    async fn refresh(&self, state: &mut State) {
+        if state
+            .fetched_at
+            .is_some_and(|fetched_at| fetched_at.elapsed() < SETTINGS_TTL)
+        {
+            return;
+        }
+        state.fetched_at = Some(Instant::now());
+
+        let settings = self.voice_activity.settings.guild(self.guild_id).await;
+        let ducking = settings.ducking();
+        if ducking != state.ducking {
+            // 切り替えた時点で止めていたものは戻す
+            self.restore(state).await;
+            state.detector = Detector::default();
+        }
+        state.ducking = ducking;
+        state.sensitivity = settings.ducking_sensitivity;
+    }
+
+    async fn duck(&self, state: &mut State) {
+        let Some(current) = self.queue.current() else {
+            return;
+        };
+        let Ok(info) = current.get_info().await else {
+            return;
+        };
+        // 既に止まっているものは触らない
+        if info.playing != PlayMode::Play {
+            return;
+        }
+
+        match state.ducking {
+            Ducking::Off => {},
+            Ducking::Pause => {
+                if current.pause().is_ok() {
+                    state.ducked = Some(Ducked::Paused(current.uuid()));
+                }
+            },
+            Ducking::Lower => {
+                if current.set_volume(info.volume * LOWERED_GAIN).is_ok() {
+                    state.ducked = Some(Ducked::Lowered {
+                        uuid: current.uuid(),
+                        volume: info.volume,
+                    });
+                }
+            },
+        }
+    }
+
+    async fn restore(&self, state: &mut State) {
+        let Some(ducked) = state.ducked.take() else {
+            return;
+        };
+        let Some(current) = self.queue.current() else {
+            return;
+        };
+
+        // 読み上げが進んでいれば元に戻すものはない。/pause で止めたものは /resume まで止めておく
+        let paused = self.voice_activity.paused_guilds.contains(&self.guild_id);
+        let result = match ducked {
+            Ducked::Paused(uuid) if uuid == current.uuid() && !paused => current.play(),
+            Ducked::Lowered { uuid, volume } if uuid == current.uuid() => current.set_volume(volume),
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            tracing::error!("failed to resume reading in guild {}\nError: {error:?}", self.guild_id);
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for VoiceActivityHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let EventContext::VoiceTick(tick) = ctx else {
+            return None;
+        };
+
+        let mut state = self.state.lock().await;
+        self.refresh(&mut state).await;
+        if state.ducking == Ducking::Off {
+            return None;
+        }
+
+        let trigger_ticks = trigger_ticks(state.sensitivity);
+        match state.detector.tick(!tick.speaking.is_empty(), trigger_ticks) {
+            Some(Transition::Duck) => self.duck(&mut state).await,
+            Some(Transition::Resume) => self.restore(&mut state).await,
+            None if state.detector.ducked => {
+                // 話している間に次の読み上げが始まったら、それも止めるか下げる
+                if let Some(current) = self.queue.current()
+                    && state.ducked.map(Ducked::uuid) != Some(current.uuid())
+                {
+                    self.restore(&mut state).await;
+                    self.duck(&mut state).await;
+                }
+            },
+            None => {},
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Detector, RESUME_TICKS, Transition, trigger_ticks};
+
+    #[test]
+    fn duck_while_talking_and_resume_in_gaps() {
+        assert_eq!(trigger_ticks(5), 5);
+        assert_eq!(trigger_ticks(1), 45);
+        assert_eq!(trigger_ticks(0), trigger_ticks(1));
+
+        let mut detector = Detector::default();
+        let mut transitions = Vec::new();
+        for speaking in [true; 5].into_iter().chain([false; RESUME_TICKS as usize]) {
+            transitions.push(detector.tick(speaking, 5));
+        }
+        assert_eq!(transitions[4], Some(Transition::Duck));
+        assert_eq!(transitions.last(), Some(&Some(Transition::Resume)));
+        assert_eq!(transitions.iter().flatten().count(), 2);
+
+        // 短い途切れを挟んでも話し続けていれば下げる
+        let mut detector = Detector::default();
+        assert_eq!(detector.tick(true, 3), None);
+        assert_eq!(detector.tick(false, 3), None);
+        assert_eq!(detector.tick(true, 3), None);
+        assert_eq!(detector.tick(true, 3), Some(Transition::Duck));
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::{DashMap, DashSet};
+use serenity::{all::GuildId, client::Context};
+use uuid::Uuid;
+
+use crate::utils::get_manager;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+// 見込みの長さの何倍を超えて再生していたら止まったとみなすか（短い発話のために猶予を足す）
+const OVERRUN_FACTOR: u32 = 3;
+const OVERRUN_GRACE: Duration = Duration::from_secs(10);
+// 長さの見込みがない効果音なども、キューの先頭がこの時間変わらなければ止まったとみなす
+const STALL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+// 再生されずに消えた発話の見込みを捨てるまでの時間
+const EXPECTATION_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Head of the queue of a guild and since when it has been there.
+#[derive(Debug, Clone, Copy)]
+struct Head {
+    uuid: Uuid,
+    since: Instant,
+}
+
+/// Monitor which skips tracks stuck at the head of the queue, to recover from stalls of the driver without a restart.
+#[derive(Debug, Default)]
+pub(crate) struct Watchdog {
+    // 発話ごとの再生にかかる長さの見込みと、見込みを覚えた時刻
+    expectations: DashMap<Uuid, (Duration, Instant)>,
+    heads: DashMap<GuildId, Head>,
+    watching: AtomicBool,
+}
+
+impl Watchdog {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers how long the track is expected to play.
+    pub(crate) fn expect(&self, uuid: Uuid, duration: Duration) {
+        self.expectations.insert(uuid, (duration, Instant::now()));
+    }
+
+    /// Checks the queue of every call periodically, except in guilds where reading is paused.
+    pub(crate) fn spawn(self: &Arc<Self>, context: Context, paused_guilds: Arc<DashSet<GuildId>>) {
+        if self.watching.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let watchdog = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                watchdog.check(&context, &paused_guilds).await;
+            }
+        });
+    }
+
+    async fn check(&self, context: &Context, paused_guilds: &DashSet<GuildId>) {
+        let manager = match get_manager(context).await {
+            Ok(manager) => manager,
+            Err(error) => {
+                tracing::error!("failed to get manager to watch tracks\nError: {error:?}");
+                return;
+            },
+        };
+
+        let now = Instant::now();
+        self.expectations
+            .retain(|_, (_, expected_at)| now.duration_since(*expected_at) < EXPECTATION_TTL);
+
+        for (guild_id, call) in manager.iter() {
+            let guild_id = GuildId::new(guild_id.0.get());
+            let queue = call.lock().await.queue().clone();
+            let current = match queue.current() {
+                Some(current) if !paused_guilds.contains(&guild_id) => current,
+                _ => {
+                    self.heads.remove(&guild_id);
+                    continue;
+                },
+            };
+
+            let uuid = current.uuid();
+            let head = *self
+                .heads
+                .entry(guild_id)
+                .and_modify(|head| {
+                    if head.uuid != uuid {
+                        *head = Head { uuid, since: now };
+                    }
+                })
+                .or_insert(Head { uuid, since: now });
+            let expected = self.expectations.get(&uuid).map(|expectation| expectation.0);
+            let unchanged_for = now.duration_since(head.since);
+            let info = current.get_info().await;
+            let play_time = info.as_ref().map_or(unchanged_for, |info| info.play_time);
+            if !is_stuck(play_time, expected, unchanged_for) {
+                continue;
+            }
+
+            tracing::warn!(
+                "skipping track {uuid} stuck in guild {guild_id} for {unchanged_for:?}, expected to play for \
+                 {expected:?}, queue length {}\nState: {info:?}",
+                queue.len()
+            );
+            if let Err(error) = queue.skip() {
+                tracing::error!("failed to skip stuck track\nError: {error:?}");
+            }
+            self.heads.remove(&guild_id);
+            self.expectations.remove(&uuid);
+        }
+    }
+}
+
+/// Whether the track has played far longer than expected, or has stayed at the head of the queue too long.
+fn is_stuck(play_time: Duration, expected: Option<Duration>, unchanged_for: Duration) -> bool {
+    let overran = expected.is_some_and(|expected| play_time > expected * OVERRUN_FACTOR + OVERRUN_GRACE);
+    overran || unchanged_for > STALL_TIMEOUT
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{STALL_TIMEOUT, is_stuck};
+
+    #[test]
+    fn detect_overrun_or_stall() {
+        let expected = Some(Duration::from_secs(2));
+        assert!(!is_stuck(Duration::from_secs(3), expected, Duration::from_secs(3)));
+        assert!(is_stuck(Duration::from_secs(30), expected, Duration::from_secs(30)));
+        assert!(!is_stuck(Duration::from_secs(30), None, Duration::from_secs(30)));
+        assert!(is_stuck(Duration::ZERO, None, STALL_TIMEOUT + Duration::from_secs(1)));
+    }
+}
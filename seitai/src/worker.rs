@@ -0,0 +1,201 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use dashmap::DashMap;
+use futures::FutureExt;
+use metrics::counter;
+use serenity::{all::GuildId, builder::CreateEmbed, model::Colour};
+use tokio::sync::{
+    mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+    watch,
+};
+use tracing::Instrument;
+
+use crate::{metrics::WORKER_PANICS, notifier::OwnerNotifier};
+
+pub(crate) type Reading = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Reads messages of each guild in order on a dedicated task, which survives panics while reading.
+pub(crate) struct ReadingWorkers {
+    workers: DashMap<GuildId, Worker>,
+    notifier: OwnerNotifier,
+}
+
+struct Worker {
+    sender: UnboundedSender<Job>,
+    state: Arc<WorkerState>,
+}
+
+#[derive(Default)]
+struct WorkerState {
+    // 取り消すたびに進めて、それ以前に受け付けた読み上げを捨てる
+    generation: watch::Sender<u64>,
+    // 読み上げ中と読み上げ待ちのメッセージの数
+    pending: AtomicUsize,
+}
+
+struct Job {
+    generation: u64,
+    reading: Reading,
+}
+
+struct PendingGuard<'a>(&'a AtomicUsize);
+
+impl ReadingWorkers {
+    pub(crate) fn new(notifier: OwnerNotifier) -> Self {
+        Self {
+            workers: DashMap::new(),
+            notifier,
+        }
+    }
+
+    /// Queues the reading to the worker of the guild, spawning the worker if there is none.
+    pub(crate) fn submit(&self, guild_id: GuildId, reading: Reading) {
+        let mut worker = self.workers.entry(guild_id).or_insert_with(|| self.spawn(guild_id));
+        worker.state.pending.fetch_add(1, Ordering::Relaxed);
+        let job = Job {
+            generation: *worker.state.generation.borrow(),
+            reading,
+        };
+        if let Err(error) = worker.sender.send(job) {
+            // ワーカーが終了している場合は作り直す
+            *worker = self.spawn(guild_id);
+            worker.state.pending.fetch_add(1, Ordering::Relaxed);
+            let _ = worker.sender.send(error.0);
+        }
+    }
+
+    /// Cancels the readings of the guild, including the one being synthesized, and returns how many were cancelled.
+    pub(crate) fn cancel(&self, guild_id: GuildId) -> usize {
+        let Some(worker) = self.workers.get(&guild_id) else {
+            return 0;
+        };
+        worker.state.generation.send_modify(|generation| *generation += 1);
+        worker.state.pending.load(Ordering::Relaxed)
+    }
+
+    /// Guilds with readings being synthesized or waiting, and how many there are.
+    pub(crate) fn pending(&self) -> Vec<(GuildId, usize)> {
+        self.workers
+            .iter()
+            .map(|worker| (*worker.key(), worker.state.pending.load(Ordering::Relaxed)))
+            .filter(|(_, pending)| *pending > 0)
+            .collect()
+    }
+
+    fn spawn(&self, guild_id: GuildId) -> Worker {
+        let (sender, receiver) = unbounded_channel();
+        let state = Arc::new(WorkerState::default());
+        let notifier = self.notifier.clone();
+        tokio::spawn(
+            supervise(guild_id, receiver, state.clone(), notifier)
+                .instrument(tracing::info_span!("reading_worker", %guild_id)),
+        );
+        Worker { sender, state }
+    }
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Logs panics with the span they occurred in, e.g. the guild of a reading worker, instead of printing to stderr.
+pub(crate) fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        let location = info
+            .location()
+            .map_or_else(|| "unknown location".to_string(), ToString::to_string);
+        tracing::error!("panicked at {location}\nPanic: {}", panic_message(info.payload()));
+    }));
+}
+
+async fn supervise(
+    guild_id: GuildId,
+    mut receiver: UnboundedReceiver<Job>,
+    state: Arc<WorkerState>,
+    notifier: OwnerNotifier,
+) {
+    loop {
+        let Err(panic) = AssertUnwindSafe(work(&mut receiver, &state)).catch_unwind().await else {
+            return;
+        };
+
+        let message = panic_message(&*panic);
+        tracing::error!("reading worker for guild {guild_id} panicked, restarting it\nPanic: {message}");
+        counter!(WORKER_PANICS).increment(1);
+        notifier
+            .notify(
+                CreateEmbed::new()
+                    .title("読み上げ中にパニックが発生しました。")
+                    .description("読み上げを再開しています。")
+                    .field("サーバー", guild_id.to_string(), true)
+                    .field("詳細", format!("```\n{message}\n```"), false)
+                    .colour(Colour::RED),
+            )
+            .await;
+    }
+}
+
+async fn work(receiver: &mut UnboundedReceiver<Job>, state: &WorkerState) {
+    let mut generation = state.generation.subscribe();
+    while let Some(job) = receiver.recv().await {
+        let _pending = PendingGuard(&state.pending);
+        if job.generation != *generation.borrow_and_update() {
+            continue;
+        }
+
+        // 読み上げ中に取り消された場合は合成を途中で止める
+        tokio::select! {
+            _ = job.reading => {},
+            _ = generation.changed() => {},
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    match panic.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => panic.downcast_ref::<String>().map_or("unknown panic", String::as_str),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use serenity::all::{GuildId, Http};
+    use tokio::sync::oneshot;
+
+    use super::ReadingWorkers;
+    use crate::notifier::OwnerNotifier;
+
+    #[tokio::test]
+    async fn keep_reading_after_panic() {
+        let workers = ReadingWorkers::new(OwnerNotifier::new(Arc::new(Http::new("")), None));
+        let guild_id = GuildId::new(1);
+        let (sender, receiver) = oneshot::channel();
+
+        workers.submit(guild_id, Box::pin(async { panic!("failed to read") }));
+        workers.submit(
+            guild_id,
+            Box::pin(async move {
+                let _ = sender.send(());
+            }),
+        );
+
+        // パニックしてもワーカーは再開し、後に受け付けた読み上げも読まれる
+        tokio::time::timeout(Duration::from_secs(1), receiver)
+            .await
+            .expect("reading after panic timed out")
+            .expect("reading after panic was dropped");
+    }
+}